@@ -1,11 +1,146 @@
 use criterion::{Criterion, criterion_group, criterion_main};
-use motley::{gui::Window, model::load_model};
+use motley::model::{export_gltf, load_model, load_scene, Mesh, Model};
+use serde_json::{json, Value};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/*
+Wraps the system allocator to track the bytes currently allocated and the highest value seen,
+so the benchmarks can report the peak memory a load needs on top of what was already in use.
+*/
+struct PeakAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for PeakAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let pointer = System.alloc(layout);
+        if !pointer.is_null() {
+            let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(allocated, Ordering::Relaxed);
+        }
+        pointer
+    }
+
+    unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+        System.dealloc(pointer, layout);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: PeakAllocator = PeakAllocator;
+
+/*
+Runs `f` and returns the most memory allocated at once while it ran, beyond what was allocated
+before it started.
+*/
+fn peak_allocation<R>(f: impl FnOnce() -> R) -> usize {
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    PEAK.store(before, Ordering::Relaxed);
+    drop(f());
+    PEAK.load(Ordering::Relaxed) - before
+}
 
 fn benchmark_model_loading(c: &mut Criterion) {
     c.bench_function("Model loading", |b| {
         b.iter(|| {
             let model = load_model("assets/DamagedHelmet/DamagedHelmet.gltf");
-            assert!(model.meshes.len() > 0);
+            assert!(!model.meshes.is_empty());
+        });
+    });
+}
+
+/*
+Loads the same asset from a cache file written by `Model::save_cache`, to compare against
+parsing the glTF file above.
+*/
+fn benchmark_cache_loading(c: &mut Criterion) {
+    let cache_path = std::env::temp_dir().join("motley_bench_DamagedHelmet.cache");
+    load_model("assets/DamagedHelmet/DamagedHelmet.gltf")
+        .save_cache(&cache_path)
+        .expect("Failed to write model cache.");
+
+    c.bench_function("Model cache loading", |b| {
+        b.iter(|| {
+            let model = Model::load_cache(&cache_path).unwrap();
+            assert!(!model.meshes.is_empty());
+        });
+    });
+}
+
+/*
+Writes a variant of the helmet with one node, mesh and material per image of the asset, each
+material using a different image as its base color, so loading it decodes five textures and
+processes five primitives. Comparing runs with and without the `parallel` feature shows the
+gain from decoding and processing them concurrently.
+*/
+fn multi_texture_asset() -> String {
+    let directory = std::fs::canonicalize("assets/DamagedHelmet").unwrap();
+    let source = std::fs::read_to_string(directory.join("DamagedHelmet.gltf")).unwrap();
+    let mut gltf: Value = serde_json::from_str(&source).unwrap();
+
+    let absolute = |uri: &Value| Value::from(directory.join(uri.as_str().unwrap()).to_str().unwrap());
+    gltf["buffers"][0]["uri"] = absolute(&gltf["buffers"][0]["uri"]);
+    let image_count = gltf["images"].as_array().unwrap().len();
+    for image in 0..image_count {
+        gltf["images"][image]["uri"] = absolute(&gltf["images"][image]["uri"]);
+    }
+
+    let primitive = gltf["meshes"][0]["primitives"][0].clone();
+    let mut meshes = Vec::new();
+    let mut materials = Vec::new();
+    let mut nodes = Vec::new();
+    for index in 0..image_count {
+        let mut primitive = primitive.clone();
+        primitive["material"] = json!(index);
+        meshes.push(json!({ "primitives": [primitive] }));
+        materials.push(json!({ "pbrMetallicRoughness": { "baseColorTexture": { "index": index } } }));
+        nodes.push(json!({ "mesh": index }));
+    }
+    gltf["meshes"] = json!(meshes);
+    gltf["materials"] = json!(materials);
+    gltf["nodes"] = json!(nodes);
+    gltf["scenes"] = json!([{ "nodes": (0..image_count).collect::<Vec<_>>() }]);
+
+    let path = std::env::temp_dir().join("motley_bench_MultiTexture.gltf");
+    std::fs::write(&path, gltf.to_string()).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+fn benchmark_multi_texture_loading(c: &mut Criterion) {
+    let path = multi_texture_asset();
+
+    c.bench_function("Multi-texture scene loading", |b| {
+        b.iter(|| {
+            let model = load_scene(&path, 0).unwrap();
+            assert_eq!(model.meshes.len(), 5);
+        });
+    });
+}
+
+/*
+Loads a plane of about a million vertices, written once as a GLB file, where reading the
+vertex streams dominates the load time. The peak memory of one load is printed before
+measuring; with the `mmap` feature the binary chunk is mapped instead of read, which leaves it
+out of the peak.
+*/
+fn benchmark_large_mesh_loading(c: &mut Criterion) {
+    let path = std::env::temp_dir().join("motley_bench_LargePlane.glb");
+    let model = Model {
+        meshes: vec![Mesh::plane(1.0, 1.0, 1000)],
+        materials: vec![Default::default()]
+    };
+    export_gltf(&model, &path, true).expect("Failed to write large mesh.");
+
+    let peak = peak_allocation(|| load_model(&path));
+    println!("Large mesh loading peak allocation: {:.1} MiB", peak as f64 / 1_048_576.0);
+
+    c.bench_function("Large mesh loading", |b| {
+        b.iter(|| {
+            let model = load_model(&path);
+            assert_eq!(model.meshes[0].vertices.len(), 1001 * 1001);
         });
     });
 }
@@ -17,7 +152,7 @@ fn create_criterion() -> Criterion {
 criterion_group! {
     name = benches;
     config = create_criterion();
-    targets = benchmark_model_loading
+    targets = benchmark_model_loading, benchmark_cache_loading, benchmark_multi_texture_loading, benchmark_large_mesh_loading
 }
 
 criterion_main!(benches);
\ No newline at end of file
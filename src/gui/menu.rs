@@ -30,6 +30,7 @@ impl MenuItem {
     }
 }
 
+#[derive(Default)]
 pub struct Menu {
     items: Vec<MenuItem>,
 }
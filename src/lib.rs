@@ -1,2 +1,3 @@
 pub mod gui;
 pub mod model;
+pub mod render;
@@ -1,12 +1,9 @@
 use glam::*;
 use std::sync::{Arc, Mutex};
 
-pub mod gui;
-use gui::{Framebuffer, Window};
-use gui::components::setup_menu;
-
-pub mod model;
-use model::{load_model, Material, Model, Vertex};
+use motley::gui::{Framebuffer, Window};
+use motley::gui::components::setup_menu;
+use motley::model::{load_model, Material, Model, Vertex};
 
 /*
 This program implements a basic 3D renderer using a software rasterizer. It includes functionalities
@@ -43,6 +40,7 @@ fn edge_function(a: &Vec2, c: &Vec2, b: &Vec2) -> f32 {
 Renders a single triangle to the framebuffer. It performs perspective transformations, rasterization,
 depth testing, and normal correction to compute a color for each pixel in the triangle.
 */
+#[allow(clippy::too_many_arguments)]
 fn draw_triangle(
     framebuffer: &mut Framebuffer,
     depth_buffer: &mut Framebuffer,
@@ -203,11 +201,12 @@ fn draw_model(
 ) {
     for mesh in &model.meshes {
         for i in 0..(mesh.indices.len() / 3) {
-            let v0 = mesh.vertices[mesh.indices[i * 3] as usize];
-            let v1 = mesh.vertices[mesh.indices[i * 3 + 1] as usize];
-            let v2 = mesh.vertices[mesh.indices[i * 3 + 2] as usize];
+            let [i0, i1, i2] = mesh.indices.triangle(i);
+            let v0 = mesh.vertices[i0 as usize];
+            let v1 = mesh.vertices[i1 as usize];
+            let v2 = mesh.vertices[i2 as usize];
 
-            let material = &model.materials[mesh.material_idx];
+            let material = &model.materials[mesh.material.index()];
 
             draw_triangle(
                 framebuffer,
@@ -0,0 +1,247 @@
+use glam::*;
+use std::ops::{Add, Mul};
+use crate::model::Scene;
+
+/*
+The `Interpolation` enum is how an animation channel moves between keyframes, as its glTF
+sampler declares: `Step` holds each value until the next keyframe, `Linear` blends them, with
+rotations taking the shortest arc, and `CubicSpline` follows the Hermite curve of the tangents
+stored around every keyframe value.
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Interpolation {
+    Step,
+    #[default]
+    Linear,
+    CubicSpline
+}
+
+/*
+The `AnimationValues` enum holds the keyframe values of a channel, one per keyframe time, or
+three for `CubicSpline` channels: in-tangent, value and out-tangent.
+*/
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AnimationValues {
+    Translation(Vec<Vec3>),
+    Rotation(Vec<Quat>),
+    Scale(Vec<Vec3>)
+}
+
+/*
+The `AnimationChannel` struct animates one property of one node: `node` is its position in
+`Scene::nodes`, `times` the keyframe times in seconds, in increasing order.
+*/
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AnimationChannel {
+    pub node: usize,
+    pub interpolation: Interpolation,
+    pub times: Vec<f32>,
+    pub values: AnimationValues
+}
+
+/*
+The `Animation` struct is one glTF animation of a `Scene`. Channels targeting morph target
+weights, or nodes outside the scene, are left out.
+*/
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Animation {
+    pub name: Option<String>,
+    pub channels: Vec<AnimationChannel>
+}
+
+impl Animation {
+    /*
+    Returns the time of the last keyframe of any channel, 0 for an animation without keyframes.
+    */
+    pub fn duration(&self) -> f32 {
+        self.channels
+            .iter()
+            .filter_map(|channel| channel.times.last().copied())
+            .fold(0.0, f32::max)
+    }
+}
+
+/*
+The `AnimationWrap` enum chooses what `Scene::sample_animation` does with times outside the
+animation. `Clamp` holds the first and last keyframes of every channel before and after it.
+`Repeat` loops the animation, taking the time modulo `Animation::duration`.
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AnimationWrap {
+    #[default]
+    Clamp,
+    Repeat
+}
+
+/*
+The `NodeTransform` struct is the pose of one node relative to its parent, as translation,
+rotation and scale.
+*/
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NodeTransform {
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3
+}
+
+impl NodeTransform {
+    /*
+    Returns the transform as a matrix: scale, then rotation, then translation.
+    */
+    pub fn matrix(&self) -> Mat4 {
+        Mat4::from_scale_rotation_translation(self.scale, self.rotation, self.translation)
+    }
+}
+
+/*
+Evaluates a channel's keyframes at `time`, holding the first and last values outside them.
+`lerp` blends two values for `Linear` channels.
+*/
+fn sample_keyframes<T>(
+    times: &[f32],
+    values: &[T],
+    interpolation: Interpolation,
+    time: f32,
+    lerp: impl Fn(T, T, f32) -> T
+) -> T
+where
+    T: Copy + Add<Output = T> + Mul<f32, Output = T>
+{
+    let value = |key: usize| match interpolation {
+        Interpolation::CubicSpline => values[key * 3 + 1],
+        _ => values[key]
+    };
+    let last = times.len() - 1;
+    if time <= times[0] {
+        return value(0);
+    }
+    if time >= times[last] {
+        return value(last);
+    }
+
+    let next = times.partition_point(|&key_time| key_time <= time);
+    let key = next - 1;
+    let delta = times[next] - times[key];
+    let s = (time - times[key]) / delta;
+    match interpolation {
+        Interpolation::Step => value(key),
+        Interpolation::Linear => lerp(value(key), value(next), s),
+        Interpolation::CubicSpline => {
+            let (s2, s3) = (s * s, s * s * s);
+            let out_tangent = values[key * 3 + 2];
+            let in_tangent = values[next * 3];
+            value(key) * (2.0 * s3 - 3.0 * s2 + 1.0)
+                + out_tangent * (delta * (s3 - 2.0 * s2 + s))
+                + value(next) * (-2.0 * s3 + 3.0 * s2)
+                + in_tangent * (delta * (s3 - s2))
+        }
+    }
+}
+
+impl Scene {
+    /*
+    Poses the scene at `time` seconds into the animation at `animation_index`, returning one
+    transform per node of `Scene::nodes`, in the same order. Nodes and properties the animation
+    does not touch keep their transform from the file. `wrap` decides what happens before the
+    start and past the end. Panics when `animation_index` is out of range.
+    */
+    pub fn sample_animation(&self, animation_index: usize, time: f32, wrap: AnimationWrap) -> Vec<NodeTransform> {
+        let animation = &self.animations[animation_index];
+        let time = match wrap {
+            AnimationWrap::Clamp => time,
+            AnimationWrap::Repeat => match animation.duration() {
+                duration if duration > 0.0 => time.rem_euclid(duration),
+                _ => 0.0
+            }
+        };
+
+        let mut transforms: Vec<NodeTransform> = self
+            .nodes
+            .iter()
+            .map(|node| NodeTransform {
+                translation: node.translation,
+                rotation: node.rotation,
+                scale: node.scale
+            })
+            .collect();
+
+        for channel in &animation.channels {
+            let transform = match transforms.get_mut(channel.node) {
+                Some(transform) => transform,
+                None => continue
+            };
+            let (times, interpolation) = (&channel.times, channel.interpolation);
+            match &channel.values {
+                AnimationValues::Translation(values) => {
+                    transform.translation = sample_keyframes(times, values, interpolation, time, Vec3::lerp);
+                },
+                AnimationValues::Rotation(values) => {
+                    transform.rotation = sample_keyframes(times, values, interpolation, time, Quat::slerp).normalize();
+                },
+                AnimationValues::Scale(values) => {
+                    transform.scale = sample_keyframes(times, values, interpolation, time, Vec3::lerp);
+                }
+            }
+        }
+        transforms
+    }
+}
+
+/*
+Reads the animations of a document, keeping the channels whose node has a position in
+`positions`, indexed by glTF node index. Channels without keyframes, with keyframe times out of
+order or with a value count not matching their keyframes are skipped.
+*/
+pub(crate) fn read_animations(document: &gltf::Document, buffers: &[&[u8]], positions: &[Option<usize>]) -> Vec<Animation> {
+    use gltf::animation::util::ReadOutputs;
+
+    document
+        .animations()
+        .map(|animation| {
+            let channels = animation
+                .channels()
+                .filter_map(|channel| {
+                    let node = (*positions.get(channel.target().node().index())?)?;
+                    let reader = channel.reader(|buffer| buffers.get(buffer.index()).copied());
+                    let times: Vec<f32> = reader.read_inputs()?.collect();
+                    let interpolation = match channel.sampler().interpolation() {
+                        gltf::animation::Interpolation::Step => Interpolation::Step,
+                        gltf::animation::Interpolation::Linear => Interpolation::Linear,
+                        gltf::animation::Interpolation::CubicSpline => Interpolation::CubicSpline
+                    };
+                    let values = match reader.read_outputs()? {
+                        ReadOutputs::Translations(values) => AnimationValues::Translation(values.map(Vec3::from).collect()),
+                        ReadOutputs::Rotations(values) => AnimationValues::Rotation(values.into_f32().map(Quat::from_array).collect()),
+                        ReadOutputs::Scales(values) => AnimationValues::Scale(values.map(Vec3::from).collect()),
+                        ReadOutputs::MorphTargetWeights(_) => return None
+                    };
+
+                    let value_count = match &values {
+                        AnimationValues::Translation(values) | AnimationValues::Scale(values) => values.len(),
+                        AnimationValues::Rotation(values) => values.len()
+                    };
+                    let expected = match interpolation {
+                        Interpolation::CubicSpline => times.len() * 3,
+                        _ => times.len()
+                    };
+                    let ordered = times.windows(2).all(|pair| pair[0] < pair[1]);
+                    if times.is_empty() || !ordered || value_count != expected {
+                        return None;
+                    }
+                    Some(AnimationChannel { node, interpolation, times, values })
+                })
+                .collect();
+
+            Animation {
+                name: animation.name().map(str::to_string),
+                channels
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,394 @@
+use glam::*;
+use crate::model::loader::{Mesh, Model};
+use crate::model::{Aabb, AnimationValues, MeshError, Scene};
+
+/*
+The `CoordinateSystem` enum tells which axis points up. Both are right-handed with +X to the
+right: glTF and most engines are `YUp`, with +Z towards the viewer, while 3ds Max, Blender and
+many CAD tools are `ZUp`, with -Y towards the viewer.
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CoordinateSystem {
+    #[default]
+    YUp,
+    ZUp
+}
+
+/*
+The `Handedness` enum tells whether a coordinate system is right-handed, as glTF is, or
+left-handed, as Direct3D, Unity and Unreal conventionally are.
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Handedness {
+    #[default]
+    RightHanded,
+    LeftHanded
+}
+
+/*
+The `Axis` enum names the axis a mirror negates, see `Mesh::mirror`.
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y,
+    Z
+}
+
+impl Axis {
+    fn index(&self) -> usize {
+        match self {
+            Axis::X => 0,
+            Axis::Y => 1,
+            Axis::Z => 2
+        }
+    }
+}
+
+fn mirror(mut value: Vec3, axis: Axis) -> Vec3 {
+    value[axis.index()] = -value[axis.index()];
+    value
+}
+
+/*
+Conjugates a transform by the mirror, negating the entries that mix the mirrored axis with the
+others. Only signs change, so mirroring twice gives the original matrix back.
+*/
+fn mirror_matrix(matrix: Mat4, axis: Axis) -> Mat4 {
+    let mut signs = Vec4::ONE;
+    signs[axis.index()] = -1.0;
+    Mat4::from_cols(
+        matrix.x_axis * signs * signs.x,
+        matrix.y_axis * signs * signs.y,
+        matrix.z_axis * signs * signs.z,
+        matrix.w_axis * signs
+    )
+}
+
+/*
+Expresses a direction or point given in `from` in `to`. The conversion is a quarter turn about
+X done by swapping and negating components, so no rounding is involved and converting back
+gives the original bits.
+*/
+fn convert(value: Vec3, from: CoordinateSystem, to: CoordinateSystem) -> Vec3 {
+    match (from, to) {
+        (CoordinateSystem::YUp, CoordinateSystem::ZUp) => Vec3::new(value.x, -value.z, value.y),
+        (CoordinateSystem::ZUp, CoordinateSystem::YUp) => Vec3::new(value.x, value.z, -value.y),
+        _ => value
+    }
+}
+
+/*
+Conjugates a transform by the conversion, so it does in `to` what it did in `from`. Each column
+is picked from the original matrix, negated when the conversion says so and converted itself,
+which avoids the additions of a matrix product and keeps the conversion exact here too.
+*/
+fn convert_matrix(matrix: Mat4, from: CoordinateSystem, to: CoordinateSystem) -> Mat4 {
+    let column = |axis: Vec3| {
+        let source = convert(axis, to, from);
+        let index = (0..3).find(|&index| source[index] != 0.0).unwrap_or(0);
+        let picked = matrix.col(index) * source[index];
+        convert(picked.xyz(), from, to).extend(picked.w)
+    };
+    Mat4::from_cols(
+        column(Vec3::X),
+        column(Vec3::Y),
+        column(Vec3::Z),
+        convert(matrix.w_axis.xyz(), from, to).extend(matrix.w_axis.w)
+    )
+}
+
+impl Mesh {
+    /*
+    Converts the mesh from one up axis to the other: positions, normals and tangent directions
+    are rotated, instance transforms are conjugated so every copy stays in place, and the cached
+    bounds are rotated with the positions. Being a rotation, the conversion keeps the winding and
+    the tangent handedness, and converting back with the arguments swapped restores every value
+    exactly.
+    */
+    pub fn convert_axes(&mut self, from: CoordinateSystem, to: CoordinateSystem) {
+        if from == to {
+            return;
+        }
+
+        for vertex in &mut self.vertices {
+            vertex.position = convert(vertex.position, from, to);
+            vertex.normal = convert(vertex.normal, from, to);
+            vertex.tangent = convert(vertex.tangent.xyz(), from, to).extend(vertex.tangent.w);
+        }
+
+        for instance in &mut self.instances {
+            *instance = convert_matrix(*instance, from, to);
+        }
+
+        if let Some(bounds) = self.bounds.filter(|bounds| !bounds.is_empty()) {
+            let (min, max) = (convert(bounds.min, from, to), convert(bounds.max, from, to));
+            self.bounds = Some(Aabb::new(min.min(max), min.max(max)));
+        }
+    }
+}
+
+impl Mesh {
+    /*
+    Mirrors the mesh across the plane perpendicular to `axis` through the origin: positions,
+    normals and tangent directions have that component negated, and instance transforms are
+    conjugated by the mirror. A mirror turns every triangle inside out, so the winding is
+    reversed to keep front faces in front, and the tangent handedness is negated so the
+    bitangent is mirrored too. Only signs and index order change, so mirroring twice restores
+    the mesh exactly. The index count must be a multiple of three; otherwise the mesh is left
+    untouched and an error is returned.
+    */
+    pub fn mirror(&mut self, axis: Axis) -> Result<(), MeshError> {
+        self.flip_winding()?;
+        for vertex in &mut self.vertices {
+            vertex.position = mirror(vertex.position, axis);
+            vertex.normal = mirror(vertex.normal, axis);
+            vertex.tangent = mirror(vertex.tangent.xyz(), axis).extend(-vertex.tangent.w);
+        }
+
+        for instance in &mut self.instances {
+            *instance = mirror_matrix(*instance, axis);
+        }
+
+        if let Some(bounds) = self.bounds.filter(|bounds| !bounds.is_empty()) {
+            let (min, max) = (mirror(bounds.min, axis), mirror(bounds.max, axis));
+            self.bounds = Some(Aabb::new(min.min(max), min.max(max)));
+        }
+        Ok(())
+    }
+}
+
+impl Model {
+    /*
+    Mirrors every mesh of the model across the plane perpendicular to `axis`, see
+    `Mesh::mirror`. When a mesh has an incomplete triangle the model is left untouched.
+    */
+    pub fn mirror(&mut self, axis: Axis) -> Result<(), MeshError> {
+        if let Some(mesh) = self.meshes.iter().find(|mesh| !mesh.indices.len().is_multiple_of(3)) {
+            return Err(MeshError::IncompleteTriangles(mesh.indices.len()));
+        }
+        for mesh in &mut self.meshes {
+            mesh.mirror(axis)?;
+        }
+        Ok(())
+    }
+
+    /*
+    Converts the model from the `from` handedness to the `to` one by mirroring it across Z,
+    which turns glTF's right-handed +Z towards the viewer into the left-handed +Z forward most
+    left-handed engines use, keeping Y up and X to the right. Nothing changes when both are the
+    same; since the mirror is its own inverse, converting back with the arguments swapped
+    restores the model. Use `mirror` to negate another axis instead.
+    */
+    pub fn convert_handedness(&mut self, from: Handedness, to: Handedness) -> Result<(), MeshError> {
+        match from == to {
+            true => Ok(()),
+            false => self.mirror(Axis::Z)
+        }
+    }
+
+    /*
+    Converts every mesh of the model from one up axis to the other, see `Mesh::convert_axes`.
+    */
+    pub fn convert_axes(&mut self, from: CoordinateSystem, to: CoordinateSystem) {
+        for mesh in &mut self.meshes {
+            mesh.convert_axes(from, to);
+        }
+    }
+}
+
+impl Scene {
+    /*
+    Rewrites the transforms the scene retains: the translation, rotation and scale of every node,
+    its instance transforms and the keyframe values, tangents included, of every animation
+    channel. The meshes are left to the caller.
+    */
+    pub(crate) fn map_transforms(
+        &mut self,
+        translation: impl Fn(Vec3) -> Vec3,
+        rotation: impl Fn(Quat) -> Quat,
+        scale: impl Fn(Vec3) -> Vec3,
+        matrix: impl Fn(Mat4) -> Mat4
+    ) {
+        for node in &mut self.nodes {
+            node.translation = translation(node.translation);
+            node.rotation = rotation(node.rotation);
+            node.scale = scale(node.scale);
+            for instance in &mut node.instances {
+                *instance = matrix(*instance);
+            }
+        }
+
+        for channel in self.animations.iter_mut().flat_map(|animation| &mut animation.channels) {
+            match &mut channel.values {
+                AnimationValues::Translation(values) => values.iter_mut().for_each(|value| *value = translation(*value)),
+                AnimationValues::Rotation(values) => values.iter_mut().for_each(|value| *value = rotation(*value)),
+                AnimationValues::Scale(values) => values.iter_mut().for_each(|value| *value = scale(*value))
+            }
+        }
+    }
+
+    /*
+    Converts the scene from the `from` handedness to the `to` one by mirroring it across Z, as
+    `Model::convert_handedness` does: the meshes are mirrored, and node transforms, instances
+    and animation channels are conjugated by the mirror, so camera and light nodes keep looking
+    at what they did. Nothing changes when both are the same. When a mesh has an incomplete
+    triangle the scene is left untouched.
+    */
+    pub fn convert_handedness(&mut self, from: Handedness, to: Handedness) -> Result<(), MeshError> {
+        if from == to {
+            return Ok(());
+        }
+        self.model.mirror(Axis::Z)?;
+        let axis = Axis::Z;
+        self.map_transforms(
+            |translation| mirror(translation, axis),
+            |rotation| {
+                let axis_part = -mirror(rotation.xyz(), axis);
+                Quat::from_xyzw(axis_part.x, axis_part.y, axis_part.z, rotation.w)
+            },
+            |scale| scale,
+            |instance| mirror_matrix(instance, axis)
+        );
+        Ok(())
+    }
+}
+
+impl Scene {
+    /*
+    Converts the scene from one up axis to the other: the meshes as `Mesh::convert_axes` does,
+    and the node transforms, instances and animation channels conjugated by the same quarter
+    turn, so the hierarchy and the poses `sample_animation` gives come out in `to` as well.
+    Scales are only reordered, and converting back restores every value exactly.
+    */
+    pub fn convert_axes(&mut self, from: CoordinateSystem, to: CoordinateSystem) {
+        self.model.convert_axes(from, to);
+        self.convert_node_axes(from, to);
+    }
+
+    /*
+    Converts the node transforms and animation channels of the scene like `convert_axes`,
+    leaving the meshes, which the loader converts on its own, as they are.
+    */
+    pub(crate) fn convert_node_axes(&mut self, from: CoordinateSystem, to: CoordinateSystem) {
+        if from == to {
+            return;
+        }
+        let signs = convert(Vec3::ONE, from, to);
+        self.map_transforms(
+            |translation| convert(translation, from, to),
+            |rotation| {
+                let axis_part = convert(rotation.xyz(), from, to);
+                Quat::from_xyzw(axis_part.x, axis_part.y, axis_part.z, rotation.w)
+            },
+            |scale| convert(scale, from, to) * signs,
+            |instance| convert_matrix(instance, from, to)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::*;
+    use crate::model::{Animation, AnimationChannel, AnimationValues, AnimationWrap, CoordinateSystem, Handedness, Interpolation, Mesh, Model, Node, Scene};
+
+    fn node(translation: Vec3, rotation: Quat, parent: Option<usize>) -> Node {
+        Node {
+            name: None,
+            source_index: 0,
+            translation,
+            rotation,
+            scale: Vec3::new(1.0, 2.0, 3.0),
+            parent,
+            children: Vec::new(),
+            meshes: Vec::new(),
+            instances: Vec::new(),
+            camera: None,
+            skin: None,
+            light: None,
+            extras: None
+        }
+    }
+
+    fn scene() -> Scene {
+        let mut parent = node(Vec3::new(1.0, 2.0, 3.0), Quat::from_rotation_y(0.7), None);
+        parent.children.push(1);
+        let child = node(Vec3::new(-4.0, 0.5, 2.0), Quat::from_rotation_x(0.3), Some(0));
+        Scene {
+            name: None,
+            nodes: vec![parent, child],
+            roots: vec![0],
+            model: Model::from_mesh(Mesh::cube(1.0)),
+            animations: vec![Animation {
+                name: None,
+                channels: vec![AnimationChannel {
+                    node: 1,
+                    interpolation: Interpolation::Linear,
+                    times: vec![0.0, 1.0],
+                    values: AnimationValues::Translation(vec![Vec3::ZERO, Vec3::new(2.0, 4.0, 6.0)])
+                }]
+            }]
+        }
+    }
+
+    fn attributes(mesh: &Mesh) -> Vec<(Vec3, Vec3, Vec4)> {
+        mesh.vertices.iter().map(|vertex| (vertex.position, vertex.normal, vertex.tangent)).collect()
+    }
+
+    #[test]
+    fn convert_handedness_to_same_handedness_changes_nothing() {
+        let mut model = Model::from_mesh(Mesh::cube(1.0));
+        let original = attributes(&model.meshes[0]);
+        model.convert_handedness(Handedness::RightHanded, Handedness::RightHanded).unwrap();
+        model.convert_handedness(Handedness::RightHanded, Handedness::RightHanded).unwrap();
+        assert_eq!(attributes(&model.meshes[0]), original);
+    }
+
+    #[test]
+    fn convert_handedness_mirrors_z_and_converts_back() {
+        let mut model = Model::from_mesh(Mesh::cube(1.0));
+        let original = model.meshes[0].clone();
+        model.convert_handedness(Handedness::RightHanded, Handedness::LeftHanded).unwrap();
+        for (converted, vertex) in model.meshes[0].vertices.iter().zip(&original.vertices) {
+            assert_eq!(converted.position, vertex.position * Vec3::new(1.0, 1.0, -1.0));
+        }
+        model.convert_handedness(Handedness::LeftHanded, Handedness::RightHanded).unwrap();
+        assert_eq!(attributes(&model.meshes[0]), attributes(&original));
+        assert_eq!(model.meshes[0].indices, original.indices);
+    }
+
+    #[test]
+    fn scene_convert_handedness_mirrors_nodes_and_animations() {
+        let original = scene();
+        let mut converted = scene();
+        converted.convert_handedness(Handedness::RightHanded, Handedness::LeftHanded).unwrap();
+
+        let mirror = Mat4::from_scale(Vec3::new(1.0, 1.0, -1.0));
+        let expected = mirror * original.world_transform(1) * mirror;
+        assert!(converted.world_transform(1).abs_diff_eq(expected, 1e-5));
+
+        let pose = converted.sample_animation(0, 0.5, AnimationWrap::Clamp);
+        assert_eq!(pose[1].translation, Vec3::new(1.0, 2.0, -3.0));
+    }
+
+    #[test]
+    fn scene_convert_axes_converts_nodes_and_animations() {
+        let original = scene();
+        let mut converted = scene();
+        converted.convert_axes(CoordinateSystem::YUp, CoordinateSystem::ZUp);
+
+        let rotation = Mat4::from_rotation_x(std::f32::consts::FRAC_PI_2);
+        let expected = rotation * original.world_transform(1) * rotation.inverse();
+        assert!(converted.world_transform(1).abs_diff_eq(expected, 1e-5));
+        assert_eq!(converted.nodes[1].translation, Vec3::new(-4.0, -2.0, 0.5));
+        assert_eq!(converted.nodes[1].scale, Vec3::new(1.0, 3.0, 2.0));
+
+        let pose = converted.sample_animation(0, 1.0, AnimationWrap::Clamp);
+        assert_eq!(pose[1].translation, Vec3::new(2.0, -6.0, 4.0));
+
+        converted.convert_axes(CoordinateSystem::ZUp, CoordinateSystem::YUp);
+        assert_eq!(converted.nodes[1].translation, original.nodes[1].translation);
+        assert_eq!(converted.nodes[1].rotation, original.nodes[1].rotation);
+        assert_eq!(converted.animations, original.animations);
+    }
+}
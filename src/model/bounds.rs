@@ -0,0 +1,252 @@
+use glam::*;
+use crate::model::loader::{Mesh, Model};
+
+/*
+The `Aabb` struct is an axis-aligned bounding box described by its minimum and maximum corners.
+The empty box stores `min` at positive infinity and `max` at negative infinity, so it contains
+no point and leaves any other box unchanged when used in a union.
+*/
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3
+}
+
+impl Default for Aabb {
+    fn default() -> Self {
+        Aabb::EMPTY
+    }
+}
+
+impl Aabb {
+    pub const EMPTY: Aabb = Aabb {
+        min: Vec3::splat(f32::INFINITY),
+        max: Vec3::splat(f32::NEG_INFINITY)
+    };
+
+    pub fn new(min: Vec3, max: Vec3) -> Self {
+        Aabb { min, max }
+    }
+
+    /*
+    Builds the smallest box enclosing every point of the iterator. An empty iterator yields
+    `Aabb::EMPTY`.
+    */
+    pub fn from_points<I: IntoIterator<Item = Vec3>>(points: I) -> Self {
+        let mut aabb = Aabb::EMPTY;
+        for point in points {
+            aabb.extend(point);
+        }
+        aabb
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min.cmpgt(self.max).any()
+    }
+
+    pub fn extend(&mut self, point: Vec3) {
+        self.min = self.min.min(point);
+        self.max = self.max.max(point);
+    }
+
+    /*
+    Returns the middle point of the box, or the origin for an empty box.
+    */
+    pub fn center(&self) -> Vec3 {
+        if self.is_empty() {
+            return Vec3::ZERO;
+        }
+        (self.min + self.max) * 0.5
+    }
+
+    /*
+    Returns the full size of the box along each axis, or zero for an empty box.
+    */
+    pub fn extent(&self) -> Vec3 {
+        if self.is_empty() {
+            return Vec3::ZERO;
+        }
+        self.max - self.min
+    }
+
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max)
+        }
+    }
+
+    /*
+    Transforms the eight corners of the box and returns the box enclosing them, which stays
+    correct under rotation where transforming only `min` and `max` would not.
+    */
+    pub fn transformed(&self, matrix: Mat4) -> Aabb {
+        if self.is_empty() {
+            return Aabb::EMPTY;
+        }
+
+        Aabb::from_points((0..8).map(|corner| {
+            let point = Vec3::new(
+                if corner & 1 == 0 { self.min.x } else { self.max.x },
+                if corner & 2 == 0 { self.min.y } else { self.max.y },
+                if corner & 4 == 0 { self.min.z } else { self.max.z }
+            );
+            matrix.transform_point3(point)
+        }))
+    }
+}
+
+/*
+The `Sphere` struct is a bounding sphere described by its center and radius. The empty sphere
+has a negative radius so it can be told apart from a sphere collapsed onto a single point.
+*/
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sphere {
+    pub center: Vec3,
+    pub radius: f32
+}
+
+impl Default for Sphere {
+    fn default() -> Self {
+        Sphere::EMPTY
+    }
+}
+
+impl Sphere {
+    pub const EMPTY: Sphere = Sphere {
+        center: Vec3::ZERO,
+        radius: -1.0
+    };
+
+    pub fn new(center: Vec3, radius: f32) -> Self {
+        Sphere { center, radius }
+    }
+
+    /*
+    Computes a bounding sphere with Ritter's algorithm: an initial sphere spans the two points
+    found by walking to the farthest point twice, and is then grown to take in every point left
+    outside. The result encloses all points and is usually within a few percent of the minimal
+    sphere.
+    */
+    pub fn from_points(points: &[Vec3]) -> Self {
+        let first = match points.first() {
+            Some(first) => *first,
+            None => return Sphere::EMPTY
+        };
+
+        let farthest_from = |origin: Vec3| {
+            points
+                .iter()
+                .copied()
+                .max_by(|a, b| a.distance_squared(origin).total_cmp(&b.distance_squared(origin)))
+                .unwrap_or(origin)
+        };
+        let a = farthest_from(first);
+        let b = farthest_from(a);
+
+        let mut center = (a + b) * 0.5;
+        let mut radius = a.distance(b) * 0.5;
+
+        for point in points {
+            let distance = point.distance(center);
+            if distance > radius {
+                let new_radius = (radius + distance) * 0.5;
+                center += (*point - center) * ((new_radius - radius) / distance);
+                radius = new_radius;
+            }
+        }
+
+        Sphere { center, radius }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.radius < 0.0
+    }
+
+    /*
+    Transforms the sphere by a matrix. Under non-uniform scale the image of a sphere is an
+    ellipsoid, so the radius is multiplied by the largest axis scale to keep the result
+    conservative.
+    */
+    pub fn transformed(&self, matrix: Mat4) -> Sphere {
+        if self.is_empty() {
+            return Sphere::EMPTY;
+        }
+
+        let max_scale = matrix
+            .x_axis
+            .xyz()
+            .length()
+            .max(matrix.y_axis.xyz().length())
+            .max(matrix.z_axis.xyz().length());
+
+        Sphere {
+            center: matrix.transform_point3(self.center),
+            radius: self.radius * max_scale
+        }
+    }
+}
+
+impl Mesh {
+    /*
+    Returns the bounding sphere of the mesh's vertex positions. Its `center` and `radius` are
+    kept together in a `Sphere` rather than returned as a bare pair, so a mesh without vertices
+    can give `Sphere::EMPTY` and the result can go straight to `Sphere::transformed`.
+    */
+    pub fn bounding_sphere(&self) -> Sphere {
+        let positions: Vec<Vec3> = self.vertices.iter().map(|vertex| vertex.position).collect();
+        Sphere::from_points(&positions)
+    }
+
+    /*
+    Returns the bounding box of the mesh. The cached `bounds` are used when present, which is
+    always the case for freshly loaded meshes; otherwise the vertex positions are scanned.
+    */
+    pub fn aabb(&self) -> Aabb {
+        if let Some(bounds) = self.bounds {
+            return bounds;
+        }
+        Aabb::from_points(self.vertices.iter().map(|vertex| vertex.position))
+    }
+
+    /*
+    Returns the cached bounding box, if the mesh still has one.
+    */
+    pub fn cached_bounds(&self) -> Option<Aabb> {
+        self.bounds
+    }
+
+    /*
+    Drops the cached bounding box, so `aabb` scans the positions again. Methods of `Mesh` that
+    change the vertices do this themselves; code editing `vertices` directly must call it.
+    */
+    pub fn invalidate_bounds(&mut self) {
+        self.bounds = None;
+    }
+}
+
+impl Model {
+    /*
+    Returns the union of the bounding boxes of every mesh in the model.
+    */
+    pub fn aabb(&self) -> Aabb {
+        self.meshes
+            .iter()
+            .fold(Aabb::EMPTY, |aabb, mesh| aabb.union(&mesh.aabb()))
+    }
+
+    /*
+    Returns a bounding sphere enclosing the vertices of every mesh in the model. The points are
+    gathered first so the sphere fits the whole model rather than a union of per-mesh spheres.
+    */
+    pub fn bounding_sphere(&self) -> Sphere {
+        let positions: Vec<Vec3> = self
+            .meshes
+            .iter()
+            .flat_map(|mesh| mesh.vertices.iter().map(|vertex| vertex.position))
+            .collect();
+        Sphere::from_points(&positions)
+    }
+}
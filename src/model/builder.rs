@@ -0,0 +1,98 @@
+use glam::*;
+use crate::model::loader::{Mesh, Vertex};
+use crate::model::{Indices, MaterialHandle, MeshError};
+
+/*
+The `MeshBuilder` struct assembles a mesh one vertex and one triangle at a time, for geometry
+generated in code rather than loaded from a file. Vertices are numbered in the order they are
+added, starting from 0, and triangles refer to them by those numbers.
+*/
+#[derive(Clone, Debug, Default)]
+pub struct MeshBuilder {
+    name: Option<String>,
+    vertices: Vec<Vertex>,
+    indices: Vec<u32>,
+    material: MaterialHandle
+}
+
+impl MeshBuilder {
+    pub fn new() -> Self {
+        MeshBuilder::default()
+    }
+
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn with_material(mut self, material: MaterialHandle) -> Self {
+        self.material = material;
+        self
+    }
+
+    /*
+    Adds a vertex without a tangent and returns its index.
+    */
+    pub fn add_vertex(&mut self, position: Vec3, normal: Vec3, tex_coord: Vec2) -> u32 {
+        self.vertices.push(Vertex {
+            position,
+            normal,
+            tex_coord,
+            ..Default::default()
+        });
+        self.vertices.len() as u32 - 1
+    }
+
+    /*
+    Adds a triangle between three vertices already added, wound counter-clockwise when seen
+    from the front. Fails without adding it when an index does not refer to an added vertex.
+    */
+    pub fn add_triangle(&mut self, a: u32, b: u32, c: u32) -> Result<(), MeshError> {
+        self.check_indices(&[a, b, c])?;
+        self.indices.extend_from_slice(&[a, b, c]);
+        Ok(())
+    }
+
+    /*
+    Adds two triangles covering the quad `a b c d`, given counter-clockwise when seen from the
+    front. Fails without adding either when an index does not refer to an added vertex.
+    */
+    pub fn add_quad(&mut self, a: u32, b: u32, c: u32, d: u32) -> Result<(), MeshError> {
+        self.check_indices(&[a, b, c, d])?;
+        self.indices.extend_from_slice(&[a, b, c, a, c, d]);
+        Ok(())
+    }
+
+    fn check_indices(&self, indices: &[u32]) -> Result<(), MeshError> {
+        match indices.iter().find(|&&index| index as usize >= self.vertices.len()) {
+            Some(&index) => Err(MeshError::IndexOutOfRange { index, vertex_count: self.vertices.len() }),
+            None => Ok(())
+        }
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.vertices.len()
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    /*
+    Finishes the mesh, with its bounds computed and 16-bit indices when the vertex count
+    allows them.
+    */
+    pub fn build(self) -> Mesh {
+        let mut mesh = Mesh {
+            name: self.name,
+            indices: Indices::for_vertices(self.indices, self.vertices.len()),
+            vertices: self.vertices,
+            material: self.material,
+            bounds: None,
+            instances: Vec::new(),
+            extras: None
+        };
+        mesh.bounds = Some(mesh.aabb());
+        mesh
+    }
+}
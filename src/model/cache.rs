@@ -0,0 +1,466 @@
+use glam::*;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use crate::model::loader::{ChannelPacking, Material, Mesh, Model, TextureChannel, Transmission, Vertex, Volume};
+use crate::model::{Aabb, ExportError, Indices, LoadError, MaterialHandle, Texture, TextureFormat, TextureSource, TextureTransform};
+
+/*
+First bytes of every cache file, followed by the format version. The version is bumped whenever
+the layout changes, so caches written by another version are refused instead of misread.
+*/
+const CACHE_MAGIC: &[u8; 8] = b"MOTLEYC\0";
+pub const CACHE_VERSION: u32 = 8;
+
+/*
+Size of a vertex in a cache file: the fifteen floats of position, normal, texture coordinates,
+tangent and barycentric coordinates, in the order `Vertex` lays them out in memory.
+*/
+const CACHED_VERTEX_SIZE: usize = 60;
+
+/*
+Appends the fields of a model to a cache file, every number little-endian. Variable-length data
+is preceded by its length, and optional data by a byte telling whether it is present.
+*/
+struct CacheWriter<W: Write> {
+    writer: W
+}
+
+impl<W: Write> CacheWriter<W> {
+    fn bytes(&mut self, bytes: &[u8]) -> Result<(), ExportError> {
+        self.writer.write_all(bytes)?;
+        Ok(())
+    }
+
+    fn u32(&mut self, value: u32) -> Result<(), ExportError> {
+        self.bytes(&value.to_le_bytes())
+    }
+
+    fn u64(&mut self, value: usize) -> Result<(), ExportError> {
+        self.bytes(&(value as u64).to_le_bytes())
+    }
+
+    fn flag(&mut self, present: bool) -> Result<(), ExportError> {
+        self.bytes(&[present as u8])
+    }
+
+    fn floats(&mut self, values: &[f32]) -> Result<(), ExportError> {
+        for value in values {
+            self.bytes(&value.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    fn blob(&mut self, bytes: &[u8]) -> Result<(), ExportError> {
+        self.u64(bytes.len())?;
+        self.bytes(bytes)
+    }
+
+    fn string(&mut self, value: Option<&str>) -> Result<(), ExportError> {
+        self.flag(value.is_some())?;
+        match value {
+            Some(value) => self.blob(value.as_bytes()),
+            None => Ok(())
+        }
+    }
+
+    fn texture(&mut self, texture: Option<&Texture>) -> Result<(), ExportError> {
+        self.flag(texture.is_some())?;
+        if let Some(texture) = texture {
+            self.flag(texture.is_resolved())?;
+            self.u32(texture.width())?;
+            self.u32(texture.height())?;
+            self.u32(texture.channel_count() as u32)?;
+            self.bytes(&[texture.format() as u8])?;
+            let transform = texture.transform();
+            self.floats(&[transform.offset.x, transform.offset.y, transform.rotation, transform.scale.x, transform.scale.y])?;
+            self.u32(texture.tex_coord())?;
+            match texture.source() {
+                Some(TextureSource::File(path)) => {
+                    self.flag(true)?;
+                    self.blob(path.to_string_lossy().as_bytes())?;
+                },
+                Some(TextureSource::Embedded(bytes)) => {
+                    self.flag(false)?;
+                    self.blob(bytes)?;
+                },
+                None => self.blob(texture.pixels())?
+            }
+        }
+        Ok(())
+    }
+
+    fn material(&mut self, material: &Material) -> Result<(), ExportError> {
+        self.string(material.name.as_deref())?;
+        self.floats(&material.base_color.to_array())?;
+        self.texture(material.base_color_texture.as_ref())?;
+        self.floats(&[material.metallic_factor, material.roughness_factor, material.occlusion_strength])?;
+        self.texture(material.metallic_roughness_texture.as_ref())?;
+        self.texture(material.occlusion_texture.as_ref())?;
+        self.floats(&[material.normal_scale])?;
+        self.texture(material.normal_texture.as_ref())?;
+        self.floats(&material.emissive_factor.to_array())?;
+        self.texture(material.emissive_texture.as_ref())?;
+        let packing = material.channel_packing;
+        self.bytes(&[packing.metallic, packing.roughness, packing.occlusion].map(|channel| channel.index() as u8))?;
+        self.flag(packing.occlusion_shared)?;
+
+        self.flag(material.transmission.is_some())?;
+        if let Some(transmission) = &material.transmission {
+            self.floats(&[transmission.factor])?;
+            self.texture(transmission.texture.as_ref())?;
+        }
+        self.floats(&[material.ior])?;
+        self.flag(material.volume.is_some())?;
+        if let Some(volume) = &material.volume {
+            self.floats(&[volume.thickness_factor])?;
+            self.texture(volume.thickness_texture.as_ref())?;
+            self.floats(&volume.attenuation_color.to_array())?;
+            self.floats(&[volume.attenuation_distance])?;
+        }
+
+        self.floats(&[material.clearcoat_factor, material.clearcoat_roughness_factor])?;
+        self.texture(material.clearcoat_texture.as_ref())?;
+        self.texture(material.clearcoat_roughness_texture.as_ref())?;
+        self.texture(material.clearcoat_normal_texture.as_ref())?;
+        self.flag(material.unlit)?;
+        self.string(material.extras.as_ref().map(|extras| extras.to_string()).as_deref())
+    }
+
+    fn mesh(&mut self, mesh: &Mesh) -> Result<(), ExportError> {
+        self.string(mesh.name.as_deref())?;
+        self.u64(mesh.material.index())?;
+        self.flag(mesh.bounds.is_some())?;
+        if let Some(bounds) = mesh.bounds {
+            self.floats(&bounds.min.to_array())?;
+            self.floats(&bounds.max.to_array())?;
+        }
+        self.u64(mesh.instances.len())?;
+        for instance in &mesh.instances {
+            self.floats(&instance.to_cols_array())?;
+        }
+        self.string(mesh.extras.as_ref().map(|extras| extras.to_string()).as_deref())?;
+
+        let mut vertices = Vec::with_capacity(mesh.vertices.len() * CACHED_VERTEX_SIZE);
+        for Vertex { position, normal, tex_coord, tangent, barycentric, .. } in &mesh.vertices {
+            let values = [
+                position.x, position.y, position.z,
+                normal.x, normal.y, normal.z,
+                tex_coord.x, tex_coord.y,
+                tangent.x, tangent.y, tangent.z, tangent.w,
+                barycentric.x, barycentric.y, barycentric.z
+            ];
+            for value in values {
+                vertices.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        self.u64(mesh.vertices.len())?;
+        self.bytes(&vertices)?;
+
+        self.u32(mesh.indices.element_size() as u32)?;
+        self.u64(mesh.indices.len())?;
+        self.bytes(&mesh.indices.to_le_bytes())
+    }
+}
+
+/*
+Reads back what `CacheWriter` wrote. Every read checks the remaining length first, so a
+truncated or corrupted file fails with an error instead of a panic or a huge allocation.
+*/
+struct CacheReader<'a> {
+    bytes: &'a [u8]
+}
+
+fn truncated() -> LoadError {
+    LoadError::InvalidData("Cache file is truncated".to_string())
+}
+
+impl<'a> CacheReader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], LoadError> {
+        if length > self.bytes.len() {
+            return Err(truncated());
+        }
+        let (taken, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], LoadError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn u32(&mut self) -> Result<u32, LoadError> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    fn u64(&mut self) -> Result<usize, LoadError> {
+        usize::try_from(u64::from_le_bytes(self.array()?)).map_err(|_| truncated())
+    }
+
+    fn flag(&mut self) -> Result<bool, LoadError> {
+        match self.array::<1>()? {
+            [0] => Ok(false),
+            [1] => Ok(true),
+            _ => Err(LoadError::InvalidData("Cache file holds an invalid flag".to_string()))
+        }
+    }
+
+    fn floats<const N: usize>(&mut self) -> Result<[f32; N], LoadError> {
+        let bytes = self.take(N * 4)?;
+        Ok(std::array::from_fn(|i| f32::from_le_bytes(bytes[i * 4..i * 4 + 4].try_into().unwrap())))
+    }
+
+    fn float(&mut self) -> Result<f32, LoadError> {
+        Ok(self.floats::<1>()?[0])
+    }
+
+    fn channel(&mut self) -> Result<TextureChannel, LoadError> {
+        match self.array::<1>()? {
+            [0] => Ok(TextureChannel::R),
+            [1] => Ok(TextureChannel::G),
+            [2] => Ok(TextureChannel::B),
+            [3] => Ok(TextureChannel::A),
+            _ => Err(LoadError::InvalidData("Cache file holds an invalid texture channel".to_string()))
+        }
+    }
+
+    fn texture_format(&mut self) -> Result<TextureFormat, LoadError> {
+        match self.array::<1>()? {
+            [0] => Ok(TextureFormat::Rgba8),
+            [1] => Ok(TextureFormat::Rgba16),
+            [2] => Ok(TextureFormat::Rgba32F),
+            _ => Err(LoadError::InvalidData("Cache file holds an invalid texture format".to_string()))
+        }
+    }
+
+    /*
+    Takes `count` elements of `size` bytes, failing before anything is allocated when the file
+    is too short to hold them.
+    */
+    fn elements(&mut self, count: usize, size: usize) -> Result<&'a [u8], LoadError> {
+        let length = count.checked_mul(size).ok_or_else(truncated)?;
+        self.take(length)
+    }
+
+    fn blob(&mut self) -> Result<&'a [u8], LoadError> {
+        let length = self.u64()?;
+        self.take(length)
+    }
+
+    fn string(&mut self) -> Result<Option<String>, LoadError> {
+        if !self.flag()? {
+            return Ok(None);
+        }
+        let bytes = self.blob()?;
+        String::from_utf8(bytes.to_vec())
+            .map(Some)
+            .map_err(|_| LoadError::InvalidData("Cache file holds a name that is not UTF-8".to_string()))
+    }
+
+    fn extras(&mut self) -> Result<Option<serde_json::Value>, LoadError> {
+        self.string()?
+            .map(|json| serde_json::from_str(&json))
+            .transpose()
+            .map_err(|error| LoadError::InvalidData(format!("Cache file holds invalid extras: {}", error)))
+    }
+
+    fn texture(&mut self) -> Result<Option<Texture>, LoadError> {
+        if !self.flag()? {
+            return Ok(None);
+        }
+        let resolved = self.flag()?;
+        let (width, height, channel_count) = (self.u32()?, self.u32()?, self.u32()? as usize);
+        let format = self.texture_format()?;
+        let [offset_x, offset_y, rotation, scale_x, scale_y] = self.floats()?;
+        let transform = TextureTransform {
+            offset: Vec2::new(offset_x, offset_y),
+            rotation,
+            scale: Vec2::new(scale_x, scale_y)
+        };
+        let tex_coord = self.u32()?;
+        if !resolved {
+            let source = match self.flag()? {
+                true => {
+                    let path = String::from_utf8(self.blob()?.to_vec())
+                        .map_err(|_| LoadError::InvalidData("Cache file holds a texture path that is not UTF-8".to_string()))?;
+                    TextureSource::File(PathBuf::from(path))
+                },
+                false => TextureSource::Embedded(Arc::from(self.blob()?))
+            };
+            return Ok(Some(Texture::deferred_with_size(source, width, height, channel_count, format).with_transform(transform).with_tex_coord(tex_coord)));
+        }
+
+        let pixels = self.blob()?.to_vec();
+        Texture::from_pixels(pixels, width, height, channel_count, format)
+            .map(|texture| Some(texture.with_transform(transform).with_tex_coord(tex_coord)))
+            .map_err(|reason| LoadError::InvalidData(format!("Cache file holds an invalid texture: {}", reason)))
+    }
+
+    fn material(&mut self) -> Result<Material, LoadError> {
+        let name = self.string()?;
+        let base_color = Vec4::from(self.floats()?);
+        let base_color_texture = self.texture()?;
+        let [metallic_factor, roughness_factor, occlusion_strength] = self.floats()?;
+        let metallic_roughness_texture = self.texture()?;
+        let occlusion_texture = self.texture()?;
+        let normal_scale = self.float()?;
+        let normal_texture = self.texture()?;
+        let emissive_factor = Vec3::from(self.floats()?);
+        let emissive_texture = self.texture()?;
+        let channel_packing = ChannelPacking {
+            metallic: self.channel()?,
+            roughness: self.channel()?,
+            occlusion: self.channel()?,
+            occlusion_shared: self.flag()?
+        };
+        let transmission = match self.flag()? {
+            true => Some(Transmission { factor: self.float()?, texture: self.texture()? }),
+            false => None
+        };
+        let ior = self.float()?;
+        let volume = match self.flag()? {
+            true => Some(Volume {
+                thickness_factor: self.float()?,
+                thickness_texture: self.texture()?,
+                attenuation_color: Vec3::from(self.floats()?),
+                attenuation_distance: self.float()?
+            }),
+            false => None
+        };
+        let [clearcoat_factor, clearcoat_roughness_factor] = self.floats()?;
+
+        Ok(Material {
+            name,
+            base_color,
+            base_color_texture,
+            metallic_factor,
+            roughness_factor,
+            metallic_roughness_texture,
+            normal_texture,
+            normal_scale,
+            occlusion_texture,
+            occlusion_strength,
+            emissive_factor,
+            emissive_texture,
+            channel_packing,
+            transmission,
+            ior,
+            volume,
+            clearcoat_factor,
+            clearcoat_texture: self.texture()?,
+            clearcoat_roughness_factor,
+            clearcoat_roughness_texture: self.texture()?,
+            clearcoat_normal_texture: self.texture()?,
+            unlit: self.flag()?,
+            extras: self.extras()?
+        })
+    }
+
+    fn mesh(&mut self) -> Result<Mesh, LoadError> {
+        let name = self.string()?;
+        let material = u32::try_from(self.u64()?)
+            .map(MaterialHandle)
+            .map_err(|_| LoadError::InvalidData("Cache file holds an invalid material handle".to_string()))?;
+        let bounds = match self.flag()? {
+            true => Some(Aabb { min: Vec3::from(self.floats()?), max: Vec3::from(self.floats()?) }),
+            false => None
+        };
+        let instance_count = self.u64()?;
+        let instances = self
+            .elements(instance_count, 64)?
+            .chunks_exact(64)
+            .map(|matrix| Mat4::from_cols_array(&std::array::from_fn(|i| f32::from_le_bytes(matrix[i * 4..i * 4 + 4].try_into().unwrap()))))
+            .collect();
+        let extras = self.extras()?;
+
+        let vertex_count = self.u64()?;
+        let vertices = self
+            .elements(vertex_count, CACHED_VERTEX_SIZE)?
+            .chunks_exact(CACHED_VERTEX_SIZE)
+            .map(|vertex| {
+                let value = |i: usize| f32::from_le_bytes(vertex[i * 4..i * 4 + 4].try_into().unwrap());
+                Vertex {
+                    position: Vec3::new(value(0), value(1), value(2)),
+                    normal: Vec3::new(value(3), value(4), value(5)),
+                    tex_coord: Vec2::new(value(6), value(7)),
+                    tangent: Vec4::new(value(8), value(9), value(10), value(11)),
+                    barycentric: Vec3::new(value(12), value(13), value(14)),
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        let index_size = self.u32()?;
+        let index_count = self.u64()?;
+        let indices = match index_size {
+            2 => Indices::U16(
+                self.elements(index_count, 2)?
+                    .chunks_exact(2)
+                    .map(|index| u16::from_le_bytes([index[0], index[1]]))
+                    .collect()
+            ),
+            4 => Indices::U32(
+                self.elements(index_count, 4)?
+                    .chunks_exact(4)
+                    .map(|index| u32::from_le_bytes([index[0], index[1], index[2], index[3]]))
+                    .collect()
+            ),
+            _ => return Err(LoadError::InvalidData(format!("Cache file holds {}-byte indices", index_size)))
+        };
+
+        Ok(Mesh { name, vertices, indices, material, bounds, instances, extras })
+    }
+}
+
+impl Model {
+    /*
+    Writes the model to a cache file that `Model::load_cache` reads back far faster than the
+    source file can be parsed: vertices, indices and texture pixels are stored as raw
+    little-endian arrays, with no decoding, validation or texture decompression left to do.
+    Deferred textures are stored as their source and come back deferred. The format is private
+    to this crate and tied to `CACHE_VERSION`, so caches should be regenerated from the source
+    asset rather than shipped as the only copy.
+    */
+    pub fn save_cache(&self, path: impl AsRef<Path>) -> Result<(), ExportError> {
+        let mut cache = CacheWriter { writer: BufWriter::new(File::create(path)?) };
+        cache.bytes(CACHE_MAGIC)?;
+        cache.u32(CACHE_VERSION)?;
+        cache.u64(self.materials.len())?;
+        for material in &self.materials {
+            cache.material(material)?;
+        }
+        cache.u64(self.meshes.len())?;
+        for mesh in &self.meshes {
+            cache.mesh(mesh)?;
+        }
+        cache.writer.flush()?;
+        Ok(())
+    }
+
+    /*
+    Reads a model written by `Model::save_cache`. Files that do not start with the cache magic,
+    or are truncated or corrupted, fail with `LoadError::InvalidData`, and caches written by
+    another version of the format fail with `LoadError::CacheVersionMismatch`.
+    */
+    pub fn load_cache(path: impl AsRef<Path>) -> Result<Model, LoadError> {
+        let bytes = std::fs::read(path)?;
+        let mut cache = CacheReader { bytes: &bytes };
+        if cache.take(CACHE_MAGIC.len()).ok() != Some(CACHE_MAGIC.as_slice()) {
+            return Err(LoadError::InvalidData("File is not a model cache".to_string()));
+        }
+        let version = cache.u32()?;
+        if version != CACHE_VERSION {
+            return Err(LoadError::CacheVersionMismatch { found: version, expected: CACHE_VERSION });
+        }
+
+        let material_count = cache.u64()?;
+        let materials = (0..material_count).map(|_| cache.material()).collect::<Result<Vec<_>, _>>()?;
+        let mesh_count = cache.u64()?;
+        let meshes = (0..mesh_count).map(|_| cache.mesh()).collect::<Result<Vec<_>, _>>()?;
+        if !cache.bytes.is_empty() {
+            return Err(LoadError::InvalidData("Cache file has trailing data".to_string()));
+        }
+        Ok(Model { meshes, materials })
+    }
+}
@@ -0,0 +1,314 @@
+use std::fmt;
+use std::path::Path;
+use gltf::accessor::{DataType, Dimensions};
+use gltf::Semantic;
+use crate::model::loader::{is_relative_uri, parse_document, resolve_uri};
+use crate::model::Severity;
+
+/*
+The `GltfValidationMessage` struct records one violation of the glTF specification found by
+`validate_gltf`. `path` locates the offending value in the JSON document, in the form the glTF
+crate uses, such as `accessors[3].bufferView` or `meshes[0].primitives[1].attributes["NORMAL"]`,
+and is empty for problems with the file as a whole.
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GltfValidationMessage {
+    pub severity: Severity,
+    pub path: String,
+    pub message: String
+}
+
+impl fmt::Display for GltfValidationMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.path.is_empty() {
+            true => write!(f, "{:?}: {}", self.severity, self.message),
+            false => write!(f, "{:?}: {}: {}", self.severity, self.path, self.message)
+        }
+    }
+}
+
+/*
+Collects the messages of a validation pass.
+*/
+struct Messages(Vec<GltfValidationMessage>);
+
+impl Messages {
+    fn error(&mut self, path: impl Into<String>, message: impl Into<String>) {
+        self.push(Severity::Error, path, message);
+    }
+
+    fn warning(&mut self, path: impl Into<String>, message: impl Into<String>) {
+        self.push(Severity::Warning, path, message);
+    }
+
+    fn push(&mut self, severity: Severity, path: impl Into<String>, message: impl Into<String>) {
+        self.0.push(GltfValidationMessage { severity, path: path.into(), message: message.into() });
+    }
+}
+
+/*
+Checks a GLTF or GLB file against the glTF specification without building a model, for
+vetting assets before they ship. This looks at the file as written, where `Model::validate`
+looks at the geometry the loader produced.
+
+The document is first checked for structure the way the loader checks it: required properties,
+indices pointing at existing objects and known enum values. When that fails, those messages are
+returned alone, since the further checks need a well-formed document. Otherwise buffers, buffer
+views and accessors are checked to stay in bounds of each other and of the data actually
+present, accessors for the `normalized` flag and their `min` and `max` arrays, mesh attributes
+and indices for the component types the specification allows, with `KHR_mesh_quantization`
+widening them when the file uses it, and materials for factors outside their ranges.
+
+Required extensions the loader does not support are reported as warnings, and a primitive
+without positions, which the specification allows but nothing can draw, as a warning too. An
+empty list means no violation was found.
+*/
+pub fn validate_gltf(path: impl AsRef<Path>) -> Vec<GltfValidationMessage> {
+    let path = path.as_ref();
+    let mut messages = Messages(Vec::new());
+
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(error) => {
+            messages.error("", format!("File could not be read: {}", error));
+            return messages.0;
+        }
+    };
+
+    let (document, blob) = match parse_document(&bytes) {
+        Ok(parsed) => parsed,
+        Err(gltf::Error::Validation(errors)) => {
+            for (json_path, error) in errors {
+                match error {
+                    gltf::json::validation::Error::Unsupported => messages.warning(json_path.0, "Required extension is not supported"),
+                    error => messages.error(json_path.0, structure_message(error))
+                }
+            }
+            return messages.0;
+        },
+        Err(error) => {
+            messages.error("", format!("Document could not be parsed: {}", error));
+            return messages.0;
+        }
+    };
+
+    check_buffers(&document, blob.as_deref(), path, &mut messages);
+    check_accessors(&document, &mut messages);
+    check_meshes(&document, &mut messages);
+    check_materials(&document, &mut messages);
+    messages.0
+}
+
+fn structure_message(error: gltf::json::validation::Error) -> &'static str {
+    match error {
+        gltf::json::validation::Error::IndexOutOfBounds => "Index refers to an object that does not exist",
+        gltf::json::validation::Error::Invalid => "Value is not allowed here",
+        gltf::json::validation::Error::Missing => "Required property is missing",
+        gltf::json::validation::Error::Oversize => "Size or offset exceeds what this platform can address",
+        gltf::json::validation::Error::Unsupported => "Required extension is not supported"
+    }
+}
+
+/*
+Checks that every buffer has at least its declared length of data, looking up the sizes of
+external files without reading them, and that every buffer view fits in its buffer.
+*/
+fn check_buffers(document: &gltf::Document, blob: Option<&[u8]>, file_path: &Path, messages: &mut Messages) {
+    for buffer in document.buffers() {
+        let path = format!("buffers[{}]", buffer.index());
+        let length = match buffer.source() {
+            gltf::buffer::Source::Bin => match blob {
+                Some(blob) => blob.len(),
+                None => {
+                    messages.error(path, "Buffer refers to a missing binary chunk");
+                    continue;
+                }
+            },
+            gltf::buffer::Source::Uri(uri) if is_relative_uri(uri) => {
+                let buffer_path = resolve_uri(uri, file_path);
+                match std::fs::metadata(&buffer_path) {
+                    Ok(metadata) => metadata.len() as usize,
+                    Err(error) => {
+                        messages.error(format!("{}.uri", path), format!("File {} could not be read: {}", buffer_path.display(), error));
+                        continue;
+                    }
+                }
+            },
+            gltf::buffer::Source::Uri(_) => match gltf::buffer::Data::from_source(buffer.source(), file_path.parent()) {
+                Ok(data) => data.len(),
+                Err(error) => {
+                    messages.error(format!("{}.uri", path), format!("Data could not be read: {}", error));
+                    continue;
+                }
+            }
+        };
+        if length < buffer.length() {
+            messages.error(format!("{}.byteLength", path), format!("Buffer declares {} bytes but holds {}", buffer.length(), length));
+        }
+    }
+
+    for view in document.views() {
+        if view.offset() + view.length() > view.buffer().length() {
+            messages.error(
+                format!("bufferViews[{}]", view.index()),
+                format!("Buffer view ends at byte {}, past the {} bytes of buffer {}", view.offset() + view.length(), view.buffer().length(), view.buffer().index())
+            );
+        }
+    }
+}
+
+/*
+Checks that every accessor reads within its buffer view, at offsets aligned to its component
+size, that only integer components below 32 bits are normalized, and that `min` and `max` hold
+one value per component.
+*/
+fn check_accessors(document: &gltf::Document, messages: &mut Messages) {
+    for accessor in document.accessors() {
+        let path = format!("accessors[{}]", accessor.index());
+
+        if accessor.normalized() && matches!(accessor.data_type(), DataType::F32 | DataType::U32) {
+            messages.error(format!("{}.normalized", path), format!("Accessor of {:?} components cannot be normalized", accessor.data_type()));
+        }
+
+        let components = accessor.dimensions().multiplicity();
+        for (name, bound) in [("min", accessor.min()), ("max", accessor.max())] {
+            let length = bound.as_ref().map(|bound| bound.as_array().map_or(0, Vec::len));
+            if length.is_some_and(|length| length != components) {
+                messages.error(format!("{}.{}", path, name), format!("Array holds {} values for {} components", length.unwrap_or(0), components));
+            }
+        }
+
+        let view = match accessor.view() {
+            Some(view) => view,
+            None => continue
+        };
+        let component_size = accessor.data_type().size();
+        if (view.offset() + accessor.offset()) % component_size != 0 {
+            messages.error(format!("{}.byteOffset", path), format!("Accessor does not start at a multiple of its {} byte component size", component_size));
+        }
+        let stride = view.stride().unwrap_or(accessor.size());
+        if stride < accessor.size() {
+            messages.error(format!("bufferViews[{}].byteStride", view.index()), format!("Stride of {} bytes is smaller than the {} byte elements of accessor {}", stride, accessor.size(), accessor.index()));
+        }
+        if accessor.count() > 0 {
+            let end = accessor.offset() + stride * (accessor.count() - 1) + accessor.size();
+            if end > view.length() {
+                messages.error(path, format!("Accessor reads {} bytes of buffer view {}, which has {}", end, view.index(), view.length()));
+            }
+        }
+    }
+}
+
+/*
+The element types an attribute may have, and the component types it may use, each with whether
+it is normalized.
+*/
+struct AttributeFormat {
+    dimensions: &'static [Dimensions],
+    components: Vec<(DataType, bool)>
+}
+
+/*
+Gives the format the specification allows for an attribute. Custom attributes, whose names start with an underscore, are
+free to use anything; the glTF crate only keeps them when its `extras` feature is enabled.
+*/
+fn attribute_format(semantic: &Semantic, quantized: bool) -> Option<AttributeFormat> {
+    let float = (DataType::F32, false);
+    let unsigned = [(DataType::U8, true), (DataType::U16, true)];
+    let signed = [(DataType::I8, true), (DataType::I16, true)];
+    let any_integer = [DataType::I8, DataType::U8, DataType::I16, DataType::U16]
+        .into_iter()
+        .flat_map(|data_type| [(data_type, false), (data_type, true)]);
+
+    let (dimensions, mut components): (&'static [Dimensions], Vec<(DataType, bool)>) = match semantic {
+        Semantic::Positions => (&[Dimensions::Vec3], vec![float]),
+        Semantic::Normals => (&[Dimensions::Vec3], vec![float]),
+        Semantic::Tangents => (&[Dimensions::Vec4], vec![float]),
+        Semantic::TexCoords(_) => (&[Dimensions::Vec2], [float].into_iter().chain(unsigned).collect()),
+        Semantic::Colors(_) => (&[Dimensions::Vec3, Dimensions::Vec4], [float].into_iter().chain(unsigned).collect()),
+        Semantic::Joints(_) => (&[Dimensions::Vec4], vec![(DataType::U8, false), (DataType::U16, false)]),
+        Semantic::Weights(_) => (&[Dimensions::Vec4], [float].into_iter().chain(unsigned).collect()),
+        #[allow(unreachable_patterns)]
+        _ => return None
+    };
+    if quantized {
+        match semantic {
+            Semantic::Positions | Semantic::TexCoords(_) => components.extend(any_integer),
+            Semantic::Normals | Semantic::Tangents => components.extend(signed),
+            _ => {}
+        }
+    }
+    Some(AttributeFormat { dimensions, components })
+}
+
+/*
+Checks every primitive: attribute and index accessors for the types the specification allows
+and every attribute for the vertex count of the positions. The `min` and `max` required of
+positions are checked with the structure.
+*/
+fn check_meshes(document: &gltf::Document, messages: &mut Messages) {
+    let quantized = document.extensions_used().any(|name| name == "KHR_mesh_quantization");
+
+    for mesh in document.meshes() {
+        for primitive in mesh.primitives() {
+            let path = format!("meshes[{}].primitives[{}]", mesh.index(), primitive.index());
+            let positions = primitive.get(&Semantic::Positions);
+            if positions.is_none() {
+                messages.warning(format!("{}.attributes", path), "Primitive has no POSITION attribute and cannot be drawn");
+            }
+
+            for (semantic, accessor) in primitive.attributes() {
+                let attribute_path = format!("{}.attributes[\"{}\"]", path, semantic.to_string());
+                if let Some(format) = attribute_format(&semantic, quantized) {
+                    if !format.dimensions.contains(&accessor.dimensions()) {
+                        messages.error(attribute_path.clone(), format!("Accessor {} has type {:?}, which {} does not allow", accessor.index(), accessor.dimensions(), semantic.to_string()));
+                    }
+                    if !format.components.contains(&(accessor.data_type(), accessor.normalized())) {
+                        let normalized = if accessor.normalized() { "normalized " } else { "" };
+                        messages.error(attribute_path.clone(), format!("Accessor {} has {}{:?} components, which {} does not allow", accessor.index(), normalized, accessor.data_type(), semantic.to_string()));
+                    }
+                }
+                if let Some(positions) = positions.as_ref().filter(|positions| positions.count() != accessor.count()) {
+                    messages.error(attribute_path.clone(), format!("Accessor {} has {} elements where POSITION has {}", accessor.index(), accessor.count(), positions.count()));
+                }
+            }
+
+            if let Some(indices) = primitive.indices() {
+                let valid = indices.dimensions() == Dimensions::Scalar
+                    && !indices.normalized()
+                    && matches!(indices.data_type(), DataType::U8 | DataType::U16 | DataType::U32);
+                if !valid {
+                    messages.error(format!("{}.indices", path), format!("Accessor {} is not made of unsigned integer scalars", indices.index()));
+                }
+            }
+        }
+    }
+}
+
+/*
+Checks that the factors of every material lie in the ranges the specification gives them.
+*/
+fn check_materials(document: &gltf::Document, messages: &mut Messages) {
+    for material in document.materials() {
+        let path = match material.index() {
+            Some(index) => format!("materials[{}]", index),
+            None => continue
+        };
+        let pbr = material.pbr_metallic_roughness();
+        let mut check_unit = |name: &str, values: &[f32]| {
+            if values.iter().any(|value| !(0.0..=1.0).contains(value)) {
+                messages.error(format!("{}.{}", path, name), format!("Value {:?} is outside [0, 1]", values));
+            }
+        };
+        check_unit("pbrMetallicRoughness.baseColorFactor", &pbr.base_color_factor());
+        check_unit("pbrMetallicRoughness.metallicFactor", &[pbr.metallic_factor()]);
+        check_unit("pbrMetallicRoughness.roughnessFactor", &[pbr.roughness_factor()]);
+        check_unit("emissiveFactor", &material.emissive_factor());
+        if let Some(occlusion) = material.occlusion_texture() {
+            check_unit("occlusionTexture.strength", &[occlusion.strength()]);
+        }
+        if material.alpha_cutoff().is_some_and(|cutoff| cutoff < 0.0) {
+            messages.error(format!("{}.alphaCutoff", path), "Value is negative");
+        }
+    }
+}
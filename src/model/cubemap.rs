@@ -0,0 +1,59 @@
+use glam::*;
+use std::f32::consts::PI;
+use crate::model::{FilterMode, Texture, WrapMode};
+
+/*
+Returns the direction from the center of a cube through texture coordinates `s` and `t`, both
+in [-1, 1] with `t` growing down the face, of face `face` in the order +X, -X, +Y, -Y, +Z, -Z.
+The faces are oriented as OpenGL, Vulkan and WebGPU expect cubemap faces to be, seen from inside
+the cube.
+*/
+fn face_direction(face: usize, s: f32, t: f32) -> Vec3 {
+    match face {
+        0 => Vec3::new(1.0, -t, -s),
+        1 => Vec3::new(-1.0, -t, s),
+        2 => Vec3::new(s, 1.0, t),
+        3 => Vec3::new(s, -1.0, -t),
+        4 => Vec3::new(s, -t, 1.0),
+        _ => Vec3::new(-s, -t, -1.0)
+    }
+}
+
+/*
+Returns the texture coordinates of an equirectangular panorama looking along `direction`. The
+top row of the panorama looks up along +Y and the bottom one down, and its center looks along
+-Z, with +X a quarter of the width to the right of it.
+*/
+fn panorama_coordinates(direction: Vec3) -> Vec2 {
+    let direction = direction.normalize();
+    Vec2::new(
+        0.5 + direction.x.atan2(-direction.z) / (2.0 * PI),
+        direction.y.clamp(-1.0, 1.0).acos() / PI
+    )
+}
+
+/*
+Resamples an equirectangular panorama, such as an HDR environment map, into the six faces of a
+cubemap, each `face_size` pixels square, in the order +X, -X, +Y, -Y, +Z, -Z. Faces are
+oriented for uploading as the layers of a cubemap in OpenGL, Vulkan or WebGPU, and the
+panorama is read as described on `panorama_coordinates`: its center is seen looking along -Z.
+
+Every face pixel samples the panorama bilinearly at the direction through its center, wrapping
+around horizontally and clamping at the poles. The faces are RGBA in the format of the
+panorama, so an `Rgba32F` panorama keeps its range. Sampling is not averaged over the area a
+face pixel covers, so faces much smaller than the panorama alias; resize the panorama first to
+about four times the face size in width for a smoother result. Panics when the panorama has
+no pixels.
+*/
+pub fn equirect_to_cubemap(texture: &Texture, face_size: u32) -> [Texture; 6] {
+    let half_texel = 0.5 / texture.height() as f32;
+    std::array::from_fn(|face| {
+        Texture::from_fn_with_format(face_size, face_size, texture.format(), |x, y| {
+            let s = 2.0 * (x as f32 + 0.5) / face_size as f32 - 1.0;
+            let t = 2.0 * (y as f32 + 0.5) / face_size as f32 - 1.0;
+            let uv = panorama_coordinates(face_direction(face, s, t));
+            let uv = Vec2::new(uv.x, uv.y.clamp(half_texel, 1.0 - half_texel));
+            texture.sample(uv, WrapMode::Repeat, FilterMode::Bilinear)
+        })
+    })
+}
@@ -0,0 +1,154 @@
+use glam::*;
+use draco_oxide_core::attribute::ComponentDataType;
+use crate::model::loader::Vertex;
+use crate::model::LoadError;
+
+pub(crate) const EXTENSION_NAME: &str = "KHR_draco_mesh_compression";
+
+type Geometry = (Vec<Vertex>, Vec<u32>);
+
+/*
+Lists the validation paths of the accessors read from Draco data instead of a buffer view. The
+extension leaves their `bufferView` out, which the glTF crate otherwise reports as missing.
+*/
+fn compressed_accessor_paths(document: &gltf::Document) -> Vec<String> {
+    document
+        .meshes()
+        .flat_map(|mesh| mesh.primitives())
+        .filter(|primitive| primitive.extension_value(EXTENSION_NAME).is_some())
+        .flat_map(|primitive| {
+            primitive
+                .attributes()
+                .map(|(_, accessor)| accessor.index())
+                .chain(primitive.indices().map(|accessor| accessor.index()))
+                .collect::<Vec<_>>()
+        })
+        .map(|index| format!("accessors[{}].bufferView", index))
+        .collect()
+}
+
+/*
+Validates a document whose primitives may be compressed with Draco. Compressed accessors leave
+their `bufferView` out, which the glTF crate reports as missing, so those reports are ignored.
+*/
+pub(crate) fn validate(json: gltf::json::Root) -> Result<gltf::Document, gltf::Error> {
+    match gltf::Document::from_json(json.clone()) {
+        Err(gltf::Error::Validation(errors)) => {
+            let document = gltf::Document::from_json_without_validation(json);
+            let compressed = compressed_accessor_paths(&document);
+            let errors: Vec<_> = errors
+                .into_iter()
+                .filter(|(path, error)| {
+                    *error != gltf::json::validation::Error::Missing || !compressed.iter().any(|p| p == path.as_str())
+                })
+                .collect();
+            if errors.is_empty() {
+                Ok(document)
+            } else {
+                Err(gltf::Error::Validation(errors))
+            }
+        },
+        result => result
+    }
+}
+
+/*
+Reads the decoded values of the Draco attribute with the given unique id, one entry per Draco
+point. Only 32-bit float attributes are read, which is what the decoder produces for quantized
+data.
+*/
+fn read_attribute<const N: usize>(
+    mesh: &draco_oxide_core::mesh::Mesh,
+    id: u64
+) -> Result<Vec<[f32; N]>, LoadError> {
+    let attribute = mesh
+        .attributes
+        .iter()
+        .find(|attribute| attribute.get_id().as_usize() as u64 == id)
+        .ok_or_else(|| LoadError::InvalidData(format!("Draco attribute {} is missing", id)))?;
+
+    if attribute.get_component_type() != ComponentDataType::F32 || attribute.get_num_components() != N {
+        return Err(LoadError::InvalidData(format!("Draco attribute {} is not a float vector of {} components", id, N)));
+    }
+
+    let values = attribute.unique_vals_as_slice::<[f32; N]>();
+    (0..attribute.len())
+        .map(|point| {
+            values
+                .get(usize::from(attribute.get_unique_val_idx(point.into())))
+                .copied()
+                .ok_or_else(|| LoadError::InvalidData(format!("Draco attribute {} has too few values", id)))
+        })
+        .collect()
+}
+
+/*
+Decodes a primitive compressed with KHR_draco_mesh_compression into vertices and indices.
+Returns `None` for primitives without the extension so they can be read through the regular
+accessors. Positions are required; normals, the first texture coordinates and tangents are read
+when the extension maps them to a Draco attribute.
+*/
+pub(crate) fn decode_primitive(
+    primitive: &gltf::Primitive,
+    document: &gltf::Document,
+    buffers: &[&[u8]]
+) -> Option<Result<Geometry, LoadError>> {
+    let extension = primitive.extension_value(EXTENSION_NAME)?;
+    Some(decode_extension(extension, document, buffers))
+}
+
+fn decode_extension(
+    extension: &serde_json::Value,
+    document: &gltf::Document,
+    buffers: &[&[u8]]
+) -> Result<Geometry, LoadError> {
+    let view = extension
+        .get("bufferView")
+        .and_then(|index| index.as_u64())
+        .and_then(|index| document.views().nth(index as usize))
+        .ok_or_else(|| LoadError::InvalidData("Draco extension refers to a missing buffer view".to_string()))?;
+    let bytes = buffers
+        .get(view.buffer().index())
+        .and_then(|buffer| buffer.get(view.offset()..view.offset() + view.length()))
+        .ok_or_else(|| LoadError::InvalidData("Draco buffer view reads past the end of its buffer".to_string()))?;
+
+    let mesh = draco_oxide_decoder::decode_mesh(bytes)
+        .map_err(|error| LoadError::InvalidData(format!("Draco decoding failed: {}", error)))?;
+    let attribute_id = |semantic: &str| extension["attributes"][semantic].as_u64();
+
+    let position_id = attribute_id("POSITION")
+        .ok_or_else(|| LoadError::InvalidData("Vertices must have positions".to_string()))?;
+    let mut vertices: Vec<Vertex> = read_attribute::<3>(&mesh, position_id)?
+        .into_iter()
+        .map(|position| Vertex {
+            position: Vec3::from(position),
+            ..Default::default()
+        })
+        .collect();
+
+    if let Some(id) = attribute_id("NORMAL") {
+        for (vertex, normal) in vertices.iter_mut().zip(read_attribute::<3>(&mesh, id)?) {
+            vertex.normal = Vec3::from(normal);
+        }
+    }
+
+    if let Some(id) = attribute_id("TEXCOORD_0") {
+        for (vertex, tex_coord) in vertices.iter_mut().zip(read_attribute::<2>(&mesh, id)?) {
+            vertex.tex_coord = Vec2::from(tex_coord);
+        }
+    }
+
+    if let Some(id) = attribute_id("TANGENT") {
+        for (vertex, tangent) in vertices.iter_mut().zip(read_attribute::<4>(&mesh, id)?) {
+            vertex.tangent = Vec4::from(tangent);
+        }
+    }
+
+    let indices = mesh
+        .faces
+        .iter()
+        .flat_map(|face| face.iter().map(|&point| usize::from(point) as u32))
+        .collect();
+
+    Ok((vertices, indices))
+}
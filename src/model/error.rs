@@ -0,0 +1,174 @@
+use std::{fmt, io};
+
+/*
+The `LoadError` enum describes the ways loading a model can fail. Import failures from the
+glTF crate and I/O failures are wrapped as they are, `InvalidData` reports content the loader
+cannot interpret, `Texture` reports an image that could not be read or decoded, with the
+`TextureError` saying why, `InvalidOptions` reports `LoaderOptions` no load can honor, such as a
+zero scale, `CacheVersionMismatch` reports a cache file written by another version of the
+cache format, `Cancelled` reports a load stopped by its progress callback, and the remaining
+variants describe lookups that found nothing matching the request.
+*/
+#[derive(Debug)]
+pub enum LoadError {
+    Gltf(gltf::Error),
+    Io(io::Error),
+    InvalidData(String),
+    Texture(TextureError),
+    InvalidOptions(String),
+    CacheVersionMismatch { found: u32, expected: u32 },
+    Cancelled,
+    NodeNotFound(String),
+    SceneNotFound(usize)
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Gltf(error) => write!(f, "Failed to load model. ({})", error),
+            LoadError::Io(error) => write!(f, "Failed to load model. ({})", error),
+            LoadError::InvalidData(message) => write!(f, "Failed to load model. ({})", message),
+            LoadError::Texture(error) => write!(f, "{}", error),
+            LoadError::InvalidOptions(message) => write!(f, "Failed to load model. ({})", message),
+            LoadError::CacheVersionMismatch { found, expected } => write!(f, "Failed to load model. (Cache version {} does not match version {})", found, expected),
+            LoadError::Cancelled => write!(f, "Failed to load model. (Cancelled by the progress callback)"),
+            LoadError::NodeNotFound(name) => write!(f, "Failed to load model. (No node named \"{}\")", name),
+            LoadError::SceneNotFound(index) => write!(f, "Failed to load model. (No scene at index {})", index)
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoadError::Gltf(error) => Some(error),
+            LoadError::Io(error) => Some(error),
+            LoadError::Texture(error) => Some(error),
+            _ => None
+        }
+    }
+}
+
+impl From<gltf::Error> for LoadError {
+    fn from(error: gltf::Error) -> Self {
+        LoadError::Gltf(error)
+    }
+}
+
+impl From<io::Error> for LoadError {
+    fn from(error: io::Error) -> Self {
+        LoadError::Io(error)
+    }
+}
+
+impl From<TextureError> for LoadError {
+    fn from(error: TextureError) -> Self {
+        LoadError::Texture(error)
+    }
+}
+
+/*
+The `MeshError` enum describes why an operation on a mesh's geometry was refused. Operations
+returning it leave the mesh untouched when they fail.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub enum MeshError {
+    IncompleteTriangles(usize),
+    IndexOutOfRange { index: u32, vertex_count: usize },
+    NonInvertibleMatrix,
+    TriangleLimitExceeded { projected: usize, limit: usize }
+}
+
+impl fmt::Display for MeshError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MeshError::IncompleteTriangles(count) => write!(f, "Failed to process mesh. (Index count {} is not a multiple of three)", count),
+            MeshError::IndexOutOfRange { index, vertex_count } => write!(f, "Failed to process mesh. (Index {} is out of range for {} vertices)", index, vertex_count),
+            MeshError::NonInvertibleMatrix => write!(f, "Failed to process mesh. (Transform matrix is not invertible)"),
+            MeshError::TriangleLimitExceeded { projected, limit } => write!(f, "Failed to process mesh. (Result would have {} triangles, more than the limit of {})", projected, limit)
+        }
+    }
+}
+
+impl std::error::Error for MeshError {}
+
+/*
+The `ExportError` enum describes why a model could not be written out. I/O failures are
+wrapped as they are.
+*/
+#[derive(Debug)]
+pub enum ExportError {
+    Io(io::Error)
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::Io(error) => write!(f, "Failed to export model. ({})", error)
+        }
+    }
+}
+
+impl std::error::Error for ExportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ExportError::Io(error) => Some(error)
+        }
+    }
+}
+
+impl From<io::Error> for ExportError {
+    fn from(error: io::Error) -> Self {
+        ExportError::Io(error)
+    }
+}
+
+/*
+The `TextureError` enum describes why a texture could not be loaded or resolved: its file could
+not be read, or its bytes are not an image stb_image can decode, naming the source. A texture
+built from pixels in memory fails with `InvalidData` when they do not match its dimensions.
+*/
+#[derive(Debug)]
+pub enum TextureError {
+    Io(io::Error),
+    Decode(String),
+    InvalidData(String)
+}
+
+impl TextureError {
+    /*
+    Returns why the texture failed, without the message around it, for reports that give their
+    own context.
+    */
+    pub fn reason(&self) -> String {
+        match self {
+            TextureError::Io(error) => error.to_string(),
+            TextureError::Decode(source) => format!("Cannot decode {}", source),
+            TextureError::InvalidData(message) => message.clone()
+        }
+    }
+}
+
+impl fmt::Display for TextureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TextureError::InvalidData(_) => write!(f, "Failed to create texture. ({})", self.reason()),
+            _ => write!(f, "Failed to load texture. ({})", self.reason())
+        }
+    }
+}
+
+impl std::error::Error for TextureError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TextureError::Io(error) => Some(error),
+            _ => None
+        }
+    }
+}
+
+impl From<io::Error> for TextureError {
+    fn from(error: io::Error) -> Self {
+        TextureError::Io(error)
+    }
+}
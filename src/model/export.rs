@@ -0,0 +1,625 @@
+use glam::*;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use crate::model::loader::{Material, Mesh, Model};
+use crate::model::{Indices, Texture};
+use crate::model::ExportError;
+
+/*
+Returns a name usable in OBJ and MTL statements, which end at the first whitespace.
+*/
+fn statement_name(name: Option<&str>, fallback: String) -> String {
+    match name {
+        Some(name) if !name.trim().is_empty() => name.split_whitespace().collect::<Vec<_>>().join("_"),
+        _ => fallback
+    }
+}
+
+fn material_name(material: &Material, index: usize) -> String {
+    statement_name(material.name.as_deref(), format!("material_{}", index))
+}
+
+/*
+Writes the MTL file of an OBJ export. Base color textures are written as PNG images next to it,
+named after the MTL file and their material, and referenced through `map_Kd`.
+*/
+fn write_mtl(model: &Model, path: &Path) -> Result<(), ExportError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    let stem = path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+    for (index, material) in model.materials.iter().enumerate() {
+        let name = material_name(material, index);
+        let color = material.base_color;
+        writeln!(writer, "newmtl {}", name)?;
+        writeln!(writer, "Kd {} {} {}", color.x, color.y, color.z)?;
+        writeln!(writer, "d {}", color.w)?;
+        if let Some(texture) = &material.base_color_texture {
+            let image_name = format!("{}_{}.png", stem, name);
+            std::fs::write(path.with_file_name(&image_name), texture.encode_png().map_err(io::Error::other)?)?;
+            writeln!(writer, "map_Kd {}", image_name)?;
+        }
+        writeln!(writer)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/*
+Writes a model as a Wavefront OBJ file with positions, normals, texture coordinates and
+triangles, plus an MTL file next to it, named like the OBJ file, holding the base color and
+base color texture of every material; see `write_mtl`. Each mesh becomes an object and a group
+using its material. Meshes whose normals or texture coordinates are all zero, as the loader
+leaves them when a file has none, are written without them, and their faces reference only the
+streams written. Instanced meshes are written once per instance with the instance transform
+applied, since OBJ has no instancing. Texture coordinates are flipped vertically to OBJ's
+bottom-left origin, and indices that do not form a full triangle are skipped.
+*/
+pub fn export_obj(model: &Model, path: impl AsRef<Path>) -> Result<(), ExportError> {
+    let path = path.as_ref();
+    let mtl_path = path.with_extension("mtl");
+    write_mtl(model, &mtl_path)?;
+
+    let mut writer = BufWriter::new(File::create(path)?);
+    if let Some(mtl_name) = mtl_path.file_name() {
+        writeln!(writer, "mtllib {}", mtl_name.to_string_lossy())?;
+    }
+
+    let (mut position_offset, mut tex_coord_offset, mut normal_offset) = (1, 1, 1);
+    for (mesh_index, mesh) in model.meshes.iter().enumerate() {
+        let has_normals = mesh.vertices.iter().any(|vertex| vertex.normal != Vec3::ZERO);
+        let has_tex_coords = mesh.vertices.iter().any(|vertex| vertex.tex_coord != Vec2::ZERO);
+        let instances = if mesh.instances.is_empty() { vec![Mat4::IDENTITY] } else { mesh.instances.clone() };
+        let material = model
+            .material(mesh.material)
+            .map(|material| material_name(material, mesh.material.index()));
+
+        for (instance_index, instance) in instances.iter().enumerate() {
+            let normal_matrix = Mat3::from_mat4(*instance).inverse().transpose();
+            let mut name = statement_name(mesh.name.as_deref(), format!("mesh_{}", mesh_index));
+            if instances.len() > 1 {
+                name = format!("{}_{}", name, instance_index);
+            }
+            writeln!(writer, "o {}", name)?;
+            writeln!(writer, "g {}", name)?;
+            if let Some(material) = &material {
+                writeln!(writer, "usemtl {}", material)?;
+            }
+
+            for vertex in &mesh.vertices {
+                let position = instance.transform_point3(vertex.position);
+                writeln!(writer, "v {} {} {}", position.x, position.y, position.z)?;
+            }
+            if has_tex_coords {
+                for vertex in &mesh.vertices {
+                    writeln!(writer, "vt {} {}", vertex.tex_coord.x, 1.0 - vertex.tex_coord.y)?;
+                }
+            }
+            if has_normals {
+                for vertex in &mesh.vertices {
+                    let normal = (normal_matrix * vertex.normal).normalize_or_zero();
+                    writeln!(writer, "vn {} {} {}", normal.x, normal.y, normal.z)?;
+                }
+            }
+
+            for triangle in mesh.indices.triangles() {
+                let corners: Vec<String> = triangle
+                    .iter()
+                    .map(|&index| {
+                        let index = index as usize;
+                        let mut corner = (index + position_offset).to_string();
+                        if has_tex_coords || has_normals {
+                            corner.push('/');
+                        }
+                        if has_tex_coords {
+                            corner += &(index + tex_coord_offset).to_string();
+                        }
+                        if has_normals {
+                            corner += &format!("/{}", index + normal_offset);
+                        }
+                        corner
+                    })
+                    .collect();
+                writeln!(writer, "f {}", corners.join(" "))?;
+            }
+
+            position_offset += mesh.vertices.len();
+            if has_tex_coords {
+                tex_coord_offset += mesh.vertices.len();
+            }
+            if has_normals {
+                normal_offset += mesh.vertices.len();
+            }
+        }
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+const FLOAT: u32 = 5126;
+const UNSIGNED_SHORT: u32 = 5123;
+const UNSIGNED_INT: u32 = 5125;
+
+/*
+Accumulates the binary buffer of a glTF file along with the buffer views, accessors, images and
+textures describing it. Views start on four-byte boundaries, as glTF requires of float
+accessors, so views holding 16-bit indices or images are padded.
+*/
+#[derive(Default)]
+struct GltfBuffer {
+    data: Vec<u8>,
+    views: Vec<Value>,
+    accessors: Vec<Value>,
+    images: Vec<Value>,
+    textures: Vec<Value>
+}
+
+impl GltfBuffer {
+    fn push_view(&mut self, bytes: Vec<u8>, target: Option<u32>) -> usize {
+        let mut view = json!({
+            "buffer": 0,
+            "byteOffset": self.data.len(),
+            "byteLength": bytes.len()
+        });
+        if let Some(target) = target {
+            view["target"] = json!(target);
+        }
+        self.data.extend(bytes);
+        self.data.resize(self.data.len().next_multiple_of(4), 0);
+        self.views.push(view);
+        self.views.len() - 1
+    }
+
+    /*
+    Writes float vectors as a new accessor and returns its index. Positions pass `with_bounds`,
+    since glTF requires their min and max.
+    */
+    fn push_vectors<const N: usize>(&mut self, values: &[[f32; N]], target: Option<u32>, with_bounds: bool) -> usize {
+        let bytes = values.iter().flatten().flat_map(|value| value.to_le_bytes()).collect();
+        let view = self.push_view(bytes, target);
+        let kind = ["SCALAR", "VEC2", "VEC3", "VEC4"][N - 1];
+        let mut accessor = json!({
+            "bufferView": view,
+            "componentType": FLOAT,
+            "count": values.len(),
+            "type": kind
+        });
+        if with_bounds {
+            let fold = |start: f32, pick: fn(f32, f32) -> f32| {
+                values.iter().fold([start; N], |bound, value| std::array::from_fn(|i| pick(bound[i], value[i])))
+            };
+            accessor["min"] = json!(fold(f32::INFINITY, f32::min).to_vec());
+            accessor["max"] = json!(fold(f32::NEG_INFINITY, f32::max).to_vec());
+        }
+        self.accessors.push(accessor);
+        self.accessors.len() - 1
+    }
+
+    fn push_indices(&mut self, indices: &Indices) -> usize {
+        let view = self.push_view(indices.to_le_bytes(), Some(ELEMENT_ARRAY_BUFFER));
+        self.accessors.push(json!({
+            "bufferView": view,
+            "componentType": if indices.is_u16() { UNSIGNED_SHORT } else { UNSIGNED_INT },
+            "count": indices.len(),
+            "type": "SCALAR"
+        }));
+        self.accessors.len() - 1
+    }
+
+    /*
+    Writes a mesh as a triangle primitive. Tangents are only written when some vertex has one.
+    */
+    fn push_primitive(&mut self, mesh: &Mesh, material_count: usize) -> Value {
+        let positions: Vec<[f32; 3]> = mesh.vertices.iter().map(|vertex| vertex.position.to_array()).collect();
+        let normals: Vec<[f32; 3]> = mesh.vertices.iter().map(|vertex| vertex.normal.to_array()).collect();
+        let tex_coords: Vec<[f32; 2]> = mesh.vertices.iter().map(|vertex| vertex.tex_coord.to_array()).collect();
+
+        let mut attributes = json!({
+            "POSITION": self.push_vectors(&positions, Some(ARRAY_BUFFER), true),
+            "NORMAL": self.push_vectors(&normals, Some(ARRAY_BUFFER), false),
+            "TEXCOORD_0": self.push_vectors(&tex_coords, Some(ARRAY_BUFFER), false)
+        });
+        if mesh.vertices.iter().any(|vertex| vertex.tangent != Vec4::ZERO) {
+            let tangents: Vec<[f32; 4]> = mesh.vertices.iter().map(|vertex| vertex.tangent.to_array()).collect();
+            attributes["TANGENT"] = json!(self.push_vectors(&tangents, Some(ARRAY_BUFFER), false));
+        }
+
+        let mut indices = mesh.indices.clone();
+        indices.truncate(indices.len() / 3 * 3);
+        let mut primitive = json!({
+            "attributes": attributes,
+            "indices": self.push_indices(&indices)
+        });
+        if mesh.material.index() < material_count {
+            primitive["material"] = json!(mesh.material.index());
+        }
+        primitive
+    }
+
+    /*
+    Embeds a texture as a PNG image and returns the index of the glTF texture sampling it.
+    */
+    fn push_texture(&mut self, texture: &Texture) -> Result<usize, ExportError> {
+        let png = texture.encode_png().map_err(io::Error::other)?;
+        let view = self.push_view(png, None);
+        self.images.push(json!({ "bufferView": view, "mimeType": "image/png" }));
+        self.textures.push(json!({ "source": self.images.len() - 1 }));
+        Ok(self.textures.len() - 1)
+    }
+
+    /*
+    Writes instance transforms as the attributes of `EXT_mesh_gpu_instancing`.
+    */
+    fn push_instances(&mut self, instances: &[Mat4]) -> Value {
+        let (mut translations, mut rotations, mut scales) = (Vec::new(), Vec::new(), Vec::new());
+        for instance in instances {
+            let (scale, rotation, translation) = instance.to_scale_rotation_translation();
+            translations.push(translation.to_array());
+            rotations.push(rotation.to_array());
+            scales.push(scale.to_array());
+        }
+        json!({
+            "attributes": {
+                "TRANSLATION": self.push_vectors(&translations, None, false),
+                "ROTATION": self.push_vectors(&rotations, None, false),
+                "SCALE": self.push_vectors(&scales, None, false)
+            }
+        })
+    }
+}
+
+/*
+Embeds a texture and describes the reference to it, with its `KHR_texture_transform` when it
+has one.
+*/
+fn texture_info(
+    texture: &Texture,
+    buffer: &mut GltfBuffer,
+    extensions_used: &mut BTreeSet<&'static str>
+) -> Result<Value, ExportError> {
+    let mut info = json!({ "index": buffer.push_texture(texture)? });
+    if texture.tex_coord() != 0 {
+        info["texCoord"] = json!(texture.tex_coord());
+    }
+    let transform = texture.transform();
+    if !transform.is_identity() {
+        info["extensions"] = json!({
+            "KHR_texture_transform": {
+                "offset": transform.offset.to_array(),
+                "rotation": transform.rotation,
+                "scale": transform.scale.to_array()
+            }
+        });
+        extensions_used.insert("KHR_texture_transform");
+    }
+    Ok(info)
+}
+
+/*
+Describes a material with its base color and the extensions the loader reads back, embedding
+its textures and recording the extensions used.
+*/
+fn gltf_material(
+    material: &Material,
+    buffer: &mut GltfBuffer,
+    extensions_used: &mut BTreeSet<&'static str>
+) -> Result<Value, ExportError> {
+    let mut value = json!({
+        "pbrMetallicRoughness": {
+            "baseColorFactor": material.base_color.to_array(),
+            "metallicFactor": material.metallic_factor,
+            "roughnessFactor": material.roughness_factor
+        }
+    });
+    if let Some(name) = &material.name {
+        value["name"] = json!(name);
+    }
+    if let Some(extras) = &material.extras {
+        value["extras"] = extras.clone();
+    }
+    if let Some(texture) = &material.base_color_texture {
+        value["pbrMetallicRoughness"]["baseColorTexture"] = texture_info(texture, buffer, extensions_used)?;
+    }
+    if let Some(texture) = &material.metallic_roughness_texture {
+        value["pbrMetallicRoughness"]["metallicRoughnessTexture"] = texture_info(texture, buffer, extensions_used)?;
+    }
+    if let Some(texture) = &material.normal_texture {
+        value["normalTexture"] = texture_info(texture, buffer, extensions_used)?;
+        value["normalTexture"]["scale"] = json!(material.normal_scale);
+    }
+    if material.emissive_factor != Vec3::ZERO {
+        value["emissiveFactor"] = json!(material.emissive_factor.to_array());
+    }
+    if let Some(texture) = &material.emissive_texture {
+        value["emissiveTexture"] = texture_info(texture, buffer, extensions_used)?;
+    }
+    if let Some(texture) = &material.occlusion_texture {
+        value["occlusionTexture"] = texture_info(texture, buffer, extensions_used)?;
+        value["occlusionTexture"]["strength"] = json!(material.occlusion_strength);
+    }
+
+    let mut extensions = Map::new();
+    if let Some(transmission) = &material.transmission {
+        let mut extension = json!({ "transmissionFactor": transmission.factor });
+        if let Some(texture) = &transmission.texture {
+            extension["transmissionTexture"] = texture_info(texture, buffer, extensions_used)?;
+        }
+        extensions.insert("KHR_materials_transmission".to_string(), extension);
+    }
+    if material.ior != 1.5 {
+        extensions.insert("KHR_materials_ior".to_string(), json!({ "ior": material.ior }));
+    }
+    if let Some(volume) = &material.volume {
+        let mut extension = json!({
+            "thicknessFactor": volume.thickness_factor,
+            "attenuationColor": volume.attenuation_color.to_array()
+        });
+        if volume.attenuation_distance.is_finite() {
+            extension["attenuationDistance"] = json!(volume.attenuation_distance);
+        }
+        if let Some(texture) = &volume.thickness_texture {
+            extension["thicknessTexture"] = texture_info(texture, buffer, extensions_used)?;
+        }
+        extensions.insert("KHR_materials_volume".to_string(), extension);
+    }
+    if material.clearcoat_factor > 0.0 {
+        let mut extension = json!({
+            "clearcoatFactor": material.clearcoat_factor,
+            "clearcoatRoughnessFactor": material.clearcoat_roughness_factor
+        });
+        for (key, texture) in [
+            ("clearcoatTexture", &material.clearcoat_texture),
+            ("clearcoatRoughnessTexture", &material.clearcoat_roughness_texture),
+            ("clearcoatNormalTexture", &material.clearcoat_normal_texture)
+        ] {
+            if let Some(texture) = texture {
+                extension[key] = texture_info(texture, buffer, extensions_used)?;
+            }
+        }
+        extensions.insert("KHR_materials_clearcoat".to_string(), extension);
+    }
+    if material.unlit {
+        extensions.insert("KHR_materials_unlit".to_string(), json!({}));
+    }
+
+    for name in [
+        "KHR_materials_transmission",
+        "KHR_materials_ior",
+        "KHR_materials_volume",
+        "KHR_materials_clearcoat",
+        "KHR_materials_unlit"
+    ] {
+        if extensions.contains_key(name) {
+            extensions_used.insert(name);
+        }
+    }
+    if !extensions.is_empty() {
+        value["extensions"] = Value::Object(extensions);
+    }
+    Ok(value)
+}
+
+/*
+Writes a GLB container: the 12-byte header followed by the JSON chunk padded with spaces and
+the binary chunk padded with zeros, both to four bytes.
+*/
+fn write_glb(path: &Path, json: &[u8], data: &[u8]) -> Result<(), ExportError> {
+    let padded = |length: usize| length.div_ceil(4) * 4;
+    let json_length = padded(json.len());
+    let data_length = padded(data.len());
+    let mut total = 12 + 8 + json_length;
+    if !data.is_empty() {
+        total += 8 + data_length;
+    }
+
+    let mut writer = BufWriter::new(File::create(path)?);
+    writer.write_all(b"glTF")?;
+    writer.write_all(&2u32.to_le_bytes())?;
+    writer.write_all(&(total as u32).to_le_bytes())?;
+
+    writer.write_all(&(json_length as u32).to_le_bytes())?;
+    writer.write_all(b"JSON")?;
+    writer.write_all(json)?;
+    writer.write_all(&vec![b' '; json_length - json.len()])?;
+
+    if !data.is_empty() {
+        writer.write_all(&(data_length as u32).to_le_bytes())?;
+        writer.write_all(b"BIN\0")?;
+        writer.write_all(data)?;
+        writer.write_all(&vec![0; data_length - data.len()])?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/*
+Writes a model as a glTF 2.0 file, or as a single GLB file when `binary` is set. Otherwise the
+geometry goes to a `.bin` file next to the glTF file, named like it. Consecutive meshes sharing
+a name, instances and extras become the primitives of one glTF mesh, mirroring how the loader
+splits them, and each glTF mesh gets a root node of the only scene, with its instances written
+through `EXT_mesh_gpu_instancing`. Materials keep their base color, their extras and the
+transmission, index of refraction, volume and clearcoat factors, and their textures are
+embedded in the buffer as PNG images along with their texture transforms. Meshes without
+vertices or a whole triangle are left out, since glTF accessors cannot be empty, and trailing
+indices that do not form a triangle are dropped.
+*/
+pub fn export_gltf(model: &Model, path: impl AsRef<Path>, binary: bool) -> Result<(), ExportError> {
+    let path = path.as_ref();
+    let mut buffer = GltfBuffer::default();
+    let mut extensions_used = BTreeSet::new();
+
+    let materials = model
+        .materials
+        .iter()
+        .map(|material| gltf_material(material, &mut buffer, &mut extensions_used))
+        .collect::<Result<Vec<Value>, ExportError>>()?;
+
+    let exported: Vec<&Mesh> = model
+        .meshes
+        .iter()
+        .filter(|mesh| !mesh.vertices.is_empty() && mesh.indices.len() >= 3)
+        .collect();
+
+    let mut meshes = Vec::new();
+    let mut nodes = Vec::new();
+    for group in exported.chunk_by(|a, b| a.name == b.name && a.instances == b.instances && a.extras == b.extras) {
+        let primitives: Vec<Value> = group
+            .iter()
+            .map(|mesh| buffer.push_primitive(mesh, model.materials.len()))
+            .collect();
+        let mut mesh = json!({ "primitives": primitives });
+        let mut node = json!({ "mesh": meshes.len() });
+        if let Some(name) = &group[0].name {
+            mesh["name"] = json!(name);
+            node["name"] = json!(name);
+        }
+        if let Some(extras) = &group[0].extras {
+            mesh["extras"] = extras.clone();
+        }
+        if !group[0].instances.is_empty() {
+            node["extensions"] = json!({ "EXT_mesh_gpu_instancing": buffer.push_instances(&group[0].instances) });
+            extensions_used.insert("EXT_mesh_gpu_instancing");
+        }
+        meshes.push(mesh);
+        nodes.push(node);
+    }
+
+    let mut root = Map::new();
+    root.insert("asset".to_string(), json!({ "version": "2.0", "generator": "motley" }));
+    root.insert("scene".to_string(), json!(0));
+    root.insert("scenes".to_string(), json!([{ "nodes": (0..nodes.len()).collect::<Vec<_>>() }]));
+    for (key, values) in [
+        ("nodes", nodes),
+        ("meshes", meshes),
+        ("materials", materials),
+        ("textures", std::mem::take(&mut buffer.textures)),
+        ("images", std::mem::take(&mut buffer.images)),
+        ("accessors", std::mem::take(&mut buffer.accessors)),
+        ("bufferViews", std::mem::take(&mut buffer.views))
+    ] {
+        if !values.is_empty() {
+            root.insert(key.to_string(), Value::Array(values));
+        }
+    }
+    if !extensions_used.is_empty() {
+        root.insert("extensionsUsed".to_string(), json!(extensions_used));
+    }
+
+    if !buffer.data.is_empty() {
+        let mut gltf_buffer = json!({ "byteLength": buffer.data.len() });
+        if !binary {
+            let bin_path = path.with_extension("bin");
+            std::fs::write(&bin_path, &buffer.data)?;
+            if let Some(bin_name) = bin_path.file_name() {
+                gltf_buffer["uri"] = json!(bin_name.to_string_lossy());
+            }
+        }
+        root.insert("buffers".to_string(), json!([gltf_buffer]));
+    }
+
+    let json = serde_json::to_vec(&Value::Object(root)).map_err(io::Error::from)?;
+    if binary {
+        write_glb(path, &json, &buffer.data)
+    } else {
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+}
+
+/*
+The `StlFormat` enum selects the encoding of an STL export: the compact binary layout that
+slicers expect, or the text layout, which is larger but easy to read when debugging.
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StlFormat {
+    #[default]
+    Binary,
+    Ascii
+}
+
+/*
+Collects the facets of an STL export as a normal and three corners each, from the model with
+its transforms baked in. Facet normals follow the counter-clockwise winding rather than the
+vertex normals. Triangles with a zero area, a non-finite position or an out-of-range index have
+no normal and are counted as skipped instead.
+*/
+fn stl_facets(model: &Model) -> (Vec<[Vec3; 4]>, usize) {
+    let mut facets = Vec::new();
+    let mut skipped = 0;
+    for mesh in &model.flattened().meshes {
+        for triangle in mesh.indices.triangles() {
+            let corners = triangle.map(|index| mesh.vertices.get(index as usize).map(|vertex| vertex.position));
+            let [Some(a), Some(b), Some(c)] = corners else {
+                skipped += 1;
+                continue;
+            };
+            let (ab, ac) = (b - a, c - a);
+            let normal = ab.cross(ac);
+            if !normal.is_finite() || normal.length() <= f32::EPSILON * ab.length() * ac.length() {
+                skipped += 1;
+                continue;
+            }
+            facets.push([normal.normalize(), a, b, c]);
+        }
+    }
+    (facets, skipped)
+}
+
+impl Model {
+    /*
+    Writes every triangle of the model to a binary STL file for 3D printing, in world space with
+    instance transforms applied, since STL has no meshes, instances or materials. Binary files
+    start with an 80-byte header that does not begin with `solid`, so readers do not mistake them
+    for text. Facet normals are recomputed from the winding and degenerate triangles are
+    skipped; returns how many were skipped.
+    */
+    pub fn export_stl(&self, path: impl AsRef<Path>) -> Result<usize, ExportError> {
+        self.export_stl_with(path, StlFormat::Binary)
+    }
+
+    /*
+    Same as `export_stl`, in the STL encoding chosen by `format`.
+    */
+    pub fn export_stl_with(&self, path: impl AsRef<Path>, format: StlFormat) -> Result<usize, ExportError> {
+        let (facets, skipped) = stl_facets(self);
+        let mut writer = BufWriter::new(File::create(path)?);
+        match format {
+            StlFormat::Binary => {
+                let mut header = [b' '; 80];
+                let title = b"Binary STL exported by Motley";
+                header[..title.len()].copy_from_slice(title);
+                writer.write_all(&header)?;
+                writer.write_all(&(facets.len() as u32).to_le_bytes())?;
+                for facet in &facets {
+                    for vector in facet {
+                        for component in vector.to_array() {
+                            writer.write_all(&component.to_le_bytes())?;
+                        }
+                    }
+                    writer.write_all(&0u16.to_le_bytes())?;
+                }
+            },
+            StlFormat::Ascii => {
+                writeln!(writer, "solid motley")?;
+                for [normal, a, b, c] in &facets {
+                    writeln!(writer, "  facet normal {} {} {}", normal.x, normal.y, normal.z)?;
+                    writeln!(writer, "    outer loop")?;
+                    for corner in [a, b, c] {
+                        writeln!(writer, "      vertex {} {} {}", corner.x, corner.y, corner.z)?;
+                    }
+                    writeln!(writer, "    endloop")?;
+                    writeln!(writer, "  endfacet")?;
+                }
+                writeln!(writer, "endsolid motley")?;
+            }
+        }
+
+        writer.flush()?;
+        Ok(skipped)
+    }
+}
@@ -0,0 +1,269 @@
+use glam::*;
+use std::collections::HashMap;
+use crate::model::loader::Mesh;
+use crate::model::Indices;
+
+/*
+The `HalfEdge` struct is one side of an edge, running inside `face` from the vertex `origin` to
+the origin of `next`. `corner` is the index of the mesh vertex the face uses at `origin`, which
+keeps the normals and texture coordinates of that corner. `twin` is the half-edge running the
+other way in the neighbouring face, or `None` on a boundary.
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HalfEdge {
+    pub origin: usize,
+    pub corner: u32,
+    pub next: usize,
+    pub twin: Option<usize>,
+    pub face: usize
+}
+
+/*
+The `HalfEdgeMesh` struct holds the connectivity of a triangle mesh for topology queries. Its
+vertices are the distinct positions of the mesh, compared bitwise, so vertices split along
+normal or texture seams are one vertex here and the surface stays connected across the seam.
+Face `f` is made of the half-edges `3 * f`, `3 * f + 1` and `3 * f + 2`, in the winding of the
+triangle. An edge shared by more than two faces, or by two faces wound the same way, is
+non-manifold: its half-edges get no twin and count as boundary.
+*/
+#[derive(Clone, Debug)]
+pub struct HalfEdgeMesh {
+    positions: Vec<Vec3>,
+    half_edges: Vec<HalfEdge>,
+    outgoing_offsets: Vec<usize>,
+    outgoing: Vec<usize>,
+    mesh: Mesh
+}
+
+impl HalfEdgeMesh {
+    /*
+    Builds the half-edge structure of a mesh. Triangles with out-of-range indices and trailing
+    indices are left out.
+    */
+    pub fn from_mesh(mesh: &Mesh) -> HalfEdgeMesh {
+        let mut lookup: HashMap<[u32; 3], usize> = HashMap::new();
+        let mut positions = Vec::new();
+        let ids: Vec<usize> = mesh
+            .vertices
+            .iter()
+            .map(|vertex| {
+                *lookup.entry(vertex.position.to_array().map(f32::to_bits)).or_insert_with(|| {
+                    positions.push(vertex.position);
+                    positions.len() - 1
+                })
+            })
+            .collect();
+
+        let mut half_edges = Vec::new();
+        let triangles = mesh
+            .indices
+            .triangles()
+            .filter(|triangle| triangle.iter().all(|&index| (index as usize) < mesh.vertices.len()));
+        for (face, triangle) in triangles.enumerate() {
+            for corner in 0..3 {
+                half_edges.push(HalfEdge {
+                    origin: ids[triangle[corner] as usize],
+                    corner: triangle[corner],
+                    next: face * 3 + (corner + 1) % 3,
+                    twin: None,
+                    face
+                });
+            }
+        }
+
+        let mut directed: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+        for (index, half_edge) in half_edges.iter().enumerate() {
+            directed.entry((half_edge.origin, half_edges[half_edge.next].origin)).or_default().push(index);
+        }
+        for index in 0..half_edges.len() {
+            let (from, to) = (half_edges[index].origin, half_edges[half_edges[index].next].origin);
+            if from == to || directed[&(from, to)].len() != 1 {
+                continue;
+            }
+            if let Some(&[twin]) = directed.get(&(to, from)).map(Vec::as_slice) {
+                half_edges[index].twin = Some(twin);
+            }
+        }
+
+        let mut outgoing_offsets = vec![0; positions.len() + 1];
+        for half_edge in &half_edges {
+            outgoing_offsets[half_edge.origin + 1] += 1;
+        }
+        for vertex in 0..positions.len() {
+            outgoing_offsets[vertex + 1] += outgoing_offsets[vertex];
+        }
+        let mut filled = outgoing_offsets.clone();
+        let mut outgoing = vec![0; half_edges.len()];
+        for (index, half_edge) in half_edges.iter().enumerate() {
+            outgoing[filled[half_edge.origin]] = index;
+            filled[half_edge.origin] += 1;
+        }
+
+        HalfEdgeMesh {
+            positions,
+            half_edges,
+            outgoing_offsets,
+            outgoing,
+            mesh: Mesh { indices: Indices::U16(Vec::new()), ..mesh.clone() }
+        }
+    }
+
+    /*
+    Rebuilds a mesh with the vertices, material, instances and extras of the source mesh and
+    one triangle per face, each using the mesh vertices its corners used.
+    */
+    pub fn to_mesh(&self) -> Mesh {
+        let indices = self.half_edges.iter().map(|half_edge| half_edge.corner).collect();
+        Mesh {
+            indices: Indices::for_vertices(indices, self.mesh.vertices.len()),
+            ..self.mesh.clone()
+        }
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn face_count(&self) -> usize {
+        self.half_edges.len() / 3
+    }
+
+    pub fn position(&self, vertex: usize) -> Vec3 {
+        self.positions[vertex]
+    }
+
+    pub fn half_edges(&self) -> &[HalfEdge] {
+        &self.half_edges
+    }
+
+    /*
+    Returns the vertex a half-edge points to.
+    */
+    pub fn destination(&self, half_edge: usize) -> usize {
+        self.half_edges[self.half_edges[half_edge].next].origin
+    }
+
+    pub fn face_half_edges(&self, face: usize) -> [usize; 3] {
+        [face * 3, face * 3 + 1, face * 3 + 2]
+    }
+
+    pub fn face_vertices(&self, face: usize) -> [usize; 3] {
+        self.face_half_edges(face).map(|half_edge| self.half_edges[half_edge].origin)
+    }
+
+    /*
+    Iterates over the faces sharing an edge with `face`, across its manifold edges only.
+    */
+    pub fn face_neighbors(&self, face: usize) -> impl Iterator<Item = usize> + '_ {
+        self.face_half_edges(face)
+            .into_iter()
+            .filter_map(|half_edge| self.half_edges[half_edge].twin)
+            .map(|twin| self.half_edges[twin].face)
+    }
+
+    /*
+    Lists the half-edges leaving a vertex, one per face using it and more for a degenerate face
+    repeating the vertex, in face order.
+    */
+    pub fn vertex_half_edges(&self, vertex: usize) -> &[usize] {
+        &self.outgoing[self.outgoing_offsets[vertex]..self.outgoing_offsets[vertex + 1]]
+    }
+
+    /*
+    Iterates over the faces using a vertex, each once, in face order.
+    */
+    pub fn vertex_faces(&self, vertex: usize) -> impl Iterator<Item = usize> + '_ {
+        self.vertex_half_edges(vertex)
+            .iter()
+            .filter(move |&&half_edge| (half_edge / 3 * 3..half_edge).all(|earlier| self.half_edges[earlier].origin != vertex))
+            .map(|&half_edge| self.half_edges[half_edge].face)
+    }
+
+    /*
+    Iterates over the vertices sharing an edge with a vertex, each once, in the order the faces
+    around it reach them.
+    */
+    pub fn vertex_neighbors(&self, vertex: usize) -> impl Iterator<Item = usize> + '_ {
+        let mut neighbors = Vec::new();
+        for &half_edge in self.vertex_half_edges(vertex) {
+            let next = self.half_edges[half_edge].next;
+            for neighbor in [self.half_edges[next].origin, self.half_edges[self.half_edges[next].next].origin] {
+                if neighbor != vertex && !neighbors.contains(&neighbor) {
+                    neighbors.push(neighbor);
+                }
+            }
+        }
+        neighbors.into_iter()
+    }
+
+    /*
+    Iterates over the edges as pairs of vertices, each edge once: a manifold edge through the
+    first of its two half-edges, any other through every half-edge on it.
+    */
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.half_edges.len())
+            .filter(|&half_edge| self.half_edges[half_edge].twin.is_none_or(|twin| twin > half_edge))
+            .map(|half_edge| (self.half_edges[half_edge].origin, self.destination(half_edge)))
+    }
+
+    pub fn is_boundary_edge(&self, half_edge: usize) -> bool {
+        self.half_edges[half_edge].twin.is_none()
+    }
+
+    /*
+    Iterates over the half-edges without a twin, which lie on holes, on the outline of open
+    surfaces and on non-manifold edges.
+    */
+    pub fn boundary_edges(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.half_edges.len()).filter(|&half_edge| self.is_boundary_edge(half_edge))
+    }
+
+    /*
+    Tells whether a vertex lies on a boundary edge. Vertices used by no face are not.
+    */
+    pub fn is_boundary_vertex(&self, vertex: usize) -> bool {
+        self.vertex_half_edges(vertex).iter().any(|&half_edge| {
+            let previous = self.half_edges[self.half_edges[half_edge].next].next;
+            self.is_boundary_edge(half_edge) || self.is_boundary_edge(previous)
+        })
+    }
+}
+
+impl Mesh {
+    /*
+    Lists the edges used by exactly one triangle, which outline the holes of the mesh and the
+    borders of open surfaces, as the indices of their two vertices in the winding of that
+    triangle. Edges are compared by position, so vertices split along seams do not open the
+    surface. Edges shared by three or more triangles are not listed but still keep the mesh from
+    being watertight.
+    */
+    pub fn boundary_edges(&self) -> Vec<(u32, u32)> {
+        let topology = HalfEdgeMesh::from_mesh(self);
+        let half_edges = topology.half_edges();
+        let mut counts: HashMap<(usize, usize), usize> = HashMap::new();
+        for (index, half_edge) in half_edges.iter().enumerate() {
+            let (a, b) = (half_edge.origin, topology.destination(index));
+            if a != b {
+                *counts.entry((a.min(b), a.max(b))).or_default() += 1;
+            }
+        }
+
+        (0..half_edges.len())
+            .filter(|&half_edge| {
+                let (a, b) = (half_edges[half_edge].origin, topology.destination(half_edge));
+                counts.get(&(a.min(b), a.max(b))) == Some(&1)
+            })
+            .map(|half_edge| (half_edges[half_edge].corner, half_edges[half_edges[half_edge].next].corner))
+            .collect()
+    }
+
+    /*
+    Tells whether the mesh encloses a volume: it has triangles, and every edge is shared by
+    exactly two of them wound in opposite directions, with positions compared as in
+    `boundary_edges`.
+    */
+    pub fn is_watertight(&self) -> bool {
+        let topology = HalfEdgeMesh::from_mesh(self);
+        topology.face_count() > 0 && topology.boundary_edges().next().is_none()
+    }
+}
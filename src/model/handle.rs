@@ -0,0 +1,42 @@
+/*
+The `MaterialHandle` struct refers to a material of a `Model` by its position in
+`Model::materials`. A handle only means something for the model it came from, and
+`Model::material` returns `None` for a handle past the end of the list. `From<usize>` and
+`index` convert to and from plain indices for code that works with other arrays; `From` panics
+for an index that does not fit in 32 bits.
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
+pub struct MaterialHandle(pub u32);
+
+impl MaterialHandle {
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl From<usize> for MaterialHandle {
+    fn from(index: usize) -> Self {
+        MaterialHandle(u32::try_from(index).expect("Failed to create material handle. (Index does not fit in 32 bits)"))
+    }
+}
+
+/*
+The `MeshHandle` struct refers to a mesh of a `Model` by its position in `Model::meshes`, in the
+same way `MaterialHandle` refers to a material.
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
+pub struct MeshHandle(pub u32);
+
+impl MeshHandle {
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl From<usize> for MeshHandle {
+    fn from(index: usize) -> Self {
+        MeshHandle(u32::try_from(index).expect("Failed to create mesh handle. (Index does not fit in 32 bits)"))
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+use crate::model::loader::{Mesh, Vertex};
+use crate::model::Indices;
+
+/*
+Returns the raw bits of every attribute of a vertex, so only bitwise-identical vertices share a
+key.
+*/
+fn vertex_bits(vertex: &Vertex) -> [u32; 15] {
+    let mut key = [0; 15];
+    let values = vertex
+        .position
+        .to_array()
+        .into_iter()
+        .chain(vertex.normal.to_array())
+        .chain(vertex.tex_coord.to_array())
+        .chain(vertex.tangent.to_array())
+        .chain(vertex.barycentric.to_array());
+    for (bits, value) in key.iter_mut().zip(values) {
+        *bits = value.to_bits();
+    }
+    key
+}
+
+/*
+Returns the complete triangles of a mesh whose indices are all in range, in order.
+*/
+fn valid_triangles(mesh: &Mesh) -> impl Iterator<Item = [u32; 3]> + '_ {
+    mesh.indices
+        .triangles()
+        .filter(|triangle| triangle.iter().all(|&index| (index as usize) < mesh.vertices.len()))
+}
+
+impl Mesh {
+    /*
+    Returns a triangle soup copy of the mesh: every corner gets its own vertex, in triangle
+    order, and the indices simply count up. Triangles with out-of-range indices and trailing
+    indices are dropped.
+    */
+    pub fn to_non_indexed(&self) -> Mesh {
+        let vertices: Vec<Vertex> = valid_triangles(self)
+            .flatten()
+            .map(|index| self.vertices[index as usize])
+            .collect();
+
+        Mesh {
+            name: self.name.clone(),
+            indices: Indices::for_vertices((0..vertices.len() as u32).collect(), vertices.len()),
+            vertices,
+            material: self.material,
+            bounds: None,
+            instances: self.instances.clone(),
+            extras: self.extras.clone()
+        }
+    }
+
+    /*
+    Returns a copy of the mesh where vertices that are bitwise identical in every attribute are
+    shared through the index buffer. Vertices are kept in the order they are first used, so the
+    triangle order and every attribute value are preserved, and a soup produced by
+    `to_non_indexed` comes back unchanged after another expansion. Unused vertices, triangles
+    with out-of-range indices and trailing indices are dropped.
+    */
+    pub fn to_indexed(&self) -> Mesh {
+        let mut lookup: HashMap<[u32; 15], u32> = HashMap::new();
+        let mut vertices = Vec::new();
+        let indices: Vec<u32> = valid_triangles(self)
+            .flatten()
+            .map(|index| {
+                let vertex = self.vertices[index as usize];
+                *lookup.entry(vertex_bits(&vertex)).or_insert_with(|| {
+                    vertices.push(vertex);
+                    vertices.len() as u32 - 1
+                })
+            })
+            .collect();
+
+        Mesh {
+            name: self.name.clone(),
+            indices: Indices::for_vertices(indices, vertices.len()),
+            vertices,
+            material: self.material,
+            bounds: None,
+            instances: self.instances.clone(),
+            extras: self.extras.clone()
+        }
+    }
+}
@@ -0,0 +1,268 @@
+use std::slice;
+use crate::model::loader::Mesh;
+
+/*
+The `Indices` enum stores the index buffer of a mesh with 16-bit or 32-bit elements. Meshes with
+at most `u16::MAX` vertices fit in `U16`, which halves the index memory and can be uploaded as
+is; 65535 itself is never used, since graphics APIs reserve it to restart strips. The helpers
+read and write indices as `u32` whatever the storage, and writing an index too large for
+`U16` widens the whole buffer to `U32`.
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Indices {
+    U16(Vec<u16>),
+    U32(Vec<u32>)
+}
+
+impl Default for Indices {
+    fn default() -> Self {
+        Indices::U32(Vec::new())
+    }
+}
+
+impl From<Vec<u16>> for Indices {
+    fn from(indices: Vec<u16>) -> Self {
+        Indices::U16(indices)
+    }
+}
+
+impl From<Vec<u32>> for Indices {
+    fn from(indices: Vec<u32>) -> Self {
+        Indices::U32(indices)
+    }
+}
+
+fn fits_u16(index: u32) -> bool {
+    index < u16::MAX as u32
+}
+
+impl Indices {
+    /*
+    Builds an index buffer for a mesh with `vertex_count` vertices, stored as `U16` when the
+    vertex count and every index allow it and as `U32` otherwise.
+    */
+    pub fn for_vertices(indices: Vec<u32>, vertex_count: usize) -> Self {
+        let mut indices = Indices::U32(indices);
+        if vertex_count <= u16::MAX as usize {
+            indices.shrink();
+        }
+        indices
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Indices::U16(indices) => indices.len(),
+            Indices::U32(indices) => indices.len()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_u16(&self) -> bool {
+        matches!(self, Indices::U16(_))
+    }
+
+    /*
+    Size in bytes of one index: 2 for `U16` and 4 for `U32`.
+    */
+    pub fn element_size(&self) -> usize {
+        match self {
+            Indices::U16(_) => 2,
+            Indices::U32(_) => 4
+        }
+    }
+
+    pub fn byte_size(&self) -> usize {
+        self.len() * self.element_size()
+    }
+
+    pub fn get(&self, position: usize) -> Option<u32> {
+        match self {
+            Indices::U16(indices) => indices.get(position).map(|&index| index as u32),
+            Indices::U32(indices) => indices.get(position).copied()
+        }
+    }
+
+    /*
+    Returns the three indices of a triangle, the ones starting at `triangle * 3`. Panics when the
+    triangle is past the end of the buffer.
+    */
+    pub fn triangle(&self, triangle: usize) -> [u32; 3] {
+        let start = triangle * 3;
+        match self {
+            Indices::U16(indices) => [0, 1, 2].map(|corner| indices[start + corner] as u32),
+            Indices::U32(indices) => [0, 1, 2].map(|corner| indices[start + corner])
+        }
+    }
+
+    /*
+    Replaces the index at `position`, widening the buffer to `U32` if the index does not fit
+    in 16 bits. Panics when the position is past the end of the buffer.
+    */
+    pub fn set(&mut self, position: usize, index: u32) {
+        if !fits_u16(index) {
+            self.widen();
+        }
+        match self {
+            Indices::U16(indices) => indices[position] = index as u16,
+            Indices::U32(indices) => indices[position] = index
+        }
+    }
+
+    /*
+    Appends an index, widening the buffer to `U32` if the index does not fit in 16 bits.
+    */
+    pub fn push(&mut self, index: u32) {
+        if !fits_u16(index) {
+            self.widen();
+        }
+        match self {
+            Indices::U16(indices) => indices.push(index as u16),
+            Indices::U32(indices) => indices.push(index)
+        }
+    }
+
+    pub fn extend<I: IntoIterator<Item = u32>>(&mut self, indices: I) {
+        for index in indices {
+            self.push(index);
+        }
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        match self {
+            Indices::U16(indices) => indices.truncate(len),
+            Indices::U32(indices) => indices.truncate(len)
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        match self {
+            Indices::U16(indices) => Iter::U16(indices.iter()),
+            Indices::U32(indices) => Iter::U32(indices.iter())
+        }
+    }
+
+    /*
+    Iterates over the complete triangles, ignoring trailing indices that do not form one.
+    */
+    pub fn triangles(&self) -> impl Iterator<Item = [u32; 3]> + '_ {
+        (0..self.len() / 3).map(|triangle| self.triangle(triangle))
+    }
+
+    /*
+    Replaces the whole buffer with `indices`, keeping the `U16` storage when all the new indices
+    fit in it.
+    */
+    pub fn replace(&mut self, indices: Vec<u32>) {
+        let was_u16 = self.is_u16();
+        *self = Indices::U32(indices);
+        if was_u16 {
+            self.shrink();
+        }
+    }
+
+    /*
+    Replaces every index with the one `remap` returns for it, as `replace` does.
+    */
+    pub fn remap<F: FnMut(u32) -> u32>(&mut self, remap: F) {
+        let remapped = self.iter().map(remap).collect();
+        self.replace(remapped);
+    }
+
+    pub fn to_u32(&self) -> Vec<u32> {
+        self.iter().collect()
+    }
+
+    /*
+    Converts the buffer to `U16` if every index fits in 16 bits, and tells whether the buffer is
+    now `U16`.
+    */
+    pub fn shrink(&mut self) -> bool {
+        if let Indices::U32(indices) = self {
+            if !indices.iter().all(|&index| fits_u16(index)) {
+                return false;
+            }
+            *self = Indices::U16(indices.iter().map(|&index| index as u16).collect());
+        }
+        true
+    }
+
+    pub fn widen(&mut self) {
+        if let Indices::U16(indices) = self {
+            *self = Indices::U32(indices.iter().map(|&index| index as u32).collect());
+        }
+    }
+
+    /*
+    Packs the indices into little-endian bytes at their stored width, ready to be uploaded as an
+    index buffer of `element_size` bytes per index.
+    */
+    pub fn to_le_bytes(&self) -> Vec<u8> {
+        match self {
+            Indices::U16(indices) => indices.iter().flat_map(|index| index.to_le_bytes()).collect(),
+            Indices::U32(indices) => indices.iter().flat_map(|index| index.to_le_bytes()).collect()
+        }
+    }
+}
+
+/*
+The `Iter` enum walks an index buffer, yielding every index as a `u32`.
+*/
+#[derive(Clone, Debug)]
+pub enum Iter<'a> {
+    U16(slice::Iter<'a, u16>),
+    U32(slice::Iter<'a, u32>)
+}
+
+impl Iterator for Iter<'_> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        match self {
+            Iter::U16(iter) => iter.next().map(|&index| index as u32),
+            Iter::U32(iter) => iter.next().copied()
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Iter::U16(iter) => iter.size_hint(),
+            Iter::U32(iter) => iter.size_hint()
+        }
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl<'a> IntoIterator for &'a Indices {
+    type Item = u32;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl Mesh {
+    /*
+    Stores the indices as `U16` when the vertex count allows it, and tells whether they are now
+    `U16`. Meshes with more than `u16::MAX` vertices, or with indices that do not fit, keep
+    32-bit indices.
+    */
+    pub fn shrink_indices(&mut self) -> bool {
+        if self.vertices.len() <= u16::MAX as usize {
+            self.indices.shrink();
+        }
+        self.indices.is_u16()
+    }
+
+    /*
+    Stores the indices as `U32`, for code or APIs that only take 32-bit indices.
+    */
+    pub fn widen_indices(&mut self) {
+        self.indices.widen();
+    }
+}
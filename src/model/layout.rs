@@ -0,0 +1,191 @@
+use crate::model::loader::{Mesh, Vertex};
+
+/*
+Size in bytes of one vertex in the buffer written by `Mesh::interleaved_bytes`, which is also
+the size of `Vertex` itself.
+*/
+pub const VERTEX_STRIDE: usize = 64;
+
+const _: () = assert!(std::mem::size_of::<Vertex>() == VERTEX_STRIDE);
+
+/*
+The `VertexSemantic` enum names the attribute a `VertexAttribute` holds.
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VertexSemantic {
+    Position,
+    Normal,
+    TexCoord,
+    Tangent,
+    Barycentric
+}
+
+/*
+The `VertexFormat` enum describes the components of an attribute, named after wgpu's vertex
+formats. The `Float32` formats are little-endian 32-bit floats, like OpenGL's `GL_FLOAT` with
+two to four components, and `Unorm8x4` is four bytes mapping 0..=255 onto [0, 1], like
+`GL_UNSIGNED_BYTE` with normalization enabled.
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VertexFormat {
+    Float32x2,
+    Float32x3,
+    Float32x4,
+    Unorm8x4
+}
+
+impl VertexFormat {
+    pub fn component_count(&self) -> usize {
+        match self {
+            VertexFormat::Float32x2 => 2,
+            VertexFormat::Float32x3 => 3,
+            VertexFormat::Float32x4 | VertexFormat::Unorm8x4 => 4
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        match self {
+            VertexFormat::Unorm8x4 => 4,
+            _ => self.component_count() * 4
+        }
+    }
+
+    /*
+    Appends the first `component_count` values in this format. Values written as `Unorm8x4` are
+    clamped to [0, 1] and rounded to the nearest step.
+    */
+    fn write(&self, values: [f32; 4], bytes: &mut Vec<u8>) {
+        for &value in &values[..self.component_count()] {
+            match self {
+                VertexFormat::Unorm8x4 => bytes.push((value.clamp(0.0, 1.0) * 255.0).round() as u8),
+                _ => bytes.extend_from_slice(&value.to_le_bytes())
+            }
+        }
+    }
+}
+
+/*
+The `VertexAttribute` struct places one attribute within an interleaved vertex: what it holds,
+how it is stored and its byte offset from the start of the vertex.
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VertexAttribute {
+    pub semantic: VertexSemantic,
+    pub format: VertexFormat,
+    pub offset: usize
+}
+
+const VERTEX_LAYOUT: [VertexAttribute; 5] = [
+    VertexAttribute { semantic: VertexSemantic::Position, format: VertexFormat::Float32x3, offset: 0 },
+    VertexAttribute { semantic: VertexSemantic::Normal, format: VertexFormat::Float32x3, offset: 12 },
+    VertexAttribute { semantic: VertexSemantic::TexCoord, format: VertexFormat::Float32x2, offset: 24 },
+    VertexAttribute { semantic: VertexSemantic::Tangent, format: VertexFormat::Float32x4, offset: 32 },
+    VertexAttribute { semantic: VertexSemantic::Barycentric, format: VertexFormat::Float32x3, offset: 48 }
+];
+
+/*
+The `PackedVertices` struct holds a vertex buffer written by `Mesh::pack_vertices`, along with
+the distance in bytes between consecutive vertices and where each requested attribute sits
+within a vertex.
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub struct PackedVertices {
+    pub bytes: Vec<u8>,
+    pub stride: usize,
+    pub attributes: Vec<VertexAttribute>
+}
+
+/*
+Describes the layout of the buffer written by `Mesh::interleaved_bytes`: the position at byte
+0, the normal at 12, the texture coordinates at 24, the tangent, with its handedness in `w`, at
+32 and the barycentric coordinates at 48, in a vertex `VERTEX_STRIDE` bytes long whose last four
+bytes are zero.
+*/
+pub fn vertex_layout() -> &'static [VertexAttribute] {
+    &VERTEX_LAYOUT
+}
+
+impl Mesh {
+    /*
+    Packs the vertices into one buffer following `vertex_layout`, ready to be uploaded as a
+    vertex buffer. The buffer is `vertices.len() * VERTEX_STRIDE` bytes long.
+    */
+    pub fn interleaved_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.vertices.len() * VERTEX_STRIDE);
+        for vertex in &self.vertices {
+            let components = vertex
+                .position
+                .to_array()
+                .into_iter()
+                .chain(vertex.normal.to_array())
+                .chain(vertex.tex_coord.to_array())
+                .chain(vertex.tangent.to_array())
+                .chain(vertex.barycentric.extend(0.0).to_array());
+            for component in components {
+                bytes.extend_from_slice(&component.to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    pub fn positions(&self) -> impl ExactSizeIterator<Item = [f32; 3]> + '_ {
+        self.vertices.iter().map(|vertex| vertex.position.to_array())
+    }
+
+    pub fn normals(&self) -> impl ExactSizeIterator<Item = [f32; 3]> + '_ {
+        self.vertices.iter().map(|vertex| vertex.normal.to_array())
+    }
+
+    pub fn tex_coords(&self) -> impl ExactSizeIterator<Item = [f32; 2]> + '_ {
+        self.vertices.iter().map(|vertex| vertex.tex_coord.to_array())
+    }
+
+    pub fn tangents(&self) -> impl ExactSizeIterator<Item = [f32; 4]> + '_ {
+        self.vertices.iter().map(|vertex| vertex.tangent.to_array())
+    }
+
+    pub fn barycentrics(&self) -> impl ExactSizeIterator<Item = [f32; 3]> + '_ {
+        self.vertices.iter().map(|vertex| vertex.barycentric.to_array())
+    }
+
+    /*
+    Packs the requested attributes, in the order given and each in its own format, into one
+    interleaved buffer ready to be uploaded as a vertex buffer. Attributes follow each other
+    without gaps and the stride is rounded up to a multiple of four bytes, as GPU APIs require.
+    A format with fewer components than the attribute drops the last ones, and one with more
+    pads them with zeros, except a position's `w` which is 1.0.
+    */
+    pub fn pack_vertices(&self, layout: &[(VertexSemantic, VertexFormat)]) -> PackedVertices {
+        let mut offset = 0;
+        let attributes: Vec<VertexAttribute> = layout
+            .iter()
+            .map(|&(semantic, format)| {
+                let attribute = VertexAttribute { semantic, format, offset };
+                offset += format.size();
+                attribute
+            })
+            .collect();
+        let stride = offset.next_multiple_of(4);
+
+        let mut bytes = Vec::with_capacity(self.vertices.len() * stride);
+        for vertex in &self.vertices {
+            for attribute in &attributes {
+                let values = match attribute.semantic {
+                    VertexSemantic::Position => vertex.position.extend(1.0).to_array(),
+                    VertexSemantic::Normal => vertex.normal.extend(0.0).to_array(),
+                    VertexSemantic::TexCoord => [vertex.tex_coord.x, vertex.tex_coord.y, 0.0, 0.0],
+                    VertexSemantic::Tangent => vertex.tangent.to_array(),
+                    VertexSemantic::Barycentric => vertex.barycentric.extend(0.0).to_array()
+                };
+                attribute.format.write(values, &mut bytes);
+            }
+            bytes.resize(bytes.len() + stride - offset, 0);
+        }
+
+        PackedVertices {
+            bytes,
+            stride,
+            attributes
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use std::path::Path;
+use crate::model::loader::{buffer_read_error, build_model, is_relative_uri, parse_document, resolve_uri, Model};
+use crate::model::LoaderOptions;
+use crate::model::LoadError;
+
+/*
+Reads the data of every buffer in the document. Relative URIs are resolved like `load_model`
+resolves them and the files read with async I/O, the
+GLB binary chunk is taken from `blob` and data URIs are decoded in place. Like `gltf::import`,
+each buffer is padded to a multiple of four bytes and checked against its declared length.
+*/
+async fn read_buffers(
+    document: &gltf::Document,
+    path: &Path,
+    mut blob: Option<Vec<u8>>
+) -> Result<Vec<gltf::buffer::Data>, LoadError> {
+    let mut buffers = Vec::new();
+    for buffer in document.buffers() {
+        let mut data = match buffer.source() {
+            gltf::buffer::Source::Bin => blob
+                .take()
+                .ok_or_else(|| LoadError::InvalidData("Buffer refers to a missing binary chunk".to_string()))?,
+            gltf::buffer::Source::Uri(uri) if is_relative_uri(uri) => {
+                let buffer_path = resolve_uri(uri, path);
+                tokio::fs::read(&buffer_path).await.map_err(|error| buffer_read_error(buffer.index(), &buffer_path, error))?
+            },
+            gltf::buffer::Source::Uri(_) => {
+                gltf::buffer::Data::from_source(buffer.source(), path.parent())?.0
+            }
+        };
+
+        if data.len() < buffer.length() {
+            return Err(LoadError::InvalidData(format!("Buffer {} is shorter than its declared length", buffer.index())));
+        }
+        while data.len() % 4 != 0 {
+            data.push(0);
+        }
+        buffers.push(gltf::buffer::Data(data));
+    }
+    Ok(buffers)
+}
+
+/*
+Loads a model like `load_model` without blocking the async runtime. The model file and its
+buffers are read with tokio's async file I/O, then building the meshes and decoding textures,
+which also reads the texture images, runs on tokio's blocking thread pool. Failures are
+returned instead of panicking.
+*/
+pub async fn load_model_async(path: impl AsRef<Path>) -> Result<Model, LoadError> {
+    let path = path.as_ref().to_path_buf();
+    let bytes = tokio::fs::read(&path).await?;
+    let (document, blob) = parse_document(&bytes)?;
+
+    let buffers = read_buffers(&document, &path, blob).await?;
+
+    tokio::task::spawn_blocking(move || {
+        let buffers: Vec<&[u8]> = buffers.iter().map(|buffer| &buffer.0[..]).collect();
+        build_model(&document, &buffers, &path, &LoaderOptions::default(), None)
+    })
+    .await
+    .map_err(|error| LoadError::InvalidData(format!("Model processing did not complete: {}", error)))?
+}
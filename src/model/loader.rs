@@ -1,17 +1,43 @@
 use glam::*;
-use crate::model::{Texture, load_texture};
-use std::path::Path;
+use crate::model::progress::{LoadPhase, LoadProgress, Progress};
+use crate::model::resize::clamped_size;
+use crate::model::resolve::material_textures_mut;
+use crate::model::{Aabb, CoordinateSystem, Indices, LoadError, LoadWarning, LoaderOptions, MaterialHandle, NormalMode, PrimitiveLocation, ResizeFilter, Texture, TextureError, TextureLoading, TextureSource, TextureTransform};
+use crate::model::texture::decode_texture;
+use std::collections::BTreeMap;
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
 
 /*
 The `Vertex` struct represents a single vertex in a 3D mesh. It includes position and normal
-data, which are essential for rendering and lighting calculations. The `Default` trait provides
-a default vertex with zeroed position and normal.
+data, which are essential for rendering and lighting calculations. The `tangent` stores the
+tangent direction in `xyz` and the bitangent handedness (+1.0 or -1.0) in `w`, as glTF does; a
+zero tangent means the mesh has none. `barycentric` is zero unless `add_barycentric_attribute`
+filled it in for wireframe shading. The `Default` trait provides a default vertex with all
+attributes zeroed.
+
+The struct is `#[repr(C)]` and laid out as the buffer of `Mesh::interleaved_bytes`: position
+at byte 0, normal at 12, texture coordinates at 24, tangent at 32 and barycentric coordinates at
+48, followed by the four bytes of `padding`, which stay zero, for 64 bytes. The tangent's
+alignment rounds the size up to 64 anyway, and naming the last four bytes keeps the struct free
+of implicit padding while `..Default::default()` still fills it in. With the `bytemuck` feature
+it implements `Pod` and `Zeroable`, so a vertex slice can be cast to bytes and uploaded as is.
 */
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bytemuck", derive(bytemuck::Pod, bytemuck::Zeroable))]
+#[repr(C)]
 pub struct Vertex {
     pub position: Vec3,
     pub normal: Vec3,
-    pub tex_coord: Vec2
+    pub tex_coord: Vec2,
+    pub tangent: Vec4,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub barycentric: Vec3,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub padding: f32
 }
 
 impl Default for Vertex {
@@ -19,157 +45,1509 @@ impl Default for Vertex {
         Vertex {
             position: Vec3::ZERO,
             normal: Vec3::ZERO,
-            tex_coord: Vec2::ZERO
+            tex_coord: Vec2::ZERO,
+            tangent: Vec4::ZERO,
+            barycentric: Vec3::ZERO,
+            padding: 0.0
         }
     }
 }
 
 /*
 The `Mesh` struct represents a collection of vertices and indices forming a 3D object. It
-also stores a reference to the material index used for rendering the mesh.
+also stores a handle to the material of the model used for rendering the mesh. `bounds` caches the
+mesh's bounding box; the loaders always fill it, from the accessor bounds declared by the source
+file when usable and by scanning the positions otherwise. It stays private so the methods that
+move, add or drop vertices can reset it, and `Mesh::aabb` then scans the positions again; code
+editing `vertices` directly calls `Mesh::invalidate_bounds` afterwards. `instances` lists one transform
+per drawn copy of the mesh, with an empty list meaning a single copy at the identity. `name`
+is the name authored on the source mesh, shared by every primitive it contains. The loaders
+store `indices` as 16-bit whenever the vertex count allows it. `extras` holds the custom JSON
+authored in the `extras` of the source mesh, read only with the `serde_json` feature.
 */
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Mesh {
+    pub name: Option<String>,
     pub vertices: Vec<Vertex>,
-    pub indices: Vec<u32>,
-    pub material_idx: usize
+    pub indices: Indices,
+    pub material: MaterialHandle,
+    pub(crate) bounds: Option<Aabb>,
+    pub instances: Vec<Mat4>,
+    pub extras: Option<serde_json::Value>
 }
 
 /*
 The `Material` struct defines the appearance of a mesh using a base color stored as a `Vec4`.
-The `Default` trait initializes it with a white color.
+The `Default` trait initializes it with a white color and no name. The metallic and roughness
+factors scale the values read from `metallic_roughness_texture`, and `occlusion_strength` how
+much `occlusion_texture` darkens the surface; `channel_packing` tells which channel holds each
+value and whether both textures are one image. `normal_scale` scales the X and Y of the
+tangent-space normals in `normal_texture`, and `emissive_factor` the light `emissive_texture`
+emits, with no emission by default. `transmission` and `volume`
+describe refractive surfaces such as glass and are only present when the source material uses
+the matching glTF extensions; `ior` defaults to 1.5 as the glTF specification does. The
+clearcoat fields describe the lacquer layer of `KHR_materials_clearcoat`, with a factor of 0.0
+meaning no clearcoat. `unlit` is set by `KHR_materials_unlit` and asks renderers to show the
+base color as it is, without lighting. Every texture carries the `KHR_texture_transform` of the
+reference it was loaded through. `extras` holds the custom JSON of the source material, as on
+`Mesh`.
 */
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Material {
+    pub name: Option<String>,
     pub base_color: Vec4,
-    pub base_color_texture: Option<Texture>
+    pub base_color_texture: Option<Texture>,
+    pub metallic_factor: f32,
+    pub roughness_factor: f32,
+    pub metallic_roughness_texture: Option<Texture>,
+    pub normal_texture: Option<Texture>,
+    pub normal_scale: f32,
+    pub occlusion_texture: Option<Texture>,
+    pub occlusion_strength: f32,
+    pub emissive_factor: Vec3,
+    pub emissive_texture: Option<Texture>,
+    pub channel_packing: ChannelPacking,
+    pub transmission: Option<Transmission>,
+    pub ior: f32,
+    pub volume: Option<Volume>,
+    pub clearcoat_factor: f32,
+    pub clearcoat_texture: Option<Texture>,
+    pub clearcoat_roughness_factor: f32,
+    pub clearcoat_roughness_texture: Option<Texture>,
+    pub clearcoat_normal_texture: Option<Texture>,
+    pub unlit: bool,
+    pub extras: Option<serde_json::Value>
 }
 
 impl Default for Material {
     fn default() -> Self {
         Material {
+            name: None,
             base_color: Vec4::ONE,
-            base_color_texture: None
+            base_color_texture: None,
+            metallic_factor: 1.0,
+            roughness_factor: 1.0,
+            metallic_roughness_texture: None,
+            normal_texture: None,
+            normal_scale: 1.0,
+            occlusion_texture: None,
+            occlusion_strength: 1.0,
+            emissive_factor: Vec3::ZERO,
+            emissive_texture: None,
+            channel_packing: ChannelPacking::default(),
+            transmission: None,
+            ior: 1.5,
+            volume: None,
+            clearcoat_factor: 0.0,
+            clearcoat_texture: None,
+            clearcoat_roughness_factor: 0.0,
+            clearcoat_roughness_texture: None,
+            clearcoat_normal_texture: None,
+            unlit: false,
+            extras: None
+        }
+    }
+}
+
+/*
+The `TextureChannel` enum names one channel of a texture's pixels.
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TextureChannel {
+    R,
+    G,
+    B,
+    A
+}
+
+impl TextureChannel {
+    /*
+    Returns the position of the channel within a pixel, 0 for red up to 3 for alpha.
+    */
+    pub fn index(&self) -> usize {
+        match self {
+            TextureChannel::R => 0,
+            TextureChannel::G => 1,
+            TextureChannel::B => 2,
+            TextureChannel::A => 3
+        }
+    }
+}
+
+/*
+The `ChannelPacking` struct tells where a material's metallic, roughness and occlusion values
+are stored. glTF fixes the channels: metallic in blue and roughness in green of the
+metallic-roughness texture, occlusion in red of the occlusion texture. `occlusion_shared` is
+set when both textures sample the same image, as in ORM-packed assets, so a renderer can bind
+that image once and read all three values from it.
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChannelPacking {
+    pub metallic: TextureChannel,
+    pub roughness: TextureChannel,
+    pub occlusion: TextureChannel,
+    pub occlusion_shared: bool
+}
+
+impl Default for ChannelPacking {
+    fn default() -> Self {
+        ChannelPacking {
+            metallic: TextureChannel::B,
+            roughness: TextureChannel::G,
+            occlusion: TextureChannel::R,
+            occlusion_shared: false
         }
     }
 }
 
+/*
+The `Transmission` struct holds the `KHR_materials_transmission` data: the fraction of light
+transmitted through the surface and an optional texture modulating it through its red channel.
+*/
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Transmission {
+    pub factor: f32,
+    pub texture: Option<Texture>
+}
+
+/*
+The `Volume` struct holds the `KHR_materials_volume` data: the thickness of the medium below
+the surface, optionally modulated by the green channel of a texture, and how light attenuates
+while travelling through it. An infinite attenuation distance means no attenuation.
+*/
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Volume {
+    pub thickness_factor: f32,
+    pub thickness_texture: Option<Texture>,
+    pub attenuation_color: Vec3,
+    pub attenuation_distance: f32
+}
+
 /*
 The `Model` struct aggregates multiple meshes and their associated materials, representing
 a complete 3D object that can be rendered.
 */
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Model {
     pub meshes: Vec<Mesh>,
     pub materials: Vec<Material>
 }
 
 /*
-Processes a single GLTF node, extracting its meshes and associated materials. This function reads
-vertex positions, normals, and indices, and maps them to custom `Mesh` and `Vertex` structs.
-It also handles material assignment and updates the `materials` array accordingly.
+Reads the min/max declared on a primitive's position accessor. The values are only trusted
+when the accessor stores plain floats and the bounds are finite and well ordered; quantized
+accessors declare their bounds in integer space, so they are ignored.
 */
-fn process_node(
+fn accessor_bounds(primitive: &gltf::Primitive) -> Option<Aabb> {
+    let accessor = primitive.get(&gltf::Semantic::Positions)?;
+    if accessor.data_type() != gltf::accessor::DataType::F32 || accessor.normalized() {
+        return None;
+    }
+
+    let read_vec3 = |value: gltf::json::Value| -> Option<Vec3> {
+        let values = value.as_array()?;
+        if values.len() != 3 {
+            return None;
+        }
+        let mut components = [0.0; 3];
+        for (component, value) in components.iter_mut().zip(values) {
+            *component = value.as_f64()? as f32;
+        }
+        Some(Vec3::from(components))
+    };
+
+    let min = read_vec3(accessor.min()?)?;
+    let max = read_vec3(accessor.max()?)?;
+    if !min.is_finite() || !max.is_finite() || min.cmpgt(max).any() {
+        return None;
+    }
+    Some(Aabb::new(min, max))
+}
+
+/*
+Returns the bounding box of a loaded primitive, reusing the bounds declared on its position
+accessor so the vertices only need to be scanned when the file does not provide usable ones.
+*/
+pub(crate) fn primitive_bounds(primitive: &gltf::Primitive, vertices: &[Vertex]) -> Aabb {
+    accessor_bounds(primitive)
+        .unwrap_or_else(|| Aabb::from_points(vertices.iter().map(|vertex| vertex.position)))
+}
+
+/*
+Reads the per-instance transforms a node declares through the `EXT_mesh_gpu_instancing`
+extension. Each of the TRANSLATION, ROTATION and SCALE attributes is optional and falls back
+to the identity; rotations may be stored as normalized integers, as the extension allows.
+*/
+pub(crate) fn read_instances(
     node: &gltf::Node,
-    buffers: &[gltf::buffer::Data],
-    meshes: &mut Vec<Mesh>,
-    materials: &mut [Material],
-    file_path: &str
-) {
-    if let Some(mesh) = node.mesh() {
-        for primitive in mesh.primitives() {
-            if primitive.mode() == gltf::mesh::Mode::Triangles {
-                let reader = primitive.reader(
-                    |buffer| Some(&buffers[buffer.index()])
-                );
-
-                let positions = {
-                    let iter = reader
-                        .read_positions()
-                        .expect("Failed to process mesh node. (Vertices must have positions)");
-
-                    iter.map(|arr| -> Vec3 { Vec3::from(arr) }).collect::<Vec<_>>()
-                };
-
-                let mut vertices: Vec<Vertex> = positions
-                    .into_iter()
-                    .map(|position| {
-                        Vertex {
-                             position,
-                             ..Default::default()
-                        }
-                }).collect();
-
-                if let Some(normals) = reader.read_normals() {
-                    for (i, normal) in normals.enumerate() {
-                        vertices[i].normal = Vec3::from(normal);
-                    }
-                }
+    document: &gltf::Document,
+    buffers: &[&[u8]]
+) -> Vec<Mat4> {
+    use gltf::accessor::{DataType, Iter};
+    use gltf::animation::util::Rotations;
 
-                if let Some(tex_coords) = reader.read_tex_coords(0) {
-                    for (i, tex_coord) in tex_coords.into_f32().enumerate() {
-                        vertices[i].tex_coord = Vec2::from(tex_coord);
-                    }
-                }
+    let attributes = match node
+        .extension_value("EXT_mesh_gpu_instancing")
+        .and_then(|extension| extension.get("attributes"))
+    {
+        Some(attributes) => attributes,
+        None => return Vec::new()
+    };
+
+    let accessor = |name: &str| {
+        attributes
+            .get(name)
+            .and_then(|index| index.as_u64())
+            .and_then(|index| document.accessors().nth(index as usize))
+    };
+    let get_buffer_data = |buffer: gltf::Buffer| buffers.get(buffer.index()).copied();
+
+    let translations: Option<Vec<Vec3>> = accessor("TRANSLATION")
+        .filter(|accessor| accessor.data_type() == DataType::F32)
+        .and_then(|accessor| Iter::<[f32; 3]>::new(accessor, get_buffer_data))
+        .map(|iter| iter.map(Vec3::from).collect());
 
-                let indices = reader
-                    .read_indices()
-                    .map(|read_indices| {
-                        read_indices.into_u32().collect::<Vec<_>>()
-                    }).expect("Failed to process mesh node. (Indices are required)");
-                
-                let prim_material = primitive.material();
-                let pbr = prim_material.pbr_metallic_roughness();
-                let material_idx = primitive.material().index().unwrap_or(0);
-
-                let material = &mut materials[material_idx];
-                material.base_color = Vec4::from(pbr.base_color_factor());
-                if let Some(base_color_texture) = pbr.base_color_texture() {
-                    if let gltf::image::Source::Uri { uri, .. } = base_color_texture.texture().source().source() {
-                        let model_path = Path::new(file_path);
-                        let texture_path = model_path.parent().unwrap_or_else(|| Path::new("./")).join(uri);
-                        let texture_path_str = texture_path.into_os_string().into_string().unwrap();
-
-                        material.base_color_texture = Some(load_texture(&texture_path_str));
-                    }
+    let scales: Option<Vec<Vec3>> = accessor("SCALE")
+        .filter(|accessor| accessor.data_type() == DataType::F32)
+        .and_then(|accessor| Iter::<[f32; 3]>::new(accessor, get_buffer_data))
+        .map(|iter| iter.map(Vec3::from).collect());
+
+    let rotations: Option<Vec<Quat>> = accessor("ROTATION")
+        .and_then(|accessor| match accessor.data_type() {
+            DataType::I8 => Iter::new(accessor, get_buffer_data).map(Rotations::I8),
+            DataType::U8 => Iter::new(accessor, get_buffer_data).map(Rotations::U8),
+            DataType::I16 => Iter::new(accessor, get_buffer_data).map(Rotations::I16),
+            DataType::U16 => Iter::new(accessor, get_buffer_data).map(Rotations::U16),
+            DataType::F32 => Iter::new(accessor, get_buffer_data).map(Rotations::F32),
+            _ => None
+        })
+        .map(|rotations| rotations.into_f32().map(Quat::from_array).collect());
+
+    let count = [
+        translations.as_ref().map(Vec::len),
+        rotations.as_ref().map(Vec::len),
+        scales.as_ref().map(Vec::len)
+    ]
+    .into_iter()
+    .flatten()
+    .min()
+    .unwrap_or(0);
+
+    (0..count)
+        .map(|i| {
+            Mat4::from_scale_rotation_translation(
+                scales.as_ref().map_or(Vec3::ONE, |scales| scales[i]),
+                rotations.as_ref().map_or(Quat::IDENTITY, |rotations| rotations[i].normalize()),
+                translations.as_ref().map_or(Vec3::ZERO, |translations| translations[i])
+            )
+        })
+        .collect()
+}
+
+/*
+Decodes the percent-encoded bytes of a URI, as glTF asks for image and buffer URIs, so
+`my%20texture.png` names `my texture.png`. Malformed escapes are kept as written, and a URI
+that does not decode to UTF-8 is returned unchanged.
+*/
+fn decode_uri(uri: &str) -> String {
+    let bytes = uri.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut position = 0;
+    while position < bytes.len() {
+        let escaped = bytes
+            .get(position + 1..position + 3)
+            .filter(|_| bytes[position] == b'%')
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                position += 3;
+            },
+            None => {
+                decoded.push(bytes[position]);
+                position += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).unwrap_or_else(|_| uri.to_string())
+}
+
+/*
+Resolves the path of a buffer or image referenced by a relative URI, once percent-decoded,
+against the directory of the model file. When nothing exists there and the model path goes
+through a symbolic link, the directory the link points into is tried next, so a model linked
+from elsewhere still finds the files next to it. The path as given is returned when neither
+exists, for the error to name it.
+*/
+pub(crate) fn resolve_uri(uri: &str, file_path: &Path) -> PathBuf {
+    let relative = decode_uri(uri);
+    let path = file_path.parent().unwrap_or_else(|| Path::new("./")).join(&relative);
+    if path.exists() {
+        return path;
+    }
+    std::fs::canonicalize(file_path)
+        .ok()
+        .and_then(|canonical| Some(canonical.parent()?.join(&relative)))
+        .filter(|canonical| canonical.exists())
+        .unwrap_or(path)
+}
+
+/*
+Tells whether a URI names a file relative to the model, as opposed to a data URI or another
+scheme, which the glTF crate reads itself.
+*/
+pub(crate) fn is_relative_uri(uri: &str) -> bool {
+    !uri.contains(':')
+}
+
+/*
+Decodes a glTF image. Images referenced by URI are first asked of the texture resolver, when
+the options carry one, and otherwise resolved relative to the model file; images stored in a
+buffer view, as in GLB files, are decoded from the buffer. Fails with the reason the image could
+not be read or decoded.
+*/
+fn decode_image(
+    image: &gltf::Image,
+    buffers: &[&[u8]],
+    file_path: &Path,
+    options: &LoaderOptions
+) -> Result<Texture, TextureError> {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::info_span!("decode_image", image = image.index()).entered();
+
+    match image.source() {
+        gltf::image::Source::Uri { uri, .. } => {
+            if let Some(bytes) = options.texture_resolver.as_ref().and_then(|resolver| resolver(uri)) {
+                return decode_texture(&bytes).ok_or_else(|| TextureError::Decode(format!("{} from the texture resolver", uri)));
+            }
+            TextureSource::File(resolve_uri(uri, file_path)).load()
+        },
+        gltf::image::Source::View { view, .. } => {
+            let bytes = buffers
+                .get(view.buffer().index())
+                .and_then(|buffer| buffer.get(view.offset()..view.offset() + view.length()))
+                .ok_or_else(|| TextureError::InvalidData(format!("Buffer view {} lies outside its buffer", view.index())))?;
+            decode_texture(bytes).ok_or_else(|| TextureError::Decode(format!("image in buffer view {}", view.index())))
+        }
+    }
+}
+
+/*
+Records where a glTF image lives without decoding it, copying the encoded bytes of images
+stored in a buffer view or handed over by the texture resolver.
+*/
+fn image_source(
+    image: &gltf::Image,
+    buffers: &[&[u8]],
+    file_path: &Path,
+    options: &LoaderOptions
+) -> Option<TextureSource> {
+    match image.source() {
+        gltf::image::Source::Uri { uri, .. } => {
+            match options.texture_resolver.as_ref().and_then(|resolver| resolver(uri)) {
+                Some(bytes) => Some(TextureSource::Embedded(Arc::from(bytes))),
+                None => Some(TextureSource::File(resolve_uri(uri, file_path)))
+            }
+        },
+        gltf::image::Source::View { view, .. } => {
+            let buffer = buffers.get(view.buffer().index())?;
+            let bytes = buffer.get(view.offset()..view.offset() + view.length())?;
+            Some(TextureSource::Embedded(Arc::from(bytes)))
+        }
+    }
+}
+
+/*
+The color of the texture `LoaderOptions::placeholder_textures` puts in place of an image that
+failed to load.
+*/
+const PLACEHOLDER_COLOR: Vec4 = Vec4::new(1.0, 0.0, 1.0, 1.0);
+
+/*
+The `TextureCache` struct loads each image of a document at most once and hands out copies
+afterwards, so an image shared by several textures or materials is only decoded, or only copied
+when deferred, once. With `TextureLoading::Skip` no image is touched. A surveying cache loads
+nothing and only records which images textures ask for, so `load_nodes` can load exactly those
+up front. Every image has its own `OnceLock`, which makes the cache safe to share between the
+threads of the `parallel` feature. An image that failed keeps the reason, for the warning
+reporting it. `color_images` marks the images materials use as base color or emissive, which
+`LoaderOptions::max_texture_size` shrinks as sRGB colors.
+*/
+struct TextureCache<'a> {
+    buffers: &'a [&'a [u8]],
+    file_path: &'a Path,
+    options: &'a LoaderOptions,
+    surveying: bool,
+    requested: Vec<AtomicBool>,
+    color_images: Vec<bool>,
+    images: Vec<OnceLock<Result<Texture, String>>>
+}
+
+impl<'a> TextureCache<'a> {
+    fn new(
+        document: &gltf::Document,
+        buffers: &'a [&'a [u8]],
+        file_path: &'a Path,
+        options: &'a LoaderOptions,
+        surveying: bool
+    ) -> Self {
+        let mut color_images = vec![false; document.images().len()];
+        for material in document.materials() {
+            let textures = [
+                material.pbr_metallic_roughness().base_color_texture().map(|info| info.texture()),
+                material.emissive_texture().map(|info| info.texture())
+            ];
+            for texture in textures.into_iter().flatten() {
+                if let Some(color) = color_images.get_mut(texture.source().index()) {
+                    *color = true;
                 }
+            }
+        }
+
+        TextureCache {
+            buffers,
+            file_path,
+            options,
+            surveying,
+            requested: document.images().map(|_| AtomicBool::new(false)).collect(),
+            color_images,
+            images: document.images().map(|_| OnceLock::new()).collect()
+        }
+    }
+
+    fn load_image(&self, image: &gltf::Image) -> Result<Texture, String> {
+        self.images
+            .get(image.index())
+            .ok_or_else(|| format!("Image {} does not exist", image.index()))?
+            .get_or_init(|| match self.options.texture_loading {
+                TextureLoading::Eager => decode_image(image, self.buffers, self.file_path, self.options)
+                    .map(|texture| self.clamp_size(image, texture))
+                    .map_err(|error| error.reason()),
+                TextureLoading::Deferred => image_source(image, self.buffers, self.file_path, self.options)
+                    .map(Texture::deferred)
+                    .ok_or_else(|| "Image data lies outside its buffer".to_string()),
+                TextureLoading::Skip => Err("Textures are skipped".to_string())
+            })
+            .clone()
+    }
+
+    /*
+    Shrinks a decoded image to `LoaderOptions::max_texture_size`, keeping its aspect ratio.
+    */
+    fn clamp_size(&self, image: &gltf::Image, texture: Texture) -> Texture {
+        let size = self.options.max_texture_size.and_then(|limit| clamped_size(texture.width(), texture.height(), limit));
+        match size {
+            Some((width, height)) if self.color_images.get(image.index()).copied().unwrap_or(false) => {
+                texture.resized(width, height, ResizeFilter::Box)
+            },
+            Some((width, height)) => texture.resized_linear(width, height, ResizeFilter::Box),
+            None => texture
+        }
+    }
+
+    fn load(&self, texture: &gltf::Texture) -> Option<Texture> {
+        if self.options.texture_loading == TextureLoading::Skip {
+            return None;
+        }
+        let image = texture.source();
+        if self.surveying {
+            self.requested.get(image.index())?.store(true, Ordering::Relaxed);
+            return None;
+        }
+        match self.load_image(&image) {
+            Ok(texture) => Some(texture),
+            Err(_) if self.options.placeholder_textures => Some(Texture::solid(PLACEHOLDER_COLOR)),
+            Err(_) => None
+        }
+    }
+
+    fn requested_images(&self) -> Vec<usize> {
+        (0..self.requested.len())
+            .filter(|&index| self.requested[index].load(Ordering::Relaxed))
+            .collect()
+    }
+}
+
+/*
+Reads a `KHR_texture_transform` object from the extensions of a texture info. Missing fields
+keep their identity values.
+*/
+fn read_texture_transform(extension: Option<&gltf::json::Value>) -> TextureTransform {
+    let mut transform = TextureTransform::default();
+    let extension = match extension {
+        Some(extension) => extension,
+        None => return transform
+    };
+
+    let read_vec2 = |name: &str| -> Option<Vec2> {
+        let values = extension.get(name)?.as_array()?;
+        match values.as_slice() {
+            [x, y] => Some(Vec2::new(x.as_f64()? as f32, y.as_f64()? as f32)),
+            _ => None
+        }
+    };
+    if let Some(offset) = read_vec2("offset") {
+        transform.offset = offset;
+    }
+    if let Some(scale) = read_vec2("scale") {
+        transform.scale = scale;
+    }
+    if let Some(rotation) = extension.get("rotation").and_then(|rotation| rotation.as_f64()) {
+        transform.rotation = rotation as f32;
+    }
+    transform
+}
+
+/*
+Loads a texture referenced by a material along with the texture coordinate set the reference
+names and its texture transform. A `texCoord` in the `KHR_texture_transform` object overrides
+the set, as the extension defines.
+*/
+fn load_reference(
+    texture: &gltf::Texture,
+    tex_coord: u32,
+    transform: Option<&gltf::json::Value>,
+    textures: &TextureCache
+) -> Option<Texture> {
+    let tex_coord = transform
+        .and_then(|transform| transform.get("texCoord"))
+        .and_then(|tex_coord| tex_coord.as_u64())
+        .map_or(tex_coord, |tex_coord| tex_coord as u32);
+    let transform = read_texture_transform(transform);
+    textures.load(texture).map(|texture| texture.with_transform(transform).with_tex_coord(tex_coord))
+}
+
+fn load_info_texture(info: &gltf::texture::Info, textures: &TextureCache) -> Option<Texture> {
+    load_reference(&info.texture(), info.tex_coord(), info.extension_value("KHR_texture_transform"), textures)
+}
+
+fn load_normal_texture(info: &gltf::material::NormalTexture, textures: &TextureCache) -> Option<Texture> {
+    load_reference(&info.texture(), info.tex_coord(), info.extension_value("KHR_texture_transform"), textures)
+}
+
+fn load_occlusion_texture(info: &gltf::material::OcclusionTexture, textures: &TextureCache) -> Option<Texture> {
+    load_reference(&info.texture(), info.tex_coord(), info.extension_value("KHR_texture_transform"), textures)
+}
+
+/*
+Loads the texture referenced by a texture info object found in raw extension JSON, such as
+`{"index": 2, "texCoord": 0}`, along with its texture coordinate set and texture transform.
+*/
+fn load_extension_texture(
+    info: Option<&gltf::json::Value>,
+    document: &gltf::Document,
+    textures: &TextureCache
+) -> Option<Texture> {
+    let info = info?;
+    let index = info.get("index")?.as_u64()? as usize;
+    let texture = document.textures().nth(index)?;
+    let tex_coord = info.get("texCoord").and_then(|tex_coord| tex_coord.as_u64()).unwrap_or(0) as u32;
+    let transform = info.get("extensions").and_then(|extensions| extensions.get("KHR_texture_transform"));
+    load_reference(&texture, tex_coord, transform, textures)
+}
+
+/*
+Reads `KHR_materials_clearcoat`, which the glTF crate does not expose as a typed API, straight
+from the material's extension JSON.
+*/
+fn process_clearcoat(
+    source: &gltf::Material,
+    material: &mut Material,
+    document: &gltf::Document,
+    textures: &TextureCache
+) {
+    let clearcoat = match source.extension_value("KHR_materials_clearcoat") {
+        Some(clearcoat) => clearcoat,
+        None => return
+    };
+
+    let factor = |name: &str| clearcoat.get(name).and_then(|value| value.as_f64()).unwrap_or(0.0) as f32;
+    material.clearcoat_factor = factor("clearcoatFactor");
+    material.clearcoat_roughness_factor = factor("clearcoatRoughnessFactor");
+
+    let texture = |name: &str| load_extension_texture(clearcoat.get(name), document, textures);
+    material.clearcoat_texture = texture("clearcoatTexture");
+    material.clearcoat_roughness_texture = texture("clearcoatRoughnessTexture");
+    material.clearcoat_normal_texture = texture("clearcoatNormalTexture");
+}
+
+/*
+Fills a `Material` from its glTF counterpart: the base color, metallic-roughness, normal,
+occlusion and emissive factors and textures, and the transmission, index of refraction, volume, clearcoat and unlit extensions when the material
+declares them.
+*/
+fn process_material(
+    source: &gltf::Material,
+    material: &mut Material,
+    document: &gltf::Document,
+    textures: &TextureCache
+) {
+    let pbr = source.pbr_metallic_roughness();
+    material.base_color = Vec4::from(pbr.base_color_factor());
+    if let Some(base_color_texture) = pbr.base_color_texture() {
+        material.base_color_texture = load_info_texture(&base_color_texture, textures);
+    }
+
+    material.metallic_factor = pbr.metallic_factor();
+    material.roughness_factor = pbr.roughness_factor();
+    let metallic_roughness = pbr.metallic_roughness_texture();
+    material.metallic_roughness_texture = metallic_roughness.as_ref().and_then(|info| load_info_texture(info, textures));
+    material.normal_texture = source.normal_texture().and_then(|info| load_normal_texture(&info, textures));
+    material.normal_scale = source.normal_texture().map_or(1.0, |info| info.scale());
+    material.emissive_factor = Vec3::from(source.emissive_factor());
+    material.emissive_texture = source.emissive_texture().and_then(|info| load_info_texture(&info, textures));
+    let occlusion = source.occlusion_texture();
+    material.occlusion_texture = occlusion.as_ref().and_then(|info| load_occlusion_texture(info, textures));
+    material.occlusion_strength = occlusion.as_ref().map_or(1.0, |info| info.strength());
+    material.channel_packing.occlusion_shared = match (&metallic_roughness, &occlusion) {
+        (Some(metallic_roughness), Some(occlusion)) => {
+            metallic_roughness.texture().source().index() == occlusion.texture().source().index()
+        },
+        _ => false
+    };
+
+    material.transmission = source.transmission().map(|transmission| Transmission {
+        factor: transmission.transmission_factor(),
+        texture: transmission
+            .transmission_texture()
+            .and_then(|info| load_info_texture(&info, textures))
+    });
+
+    material.ior = source.ior().unwrap_or(1.5);
+
+    material.volume = source.volume().map(|volume| Volume {
+        thickness_factor: volume.thickness_factor(),
+        thickness_texture: volume
+            .thickness_texture()
+            .and_then(|info| load_info_texture(&info, textures)),
+        attenuation_color: Vec3::from(volume.attenuation_color()),
+        attenuation_distance: volume.attenuation_distance()
+    });
+
+    process_clearcoat(source, material, document, textures);
+    material.unlit = source.unlit();
+}
+
+/*
+The vertices and indices read from one primitive.
+*/
+type Geometry = (Vec<Vertex>, Vec<u32>);
+
+/*
+Decodes a primitive whose geometry is compressed rather than stored in accessors. Only
+KHR_draco_mesh_compression is understood, and only with the `draco` feature enabled. Malformed
+compressed data fails the load with `LoadError::InvalidData` naming the primitive.
+*/
+#[cfg(feature = "draco")]
+fn decode_compressed(
+    primitive: &gltf::Primitive,
+    document: &gltf::Document,
+    buffers: &[&[u8]],
+    location: &PrimitiveLocation
+) -> Option<Result<Geometry, LoadError>> {
+    crate::model::draco::decode_primitive(primitive, document, buffers).map(|decoded| {
+        decoded.map_err(|error| match error {
+            LoadError::InvalidData(message) => LoadError::InvalidData(format!("{} in {}", message, location)),
+            error => error
+        })
+    })
+}
+
+#[cfg(not(feature = "draco"))]
+fn decode_compressed(
+    _primitive: &gltf::Primitive,
+    _document: &gltf::Document,
+    _buffers: &[&[u8]],
+    _location: &PrimitiveLocation
+) -> Option<Result<Geometry, LoadError>> {
+    None
+}
+
+/*
+Reads an accessor as float vectors, lazily, so callers can consume the values without an
+intermediate array. Besides plain floats, the integer component types allowed by
+KHR_mesh_quantization are converted: normalized integers are mapped to [0, 1] or [-1, 1] as the
+glTF specification defines, and the others are converted as they are. Sparse accessors come back
+with their substitutions applied over the base data, or over zeros when they have no buffer view.
+*/
+fn read_vectors<'a, const N: usize>(
+    accessor: gltf::Accessor<'a>,
+    buffers: &'a [&'a [u8]]
+) -> Option<Box<dyn Iterator<Item = [f32; N]> + 'a>>
+where
+    [f32; N]: gltf::accessor::Item,
+    [i8; N]: gltf::accessor::Item,
+    [u8; N]: gltf::accessor::Item,
+    [i16; N]: gltf::accessor::Item,
+    [u16; N]: gltf::accessor::Item
+{
+    use gltf::accessor::{DataType, Iter};
+
+    let get_buffer_data = |buffer: gltf::Buffer| buffers.get(buffer.index()).copied();
+    let normalized = accessor.normalized();
+    let scale = move |value: f32, max: f32| if normalized { (value / max).max(-1.0) } else { value };
+
+    match accessor.data_type() {
+        DataType::F32 => Some(Box::new(Iter::<[f32; N]>::new(accessor, get_buffer_data)?)),
+        DataType::I8 => Some(Box::new(Iter::<[i8; N]>::new(accessor, get_buffer_data)?
+            .map(move |vector| vector.map(|value| scale(value as f32, 127.0))))),
+        DataType::U8 => Some(Box::new(Iter::<[u8; N]>::new(accessor, get_buffer_data)?
+            .map(move |vector| vector.map(|value| scale(value as f32, 255.0))))),
+        DataType::I16 => Some(Box::new(Iter::<[i16; N]>::new(accessor, get_buffer_data)?
+            .map(move |vector| vector.map(|value| scale(value as f32, 32767.0))))),
+        DataType::U16 => Some(Box::new(Iter::<[u16; N]>::new(accessor, get_buffer_data)?
+            .map(move |vector| vector.map(|value| scale(value as f32, 65535.0))))),
+        DataType::U32 => None
+    }
+}
+
+/*
+Turns an optional attribute stream into an endless one, continuing with zeros once the values
+run out or from the start when the attribute is missing.
+*/
+fn padded_stream<'a, const N: usize>(
+    values: Option<Box<dyn Iterator<Item = [f32; N]> + 'a>>
+) -> impl Iterator<Item = [f32; N]> + 'a {
+    values.into_iter().flatten().chain(std::iter::repeat([0.0; N]))
+}
+
+/*
+The attributes a primitive may lack, whose vertices then get zeros, with the names glTF gives
+them.
+*/
+const OPTIONAL_ATTRIBUTES: [(gltf::Semantic, &str); 3] = [
+    (gltf::Semantic::Normals, "NORMAL"),
+    (gltf::Semantic::TexCoords(0), "TEXCOORD_0"),
+    (gltf::Semantic::Tangents, "TANGENT")
+];
+
+/*
+Reads the optional attribute at `attribute` of `OPTIONAL_ATTRIBUTES`, noting in `warnings` when
+the primitive has it but fewer readable values than its `vertex_count` vertices. Missing
+attributes are noted by `process_primitive`, since compressed primitives lack them too.
+*/
+fn read_attribute<'a, const N: usize>(
+    primitive: &gltf::Primitive<'a>,
+    attribute: usize,
+    buffers: &'a [&'a [u8]],
+    vertex_count: usize,
+    location: &PrimitiveLocation,
+    warnings: &mut Vec<LoadWarning>
+) -> Option<Box<dyn Iterator<Item = [f32; N]> + 'a>>
+where
+    [f32; N]: gltf::accessor::Item,
+    [i8; N]: gltf::accessor::Item,
+    [u8; N]: gltf::accessor::Item,
+    [i16; N]: gltf::accessor::Item,
+    [u16; N]: gltf::accessor::Item
+{
+    let (semantic, name) = &OPTIONAL_ATTRIBUTES[attribute];
+    let accessor = primitive.get(semantic)?;
+    let count = accessor.count();
+    let values = read_vectors::<N>(accessor, buffers);
+    let count = if values.is_some() { count } else { 0 };
+    if count < vertex_count {
+        warnings.push(LoadWarning::ShortAttribute {
+            location: location.clone(),
+            semantic: name,
+            count,
+            expected: vertex_count
+        });
+    }
+    values
+}
+
+/*
+Reads the vertices and indices of a primitive through its accessors in a single pass: the
+attribute streams are walked side by side and every vertex is built whole, with attributes the
+primitive lacks, or has too few values of, left at zero. Both arrays are sized from the
+accessor counts up front. A primitive without indices draws its vertices in order, so it gets
+sequential ones. A primitive without readable positions fails the load with
+`LoadError::InvalidData`.
+*/
+fn read_primitive(
+    primitive: &gltf::Primitive,
+    buffers: &[&[u8]],
+    location: &PrimitiveLocation,
+    warnings: &mut Vec<LoadWarning>
+) -> Result<Geometry, LoadError> {
+    let positions = primitive
+        .get(&gltf::Semantic::Positions)
+        .ok_or_else(|| LoadError::InvalidData(format!("Vertices of {} must have positions", location)))?;
+    let vertex_count = positions.count();
+    let positions = read_vectors::<3>(positions, buffers)
+        .ok_or_else(|| LoadError::InvalidData(format!("Positions of {} cannot be read", location)))?;
+
+    let normals = padded_stream(read_attribute::<3>(primitive, 0, buffers, vertex_count, location, warnings));
+    let tex_coords = padded_stream(read_attribute::<2>(primitive, 1, buffers, vertex_count, location, warnings));
+    let tangents = padded_stream(read_attribute::<4>(primitive, 2, buffers, vertex_count, location, warnings));
+
+    let mut vertices = Vec::with_capacity(vertex_count);
+    vertices.extend(positions.zip(normals).zip(tex_coords).zip(tangents).map(
+        |(((position, normal), tex_coord), tangent)| Vertex {
+            position: Vec3::from(position),
+            normal: Vec3::from(normal),
+            tex_coord: Vec2::from(tex_coord),
+            tangent: Vec4::from(tangent),
+            ..Default::default()
+        }
+    ));
+
+    let accessor = match primitive.indices() {
+        Some(accessor) => accessor,
+        None => {
+            let indices = (0..vertices.len() as u32).collect();
+            return Ok((vertices, indices));
+        }
+    };
+    let reader = primitive.reader(
+        |buffer| buffers.get(buffer.index()).copied()
+    );
+    let mut indices = Vec::with_capacity(accessor.count());
+    indices.extend(
+        reader
+            .read_indices()
+            .ok_or_else(|| LoadError::InvalidData(format!("Indices of {} cannot be read", location)))?
+            .into_u32()
+    );
+
+    Ok((vertices, indices))
+}
 
-                meshes.push(Mesh {
-                    vertices,
-                    indices,
-                    material_idx
+/*
+Parses the raw JSON of an `extras` property. The glTF crate only keeps it with its `extras`
+feature, which the `serde_json` feature enables; without it there is nothing to read.
+*/
+#[cfg(feature = "serde_json")]
+pub(crate) fn read_extras(extras: &gltf::json::Extras) -> Option<serde_json::Value> {
+    extras.as_ref().and_then(|raw| serde_json::from_str(raw.get()).ok())
+}
+
+#[cfg(not(feature = "serde_json"))]
+pub(crate) fn read_extras(_extras: &gltf::json::Extras) -> Option<serde_json::Value> {
+    None
+}
+
+/*
+One triangle primitive to turn into a mesh: the glTF mesh it belongs to, the instance
+transforms of its node and the world transform baked into its vertices.
+*/
+struct PrimitiveJob<'a> {
+    mesh: gltf::Mesh<'a>,
+    primitive: gltf::Primitive<'a>,
+    instances: &'a [Mat4],
+    world_matrix: Mat4
+}
+
+/*
+Maps `items` in order. With the `parallel` feature the items are spread over rayon's thread
+pool, but the results are still collected in the order of `items`, so the output does not
+depend on scheduling.
+*/
+#[cfg(feature = "parallel")]
+fn map_ordered<T: Sync, R: Send>(items: &[T], f: impl Fn(&T) -> R + Sync + Send) -> Vec<R> {
+    use rayon::prelude::*;
+    items.par_iter().map(f).collect()
+}
+
+#[cfg(not(feature = "parallel"))]
+fn map_ordered<T, R>(items: &[T], f: impl Fn(&T) -> R) -> Vec<R> {
+    items.iter().map(f).collect()
+}
+
+/*
+Maps `items` in order like `map_ordered`, reporting the phase `phase` builds from the number of
+items finished, first with none and then as they finish. With the `parallel` feature the items
+are mapped in batches of one per thread, so the reports still come from the calling thread.
+Without `progress` the items are mapped in one go.
+*/
+fn map_reported<T: Sync, R: Send>(
+    items: &[T],
+    f: impl Fn(&T) -> R + Sync + Send,
+    progress: &mut Option<&mut Progress>,
+    phase: impl Fn(usize) -> LoadPhase
+) -> Result<Vec<R>, LoadError> {
+    let progress = match progress {
+        Some(progress) => progress,
+        None => return Ok(map_ordered(items, f))
+    };
+
+    #[cfg(feature = "parallel")]
+    let batch_size = rayon::current_num_threads();
+    #[cfg(not(feature = "parallel"))]
+    let batch_size = 1;
+
+    progress.report(phase(0))?;
+    let mut results = Vec::with_capacity(items.len());
+    for batch in items.chunks(batch_size) {
+        results.extend(map_ordered(batch, &f));
+        progress.report(phase(results.len()))?;
+    }
+    Ok(results)
+}
+
+/*
+Builds the mesh of a triangle primitive: reads its vertices and indices, decoding
+KHR_draco_mesh_compression when the `draco` feature is enabled, bakes the base color texture
+transform of its material, flips its texture coordinates and generates missing normals when
+asked to, moves it into place with its node's world transform and converts it to Y-up when the
+file is Z-up. The materials must already be filled in. The warnings about the primitive are
+returned with the mesh, and geometry that cannot be read fails the load.
+*/
+fn process_primitive(
+    job: &PrimitiveJob,
+    document: &gltf::Document,
+    buffers: &[&[u8]],
+    materials: &[Material],
+    options: &LoaderOptions
+) -> Result<(Mesh, Vec<LoadWarning>), LoadError> {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::info_span!("process_primitive", mesh = job.mesh.index(), primitive = job.primitive.index()).entered();
+
+    let location = PrimitiveLocation::new(&job.mesh, &job.primitive);
+    let mut warnings = Vec::new();
+    for (semantic, name) in &OPTIONAL_ATTRIBUTES {
+        if job.primitive.get(semantic).is_none() {
+            warnings.push(LoadWarning::MissingAttribute { location: location.clone(), semantic: name });
+        }
+    }
+
+    let (mut vertices, indices) = match decode_compressed(&job.primitive, document, buffers, &location) {
+        Some(decoded) => decoded?,
+        None => read_primitive(&job.primitive, buffers, &location, &mut warnings)?
+    };
+
+    let material = match job.primitive.material().index() {
+        Some(index) => MaterialHandle::from(index),
+        None => {
+            warnings.push(LoadWarning::DefaultMaterial { location });
+            MaterialHandle::from(document.materials().len())
+        }
+    };
+    if options.bake_texture_transform {
+        bake_texture_transform(&mut vertices, &materials[material.index()]);
+    }
+    if options.flip_uv_v {
+        for vertex in &mut vertices {
+            vertex.tex_coord.y = 1.0 - vertex.tex_coord.y;
+        }
+    }
+
+    let bounds = primitive_bounds(&job.primitive, &vertices);
+    let mut mesh = Mesh {
+        name: job.mesh.name().map(str::to_string),
+        indices: Indices::for_vertices(indices, vertices.len()),
+        vertices,
+        material,
+        bounds: Some(bounds),
+        instances: job.instances.to_vec(),
+        extras: read_extras(job.mesh.extras())
+    };
+    if options.generate_normals && mesh.vertices.iter().all(|vertex| vertex.normal == Vec3::ZERO) {
+        mesh.recalculate_normals(NormalMode::Flat);
+    }
+    apply_node_transform(&mut mesh, job.world_matrix);
+    mesh.convert_axes(options.coordinate_system, CoordinateSystem::YUp);
+    Ok((mesh, warnings))
+}
+
+/*
+Applies the transform of a material's base color texture to the texture coordinates of a
+primitive. Once every primitive is baked, `load_nodes` resets the stored transforms so renderers
+sampling with the baked coordinates do not apply them again. Vertices only hold the first
+texture coordinate set, so textures sampling another set are left alone.
+*/
+fn bake_texture_transform(vertices: &mut [Vertex], material: &Material) {
+    if let Some(texture) = material.base_color_texture.as_ref().filter(|texture| texture.tex_coord() == 0) {
+        let transform = texture.transform();
+        for vertex in vertices.iter_mut() {
+            vertex.tex_coord = transform.apply(vertex.tex_coord);
+        }
+    }
+}
+
+/*
+Bakes a node's world transform into the meshes produced for it. A node scaled to zero has no
+inverse, so only its positions are collapsed and the other attributes are left as they are.
+The cached bounds are recomputed from the moved positions.
+*/
+fn apply_node_transform(mesh: &mut Mesh, world_matrix: Mat4) {
+    if world_matrix == Mat4::IDENTITY {
+        return;
+    }
+
+    if mesh.transform(world_matrix).is_err() {
+        for vertex in &mut mesh.vertices {
+            vertex.position = world_matrix.transform_point3(vertex.position);
+        }
+    }
+    mesh.bounds = Some(mesh.aabb());
+}
+
+/*
+Lists a node and all of its descendants, parents before children, with the world transform of
+each accumulated from `parent_matrix` on the way down.
+*/
+fn collect_subtree<'a>(node: gltf::Node<'a>, parent_matrix: Mat4, nodes: &mut Vec<(gltf::Node<'a>, Mat4)>) {
+    let world_matrix = parent_matrix * Mat4::from_cols_array_2d(&node.transform().matrix());
+    let children: Vec<gltf::Node<'a>> = node.children().collect();
+    nodes.push((node, world_matrix));
+    for child in children {
+        collect_subtree(child, world_matrix, nodes);
+    }
+}
+
+/*
+Builds a model from the triangle primitives of `nodes`, each moved into place by the world
+transform listed with its node and scaled by the options' scale, after checking the options.
+The materials those primitives use are surveyed first for the images their textures need,
+which are then loaded once each; the materials are filled in from those images, and every
+primitive becomes a mesh, in node order and then primitive order.
+Materials no primitive uses keep their defaults. With the `parallel` feature the images, the
+materials and then the primitives are processed concurrently on rayon's thread pool, and since
+results are collected in order the model is the same as the one a sequential load builds.
+`progress`, when given, hears about every image and mesh finished and can cancel the load.
+Primitives that are not triangles are left out, and every such decision is handed to the
+options' warning handler once the model is built.
+*/
+pub(crate) fn load_nodes(
+    document: &gltf::Document,
+    buffers: &[&[u8]],
+    nodes: &[(gltf::Node, Mat4)],
+    file_path: &Path,
+    options: &LoaderOptions,
+    mut progress: Option<&mut Progress>
+) -> Result<Model, LoadError> {
+    options.validate()?;
+    let instances: Vec<Vec<Mat4>> = nodes
+        .iter()
+        .map(|(node, _)| read_instances(node, document, buffers))
+        .collect();
+
+    let scale = Mat4::from_scale(Vec3::splat(options.scale));
+    let mut warnings = Vec::new();
+    let mut jobs = Vec::new();
+    for ((node, world_matrix), instances) in nodes.iter().zip(&instances) {
+        if let Some(mesh) = node.mesh() {
+            for primitive in mesh.primitives() {
+                if primitive.mode() != gltf::mesh::Mode::Triangles {
+                    warnings.push(LoadWarning::UnsupportedPrimitiveMode {
+                        location: PrimitiveLocation::new(&mesh, &primitive),
+                        mode: primitive.mode()
+                    });
+                    continue;
+                }
+                jobs.push(PrimitiveJob {
+                    mesh: mesh.clone(),
+                    primitive,
+                    instances,
+                    world_matrix: scale * *world_matrix
                 });
             }
         }
     }
+
+    let mut sources = BTreeMap::new();
+    for job in &jobs {
+        let source = job.primitive.material();
+        sources.entry(source.index().unwrap_or(document.materials().len())).or_insert(source);
+    }
+    let sources: Vec<(usize, gltf::Material)> = sources.into_iter().collect();
+
+    let mut materials = default_materials(document);
+    if materials.len() == document.materials().len() && jobs.iter().any(|job| job.primitive.material().index().is_none()) {
+        materials.push(Material::default());
+    }
+    let survey = TextureCache::new(document, buffers, file_path, options, true);
+    for (index, source) in &sources {
+        process_material(source, &mut materials[*index].clone(), document, &survey);
+    }
+    let images: Vec<gltf::Image> = survey
+        .requested_images()
+        .into_iter()
+        .filter_map(|index| document.images().nth(index))
+        .collect();
+    let textures = TextureCache::new(document, buffers, file_path, options, false);
+    let decoded = map_reported(&images, |image| textures.load_image(image), &mut progress, |decoded| {
+        LoadPhase::DecodingTextures { decoded, total: images.len() }
+    })?;
+    for (image, texture) in images.iter().zip(&decoded) {
+        if let Err(reason) = texture {
+            warnings.push(LoadWarning::ImageDecodeFailed { image: image.index(), name: image.name().map(str::to_string), reason: reason.clone() });
+        }
+    }
+
+    let processed = map_ordered(&sources, |(index, source)| {
+        let mut material = materials[*index].clone();
+        process_material(source, &mut material, document, &textures);
+        material
+    });
+    for ((index, _), material) in sources.iter().zip(processed) {
+        materials[*index] = material;
+    }
+
+    let processed = map_reported(&jobs, |job| process_primitive(job, document, buffers, &materials, options), &mut progress, |processed| {
+        LoadPhase::ProcessingMeshes { processed, total: jobs.len() }
+    })?;
+    let mut meshes = Vec::with_capacity(processed.len());
+    for result in processed {
+        let (mesh, mesh_warnings) = result?;
+        meshes.push(mesh);
+        warnings.extend(mesh_warnings);
+    }
+
+    if options.bake_texture_transform {
+        for (index, _) in &sources {
+            if let Some(texture) = materials[*index].base_color_texture.as_mut().filter(|texture| texture.tex_coord() == 0) {
+                texture.set_transform(TextureTransform::default());
+            }
+        }
+    }
+    if options.flip_uv_v {
+        for (index, _) in &sources {
+            for texture in material_textures_mut(&mut materials[*index]).filter(|texture| texture.tex_coord() == 0) {
+                texture.set_transform(texture.transform().flipped_v());
+            }
+        }
+    }
+
+    report_warnings(&warnings, options);
+    Ok(Model {
+        meshes,
+        materials
+    })
 }
 
 /*
-Loads a 3D model from a GLTF file. It parses the document, processes the nodes to extract
-meshes and materials, and assembles them into a `Model` struct for further use.
+Hands the warnings of a load to the options' warning handler and, with the `tracing` feature,
+emits each of them as a warning event.
 */
-pub fn load_model(file_path: &str) -> Model {
-    let (document, buffers, _images) = gltf::import(file_path)
-        .expect("Failed to load model.");
+fn report_warnings(warnings: &[LoadWarning], options: &LoaderOptions) {
+    for warning in warnings {
+        #[cfg(feature = "tracing")]
+        tracing::warn!("{}", warning);
+        if let Some(handler) = &options.warning_handler {
+            handler(warning);
+        }
+    }
+}
+
+/*
+Computes the accumulated transform of every ancestor of a node, so a subtree loaded on its own
+keeps the placement it has in the full scene.
+*/
+fn ancestors_matrix(document: &gltf::Document, node_index: usize) -> Mat4 {
+    let mut parents = vec![None; document.nodes().len()];
+    for node in document.nodes() {
+        for child in node.children() {
+            parents[child.index()] = Some(node.index());
+        }
+    }
+
+    let nodes: Vec<_> = document.nodes().collect();
+    let mut matrix = Mat4::IDENTITY;
+    let mut current = parents[node_index];
+    while let Some(index) = current {
+        matrix = Mat4::from_cols_array_2d(&nodes[index].transform().matrix()) * matrix;
+        current = parents[index];
+    }
+    matrix
+}
 
-    let mut meshes = Vec::new();
-    let mut materials = vec![Material::default(); document.materials().len()];
+/*
+Creates one default material per material in the document, carrying over the authored names.
+Documents without materials get a single default so meshes always have one to refer to. That
+slot, right after the document's materials, is also where `load_nodes` puts glTF's default
+material when some primitive has none.
+*/
+fn default_materials(document: &gltf::Document) -> Vec<Material> {
+    let mut materials: Vec<Material> = document
+        .materials()
+        .map(|material| Material {
+            name: material.name().map(str::to_string),
+            extras: read_extras(material.extras()),
+            ..Default::default()
+        })
+        .collect();
     if materials.is_empty() {
         materials.push(Material::default());
     }
-    
-    if document.nodes().len() > 0 {
-        process_node(
-            document.nodes().next().as_ref().unwrap(),
-            &buffers,
-            &mut meshes,
-            &mut materials,
-            file_path
-        );
+    materials
+}
+
+/*
+Extensions the loader handles itself. The glTF crate refuses files listing extensions it does
+not know under `extensionsRequired`, so these entries are dropped before validation.
+*/
+const HANDLED_EXTENSIONS: &[&str] = &[
+    "KHR_mesh_quantization",
+    #[cfg(feature = "draco")]
+    crate::model::draco::EXTENSION_NAME
+];
+
+/*
+Parses and validates a GLTF or GLB document, returning it with the GLB binary chunk if there is
+one.
+*/
+pub(crate) fn parse_document(slice: &[u8]) -> Result<(gltf::Document, Option<Vec<u8>>), gltf::Error> {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::info_span!("parse_document", bytes = slice.len()).entered();
+
+    let gltf::Gltf { document, blob } = gltf::Gltf::from_slice_without_validation(slice)?;
+    let mut json = document.into_json();
+    json.extensions_required.retain(|name| !HANDLED_EXTENSIONS.contains(&name.as_str()));
+
+    #[cfg(feature = "draco")]
+    let document = crate::model::draco::validate(json)?;
+    #[cfg(not(feature = "draco"))]
+    let document = gltf::Document::from_json(json)?;
+
+    Ok((document, blob))
+}
+
+/*
+The `ImportedBuffers` enum holds the buffers of an imported document: read into memory, or with
+the `mmap` feature, left in the memory mapping of a GLB file, which only the vertices and
+indices the loader keeps are copied out of.
+*/
+pub(crate) enum ImportedBuffers {
+    Owned(Vec<gltf::buffer::Data>),
+    #[cfg(feature = "mmap")]
+    Mapped(crate::model::mmap::MappedGlb)
+}
+
+impl ImportedBuffers {
+    pub(crate) fn slices(&self) -> Vec<&[u8]> {
+        match self {
+            ImportedBuffers::Owned(buffers) => buffers.iter().map(|buffer| &buffer.0[..]).collect(),
+            #[cfg(feature = "mmap")]
+            ImportedBuffers::Mapped(glb) => vec![glb.bin()]
+        }
     }
+}
 
-    Model {
-        meshes,
-        materials
+/*
+Reads the data of every buffer in the document. Relative URIs are resolved with `resolve_uri`,
+the way image URIs are, the GLB binary chunk is taken from `blob` and data URIs are decoded in
+place. Each buffer is padded to a multiple of four bytes and checked against its declared
+length.
+*/
+fn read_buffers(
+    document: &gltf::Document,
+    file_path: &Path,
+    mut blob: Option<Vec<u8>>
+) -> Result<Vec<gltf::buffer::Data>, LoadError> {
+    let mut buffers = Vec::new();
+    for buffer in document.buffers() {
+        let mut data = match buffer.source() {
+            gltf::buffer::Source::Bin => blob
+                .take()
+                .ok_or_else(|| LoadError::InvalidData("Buffer refers to a missing binary chunk".to_string()))?,
+            gltf::buffer::Source::Uri(uri) if is_relative_uri(uri) => {
+                let path = resolve_uri(uri, file_path);
+                std::fs::read(&path).map_err(|error| buffer_read_error(buffer.index(), &path, error))?
+            },
+            gltf::buffer::Source::Uri(_) => {
+                gltf::buffer::Data::from_source(buffer.source(), file_path.parent())?.0
+            }
+        };
+
+        if data.len() < buffer.length() {
+            return Err(LoadError::InvalidData(format!("Buffer {} is shorter than its declared length", buffer.index())));
+        }
+        while data.len() % 4 != 0 {
+            data.push(0);
+        }
+        buffers.push(gltf::buffer::Data(data));
+    }
+    Ok(buffers)
+}
+
+/*
+Names the buffer and the path it was looked for at in an I/O error, which otherwise only says
+that some file was not found.
+*/
+pub(crate) fn buffer_read_error(buffer: usize, path: &Path, error: std::io::Error) -> LoadError {
+    LoadError::Io(std::io::Error::new(error.kind(), format!("Buffer {} could not be read from {}: {}", buffer, path.display(), error)))
+}
+
+/*
+Imports the document and buffers of a GLTF file. Images are not decoded here, since textures are
+loaded from their URIs when materials are processed. With the `mmap` feature, GLB files whose
+only buffer is their binary chunk are memory-mapped rather than read.
+*/
+pub(crate) fn import(file_path: &Path, progress: &mut Option<&mut Progress>) -> Result<(gltf::Document, ImportedBuffers), LoadError> {
+    if let Some(progress) = progress {
+        progress.report(LoadPhase::ParsingDocument)?;
+    }
+
+    #[cfg(feature = "mmap")]
+    if let Some((document, glb)) = crate::model::mmap::import_glb(file_path)? {
+        if let Some(progress) = progress {
+            progress.report(LoadPhase::ReadingBuffers)?;
+        }
+        return Ok((document, ImportedBuffers::Mapped(glb)));
+    }
+
+    let bytes = std::fs::read(file_path).map_err(gltf::Error::Io)?;
+    let (document, blob) = parse_document(&bytes)?;
+
+    if let Some(progress) = progress {
+        progress.report(LoadPhase::ReadingBuffers)?;
     }
-}
\ No newline at end of file
+    let buffers = read_buffers(&document, file_path, blob)?;
+    Ok((document, ImportedBuffers::Owned(buffers)))
+}
+
+/*
+Assembles a `Model` from an imported document the way `load_model` does: the first node is
+processed, and its transform is only applied when the options ask for it.
+*/
+pub(crate) fn build_model(
+    document: &gltf::Document,
+    buffers: &[&[u8]],
+    file_path: &Path,
+    options: &LoaderOptions,
+    progress: Option<&mut Progress>
+) -> Result<Model, LoadError> {
+    let nodes: Vec<(gltf::Node, Mat4)> = document
+        .nodes()
+        .take(1)
+        .map(|node| {
+            let matrix = match options.apply_transforms {
+                true => Mat4::from_cols_array_2d(&node.transform().matrix()),
+                false => Mat4::IDENTITY
+            };
+            (node, matrix)
+        })
+        .collect();
+    load_nodes(document, buffers, &nodes, file_path, options, progress)
+}
+
+/*
+Loads a 3D model from a GLTF file. It parses the document, processes the nodes to extract
+meshes and materials, and assembles them into a `Model` struct for further use.
+*/
+pub fn load_model(file_path: impl AsRef<Path>) -> Model {
+    let file_path = file_path.as_ref();
+    let (document, buffers) = import(file_path, &mut None)
+        .expect("Failed to load model.");
+    let buffers = buffers.slices();
+
+    build_model(&document, &buffers, file_path, &LoaderOptions::default(), None)
+        .expect("Failed to load model.")
+}
+
+/*
+Loads a model like `load_model`, adjusted by `options`, and reports import failures instead of
+panicking. With the `tracing` feature the load enters a span while parsing the document,
+decoding each image and processing each primitive, which subscribers can time, and emits every
+`LoadWarning` as a warning event.
+*/
+pub fn load_model_with(file_path: impl AsRef<Path>, options: &LoaderOptions) -> Result<Model, LoadError> {
+    let file_path = file_path.as_ref();
+    let (document, buffers) = import(file_path, &mut None)?;
+    let buffers = buffers.slices();
+    build_model(&document, &buffers, file_path, options, None)
+}
+
+/*
+Loads a model like `load_model_with`, calling `callback` on the loading thread as the load
+moves on: when parsing starts, when buffers are read, after each image is decoded and after
+each mesh is processed, and once finished. Returning `ControlFlow::Break` from the callback
+stops the load with `LoadError::Cancelled`. With the `parallel` feature images and meshes are
+reported in batches, as the thread pool finishes them.
+*/
+pub fn load_model_with_progress(
+    file_path: impl AsRef<Path>,
+    options: &LoaderOptions,
+    mut callback: impl FnMut(LoadProgress) -> ControlFlow<()>
+) -> Result<Model, LoadError> {
+    let file_path = file_path.as_ref();
+    let mut progress = Progress::new(&mut callback);
+    let (document, buffers) = import(file_path, &mut Some(&mut progress))?;
+    let buffers = buffers.slices();
+    let model = build_model(&document, &buffers, file_path, options, Some(&mut progress))?;
+    progress.report(LoadPhase::Finished)?;
+    Ok(model)
+}
+
+/*
+Loads a model with the default options, handing `on_progress` every `LoadProgress` of the load,
+for a progress bar that never cancels. See `load_model_with_progress` for when it is called.
+*/
+pub fn load_model_reporting(file_path: impl AsRef<Path>, mut on_progress: impl FnMut(LoadProgress)) -> Result<Model, LoadError> {
+    load_model_with_progress(file_path, &LoaderOptions::default(), |progress| {
+        on_progress(progress);
+        ControlFlow::Continue(())
+    })
+}
+
+/*
+Loads only the node called `node_name` and its descendants from a GLTF file. Node names are
+matched first; when no node carries the name, a node whose mesh has it is used instead. The
+transforms of the node and its ancestors are baked into the vertices, so the meshes appear
+where they would in the full scene.
+*/
+pub fn load_model_node(file_path: impl AsRef<Path>, node_name: &str) -> Result<Model, LoadError> {
+    load_model_node_with(file_path, node_name, &LoaderOptions::default())
+}
+
+/*
+Same as `load_model_node`, adjusted by `options`. The node transforms are always baked, so
+`apply_transforms` has no effect; `scale` and `coordinate_system` apply to the baked result.
+*/
+pub fn load_model_node_with(file_path: impl AsRef<Path>, node_name: &str, options: &LoaderOptions) -> Result<Model, LoadError> {
+    let file_path = file_path.as_ref();
+    let (document, buffers) = import(file_path, &mut None)?;
+    let buffers = buffers.slices();
+
+    let node = document
+        .nodes()
+        .find(|node| node.name() == Some(node_name))
+        .or_else(|| {
+            document
+                .nodes()
+                .find(|node| node.mesh().and_then(|mesh| mesh.name()) == Some(node_name))
+        })
+        .ok_or_else(|| LoadError::NodeNotFound(node_name.to_string()))?;
+
+    let mut nodes = Vec::new();
+    collect_subtree(node.clone(), ancestors_matrix(&document, node.index()), &mut nodes);
+    load_nodes(&document, &buffers, &nodes, file_path, options, None)
+}
+
+/*
+Loads every node of the scene at `scene_index`, starting from its root nodes, so other scenes
+in the file are left out. Node transforms are baked into the vertices as in `load_model_node`.
+*/
+pub fn load_scene(path: impl AsRef<Path>, scene_index: usize) -> Result<Model, LoadError> {
+    load_scene_with(path, scene_index, &LoaderOptions::default())
+}
+
+/*
+Same as `load_scene`, adjusted by `options`, as in `load_model_node_with`.
+*/
+pub fn load_scene_with(path: impl AsRef<Path>, scene_index: usize, options: &LoaderOptions) -> Result<Model, LoadError> {
+    let path = path.as_ref();
+    let (document, buffers) = import(path, &mut None)?;
+    let buffers = buffers.slices();
+
+    let scene = document
+        .scenes()
+        .nth(scene_index)
+        .ok_or(LoadError::SceneNotFound(scene_index))?;
+
+    let mut nodes = Vec::new();
+    for node in scene.nodes() {
+        collect_subtree(node, Mat4::IDENTITY, &mut nodes);
+    }
+    load_nodes(&document, &buffers, &nodes, path, options, None)
+}
@@ -0,0 +1,84 @@
+use crate::model::loader::{Material, Mesh, Model};
+use crate::model::{MaterialHandle, MeshHandle};
+
+impl Model {
+    /*
+    Returns the material a handle refers to, or `None` when the handle is past the end of the
+    material list.
+    */
+    pub fn material(&self, handle: MaterialHandle) -> Option<&Material> {
+        self.materials.get(handle.index())
+    }
+
+    pub fn material_mut(&mut self, handle: MaterialHandle) -> Option<&mut Material> {
+        self.materials.get_mut(handle.index())
+    }
+
+    /*
+    Returns the mesh a handle refers to, or `None` when the handle is past the end of the mesh
+    list.
+    */
+    pub fn mesh(&self, handle: MeshHandle) -> Option<&Mesh> {
+        self.meshes.get(handle.index())
+    }
+
+    pub fn mesh_mut(&mut self, handle: MeshHandle) -> Option<&mut Mesh> {
+        self.meshes.get_mut(handle.index())
+    }
+
+    /*
+    Iterates over the handles of every material, in material order.
+    */
+    pub fn material_handles(&self) -> impl Iterator<Item = MaterialHandle> {
+        (0..self.materials.len()).map(MaterialHandle::from)
+    }
+
+    /*
+    Iterates over the handles of every mesh, in mesh order.
+    */
+    pub fn mesh_handles(&self) -> impl Iterator<Item = MeshHandle> {
+        (0..self.meshes.len()).map(MeshHandle::from)
+    }
+
+    /*
+    Returns the first material whose authored name matches `name` exactly.
+    */
+    pub fn material_by_name(&self, name: &str) -> Option<&Material> {
+        self.materials
+            .iter()
+            .find(|material| material.name.as_deref() == Some(name))
+    }
+
+    pub fn material_mut_by_name(&mut self, name: &str) -> Option<&mut Material> {
+        self.materials
+            .iter_mut()
+            .find(|material| material.name.as_deref() == Some(name))
+    }
+
+    /*
+    Iterates over the meshes drawn with `material`, in model order.
+    */
+    pub fn meshes_with_material(&self, material: MaterialHandle) -> impl Iterator<Item = &Mesh> {
+        self.meshes
+            .iter()
+            .filter(move |mesh| mesh.material == material)
+    }
+
+    /*
+    Iterates over every material once, in material order, with the meshes drawn with it in
+    model order, for sorting draw calls by material. Materials no mesh uses come with no meshes,
+    and meshes whose handle is past the end of the material list are left out.
+    */
+    pub fn meshes_by_material(&self) -> impl Iterator<Item = (MaterialHandle, &Material, impl Iterator<Item = &Mesh> + '_)> + '_ {
+        let mut groups = vec![Vec::new(); self.materials.len()];
+        for (index, mesh) in self.meshes.iter().enumerate() {
+            if let Some(group) = groups.get_mut(mesh.material.index()) {
+                group.push(index);
+            }
+        }
+
+        self.materials.iter().zip(groups).enumerate().map(move |(index, (material, group))| {
+            (MaterialHandle::from(index), material, group.into_iter().map(move |mesh| &self.meshes[mesh]))
+        })
+    }
+}
@@ -0,0 +1,68 @@
+use glam::*;
+use crate::model::loader::{Mesh, Model};
+
+/*
+Sums the area and signed volume of a mesh's triangles with their positions moved by `matrix`.
+Each triangle adds the signed volume of the tetrahedron it forms with the origin; on a closed
+surface the parts outside the mesh cancel out, leaving the enclosed volume.
+*/
+fn measure(mesh: &Mesh, matrix: Mat4) -> (f32, f32) {
+    let mut area = 0.0;
+    let mut volume = 0.0;
+    for triangle in mesh.triangles() {
+        let [a, b, c] = triangle.positions().map(|position| matrix.transform_point3(position));
+        area += (b - a).cross(c - a).length() * 0.5;
+        volume += a.dot(b.cross(c)) / 6.0;
+    }
+    (area, volume)
+}
+
+/*
+Sums `measure` over every placement of a mesh: once as it is, or once per instance.
+*/
+fn measure_placed(mesh: &Mesh) -> (f32, f32) {
+    if mesh.instances.is_empty() {
+        return measure(mesh, Mat4::IDENTITY);
+    }
+    mesh.instances.iter().fold((0.0, 0.0), |(area, volume), &instance| {
+        let (instance_area, instance_volume) = measure(mesh, instance);
+        (area + instance_area, volume + instance_volume)
+    })
+}
+
+impl Mesh {
+    /*
+    Returns the total area of the triangles, in mesh space, so instances are not counted.
+    Trailing indices and triangles with out-of-range indices are skipped.
+    */
+    pub fn surface_area(&self) -> f32 {
+        measure(self, Mat4::IDENTITY).0
+    }
+
+    /*
+    Returns the volume enclosed by the mesh, in mesh space, by the divergence theorem. The
+    result is only meaningful for a closed mesh, see `is_watertight`: it is positive when the
+    triangles wind counter-clockwise seen from outside and negative when the mesh is inside out.
+    */
+    pub fn signed_volume(&self) -> f32 {
+        measure(self, Mat4::IDENTITY).1
+    }
+}
+
+impl Model {
+    /*
+    Returns the total area of every mesh, counting an instanced mesh once per instance with the
+    instance transform applied.
+    */
+    pub fn surface_area(&self) -> f32 {
+        self.meshes.iter().map(|mesh| measure_placed(mesh).0).sum()
+    }
+
+    /*
+    Returns the sum of the signed volumes of every mesh, counting an instanced mesh once per
+    instance with the instance transform applied. Overlapping meshes are counted twice.
+    */
+    pub fn signed_volume(&self) -> f32 {
+        self.meshes.iter().map(|mesh| measure_placed(mesh).1).sum()
+    }
+}
@@ -0,0 +1,200 @@
+use crate::model::loader::{Material, Mesh, Model};
+use crate::model::{MaterialHandle, Texture};
+
+/*
+Largest difference between two material factors, colors or texture transform values still
+considered equal by `Model::deduplicate_materials`.
+*/
+pub const MATERIAL_EPSILON: f32 = 1e-4;
+
+/*
+Tells whether a mesh can be folded into another one. Instanced meshes carry their own list of
+transforms, and an index count that is not a multiple of three would misalign every triangle
+appended after it, so both are left as they are.
+*/
+fn is_mergeable(mesh: &Mesh) -> bool {
+    mesh.instances.is_empty() && mesh.indices.len().is_multiple_of(3)
+}
+
+fn nearly_equal(a: f32, b: f32) -> bool {
+    a == b || (a - b).abs() <= MATERIAL_EPSILON
+}
+
+fn nearly_equal_all(a: &[f32], b: &[f32]) -> bool {
+    a.iter().zip(b).all(|(&a, &b)| nearly_equal(a, b))
+}
+
+/*
+Textures are decoded copies rather than references to a shared image, so two textures are the
+same image when their dimensions and pixels match exactly, or for deferred textures when their
+sources match. They must sample the same texture coordinate set, while their transforms may
+differ by the epsilon.
+*/
+fn same_texture(a: Option<&Texture>, b: Option<&Texture>) -> bool {
+    match (a, b) {
+        (None, None) => true,
+        (Some(a), Some(b)) => {
+            let (ta, tb) = (a.transform(), b.transform());
+            a.width() == b.width()
+                && a.height() == b.height()
+                && a.channel_count() == b.channel_count()
+                && a.format() == b.format()
+                && a.tex_coord() == b.tex_coord()
+                && nearly_equal_all(&[ta.offset.x, ta.offset.y, ta.rotation, ta.scale.x, ta.scale.y], &[tb.offset.x, tb.offset.y, tb.rotation, tb.scale.x, tb.scale.y])
+                && a.pixels() == b.pixels()
+                && a.source() == b.source()
+        },
+        _ => false
+    }
+}
+
+/*
+Tells whether two materials render the same: every factor and color within `MATERIAL_EPSILON`,
+the same textures in every slot and equal extras. Names are not compared, since exporters often
+give duplicates names like `Material.001`.
+*/
+fn same_material(a: &Material, b: &Material) -> bool {
+    let transmission = match (&a.transmission, &b.transmission) {
+        (None, None) => true,
+        (Some(a), Some(b)) => nearly_equal(a.factor, b.factor) && same_texture(a.texture.as_ref(), b.texture.as_ref()),
+        _ => false
+    };
+    let volume = match (&a.volume, &b.volume) {
+        (None, None) => true,
+        (Some(a), Some(b)) => {
+            nearly_equal(a.thickness_factor, b.thickness_factor)
+                && nearly_equal_all(&a.attenuation_color.to_array(), &b.attenuation_color.to_array())
+                && nearly_equal(a.attenuation_distance, b.attenuation_distance)
+                && same_texture(a.thickness_texture.as_ref(), b.thickness_texture.as_ref())
+        },
+        _ => false
+    };
+
+    transmission
+        && volume
+        && nearly_equal_all(&a.base_color.to_array(), &b.base_color.to_array())
+        && nearly_equal(a.metallic_factor, b.metallic_factor)
+        && nearly_equal(a.roughness_factor, b.roughness_factor)
+        && nearly_equal(a.occlusion_strength, b.occlusion_strength)
+        && nearly_equal(a.normal_scale, b.normal_scale)
+        && nearly_equal_all(&a.emissive_factor.to_array(), &b.emissive_factor.to_array())
+        && a.channel_packing == b.channel_packing
+        && nearly_equal(a.ior, b.ior)
+        && nearly_equal(a.clearcoat_factor, b.clearcoat_factor)
+        && nearly_equal(a.clearcoat_roughness_factor, b.clearcoat_roughness_factor)
+        && a.unlit == b.unlit
+        && a.extras == b.extras
+        && same_texture(a.base_color_texture.as_ref(), b.base_color_texture.as_ref())
+        && same_texture(a.metallic_roughness_texture.as_ref(), b.metallic_roughness_texture.as_ref())
+        && same_texture(a.normal_texture.as_ref(), b.normal_texture.as_ref())
+        && same_texture(a.occlusion_texture.as_ref(), b.occlusion_texture.as_ref())
+        && same_texture(a.emissive_texture.as_ref(), b.emissive_texture.as_ref())
+        && same_texture(a.clearcoat_texture.as_ref(), b.clearcoat_texture.as_ref())
+        && same_texture(a.clearcoat_roughness_texture.as_ref(), b.clearcoat_roughness_texture.as_ref())
+        && same_texture(a.clearcoat_normal_texture.as_ref(), b.clearcoat_normal_texture.as_ref())
+}
+
+impl Model {
+    /*
+    Appends the meshes and materials of `other` after those of this model, offsetting the
+    material handle of every appended mesh by the materials already present so each keeps its
+    own material. Nothing is deduplicated; `deduplicate_materials` and `merge_by_material` can
+    fold shared materials and meshes afterwards.
+    */
+    pub fn merge(&mut self, other: Model) {
+        let offset = self.materials.len();
+        self.meshes.extend(other.meshes.into_iter().map(|mut mesh| {
+            mesh.material = MaterialHandle::from(mesh.material.index() + offset);
+            mesh
+        }));
+        self.materials.extend(other.materials);
+    }
+
+    /*
+    Concatenates all mergeable meshes that share a material into a single mesh, so each
+    material ends up with at most one mergeable mesh. Vertices are copied verbatim and the
+    indices of each appended mesh are offset by the vertices already present. The merged mesh
+    takes the place of the first mesh using the material and keeps its name only when every
+    merged mesh had the same one. Returns the number of meshes folded into another.
+    */
+    pub fn merge_by_material(&mut self) -> usize {
+        let mut merged: Vec<Mesh> = Vec::with_capacity(self.meshes.len());
+        let mut targets: Vec<Option<usize>> = vec![None; self.materials.len()];
+        let mut merge_count = 0;
+
+        for mesh in self.meshes.drain(..) {
+            if !is_mergeable(&mesh) {
+                merged.push(mesh);
+                continue;
+            }
+
+            let material = mesh.material.index();
+            if material >= targets.len() {
+                targets.resize(material + 1, None);
+            }
+
+            let target = match targets[material] {
+                Some(target) => &mut merged[target],
+                None => {
+                    targets[material] = Some(merged.len());
+                    merged.push(mesh);
+                    continue;
+                }
+            };
+
+            let offset = target.vertices.len() as u32;
+            target.vertices.extend_from_slice(&mesh.vertices);
+            target.indices.extend(mesh.indices.iter().map(|index| index + offset));
+            target.bounds = match (target.bounds, mesh.bounds) {
+                (Some(a), Some(b)) => Some(a.union(&b)),
+                _ => None
+            };
+            if target.name != mesh.name {
+                target.name = None;
+            }
+            merge_count += 1;
+        }
+
+        self.meshes = merged;
+        merge_count
+    }
+
+    /*
+    Removes materials that duplicate an earlier one, as compared by factors and colors within
+    `MATERIAL_EPSILON`, identical textures and equal extras, and points every mesh that used a
+    duplicate at the first of its kind, which keeps its name. The remaining materials keep their
+    order. Meshes with an out-of-range material handle stay out of range. Returns the number of
+    materials removed. Running `merge_by_material` afterwards can then merge meshes whose
+    materials were duplicates.
+    */
+    pub fn deduplicate_materials(&mut self) -> usize {
+        let mut kept: Vec<Material> = Vec::with_capacity(self.materials.len());
+        let mut remap = Vec::with_capacity(self.materials.len());
+        for material in self.materials.drain(..) {
+            match kept.iter().position(|canonical| same_material(canonical, &material)) {
+                Some(index) => remap.push(index),
+                None => {
+                    remap.push(kept.len());
+                    kept.push(material);
+                }
+            }
+        }
+
+        let removed = remap.len() - kept.len();
+        for mesh in &mut self.meshes {
+            mesh.material = match remap.get(mesh.material.index()) {
+                Some(&index) => MaterialHandle::from(index),
+                None => MaterialHandle::from(mesh.material.index() - removed)
+            };
+        }
+        self.materials = kept;
+        removed
+    }
+}
+
+/*
+Merges duplicate materials of a model, see `Model::deduplicate_materials`.
+*/
+pub fn deduplicate_materials(model: &mut Model) -> usize {
+    model.deduplicate_materials()
+}
@@ -0,0 +1,140 @@
+use std::fs::File;
+use std::io;
+use std::ops::Range;
+use std::path::Path;
+use crate::model::loader::parse_document;
+use crate::model::LoadError;
+
+const GLB_MAGIC: &[u8; 4] = b"glTF";
+const CHUNK_JSON: u32 = 0x4E4F534A;
+const CHUNK_BIN: u32 = 0x004E4942;
+
+/*
+The `MappedFile` struct maps a whole file read-only into memory, so its bytes are paged in by
+the operating system as they are read instead of being copied up front, and pages already read
+can be dropped again under memory pressure. The file must not be truncated or rewritten while
+it is mapped. Mapping is only implemented on Unix; elsewhere opening fails as unsupported.
+*/
+pub(crate) struct MappedFile {
+    pointer: *mut libc::c_void,
+    length: usize
+}
+
+impl MappedFile {
+    #[cfg(unix)]
+    pub(crate) fn open(path: &Path) -> io::Result<MappedFile> {
+        use std::os::unix::io::AsRawFd;
+
+        let file = File::open(path)?;
+        let length = usize::try_from(file.metadata()?.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::OutOfMemory, "File is larger than the address space"))?;
+        if length == 0 {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "File is empty"));
+        }
+
+        let pointer = unsafe {
+            libc::mmap(std::ptr::null_mut(), length, libc::PROT_READ, libc::MAP_PRIVATE, file.as_raw_fd(), 0)
+        };
+        if pointer == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(MappedFile { pointer, length })
+    }
+
+    #[cfg(not(unix))]
+    pub(crate) fn open(_path: &Path) -> io::Result<MappedFile> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "Memory mapping is only available on Unix"))
+    }
+
+    pub(crate) fn bytes(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.pointer as *const u8, self.length) }
+    }
+}
+
+impl Drop for MappedFile {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        unsafe {
+            libc::munmap(self.pointer, self.length);
+        }
+    }
+}
+
+/*
+The `MappedGlb` struct keeps a GLB file mapped along with where its binary chunk lies, so
+accessors can be read straight out of the mapping.
+*/
+pub(crate) struct MappedGlb {
+    file: MappedFile,
+    bin: Range<usize>
+}
+
+impl MappedGlb {
+    pub(crate) fn bin(&self) -> &[u8] {
+        &self.file.bytes()[self.bin.clone()]
+    }
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let word = bytes.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+}
+
+/*
+Finds the JSON chunk and the binary chunk, if any, of a GLB container, or returns `None` when
+the bytes are not a GLB container or a chunk reaches past its end.
+*/
+fn split_glb(bytes: &[u8]) -> Option<(Range<usize>, Option<Range<usize>>)> {
+    if bytes.get(0..4)? != GLB_MAGIC || read_u32(bytes, 4)? != 2 {
+        return None;
+    }
+    let end = (read_u32(bytes, 8)? as usize).min(bytes.len());
+
+    let mut chunks = Vec::new();
+    let mut offset = 12;
+    while offset + 8 <= end {
+        let length = read_u32(bytes, offset)? as usize;
+        let kind = read_u32(bytes, offset + 4)?;
+        let start = offset + 8;
+        let stop = start.checked_add(length).filter(|&stop| stop <= end)?;
+        chunks.push((kind, start..stop));
+        offset = stop;
+    }
+
+    let chunk = |wanted: u32| chunks.iter().find(|(kind, _)| *kind == wanted).map(|(_, range)| range.clone());
+    Some((chunk(CHUNK_JSON)?, chunk(CHUNK_BIN)))
+}
+
+/*
+Maps a GLB file and parses its document from the mapping. Returns `None`, so the caller reads
+the file the usual way, when the file is not a GLB container, cannot be mapped, or has buffers
+outside its binary chunk, since those live in other files or data URIs.
+*/
+pub(crate) fn import_glb(path: &Path) -> Result<Option<(gltf::Document, MappedGlb)>, LoadError> {
+    let file = match MappedFile::open(path) {
+        Ok(file) => file,
+        Err(_) => return Ok(None)
+    };
+    let (json, bin) = match split_glb(file.bytes()) {
+        Some(chunks) => chunks,
+        None => return Ok(None)
+    };
+    let (document, _) = parse_document(&file.bytes()[json])?;
+
+    let bin = bin.unwrap_or(0..0);
+    let mut buffers = document.buffers();
+    let in_chunk = match (buffers.next(), buffers.next()) {
+        (None, _) => true,
+        (Some(buffer), None) => matches!(buffer.source(), gltf::buffer::Source::Bin),
+        _ => false
+    };
+    if !in_chunk {
+        return Ok(None);
+    }
+    if let Some(buffer) = document.buffers().next() {
+        if buffer.length() > bin.len() {
+            return Err(LoadError::InvalidData("Binary chunk is shorter than its buffer".to_string()));
+        }
+    }
+    Ok(Some((document, MappedGlb { file, bin })))
+}
@@ -1,5 +1,91 @@
+pub mod animation;
+pub mod axes;
+pub mod bounds;
+pub mod builder;
+pub mod cache;
+pub mod conformance;
+pub mod cubemap;
+#[cfg(feature = "draco")]
+mod draco;
+pub mod error;
+pub mod export;
+pub mod halfedge;
+pub mod handle;
+pub mod indexing;
+pub mod indices;
+pub mod layout;
 pub mod loader;
+#[cfg(feature = "async")]
+pub mod load_async;
+pub mod lookup;
+pub mod measure;
+pub mod merge;
+#[cfg(feature = "mmap")]
+mod mmap;
+pub mod normals;
+pub mod optimize;
+pub mod options;
+pub mod patch;
+pub mod primitives;
+pub mod progress;
+pub mod raycast;
+pub mod resize;
+pub mod resolve;
+pub mod scene;
+pub mod simplify;
+pub mod split;
+pub mod stats;
+pub mod stream;
+pub mod subdivide;
+pub mod tangents;
 pub mod texture;
+pub mod texture_atlas;
+pub mod transform;
+pub mod triangles;
+pub mod triangulate;
+pub mod uv_atlas;
+pub mod uv_projection;
+pub mod validate;
+pub mod warning;
+pub mod weld;
+pub mod winding;
+pub mod wireframe;
 
-pub use loader::{load_model, Material, Model, Vertex};
-pub use texture::{Texture, load_texture};
\ No newline at end of file
+pub use animation::{Animation, AnimationChannel, AnimationValues, AnimationWrap, Interpolation, NodeTransform};
+pub use axes::{Axis, CoordinateSystem, Handedness};
+pub use bounds::{Aabb, Sphere};
+pub use builder::MeshBuilder;
+pub use conformance::{validate_gltf, GltfValidationMessage};
+pub use cubemap::equirect_to_cubemap;
+pub use error::{ExportError, LoadError, MeshError, TextureError};
+pub use export::{export_gltf, export_obj, StlFormat};
+pub use halfedge::{HalfEdge, HalfEdgeMesh};
+pub use handle::{MaterialHandle, MeshHandle};
+pub use indices::Indices;
+pub use layout::{vertex_layout, PackedVertices, VertexAttribute, VertexFormat, VertexSemantic, VERTEX_STRIDE};
+pub use loader::{load_model, load_model_node, load_model_node_with, load_model_reporting, load_model_with, load_model_with_progress, load_scene, load_scene_with, ChannelPacking, Material, Mesh, Model, TextureChannel, Transmission, Vertex, Volume};
+#[cfg(feature = "async")]
+pub use load_async::load_model_async;
+pub use merge::deduplicate_materials;
+pub use normals::NormalMode;
+pub use optimize::CacheOptimization;
+pub use options::{LoaderOptions, TextureLoading, TextureResolver, WarningHandler};
+pub use patch::{MaterialMatcher, MaterialPatch};
+pub use progress::{LoadPhase, LoadProgress};
+pub use raycast::RayHit;
+pub use resize::ResizeFilter;
+pub use scene::{load_scene_graph, load_scene_graph_with, Node, Scene};
+pub use split::split_connected_components;
+pub use stats::{MemoryFootprint, MeshStats, ModelStats};
+pub use stream::stream_meshes;
+pub use subdivide::SubdivisionScheme;
+pub use tangents::compute_tangents_mikktspace;
+pub use texture::{FilterMode, Texture, TextureFormat, TextureSource, TextureTransform, WrapMode, load_texture, load_texture_from_memory};
+pub use texture_atlas::{AtlasExclusion, AtlasOptions, AtlasPlacement, AtlasReport};
+pub use triangles::Triangle;
+pub use triangulate::triangulate_polygon;
+pub use uv_atlas::generate_uvs;
+pub use uv_projection::UvProjection;
+pub use validate::{IssueKind, Severity, ValidationIssue, ValidationOptions, ValidationReport};
+pub use warning::{LoadWarning, PrimitiveLocation};
+pub use wireframe::add_barycentric_attribute;
\ No newline at end of file
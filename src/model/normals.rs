@@ -0,0 +1,177 @@
+use glam::*;
+use std::collections::HashMap;
+use crate::model::loader::{Mesh, Vertex};
+use crate::model::Indices;
+
+/*
+The `NormalMode` enum selects how `Mesh::recalculate_normals` shades the surface. `Smooth`
+shares normals between the triangles meeting at a vertex, while `Flat` gives every triangle
+its own vertices carrying the face normal.
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NormalMode {
+    Smooth,
+    Flat
+}
+
+/*
+Returns the unnormalized normal of a triangle. Front faces are wound counter-clockwise, as in
+glTF, so the vector points towards a viewer that sees the vertices in CCW order. Its length is
+twice the triangle's area, which makes it suitable for area-weighted accumulation.
+*/
+pub(crate) fn face_normal(a: Vec3, b: Vec3, c: Vec3) -> Vec3 {
+    (b - a).cross(c - a)
+}
+
+impl Mesh {
+    /*
+    Recomputes the vertex normals from the triangle geometry, assuming counter-clockwise front
+    faces. Triangles with zero area have no defined normal and are skipped: in `Smooth` mode
+    they contribute nothing, and in `Flat` mode they are dropped from the rebuilt mesh. `Flat`
+    mode copies every other vertex attribute onto the duplicated vertices and rewrites the
+    indices to `0..3n`. Trailing indices that do not form a full triangle are ignored.
+    */
+    pub fn recalculate_normals(&mut self, mode: NormalMode) {
+        match mode {
+            NormalMode::Smooth => self.recalculate_smooth_normals(),
+            NormalMode::Flat => self.recalculate_flat_normals()
+        }
+    }
+
+    /*
+    Recomputes the vertex normals like `Smooth` mode, but each corner of a triangle only averages
+    the faces around its vertex whose normal lies within `max_smoothing_angle_degrees` of the
+    triangle's own, so edges sharper than the threshold stay hard. A vertex whose corners end up
+    with different normals is duplicated once per distinct normal, copying its other attributes,
+    and the indices are updated to match. 180 degrees gives the `Smooth` result and 0 degrees
+    the face normals of `Flat` mode, with coplanar neighbours still sharing vertices. Faces are
+    connected through shared vertex indices, so vertices split along texture seams are not
+    smoothed together; weld them first if needed. Triangles with zero area or out-of-range
+    indices are skipped, and vertices no other triangle uses get a zero normal as in `Smooth`.
+    */
+    pub fn recalculate_normals_with_angle(&mut self, max_smoothing_angle_degrees: f32) {
+        let min_cos = max_smoothing_angle_degrees.clamp(0.0, 180.0).to_radians().cos() - 4.0 * f32::EPSILON;
+        let vertex_count = self.vertices.len();
+
+        let mut face_normals: Vec<Option<Vec3>> = Vec::with_capacity(self.indices.len() / 3);
+        let mut vertex_faces: Vec<Vec<usize>> = vec![Vec::new(); vertex_count];
+        for (face, triangle) in self.indices.triangles().enumerate() {
+            let corners = [triangle[0] as usize, triangle[1] as usize, triangle[2] as usize];
+            if corners.iter().any(|&index| index >= vertex_count) {
+                face_normals.push(None);
+                continue;
+            }
+
+            let [a, b, c] = corners.map(|index| self.vertices[index].position);
+            let normal = face_normal(a, b, c);
+            if normal.length_squared() == 0.0 {
+                face_normals.push(None);
+                continue;
+            }
+            face_normals.push(Some(normal));
+            for index in corners {
+                vertex_faces[index].push(face);
+            }
+        }
+
+        let mut copies: HashMap<(u32, [u32; 3]), u32> = HashMap::new();
+        let mut assigned: Vec<Option<[u32; 3]>> = vec![None; vertex_count];
+        for (face, normal) in face_normals.iter().enumerate() {
+            let direction = match normal {
+                Some(normal) => normal.normalize(),
+                None => continue
+            };
+
+            for corner in 0..3 {
+                let original = self.indices.triangle(face)[corner];
+                let smoothed = vertex_faces[original as usize]
+                    .iter()
+                    .filter_map(|&other| face_normals[other])
+                    .filter(|other| other.normalize().dot(direction) >= min_cos)
+                    .sum::<Vec3>()
+                    .normalize_or_zero();
+                let key = smoothed.to_array().map(f32::to_bits);
+
+                let index = match assigned[original as usize] {
+                    None => {
+                        assigned[original as usize] = Some(key);
+                        self.vertices[original as usize].normal = smoothed;
+                        original
+                    },
+                    Some(existing) if existing == key => original,
+                    Some(_) => *copies.entry((original, key)).or_insert_with(|| {
+                        let mut vertex = self.vertices[original as usize];
+                        vertex.normal = smoothed;
+                        self.vertices.push(vertex);
+                        self.vertices.len() as u32 - 1
+                    })
+                };
+                self.indices.set(face * 3 + corner, index);
+            }
+        }
+
+        for (vertex, assigned) in self.vertices.iter_mut().zip(assigned) {
+            if assigned.is_none() {
+                vertex.normal = Vec3::ZERO;
+            }
+        }
+    }
+
+    fn recalculate_smooth_normals(&mut self) {
+        let mut normals = vec![Vec3::ZERO; self.vertices.len()];
+
+        for triangle in self.indices.triangles() {
+            let [a, b, c] = [triangle[0] as usize, triangle[1] as usize, triangle[2] as usize];
+            if a >= normals.len() || b >= normals.len() || c >= normals.len() {
+                continue;
+            }
+
+            let normal = face_normal(
+                self.vertices[a].position,
+                self.vertices[b].position,
+                self.vertices[c].position
+            );
+            if normal.length_squared() == 0.0 {
+                continue;
+            }
+
+            normals[a] += normal;
+            normals[b] += normal;
+            normals[c] += normal;
+        }
+
+        for (vertex, normal) in self.vertices.iter_mut().zip(normals) {
+            vertex.normal = normal.normalize_or_zero();
+        }
+    }
+
+    fn recalculate_flat_normals(&mut self) {
+        let mut vertices: Vec<Vertex> = Vec::with_capacity(self.indices.len());
+
+        for triangle in self.indices.triangles() {
+            let corners = match (
+                self.vertices.get(triangle[0] as usize),
+                self.vertices.get(triangle[1] as usize),
+                self.vertices.get(triangle[2] as usize)
+            ) {
+                (Some(a), Some(b), Some(c)) => [*a, *b, *c],
+                _ => continue
+            };
+
+            let normal = face_normal(corners[0].position, corners[1].position, corners[2].position);
+            if normal.length_squared() == 0.0 {
+                continue;
+            }
+
+            let normal = normal.normalize();
+            for mut vertex in corners {
+                vertex.normal = normal;
+                vertices.push(vertex);
+            }
+        }
+
+        self.indices = Indices::for_vertices((0..vertices.len() as u32).collect(), vertices.len());
+        self.vertices = vertices;
+        self.invalidate_bounds();
+    }
+}
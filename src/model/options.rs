@@ -0,0 +1,203 @@
+use std::fmt;
+use std::sync::Arc;
+use crate::model::{CoordinateSystem, LoadError, LoadWarning};
+
+/*
+A function returning the encoded bytes of the image at a URI, exactly as the URI is written in
+the glTF file, or `None` to let the loader read it from disk.
+*/
+pub type TextureResolver = Arc<dyn Fn(&str) -> Option<Vec<u8>> + Send + Sync>;
+
+/*
+A function hearing about every decision the loader makes on its own, see `LoadWarning`.
+*/
+pub type WarningHandler = Arc<dyn Fn(&LoadWarning) + Send + Sync>;
+
+/*
+The `LoaderOptions` struct adjusts how `load_model_with` builds a model, and is configured
+through its builder methods, as in `LoaderOptions::new().flip_uv_v(true).scale(0.01)?`. The
+default matches `load_model`:
+
+- `generate_normals` gives primitives without normals flat normals, as the glTF specification
+  asks of renderers, splitting their vertices per triangle.
+- `flip_uv_v` turns texture coordinates upside down, `v` becoming `1.0 - v`, for renderers and
+  formats with the texture origin at the bottom left. The `KHR_texture_transform` stored on
+  each texture is adjusted to match, so applying it to the flipped coordinates gives the flip
+  of what it gave before; a transform baked with `bake_texture_transform` is applied first.
+- `apply_transforms` bakes the transform of the loaded node into its meshes.
+- `texture_loading` chooses when images are decoded.
+- `scale` uniformly scales positions and instance placements, for assets authored in other
+  units, such as 0.001 for a CAD export in millimeters. It is applied after the node transform,
+  so translations kept by `apply_transforms` are scaled too, and normals keep their direction.
+  `Model::normalize_to_unit` run afterwards replaces it with its own scale. A scale of zero or
+  one that is not finite is refused by the builder with `LoadError::InvalidOptions`.
+- `bake_texture_transform` applies the `KHR_texture_transform` of each material's base color
+  texture to the texture coordinates of the meshes using it, and resets the stored transform
+  so it is not applied twice. Only the first texture coordinate set is loaded, so both this
+  and `flip_uv_v` leave textures sampling another set as they are.
+- `coordinate_system` tells which axis points up in the file. glTF defines +Y as up, but some
+  exporters write Z-up data as is; with `CoordinateSystem::ZUp` the loaded model is converted
+  to Y-up, exactly as `Model::convert_axes` does, after every other adjustment.
+- `texture_resolver` is consulted for every image referenced by URI before the filesystem is,
+  so textures bundled in memory, as on the web where there is no filesystem, can be loaded.
+- `placeholder_textures` fills the texture slots whose image could not be read or decoded with
+  a 1×1 magenta texture instead of leaving them empty, so a missing file stands out on screen
+  rather than silently falling back to the factors. The failure is still reported as a
+  `LoadWarning::ImageDecodeFailed`.
+- `max_texture_size` shrinks every decoded image whose width or height exceeds it, keeping the
+  aspect ratio, to bound the memory of models shipped with huge textures. Base color and
+  emissive images are filtered as sRGB colors, the others as data. Deferred textures are left at
+  their full size.
+- `warning_handler` is called with every `LoadWarning` of the load, on the loading thread and
+  in the order the load met them once the model is built, to find out why a model looks
+  wrong. Without one the warnings are dropped, unless the `tracing` feature emits them.
+
+The same file and options give a byte-identical model on every run, with or without the
+`parallel` feature: meshes, materials and textures come out in the order of the document, and
+`Model::deduplicate_materials` and `Mesh::weld_vertices` keep the order of what they are given.
+*/
+#[derive(Clone)]
+pub struct LoaderOptions {
+    pub(crate) generate_normals: bool,
+    pub(crate) flip_uv_v: bool,
+    pub(crate) apply_transforms: bool,
+    pub(crate) texture_loading: TextureLoading,
+    pub(crate) scale: f32,
+    pub(crate) bake_texture_transform: bool,
+    pub(crate) coordinate_system: CoordinateSystem,
+    pub(crate) texture_resolver: Option<TextureResolver>,
+    pub(crate) placeholder_textures: bool,
+    pub(crate) max_texture_size: Option<u32>,
+    pub(crate) warning_handler: Option<WarningHandler>
+}
+
+impl Default for LoaderOptions {
+    fn default() -> Self {
+        LoaderOptions {
+            generate_normals: false,
+            flip_uv_v: false,
+            apply_transforms: false,
+            texture_loading: TextureLoading::Eager,
+            scale: 1.0,
+            bake_texture_transform: false,
+            coordinate_system: CoordinateSystem::YUp,
+            texture_resolver: None,
+            placeholder_textures: false,
+            max_texture_size: None,
+            warning_handler: None
+        }
+    }
+}
+
+impl LoaderOptions {
+    pub fn new() -> Self {
+        LoaderOptions::default()
+    }
+
+    pub fn generate_normals(mut self, enabled: bool) -> Self {
+        self.generate_normals = enabled;
+        self
+    }
+
+    pub fn flip_uv_v(mut self, enabled: bool) -> Self {
+        self.flip_uv_v = enabled;
+        self
+    }
+
+    pub fn apply_transforms(mut self, enabled: bool) -> Self {
+        self.apply_transforms = enabled;
+        self
+    }
+
+    pub fn texture_loading(mut self, loading: TextureLoading) -> Self {
+        self.texture_loading = loading;
+        self
+    }
+
+    /*
+    Sets the uniform scale applied to the loaded geometry. A scale of zero or one that is not
+    finite would collapse the model or fill it with NaN, so it fails with
+    `LoadError::InvalidOptions`.
+    */
+    pub fn scale(mut self, scale: f32) -> Result<Self, LoadError> {
+        self.scale = scale;
+        self.validate()?;
+        Ok(self)
+    }
+
+    pub fn bake_texture_transform(mut self, enabled: bool) -> Self {
+        self.bake_texture_transform = enabled;
+        self
+    }
+
+    pub fn coordinate_system(mut self, system: CoordinateSystem) -> Self {
+        self.coordinate_system = system;
+        self
+    }
+
+    pub fn texture_resolver(mut self, resolver: impl Fn(&str) -> Option<Vec<u8>> + Send + Sync + 'static) -> Self {
+        self.texture_resolver = Some(Arc::new(resolver));
+        self
+    }
+
+    pub fn placeholder_textures(mut self, enabled: bool) -> Self {
+        self.placeholder_textures = enabled;
+        self
+    }
+
+    pub fn max_texture_size(mut self, size: u32) -> Self {
+        self.max_texture_size = Some(size);
+        self
+    }
+
+    pub fn warning_handler(mut self, handler: impl Fn(&LoadWarning) + Send + Sync + 'static) -> Self {
+        self.warning_handler = Some(Arc::new(handler));
+        self
+    }
+}
+
+impl LoaderOptions {
+    /*
+    Checks the options before a load uses them. The builder methods already refuse invalid
+    values, so this only guards against options put together some other way inside the crate.
+    */
+    pub(crate) fn validate(&self) -> Result<(), LoadError> {
+        if self.scale == 0.0 || !self.scale.is_finite() {
+            return Err(LoadError::InvalidOptions(format!("Scale {} must be finite and non-zero", self.scale)));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for LoaderOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LoaderOptions")
+            .field("generate_normals", &self.generate_normals)
+            .field("flip_uv_v", &self.flip_uv_v)
+            .field("apply_transforms", &self.apply_transforms)
+            .field("texture_loading", &self.texture_loading)
+            .field("scale", &self.scale)
+            .field("bake_texture_transform", &self.bake_texture_transform)
+            .field("coordinate_system", &self.coordinate_system)
+            .field("texture_resolver", &self.texture_resolver.as_ref().map(|_| "Fn(&str) -> Option<Vec<u8>>"))
+            .field("placeholder_textures", &self.placeholder_textures)
+            .field("max_texture_size", &self.max_texture_size)
+            .field("warning_handler", &self.warning_handler.as_ref().map(|_| "Fn(&LoadWarning)"))
+            .finish()
+    }
+}
+
+/*
+The `TextureLoading` enum chooses what a load does with the images materials reference. `Eager`
+decodes them all during the load. `Deferred` only records where each image lives and the size
+its header declares, leaving textures without pixels until `Model::resolve_textures` decodes
+them, which suits tools that never need the pixels or upload images on their own. `Skip` leaves
+every texture slot empty.
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TextureLoading {
+    #[default]
+    Eager,
+    Deferred,
+    Skip
+}
@@ -0,0 +1,136 @@
+use glam::*;
+use crate::model::loader::{Material, Model, Transmission};
+use crate::model::{MaterialHandle, Texture};
+
+/*
+The `MaterialMatcher` enum picks the materials a `MaterialPatch` applies to: those whose name
+is exactly `Name`, those whose name matches the `Glob` pattern, where `*` stands for any run of
+characters and `?` for any single one, or the one material `Handle` refers to. Unnamed materials
+are only matched by handle.
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MaterialMatcher {
+    Name(String),
+    Glob(String),
+    Handle(MaterialHandle)
+}
+
+impl MaterialMatcher {
+    pub fn matches(&self, handle: MaterialHandle, material: &Material) -> bool {
+        match self {
+            MaterialMatcher::Name(name) => material.name.as_deref() == Some(name.as_str()),
+            MaterialMatcher::Glob(pattern) => material.name.as_deref().is_some_and(|name| glob_matches(pattern, name)),
+            MaterialMatcher::Handle(target) => *target == handle
+        }
+    }
+}
+
+/*
+Matches `name` against a pattern of literal characters, `*` and `?`, remembering the last `*`
+so a mismatch further on retries with it covering one more character.
+*/
+fn glob_matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            },
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            },
+            _ => match star {
+                Some((star_p, star_n)) => {
+                    star = Some((star_p, star_n + 1));
+                    p = star_p + 1;
+                    n = star_n + 1;
+                },
+                None => return false
+            }
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/*
+The `MaterialPatch` struct lists changes to make to a material, every field left at `None`
+keeping the material's own value. Texture and transmission fields hold the new value itself, so
+`Some(None)` removes a texture or the transmission. Build one with `..Default::default()` and
+the fields to change, as in `MaterialPatch { base_color: Some(Vec4::ONE), ..Default::default() }`.
+*/
+#[derive(Clone, Debug, Default)]
+pub struct MaterialPatch {
+    pub base_color: Option<Vec4>,
+    pub base_color_texture: Option<Option<Texture>>,
+    pub metallic_factor: Option<f32>,
+    pub roughness_factor: Option<f32>,
+    pub metallic_roughness_texture: Option<Option<Texture>>,
+    pub normal_texture: Option<Option<Texture>>,
+    pub normal_scale: Option<f32>,
+    pub occlusion_texture: Option<Option<Texture>>,
+    pub occlusion_strength: Option<f32>,
+    pub emissive_factor: Option<Vec3>,
+    pub emissive_texture: Option<Option<Texture>>,
+    pub transmission: Option<Option<Transmission>>,
+    pub ior: Option<f32>,
+    pub unlit: Option<bool>
+}
+
+impl MaterialPatch {
+    /*
+    Writes every value the patch sets into `material`.
+    */
+    pub fn apply(&self, material: &mut Material) {
+        fn set<T: Clone>(target: &mut T, value: &Option<T>) {
+            if let Some(value) = value {
+                *target = value.clone();
+            }
+        }
+
+        set(&mut material.base_color, &self.base_color);
+        set(&mut material.base_color_texture, &self.base_color_texture);
+        set(&mut material.metallic_factor, &self.metallic_factor);
+        set(&mut material.roughness_factor, &self.roughness_factor);
+        set(&mut material.metallic_roughness_texture, &self.metallic_roughness_texture);
+        set(&mut material.normal_texture, &self.normal_texture);
+        set(&mut material.normal_scale, &self.normal_scale);
+        set(&mut material.occlusion_texture, &self.occlusion_texture);
+        set(&mut material.occlusion_strength, &self.occlusion_strength);
+        set(&mut material.emissive_factor, &self.emissive_factor);
+        set(&mut material.emissive_texture, &self.emissive_texture);
+        set(&mut material.transmission, &self.transmission);
+        set(&mut material.ior, &self.ior);
+        set(&mut material.unlit, &self.unlit);
+    }
+}
+
+impl Model {
+    /*
+    Applies each patch to every material its matcher picks, in the order of `patches`, so a
+    later patch wins over an earlier one where both set a value. Returns the positions in
+    `patches` of the patches that matched no material, such as a misspelled name, which were
+    applied nowhere.
+    */
+    pub fn apply_material_patches(&mut self, patches: &[(MaterialMatcher, MaterialPatch)]) -> Vec<usize> {
+        let mut unmatched = Vec::new();
+        for (position, (matcher, patch)) in patches.iter().enumerate() {
+            let mut matched = false;
+            for (index, material) in self.materials.iter_mut().enumerate() {
+                if matcher.matches(MaterialHandle::from(index), material) {
+                    patch.apply(material);
+                    matched = true;
+                }
+            }
+            if !matched {
+                unmatched.push(position);
+            }
+        }
+        unmatched
+    }
+}
@@ -0,0 +1,225 @@
+use glam::*;
+use std::f32::consts::{PI, TAU};
+use crate::model::loader::{Material, Mesh, Model, Vertex};
+use crate::model::{Indices, MaterialHandle};
+
+fn vertex(position: Vec3, normal: Vec3, tex_coord: Vec2) -> Vertex {
+    Vertex {
+        position,
+        normal,
+        tex_coord,
+        ..Default::default()
+    }
+}
+
+fn mesh(vertices: Vec<Vertex>, indices: Vec<u32>) -> Mesh {
+    Mesh {
+        name: None,
+        indices: Indices::for_vertices(indices, vertices.len()),
+        vertices,
+        material: MaterialHandle::default(),
+        bounds: None,
+        instances: Vec::new(),
+        extras: None
+    }
+}
+
+/*
+Appends a grid of `(columns + 1) * (rows + 1)` vertices produced by `make_vertex` for every
+`(u, v)` in [0, 1], and two triangles per cell. Shapes wrapping around should compute their
+angles from `u.fract()` so the seam vertices at `u = 1` land exactly on those at `u = 0`. The grid is wound so the triangles face the
+side that sees `u` growing to the right and `v` growing downwards, as texture coordinates do.
+Triangles collapsed to a line, such as those at the poles of a sphere, are left out.
+*/
+fn append_grid(
+    vertices: &mut Vec<Vertex>,
+    indices: &mut Vec<u32>,
+    columns: u32,
+    rows: u32,
+    make_vertex: impl Fn(f32, f32) -> Vertex
+) {
+    let base = vertices.len() as u32;
+    for row in 0..=rows {
+        for column in 0..=columns {
+            vertices.push(make_vertex(column as f32 / columns as f32, row as f32 / rows as f32));
+        }
+    }
+
+    let index = |column: u32, row: u32| base + row * (columns + 1) + column;
+    for row in 0..rows {
+        for column in 0..columns {
+            let top_left = index(column, row);
+            let top_right = index(column + 1, row);
+            let bottom_left = index(column, row + 1);
+            let bottom_right = index(column + 1, row + 1);
+
+            for triangle in [[top_left, bottom_left, bottom_right], [top_left, bottom_right, top_right]] {
+                let [a, b, c] = triangle.map(|index| vertices[index as usize].position);
+                if (b - a).cross(c - a) != Vec3::ZERO {
+                    indices.extend_from_slice(&triangle);
+                }
+            }
+        }
+    }
+}
+
+/*
+Returns the outward direction around the Y axis at `u` turns, shared by the side of a cylinder
+and its caps so that their rims land on the same positions.
+*/
+fn around_y(u: f32) -> Vec3 {
+    let (sin, cos) = (u.fract() * TAU).sin_cos();
+    Vec3::new(-cos, 0.0, sin)
+}
+
+/*
+Appends a flat disc facing along `normal`, made of a centre vertex and a ring of `segments`
+triangles. Texture coordinates map the disc onto the unit square. The rim vertices are placed
+like the side of `Mesh::cylinder`, so that the two close up.
+*/
+fn append_disc(vertices: &mut Vec<Vertex>, indices: &mut Vec<u32>, center: Vec3, radius: f32, normal: Vec3, segments: u32) {
+    let base = vertices.len() as u32;
+    vertices.push(vertex(center, normal, Vec2::splat(0.5)));
+    for segment in 0..=segments {
+        let direction = around_y(segment as f32 / segments as f32);
+        let position = direction * radius + center;
+        vertices.push(vertex(position, normal, Vec2::new(0.5 + direction.x * 0.5, 0.5 + direction.z * 0.5)));
+    }
+
+    for segment in 0..segments {
+        let (a, b) = (base + 1 + segment, base + 2 + segment);
+        if normal.y > 0.0 {
+            indices.extend_from_slice(&[base, a, b]);
+        } else {
+            indices.extend_from_slice(&[base, b, a]);
+        }
+    }
+}
+
+impl Mesh {
+    /*
+    Builds an axis-aligned cube of edge length `size` centred on the origin. Each face has its
+    own four vertices, so normals stay flat and every face is mapped onto the whole texture.
+    */
+    pub fn cube(size: f32) -> Mesh {
+        let half = size * 0.5;
+        let faces = [
+            (Vec3::X, Vec3::NEG_Z, Vec3::Y),
+            (Vec3::NEG_X, Vec3::Z, Vec3::Y),
+            (Vec3::Y, Vec3::X, Vec3::NEG_Z),
+            (Vec3::NEG_Y, Vec3::X, Vec3::Z),
+            (Vec3::Z, Vec3::X, Vec3::Y),
+            (Vec3::NEG_Z, Vec3::NEG_X, Vec3::Y)
+        ];
+
+        let mut vertices = Vec::with_capacity(24);
+        let mut indices = Vec::with_capacity(36);
+        for (normal, right, up) in faces {
+            append_grid(&mut vertices, &mut indices, 1, 1, |u, v| {
+                let position = (normal + right * (u * 2.0 - 1.0) + up * (1.0 - v * 2.0)) * half;
+                vertex(position, normal, Vec2::new(u, v))
+            });
+        }
+        mesh(vertices, indices)
+    }
+
+    /*
+    Builds a sphere centred on the origin from `segments` slices around the Y axis and half as
+    many stacks from the top pole to the bottom one, so its quads are roughly square. The
+    texture wraps around once horizontally, with a seam of duplicated vertices, and runs from
+    top to bottom vertically.
+    */
+    pub fn uv_sphere(radius: f32, segments: u32) -> Mesh {
+        Mesh::uv_sphere_with_rings(radius, segments, segments / 2)
+    }
+
+    /*
+    Same as `uv_sphere`, with `rings` stacks from pole to pole.
+    */
+    pub fn uv_sphere_with_rings(radius: f32, segments: u32, rings: u32) -> Mesh {
+        let (segments, rings) = (segments.max(3), rings.max(2));
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        append_grid(&mut vertices, &mut indices, segments, rings, |u, v| {
+            // The poles are set exactly, as scaling the azimuth by a zero sine would give some
+            // of them a negative zero and split them from their neighbours.
+            let normal = match v {
+                0.0 => Vec3::Y,
+                1.0 => Vec3::NEG_Y,
+                _ => {
+                    let (sin_polar, cos_polar) = (v * PI).sin_cos();
+                    let (sin_azimuth, cos_azimuth) = (u.fract() * TAU).sin_cos();
+                    Vec3::new(-sin_polar * cos_azimuth, cos_polar, sin_polar * sin_azimuth)
+                }
+            };
+            vertex(normal * radius, normal, Vec2::new(u, v))
+        });
+        mesh(vertices, indices)
+    }
+
+    /*
+    Builds a flat rectangle in the XZ plane facing +Y and centred on the origin, split into
+    `subdivisions` quads along each side, at least one.
+    */
+    pub fn plane(width: f32, depth: f32, subdivisions: u32) -> Mesh {
+        let subdivisions = subdivisions.max(1);
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        append_grid(&mut vertices, &mut indices, subdivisions, subdivisions, |u, v| {
+            let position = Vec3::new((u - 0.5) * width, 0.0, (v - 0.5) * depth);
+            vertex(position, Vec3::Y, Vec2::new(u, v))
+        });
+        mesh(vertices, indices)
+    }
+
+    /*
+    Builds a closed cylinder along the Y axis centred on the origin. The side is made of
+    `segments` quads with smooth normals and the texture wrapped around it; the caps have their
+    own flat-shaded vertices.
+    */
+    pub fn cylinder(radius: f32, height: f32, segments: u32) -> Mesh {
+        let segments = segments.max(3);
+        let half = height * 0.5;
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        append_grid(&mut vertices, &mut indices, segments, 1, |u, v| {
+            let normal = around_y(u);
+            vertex(normal * radius + Vec3::Y * (half - v * height), normal, Vec2::new(u, v))
+        });
+        append_disc(&mut vertices, &mut indices, Vec3::Y * half, radius, Vec3::Y, segments);
+        append_disc(&mut vertices, &mut indices, Vec3::NEG_Y * half, radius, Vec3::NEG_Y, segments);
+        mesh(vertices, indices)
+    }
+
+    /*
+    Builds a torus lying in the XZ plane around the Y axis. `major` is the distance from the
+    centre to the middle of the tube and `minor` the tube's radius; `segments` divide the ring
+    and `sides` the tube. The texture wraps once around each direction.
+    */
+    pub fn torus(major: f32, minor: f32, segments: u32, sides: u32) -> Mesh {
+        let (segments, sides) = (segments.max(3), sides.max(3));
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        append_grid(&mut vertices, &mut indices, segments, sides, |u, v| {
+            let (sin_ring, cos_ring) = (u.fract() * TAU).sin_cos();
+            let (sin_tube, cos_tube) = (v.fract() * TAU).sin_cos();
+            let outward = Vec3::new(-cos_ring, 0.0, sin_ring);
+            let normal = outward * cos_tube - Vec3::Y * sin_tube;
+            vertex(outward * major + normal * minor, normal, Vec2::new(u, v))
+        });
+        mesh(vertices, indices)
+    }
+}
+
+impl Model {
+    /*
+    Wraps a single mesh into a model with one default material, which the mesh is assigned to.
+    */
+    pub fn from_mesh(mut mesh: Mesh) -> Model {
+        mesh.material = MaterialHandle::default();
+        Model {
+            meshes: vec![mesh],
+            materials: vec![Material::default()]
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use std::ops::ControlFlow;
+use crate::model::LoadError;
+
+/*
+The `LoadPhase` enum names the stage a load has reached. The texture and mesh phases count the
+items finished out of the total: they are reported once before the first item and again as
+items finish, so the counts only grow and the last report of a phase has them equal.
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LoadPhase {
+    ParsingDocument,
+    ReadingBuffers,
+    DecodingTextures { decoded: usize, total: usize },
+    ProcessingMeshes { processed: usize, total: usize },
+    Finished
+}
+
+impl LoadPhase {
+    /*
+    Places the phase within the whole load. Decoding textures and processing meshes take most
+    of the time, so they get most of the range.
+    */
+    fn fraction(&self) -> f32 {
+        let part = |done: usize, total: usize| if total == 0 { 1.0 } else { done as f32 / total as f32 };
+        match *self {
+            LoadPhase::ParsingDocument => 0.0,
+            LoadPhase::ReadingBuffers => 0.05,
+            LoadPhase::DecodingTextures { decoded, total } => 0.1 + 0.5 * part(decoded, total),
+            LoadPhase::ProcessingMeshes { processed, total } => 0.6 + 0.4 * part(processed, total),
+            LoadPhase::Finished => 1.0
+        }
+    }
+}
+
+/*
+The `LoadProgress` struct is what `load_model_with_progress` hands its callback: the phase
+reached and a coarse fraction of the whole load, between 0.0 and 1.0.
+*/
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LoadProgress {
+    pub phase: LoadPhase,
+    pub fraction: f32
+}
+
+/*
+Forwards the phases of a load to the user's callback, turning a request to stop into
+`LoadError::Cancelled`.
+*/
+pub(crate) struct Progress<'a> {
+    callback: &'a mut dyn FnMut(LoadProgress) -> ControlFlow<()>
+}
+
+impl<'a> Progress<'a> {
+    pub(crate) fn new(callback: &'a mut dyn FnMut(LoadProgress) -> ControlFlow<()>) -> Self {
+        Progress { callback }
+    }
+
+    pub(crate) fn report(&mut self, phase: LoadPhase) -> Result<(), LoadError> {
+        match (self.callback)(LoadProgress { phase, fraction: phase.fraction() }) {
+            ControlFlow::Continue(()) => Ok(()),
+            ControlFlow::Break(()) => Err(LoadError::Cancelled)
+        }
+    }
+}
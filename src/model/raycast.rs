@@ -0,0 +1,140 @@
+use glam::*;
+use crate::model::loader::{Mesh, Model};
+
+/*
+The `RayHit` struct describes where a ray meets a mesh. `distance` is measured along the
+normalized ray direction, `triangle` is the index of the triangle hit (its indices start at
+`triangle * 3`) and `barycentric` holds the weights of its three corners. The position, normal
+and texture coordinates are interpolated from the corners with those weights.
+*/
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RayHit {
+    pub distance: f32,
+    pub triangle: usize,
+    pub barycentric: Vec3,
+    pub position: Vec3,
+    pub normal: Vec3,
+    pub tex_coord: Vec2
+}
+
+/*
+Intersects a ray with a triangle using the Möller–Trumbore algorithm and returns the ray
+parameter and the barycentric weights of `b` and `c`. Rays parallel to the triangle, or nearly
+so, and degenerate triangles never hit. With `cull_back_faces` set, triangles whose
+counter-clockwise side faces away from the ray are skipped.
+*/
+fn intersect_triangle(
+    origin: Vec3,
+    direction: Vec3,
+    [a, b, c]: [Vec3; 3],
+    cull_back_faces: bool
+) -> Option<(f32, f32, f32)> {
+    let edge_ab = b - a;
+    let edge_ac = c - a;
+    let p = direction.cross(edge_ac);
+    let determinant = edge_ab.dot(p);
+
+    let threshold = 1e-7 * edge_ab.cross(edge_ac).length() * direction.length();
+    if determinant.abs() <= threshold || (cull_back_faces && determinant < 0.0) {
+        return None;
+    }
+
+    let inverse = 1.0 / determinant;
+    let offset = origin - a;
+    let u = offset.dot(p) * inverse;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+
+    let q = offset.cross(edge_ab);
+    let v = direction.dot(q) * inverse;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+
+    let t = edge_ac.dot(q) * inverse;
+    if t > 0.0 && t.is_finite() { Some((t, u, v)) } else { None }
+}
+
+impl Mesh {
+    /*
+    Returns the closest intersection of the ray starting at `origin` and heading along
+    `direction` with the mesh's triangles, ignoring instances. Only hits in front of the origin
+    count. With `cull_back_faces` set, triangles seen from their back are ignored, using the
+    counter-clockwise winding as the front. Triangles with out-of-range indices and trailing
+    indices are skipped, and a zero direction never hits.
+    */
+    pub fn raycast(&self, origin: Vec3, direction: Vec3, cull_back_faces: bool) -> Option<RayHit> {
+        let direction = direction.normalize_or_zero();
+        if direction == Vec3::ZERO {
+            return None;
+        }
+
+        let vertex_count = self.vertices.len();
+        let mut closest: Option<(f32, usize, f32, f32)> = None;
+        for (triangle, indices) in self.indices.triangles().enumerate() {
+            if indices.iter().any(|&index| index as usize >= vertex_count) {
+                continue;
+            }
+            let corners = [0, 1, 2].map(|corner| self.vertices[indices[corner] as usize].position);
+            if let Some((t, u, v)) = intersect_triangle(origin, direction, corners, cull_back_faces) {
+                if closest.is_none_or(|(closest_t, ..)| t < closest_t) {
+                    closest = Some((t, triangle, u, v));
+                }
+            }
+        }
+
+        let (distance, triangle, u, v) = closest?;
+        let [a, b, c] = self.indices.triangle(triangle).map(|index| &self.vertices[index as usize]);
+        let barycentric = Vec3::new(1.0 - u - v, u, v);
+        Some(RayHit {
+            distance,
+            triangle,
+            barycentric,
+            position: origin + direction * distance,
+            normal: (a.normal * barycentric.x + b.normal * barycentric.y + c.normal * barycentric.z).normalize_or_zero(),
+            tex_coord: a.tex_coord * barycentric.x + b.tex_coord * barycentric.y + c.tex_coord * barycentric.z
+        })
+    }
+}
+
+impl Model {
+    /*
+    Casts a ray against every mesh and returns the closest hit along with the index of the mesh
+    it belongs to, see `Mesh::raycast`. Instanced meshes are tested once per instance, with the
+    hit position and normal brought back into model space.
+    */
+    pub fn raycast(&self, origin: Vec3, direction: Vec3, cull_back_faces: bool) -> Option<(usize, RayHit)> {
+        let direction = direction.normalize_or_zero();
+        let mut closest: Option<(usize, RayHit)> = None;
+
+        for (mesh_index, mesh) in self.meshes.iter().enumerate() {
+            let hits: Vec<RayHit> = if mesh.instances.is_empty() {
+                mesh.raycast(origin, direction, cull_back_faces).into_iter().collect()
+            } else {
+                mesh.instances
+                    .iter()
+                    .filter(|instance| instance.determinant() != 0.0)
+                    .filter_map(|instance| {
+                        let inverse = instance.inverse();
+                        let local_origin = inverse.transform_point3(origin);
+                        let local_direction = inverse.transform_vector3(direction);
+                        let mut hit = mesh.raycast(local_origin, local_direction, cull_back_faces)?;
+
+                        hit.position = instance.transform_point3(hit.position);
+                        hit.normal = (Mat3::from_mat4(inverse).transpose() * hit.normal).normalize_or_zero();
+                        hit.distance = hit.position.distance(origin);
+                        Some(hit)
+                    })
+                    .collect()
+            };
+
+            for hit in hits {
+                if closest.is_none_or(|(_, closest_hit)| hit.distance < closest_hit.distance) {
+                    closest = Some((mesh_index, hit));
+                }
+            }
+        }
+        closest
+    }
+}
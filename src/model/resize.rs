@@ -0,0 +1,177 @@
+use crate::model::{Texture, TextureFormat};
+
+/*
+The `ResizeFilter` enum chooses how `Texture::resized` weighs the source pixels behind each
+output pixel. `Box` averages the pixels the output pixel covers, each by the area it covers,
+which keeps the average color of the image and suits downscaling. `Bilinear` weighs them by a
+tent that widens with the scale factor, giving smoother gradients at the cost of slight blur.
+Both blend the two nearest pixels when upscaling.
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ResizeFilter {
+    #[default]
+    Box,
+    Bilinear
+}
+
+/*
+The weights of the source pixels contributing to one output pixel along an axis, starting at
+source pixel `start`.
+*/
+struct Taps {
+    start: usize,
+    weights: Vec<f32>
+}
+
+/*
+Computes the taps of every output pixel along an axis of `source` pixels resized to `target`.
+The filter is stretched by the scale factor when shrinking, so every source pixel contributes.
+Weights falling past the edges are dropped and the others scaled back up to sum to one.
+*/
+fn axis_taps(source: usize, target: usize, filter: ResizeFilter) -> Vec<Taps> {
+    let scale = source as f32 / target as f32;
+    let width = scale.max(1.0);
+    (0..target)
+        .map(|output| {
+            let center = (output as f32 + 0.5) * scale;
+            let (left, right) = (center - width * 0.5, center + width * 0.5);
+            let radius = match filter {
+                ResizeFilter::Box => width * 0.5,
+                ResizeFilter::Bilinear => width
+            };
+            let first = (center - radius).floor().max(0.0) as usize;
+            let last = ((center + radius).ceil() as usize).min(source);
+
+            let weights: Vec<f32> = (first..last)
+                .map(|pixel| match filter {
+                    ResizeFilter::Box => (right.min(pixel as f32 + 1.0) - left.max(pixel as f32)).max(0.0),
+                    ResizeFilter::Bilinear => (1.0 - (pixel as f32 + 0.5 - center).abs() / width).max(0.0)
+                })
+                .collect();
+            let total: f32 = weights.iter().sum();
+            Taps { start: first, weights: weights.iter().map(|weight| weight / total).collect() }
+        })
+        .collect()
+}
+
+fn srgb_to_linear(value: f32) -> f32 {
+    match value <= 0.04045 {
+        true => value / 12.92,
+        false => ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(value: f32) -> f32 {
+    match value <= 0.0031308 {
+        true => value * 12.92,
+        false => 1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/*
+Resizes the channels of `texture`, decoding the color channels from sRGB before filtering and
+encoding them back afterwards when `srgb` is set.
+*/
+fn resize(texture: &Texture, width: u32, height: u32, filter: ResizeFilter, srgb: bool) -> Texture {
+    assert!(width > 0 && height > 0, "Failed to resize texture. (Size must be positive)");
+    assert!(!texture.pixels().is_empty(), "Failed to resize texture. (Texture has no pixels)");
+
+    let format = texture.format();
+    let channel_count = texture.channel_count();
+    let color_channels = if channel_count >= 3 { 3 } else { 1 };
+    let srgb = srgb && format != TextureFormat::Rgba32F;
+    let size = format.bytes_per_channel();
+    let (source_width, source_height) = (texture.width() as usize, texture.height() as usize);
+
+    let values: Vec<f32> = texture
+        .pixels()
+        .chunks_exact(size)
+        .enumerate()
+        .map(|(index, bytes)| {
+            let value = format.decode(bytes);
+            match srgb && index % channel_count < color_channels {
+                true => srgb_to_linear(value),
+                false => value
+            }
+        })
+        .collect();
+
+    let columns = axis_taps(source_width, width as usize, filter);
+    let mut rows_resized = vec![0.0; source_height * width as usize * channel_count];
+    for y in 0..source_height {
+        for (x, taps) in columns.iter().enumerate() {
+            for channel in 0..channel_count {
+                let value: f32 = taps
+                    .weights
+                    .iter()
+                    .enumerate()
+                    .map(|(offset, weight)| weight * values[(y * source_width + taps.start + offset) * channel_count + channel])
+                    .sum();
+                rows_resized[(y * width as usize + x) * channel_count + channel] = value;
+            }
+        }
+    }
+
+    let rows = axis_taps(source_height, height as usize, filter);
+    let mut pixels = Vec::with_capacity(width as usize * height as usize * channel_count * size);
+    for taps in &rows {
+        for x in 0..width as usize {
+            for channel in 0..channel_count {
+                let value: f32 = taps
+                    .weights
+                    .iter()
+                    .enumerate()
+                    .map(|(offset, weight)| weight * rows_resized[((taps.start + offset) * width as usize + x) * channel_count + channel])
+                    .sum();
+                let value = match srgb && channel < color_channels {
+                    true => linear_to_srgb(value.max(0.0)),
+                    false => value
+                };
+                format.encode(value, &mut pixels);
+            }
+        }
+    }
+
+    Texture::from_pixels(pixels, width, height, channel_count, format)
+        .expect("Failed to resize texture. (Pixel count does not match the dimensions)")
+        .with_transform(texture.transform())
+        .with_tex_coord(texture.tex_coord())
+}
+
+/*
+Returns the size of a `width`×`height` image scaled down, keeping its aspect ratio, so neither
+side exceeds `limit`, or `None` when it already fits. Sides never shrink below one pixel.
+*/
+pub(crate) fn clamped_size(width: u32, height: u32, limit: u32) -> Option<(u32, u32)> {
+    let largest = width.max(height);
+    if largest <= limit || limit == 0 {
+        return None;
+    }
+    let scale = limit as f64 / largest as f64;
+    let side = |side: u32| ((side as f64 * scale).round() as u32).clamp(1, limit);
+    Some((side(width), side(height)))
+}
+
+impl Texture {
+    /*
+    Returns a copy of the texture resized to `width`×`height` pixels with `filter`, keeping its
+    format, channel count, transform and texture coordinate set. Any size works, including
+    sizes that do not divide the source evenly and a single pixel. The pixels are taken to be
+    sRGB-encoded colors, as in base color and emissive textures, and are filtered in linear
+    space so downscaled albedo keeps its brightness; alpha and `Rgba32F` pixels, which are
+    linear already, are filtered as they are. Use `resized_linear` for textures holding data,
+    such as normal, metallic-roughness and occlusion maps. Panics when either size is zero or
+    the texture has no pixels.
+    */
+    pub fn resized(&self, width: u32, height: u32, filter: ResizeFilter) -> Texture {
+        resize(self, width, height, filter, true)
+    }
+
+    /*
+    Same as `resized`, filtering every channel as it is stored.
+    */
+    pub fn resized_linear(&self, width: u32, height: u32, filter: ResizeFilter) -> Texture {
+        resize(self, width, height, filter, false)
+    }
+}
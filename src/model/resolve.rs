@@ -0,0 +1,83 @@
+use crate::model::loader::{Material, Model};
+use crate::model::{Texture, TextureError, TextureSource};
+
+/*
+Lists every texture slot of a material that holds a texture, mutably.
+*/
+pub(crate) fn material_textures_mut(material: &mut Material) -> impl Iterator<Item = &mut Texture> {
+    [
+        material.base_color_texture.as_mut(),
+        material.metallic_roughness_texture.as_mut(),
+        material.normal_texture.as_mut(),
+        material.occlusion_texture.as_mut(),
+        material.emissive_texture.as_mut(),
+        material.transmission.as_mut().and_then(|transmission| transmission.texture.as_mut()),
+        material.volume.as_mut().and_then(|volume| volume.thickness_texture.as_mut()),
+        material.clearcoat_texture.as_mut(),
+        material.clearcoat_roughness_texture.as_mut(),
+        material.clearcoat_normal_texture.as_mut()
+    ]
+    .into_iter()
+    .flatten()
+}
+
+/*
+Decodes the deferred textures among `textures`, each source once: textures sharing a source with
+one decoded earlier get a copy of its pixels, under their own transform.
+*/
+fn resolve_all<'a>(
+    textures: impl Iterator<Item = &'a mut Texture>,
+    decoded: &mut Vec<(TextureSource, Texture)>
+) -> Result<(), TextureError> {
+    for texture in textures {
+        let source = match texture.source() {
+            Some(source) => source.clone(),
+            None => continue
+        };
+        let pixels = match decoded.iter().find(|(known, _)| *known == source) {
+            Some((_, pixels)) => pixels.clone(),
+            None => {
+                let pixels = source.load()?;
+                decoded.push((source, pixels.clone()));
+                pixels
+            }
+        };
+        *texture = pixels.with_transform(texture.transform()).with_tex_coord(texture.tex_coord());
+    }
+    Ok(())
+}
+
+impl Material {
+    /*
+    Decodes every deferred texture of the material. On failure the textures resolved before the
+    one that failed keep their pixels and the others stay deferred.
+    */
+    pub fn resolve_textures(&mut self) -> Result<(), TextureError> {
+        resolve_all(material_textures_mut(self), &mut Vec::new())
+    }
+}
+
+impl Model {
+    /*
+    Decodes every deferred texture of a model loaded with `TextureLoading::Deferred`, decoding
+    an image shared by several materials only once. Models loaded eagerly are left as they are.
+    */
+    pub fn resolve_textures(&mut self) -> Result<(), TextureError> {
+        let all: Vec<usize> = (0..self.materials.len()).collect();
+        self.resolve_material_textures(&all)
+    }
+
+    /*
+    Same as `resolve_textures`, limited to the materials at `material_indices`. Indices without
+    a material are ignored.
+    */
+    pub fn resolve_material_textures(&mut self, material_indices: &[usize]) -> Result<(), TextureError> {
+        let mut decoded = Vec::new();
+        for (index, material) in self.materials.iter_mut().enumerate() {
+            if material_indices.contains(&index) {
+                resolve_all(material_textures_mut(material), &mut decoded)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,257 @@
+use glam::*;
+use std::path::Path;
+use crate::model::animation::{read_animations, Animation};
+use crate::model::loader::{import, load_nodes, read_extras, read_instances, Model};
+use crate::model::{CoordinateSystem, LoadError, LoaderOptions, MeshHandle};
+
+/*
+The `Node` struct is one node of a `Scene`: its name, its transform relative to its parent as
+translation, rotation and scale, and the positions of its parent and children in
+`Scene::nodes`. `source_index` is the index of the node in the glTF document, for matching it
+with animation channels and other references into the file. `meshes` holds one handle per
+triangle primitive of the node's glTF mesh, into `Scene::model`, and `instances` the transforms
+of `EXT_mesh_gpu_instancing` relative to the node, empty for a node drawn once. `camera`, `skin`
+and `light` are the indices of the camera, skin and `KHR_lights_punctual` light the node refers
+to in the document. `extras` holds the custom JSON of the node, as on `Mesh`.
+*/
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Node {
+    pub name: Option<String>,
+    pub source_index: usize,
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+    pub parent: Option<usize>,
+    pub children: Vec<usize>,
+    pub meshes: Vec<MeshHandle>,
+    pub instances: Vec<Mat4>,
+    pub camera: Option<usize>,
+    pub skin: Option<usize>,
+    pub light: Option<usize>,
+    pub extras: Option<serde_json::Value>
+}
+
+impl Node {
+    /*
+    Returns the transform of the node relative to its parent: scale, then rotation, then
+    translation.
+    */
+    pub fn local_transform(&self) -> Mat4 {
+        Mat4::from_scale_rotation_translation(self.scale, self.rotation, self.translation)
+    }
+}
+
+/*
+The `Scene` struct keeps the node hierarchy of a glTF scene next to the meshes and materials it
+draws. The meshes of `model` stay in the space of their glTF mesh, with no node transform baked
+in and no instances, and a glTF mesh used by several nodes is loaded once and shared by handle.
+`roots` lists the nodes without a parent. Nodes are stored parents first, in depth-first order,
+so a parent always comes before its children. `animations` holds the glTF animations, their
+channels referring to nodes by position in `nodes`; `sample_animation` evaluates them.
+*/
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Scene {
+    pub name: Option<String>,
+    pub nodes: Vec<Node>,
+    pub roots: Vec<usize>,
+    pub model: Model,
+    pub animations: Vec<Animation>
+}
+
+impl Scene {
+    /*
+    Returns the transform from the space of a node to the space of the scene, accumulating the
+    local transforms of its ancestors. Panics when `node` is out of range.
+    */
+    pub fn world_transform(&self, node: usize) -> Mat4 {
+        let mut matrix = self.nodes[node].local_transform();
+        let mut current = self.nodes[node].parent;
+        while let Some(parent) = current {
+            matrix = self.nodes[parent].local_transform() * matrix;
+            current = self.nodes[parent].parent;
+        }
+        matrix
+    }
+
+    /*
+    Iterates over the nodes reachable from the roots, depth first: every node comes before its
+    children, which come in order.
+    */
+    pub fn depth_first(&self) -> impl Iterator<Item = usize> + '_ {
+        let mut stack: Vec<usize> = self.roots.iter().rev().copied().collect();
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(self.nodes[node].children.iter().rev());
+            Some(node)
+        })
+    }
+
+    /*
+    Builds a flat model drawing the scene: every mesh once, with one instance per placement of
+    it in the scene, in depth-first node order. A mesh placed once at the identity is left
+    without instances. `Model::flattened` turns the result into independent world-space meshes.
+    */
+    pub fn to_model(&self) -> Model {
+        let mut model = self.model.clone();
+        let mut world = vec![Mat4::IDENTITY; self.nodes.len()];
+        for node in self.depth_first() {
+            let parent = self.nodes[node].parent.map_or(Mat4::IDENTITY, |parent| world[parent]);
+            world[node] = parent * self.nodes[node].local_transform();
+            for handle in &self.nodes[node].meshes {
+                if let Some(mesh) = model.mesh_mut(*handle) {
+                    match self.nodes[node].instances.is_empty() {
+                        true => mesh.instances.push(world[node]),
+                        false => mesh.instances.extend(self.nodes[node].instances.iter().map(|&instance| world[node] * instance))
+                    }
+                }
+            }
+        }
+
+        for mesh in &mut model.meshes {
+            if mesh.instances == [Mat4::IDENTITY] {
+                mesh.instances.clear();
+            }
+        }
+        model
+    }
+}
+
+/*
+Lists a node and its descendants depth first, each with the position of its parent in the
+list. Nodes already listed are skipped, so a malformed file naming a node twice cannot make the
+hierarchy loop.
+*/
+fn collect_nodes<'a>(
+    node: gltf::Node<'a>,
+    parent: Option<usize>,
+    listed: &mut [bool],
+    nodes: &mut Vec<(gltf::Node<'a>, Option<usize>)>
+) {
+    if std::mem::replace(&mut listed[node.index()], true) {
+        return;
+    }
+    let position = nodes.len();
+    let children: Vec<gltf::Node<'a>> = node.children().collect();
+    nodes.push((node, parent));
+    for child in children {
+        collect_nodes(child, Some(position), listed, nodes);
+    }
+}
+
+/*
+Loads the scene at `scene_index` of a GLTF file as a node hierarchy. Each glTF mesh the scene
+uses is loaded once, the first node using it deciding where it goes in the mesh list, and every
+node using it refers to the same meshes. See `Scene` for the layout.
+*/
+pub fn load_scene_graph(path: impl AsRef<Path>, scene_index: usize) -> Result<Scene, LoadError> {
+    load_scene_graph_with(path, scene_index, &LoaderOptions::default())
+}
+
+/*
+Same as `load_scene_graph`, adjusted by `options`. Node transforms stay on the nodes, so
+`apply_transforms` has no effect. `scale` and `coordinate_system` apply to the whole scene: the
+meshes are scaled and converted like the loaders do, and the node translations, instances and
+animation channels with them, so the scene keeps its layout.
+*/
+pub fn load_scene_graph_with(path: impl AsRef<Path>, scene_index: usize, options: &LoaderOptions) -> Result<Scene, LoadError> {
+    let path = path.as_ref();
+    let (document, buffers) = import(path, &mut None)?;
+    let buffers = buffers.slices();
+
+    let scene = document
+        .scenes()
+        .nth(scene_index)
+        .ok_or(LoadError::SceneNotFound(scene_index))?;
+
+    let mut listed = vec![false; document.nodes().len()];
+    let mut sources = Vec::new();
+    for root in scene.nodes() {
+        collect_nodes(root, None, &mut listed, &mut sources);
+    }
+
+    let mut mesh_nodes = Vec::new();
+    let mut mesh_handles: Vec<Option<Vec<MeshHandle>>> = vec![None; document.meshes().len()];
+    let mut mesh_count = 0;
+    for (node, _) in &sources {
+        let mesh = match node.mesh() {
+            Some(mesh) if mesh_handles[mesh.index()].is_none() => mesh,
+            _ => continue
+        };
+        let primitive_count = mesh.primitives().filter(|primitive| primitive.mode() == gltf::mesh::Mode::Triangles).count();
+        mesh_handles[mesh.index()] = Some((mesh_count..mesh_count + primitive_count).map(MeshHandle::from).collect());
+        mesh_count += primitive_count;
+        mesh_nodes.push((node.clone(), Mat4::IDENTITY));
+    }
+
+    let mut model = load_nodes(&document, &buffers, &mesh_nodes, path, options, None)?;
+    for mesh in &mut model.meshes {
+        mesh.instances.clear();
+    }
+
+    let mut nodes: Vec<Node> = sources
+        .iter()
+        .map(|(node, parent)| {
+            let (translation, rotation, scale) = node.transform().decomposed();
+            Node {
+                name: node.name().map(str::to_string),
+                source_index: node.index(),
+                translation: Vec3::from(translation),
+                rotation: Quat::from_array(rotation),
+                scale: Vec3::from(scale),
+                parent: *parent,
+                children: Vec::new(),
+                meshes: node
+                    .mesh()
+                    .and_then(|mesh| mesh_handles[mesh.index()].clone())
+                    .unwrap_or_default(),
+                instances: read_instances(node, &document, &buffers),
+                camera: node.camera().map(|camera| camera.index()),
+                skin: node.skin().map(|skin| skin.index()),
+                light: node
+                    .extension_value("KHR_lights_punctual")
+                    .and_then(|extension| extension.get("light"))
+                    .and_then(|light| light.as_u64())
+                    .map(|light| light as usize),
+                extras: read_extras(node.extras())
+            }
+        })
+        .collect();
+
+    let mut positions = vec![None; document.nodes().len()];
+    for (position, (node, _)) in sources.iter().enumerate() {
+        positions[node.index()] = Some(position);
+    }
+    let animations = read_animations(&document, &buffers, &positions);
+
+    let mut roots = Vec::new();
+    for position in 0..nodes.len() {
+        match nodes[position].parent {
+            Some(parent) => nodes[parent].children.push(position),
+            None => roots.push(position)
+        }
+    }
+
+    let mut scene = Scene {
+        name: scene.name().map(str::to_string),
+        nodes,
+        roots,
+        model,
+        animations
+    };
+    if options.scale != 1.0 {
+        let scale = options.scale;
+        scene.map_transforms(
+            |translation| translation * scale,
+            |rotation| rotation,
+            |node_scale| node_scale,
+            |mut instance| {
+                instance.w_axis = (instance.w_axis.xyz() * scale).extend(instance.w_axis.w);
+                instance
+            }
+        );
+    }
+    scene.convert_node_axes(options.coordinate_system, CoordinateSystem::YUp);
+    Ok(scene)
+}
@@ -0,0 +1,464 @@
+use glam::*;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use crate::model::loader::{Mesh, Model};
+use crate::model::Indices;
+use crate::model::normals::face_normal;
+
+/*
+Weight of the planes added along boundary edges, relative to the planes of the triangles
+themselves. The higher it is, the more a boundary resists being pulled inwards.
+*/
+const BOUNDARY_WEIGHT: f64 = 10.0;
+
+/*
+A symmetric 4x4 error quadric stored as its upper triangle. Evaluating it at a point gives the
+sum of the squared distances from the point to the planes accumulated into it.
+*/
+#[derive(Clone, Copy, Default)]
+struct Quadric([f64; 10]);
+
+impl Quadric {
+    fn from_plane(normal: DVec3, point: DVec3, weight: f64) -> Quadric {
+        let (a, b, c) = (normal.x, normal.y, normal.z);
+        let d = -normal.dot(point);
+        Quadric([
+            a * a, a * b, a * c, a * d,
+            b * b, b * c, b * d,
+            c * c, c * d,
+            d * d
+        ].map(|value| value * weight))
+    }
+
+    fn add(&mut self, other: &Quadric) {
+        for (value, other) in self.0.iter_mut().zip(other.0) {
+            *value += other;
+        }
+    }
+
+    fn error(&self, point: DVec3) -> f64 {
+        let [aa, ab, ac, ad, bb, bc, bd, cc, cd, dd] = self.0;
+        let (x, y, z) = (point.x, point.y, point.z);
+        let error = aa * x * x + 2.0 * ab * x * y + 2.0 * ac * x * z + 2.0 * ad * x
+            + bb * y * y + 2.0 * bc * y * z + 2.0 * bd * y
+            + cc * z * z + 2.0 * cd * z
+            + dd;
+        error.max(0.0)
+    }
+}
+
+/*
+A candidate collapse of vertex `from` onto vertex `to`. The versions of both vertices are
+recorded so entries made stale by later collapses can be recognised and skipped.
+*/
+struct Collapse {
+    cost: f64,
+    from: u32,
+    to: u32,
+    versions: (u32, u32)
+}
+
+impl PartialEq for Collapse {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Collapse {}
+
+impl PartialOrd for Collapse {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Collapse {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost)
+    }
+}
+
+/*
+Whether a vertex may be moved onto a neighbour. Boundary vertices may only slide along the
+boundary. Seam vertices share their position with exactly one other vertex, the two sides of an
+attribute seam; they only slide along the seam and always together with their sibling, so the
+seam stays closed. Vertices of non-manifold edges, of seams joining more than two vertices and
+of seams reaching a boundary are left alone.
+*/
+#[derive(Clone, Copy, PartialEq)]
+enum VertexKind {
+    Free,
+    Boundary,
+    Seam,
+    Locked
+}
+
+/*
+The working state of a simplification: the triangles still alive, the triangles around each
+vertex and the accumulated quadric of each vertex.
+*/
+struct Simplifier<'a> {
+    positions: Vec<Vec3>,
+    triangles: Vec<[u32; 3]>,
+    alive: Vec<bool>,
+    vertex_triangles: Vec<Vec<usize>>,
+    quadrics: Vec<Quadric>,
+    kinds: Vec<VertexKind>,
+    boundary_edges: &'a HashMap<(u32, u32), usize>,
+    position_ids: Vec<u32>,
+    siblings: Vec<u32>,
+    versions: Vec<u32>,
+    removed: Vec<bool>
+}
+
+fn edge_key(a: u32, b: u32) -> (u32, u32) {
+    if a < b { (a, b) } else { (b, a) }
+}
+
+impl Simplifier<'_> {
+    fn neighbours(&self, vertex: u32) -> Vec<u32> {
+        let mut neighbours: Vec<u32> = self.vertex_triangles[vertex as usize]
+            .iter()
+            .filter(|&&triangle| self.alive[triangle])
+            .flat_map(|&triangle| self.triangles[triangle])
+            .filter(|&other| other != vertex)
+            .collect();
+        neighbours.sort_unstable();
+        neighbours.dedup();
+        neighbours
+    }
+
+    fn is_boundary_edge(&self, a: u32, b: u32) -> bool {
+        let key = edge_key(self.position_ids[a as usize], self.position_ids[b as usize]);
+        self.boundary_edges.get(&key) == Some(&1)
+    }
+
+    fn candidate(&self, from: u32, to: u32) -> Option<Collapse> {
+        let allowed = match self.kinds[from as usize] {
+            VertexKind::Free => true,
+            VertexKind::Boundary => self.is_boundary_edge(from, to),
+            VertexKind::Seam => !self.is_boundary_edge(from, to),
+            VertexKind::Locked => false
+        };
+        if !allowed {
+            return None;
+        }
+
+        let mut quadric = self.quadrics[from as usize];
+        quadric.add(&self.quadrics[to as usize]);
+        Some(Collapse {
+            cost: quadric.error(self.positions[to as usize].as_dvec3()),
+            from,
+            to,
+            versions: (self.versions[from as usize], self.versions[to as usize])
+        })
+    }
+
+    fn shared_triangles(&self, a: u32, b: u32) -> usize {
+        self.vertex_triangles[a as usize]
+            .iter()
+            .filter(|&&triangle| self.alive[triangle] && self.triangles[triangle].contains(&b))
+            .count()
+    }
+
+    /*
+    Finds the collapse mirroring `from` onto `to` on the other side of a seam. The edge must run
+    along the seam, that is border a single triangle on this side, and the sibling of `from`
+    must have a neighbour at the position of `to` along a seam edge of its own.
+    */
+    fn seam_partner(&self, from: u32, to: u32) -> Option<(u32, u32)> {
+        if self.shared_triangles(from, to) != 1 {
+            return None;
+        }
+        let sibling = self.siblings[from as usize];
+        if sibling == u32::MAX || self.removed[sibling as usize] {
+            return None;
+        }
+        self.neighbours(sibling)
+            .into_iter()
+            .find(|&neighbour| {
+                self.position_ids[neighbour as usize] == self.position_ids[to as usize]
+                    && self.shared_triangles(sibling, neighbour) == 1
+            })
+            .map(|neighbour| (sibling, neighbour))
+    }
+
+    /*
+    Checks that collapsing `from` onto `to` keeps the surface manifold and does not flip any of
+    the triangles that survive it. The link condition requires the two vertices to share no
+    neighbours other than the ones opposite the edge between them.
+    */
+    fn is_legal(&self, from: u32, to: u32) -> bool {
+        let shared_triangles = self.shared_triangles(from, to);
+        if shared_triangles == 0 {
+            return false;
+        }
+
+        let to_neighbours = self.neighbours(to);
+        let shared_neighbours = self
+            .neighbours(from)
+            .iter()
+            .filter(|neighbour| to_neighbours.binary_search(neighbour).is_ok())
+            .count();
+        if shared_neighbours > shared_triangles {
+            return false;
+        }
+
+        self.vertex_triangles[from as usize]
+            .iter()
+            .filter(|&&triangle| self.alive[triangle] && !self.triangles[triangle].contains(&to))
+            .all(|&triangle| {
+                let corners = self.triangles[triangle].map(|vertex| self.positions[vertex as usize]);
+                let moved = self.triangles[triangle].map(|vertex| {
+                    self.positions[if vertex == from { to } else { vertex } as usize]
+                });
+                let before = face_normal(corners[0], corners[1], corners[2]);
+                let after = face_normal(moved[0], moved[1], moved[2]);
+                before.dot(after) > 0.0
+            })
+    }
+
+    /*
+    Moves `from` onto `to`, removing the triangles that shared the edge. Returns how many
+    triangles were removed.
+    */
+    fn collapse(&mut self, from: u32, to: u32) -> usize {
+        let mut removed = 0;
+        for triangle in std::mem::take(&mut self.vertex_triangles[from as usize]) {
+            if !self.alive[triangle] {
+                continue;
+            }
+            if self.triangles[triangle].contains(&to) {
+                self.alive[triangle] = false;
+                removed += 1;
+            } else {
+                for vertex in &mut self.triangles[triangle] {
+                    if *vertex == from {
+                        *vertex = to;
+                    }
+                }
+                self.vertex_triangles[to as usize].push(triangle);
+            }
+        }
+
+        let quadric = self.quadrics[from as usize];
+        self.quadrics[to as usize].add(&quadric);
+        self.removed[from as usize] = true;
+        self.versions[from as usize] += 1;
+        self.versions[to as usize] += 1;
+        removed
+    }
+}
+
+impl Mesh {
+    /*
+    Returns the number of complete triangles in the mesh.
+    */
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    /*
+    Returns a simplified copy of the mesh with about `target_ratio` of its triangles, clamped to
+    [0, 1], built by repeatedly collapsing the edge with the smallest quadric error. Each
+    collapse moves a vertex onto one of its neighbours, so the remaining vertices keep their
+    original attributes.
+    Collapses that would break the surface's manifoldness or flip a triangle are skipped,
+    boundary edges are weighted to hold their shape and attribute seams are only collapsed
+    along their length, one side mirroring the other.
+    Simplification stops early once the cheapest collapse would move the surface by more than
+    `max_error`, so the target is not always reached; `triangle_count` on the result gives the
+    count achieved, and `f32::INFINITY` removes the bound. Triangles with out-of-range indices
+    are dropped.
+    */
+    pub fn simplify(&self, target_ratio: f32, max_error: f32) -> Mesh {
+        let vertex_count = self.vertices.len();
+        let triangles: Vec<[u32; 3]> = self
+            .indices
+            .triangles()
+            .filter(|triangle| triangle.iter().all(|&index| (index as usize) < vertex_count))
+            .collect();
+
+        let mut position_lookup: HashMap<[u32; 3], u32> = HashMap::new();
+        let mut position_sizes: Vec<usize> = Vec::new();
+        let position_ids: Vec<u32> = self
+            .vertices
+            .iter()
+            .map(|vertex| {
+                let key = vertex.position.to_array().map(f32::to_bits);
+                let id = *position_lookup.entry(key).or_insert_with(|| {
+                    position_sizes.push(0);
+                    position_sizes.len() as u32 - 1
+                });
+                position_sizes[id as usize] += 1;
+                id
+            })
+            .collect();
+
+        let mut edge_uses: HashMap<(u32, u32), usize> = HashMap::new();
+        for triangle in &triangles {
+            for corner in 0..3 {
+                let a = position_ids[triangle[corner] as usize];
+                let b = position_ids[triangle[(corner + 1) % 3] as usize];
+                *edge_uses.entry(edge_key(a, b)).or_default() += 1;
+            }
+        }
+
+        let mut position_quadrics = vec![Quadric::default(); position_sizes.len()];
+        let mut kinds: Vec<VertexKind> = position_ids
+            .iter()
+            .map(|&id| match position_sizes[id as usize] {
+                1 => VertexKind::Free,
+                2 => VertexKind::Seam,
+                _ => VertexKind::Locked
+            })
+            .collect();
+        let mut vertex_triangles = vec![Vec::new(); vertex_count];
+
+        for (index, triangle) in triangles.iter().enumerate() {
+            let corners = triangle.map(|vertex| self.vertices[vertex as usize].position.as_dvec3());
+            let normal = (corners[1] - corners[0]).cross(corners[2] - corners[0]).normalize_or_zero();
+            let plane = Quadric::from_plane(normal, corners[0], 1.0);
+
+            for corner in 0..3 {
+                let (a, b) = (triangle[corner], triangle[(corner + 1) % 3]);
+                vertex_triangles[a as usize].push(index);
+                position_quadrics[position_ids[a as usize] as usize].add(&plane);
+
+                match edge_uses[&edge_key(position_ids[a as usize], position_ids[b as usize])] {
+                    1 => {
+                        let edge = corners[(corner + 1) % 3] - corners[corner];
+                        let side = edge.cross(normal).normalize_or_zero();
+                        let boundary = Quadric::from_plane(side, corners[corner], BOUNDARY_WEIGHT);
+                        for vertex in [a, b] {
+                            position_quadrics[position_ids[vertex as usize] as usize].add(&boundary);
+                            kinds[vertex as usize] = match kinds[vertex as usize] {
+                                VertexKind::Free | VertexKind::Boundary => VertexKind::Boundary,
+                                _ => VertexKind::Locked
+                            };
+                        }
+                    },
+                    2 => {},
+                    _ => {
+                        kinds[a as usize] = VertexKind::Locked;
+                        kinds[b as usize] = VertexKind::Locked;
+                    }
+                }
+            }
+        }
+
+        let mut siblings = vec![u32::MAX; vertex_count];
+        let mut first_at_position = vec![u32::MAX; position_sizes.len()];
+        for (vertex, &id) in position_ids.iter().enumerate() {
+            if position_sizes[id as usize] == 2 {
+                match first_at_position[id as usize] {
+                    u32::MAX => first_at_position[id as usize] = vertex as u32,
+                    first => {
+                        siblings[vertex] = first;
+                        siblings[first as usize] = vertex as u32;
+                    }
+                }
+            }
+        }
+
+        let mut simplifier = Simplifier {
+            positions: self.vertices.iter().map(|vertex| vertex.position).collect(),
+            alive: vec![true; triangles.len()],
+            triangles,
+            vertex_triangles,
+            quadrics: position_ids.iter().map(|&id| position_quadrics[id as usize]).collect(),
+            kinds,
+            boundary_edges: &edge_uses,
+            position_ids,
+            siblings,
+            versions: vec![0; vertex_count],
+            removed: vec![false; vertex_count]
+        };
+
+        let mut heap = BinaryHeap::new();
+        for triangle in &simplifier.triangles {
+            for corner in 0..3 {
+                let (a, b) = (triangle[corner], triangle[(corner + 1) % 3]);
+                heap.extend(simplifier.candidate(a, b));
+                heap.extend(simplifier.candidate(b, a));
+            }
+        }
+
+        let target = (simplifier.triangles.len() as f32 * target_ratio.clamp(0.0, 1.0)).ceil() as usize;
+        let max_cost = (max_error as f64).powi(2);
+        let mut triangle_count = simplifier.triangles.len();
+
+        while triangle_count > target {
+            let collapse = match heap.pop() {
+                Some(collapse) if collapse.cost <= max_cost => collapse,
+                _ => break
+            };
+            let (from, to) = (collapse.from, collapse.to);
+            let current = (simplifier.versions[from as usize], simplifier.versions[to as usize]);
+            if simplifier.removed[from as usize] || simplifier.removed[to as usize] || collapse.versions != current {
+                continue;
+            }
+
+            let mirrored = match simplifier.kinds[from as usize] {
+                VertexKind::Seam => match simplifier.seam_partner(from, to) {
+                    Some(mirrored) => Some(mirrored),
+                    None => continue
+                },
+                _ => None
+            };
+            let legal = simplifier.is_legal(from, to)
+                && mirrored.is_none_or(|(from, to)| simplifier.is_legal(from, to));
+            if !legal {
+                continue;
+            }
+
+            for (from, to) in std::iter::once((from, to)).chain(mirrored) {
+                triangle_count -= simplifier.collapse(from, to);
+                for neighbour in simplifier.neighbours(to) {
+                    heap.extend(simplifier.candidate(to, neighbour));
+                    heap.extend(simplifier.candidate(neighbour, to));
+                }
+            }
+        }
+
+        let mut remap = vec![u32::MAX; vertex_count];
+        let mut vertices = Vec::new();
+        let mut indices = Vec::with_capacity(triangle_count * 3);
+        for (triangle, _) in simplifier.triangles.iter().zip(&simplifier.alive).filter(|(_, &alive)| alive) {
+            for &vertex in triangle {
+                if remap[vertex as usize] == u32::MAX {
+                    remap[vertex as usize] = vertices.len() as u32;
+                    vertices.push(self.vertices[vertex as usize]);
+                }
+                indices.push(remap[vertex as usize]);
+            }
+        }
+
+        Mesh {
+            name: self.name.clone(),
+            indices: Indices::for_vertices(indices, vertices.len()),
+            vertices,
+            material: self.material,
+            bounds: None,
+            instances: self.instances.clone(),
+            extras: self.extras.clone()
+        }
+    }
+}
+
+impl Model {
+    /*
+    Builds one simplified copy of the model per entry of `ratios`, each mesh keeping about that
+    fraction of its triangles. No error bound is applied, so every level gets as close to its
+    ratio as the collapse rules allow. Materials are shared by cloning.
+    */
+    pub fn generate_lods(&self, ratios: &[f32]) -> Vec<Model> {
+        ratios
+            .iter()
+            .map(|&ratio| Model {
+                meshes: self.meshes.iter().map(|mesh| mesh.simplify(ratio, f32::INFINITY)).collect(),
+                materials: self.materials.clone()
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+use crate::model::loader::{Mesh, Model};
+use crate::model::{Indices, MaterialHandle};
+
+/*
+Returns a copy of the mesh holding only the vertices its indices refer to, in their original
+order, with the indices remapped. Out-of-range indices stay out of range of the compacted
+vertices. The declared bounds are kept only when no vertex was dropped, since they may no longer
+be tight otherwise.
+*/
+fn compact(mesh: &Mesh) -> Mesh {
+    let vertex_count = mesh.vertices.len();
+    let mut remap: Vec<Option<u32>> = vec![None; vertex_count];
+    for index in &mesh.indices {
+        if let Some(slot) = remap.get_mut(index as usize) {
+            *slot = Some(0);
+        }
+    }
+
+    let mut vertices = Vec::with_capacity(vertex_count);
+    for (vertex, slot) in mesh.vertices.iter().zip(remap.iter_mut()) {
+        if slot.is_some() {
+            *slot = Some(vertices.len() as u32);
+            vertices.push(*vertex);
+        }
+    }
+
+    let removed = (vertex_count - vertices.len()) as u32;
+    let indices = mesh
+        .indices
+        .iter()
+        .map(|index| match remap.get(index as usize) {
+            Some(slot) => slot.unwrap_or(index),
+            None => index - removed
+        })
+        .collect();
+    let indices = Indices::for_vertices(indices, vertices.len());
+
+    Mesh {
+        name: mesh.name.clone(),
+        bounds: if removed == 0 { mesh.bounds } else { None },
+        vertices,
+        indices,
+        material: mesh.material,
+        instances: mesh.instances.clone(),
+        extras: mesh.extras.clone()
+    }
+}
+
+/*
+Follows the parent links of a union-find forest up to the root of `node`, halving the path on
+the way so later lookups are shorter.
+*/
+fn find_root(parents: &mut [usize], mut node: usize) -> usize {
+    while parents[node] != node {
+        parents[node] = parents[parents[node]];
+        node = parents[node];
+    }
+    node
+}
+
+fn union(parents: &mut [usize], a: usize, b: usize) {
+    let (a, b) = (find_root(parents, a), find_root(parents, b));
+    parents[a.max(b)] = a.min(b);
+}
+
+/*
+Splits a mesh into its connected components, one mesh per island of triangles, in the order of
+each island's first triangle. Triangles are connected when they share a vertex, and vertices
+at the same position count as shared, so vertices split along texture or normal seams do not
+break an island apart. Each island keeps only the vertices it uses, with its indices remapped,
+and the name, material and instances of the source. Trailing indices and triangles with
+out-of-range indices are dropped.
+*/
+pub fn split_connected_components(mesh: &Mesh) -> Vec<Mesh> {
+    let vertex_count = mesh.vertices.len();
+    let triangles: Vec<[u32; 3]> = mesh
+        .indices
+        .triangles()
+        .filter(|triangle| triangle.iter().all(|&index| (index as usize) < vertex_count))
+        .collect();
+
+    let mut parents: Vec<usize> = (0..vertex_count).collect();
+
+    let mut first_at_position: HashMap<[u32; 3], usize> = HashMap::new();
+    for (index, vertex) in mesh.vertices.iter().enumerate() {
+        let first = *first_at_position.entry(vertex.position.to_array().map(f32::to_bits)).or_insert(index);
+        union(&mut parents, first, index);
+    }
+    for triangle in &triangles {
+        union(&mut parents, triangle[0] as usize, triangle[1] as usize);
+        union(&mut parents, triangle[0] as usize, triangle[2] as usize);
+    }
+
+    let mut islands: Vec<Vec<u32>> = Vec::new();
+    let mut island_of_root: HashMap<usize, usize> = HashMap::new();
+    for triangle in &triangles {
+        let root = find_root(&mut parents, triangle[0] as usize);
+        let island = *island_of_root.entry(root).or_insert_with(|| {
+            islands.push(Vec::new());
+            islands.len() - 1
+        });
+        islands[island].extend_from_slice(triangle);
+    }
+
+    islands
+        .into_iter()
+        .map(|indices| {
+            let island = Mesh {
+                name: mesh.name.clone(),
+                vertices: mesh.vertices.clone(),
+                indices: indices.into(),
+                material: mesh.material,
+                bounds: None,
+                instances: mesh.instances.clone(),
+                extras: mesh.extras.clone()
+            };
+            compact(&island)
+        })
+        .collect()
+}
+
+impl Model {
+    /*
+    Returns a model holding only the meshes drawn with `material`, with that material as its
+    only one and every mesh pointing at it. Vertices no index refers to are left out of the
+    copies. Returns `None` when the model has no such material; a material no mesh uses gives a
+    model without meshes.
+    */
+    pub fn extract_material(&self, handle: MaterialHandle) -> Option<Model> {
+        let material = self.material(handle)?;
+        let meshes = self
+            .meshes_with_material(handle)
+            .map(|mesh| {
+                let mut mesh = compact(mesh);
+                mesh.material = MaterialHandle::default();
+                mesh
+            })
+            .collect();
+
+        Some(Model {
+            meshes,
+            materials: vec![material.clone()]
+        })
+    }
+
+    /*
+    Splits the model into one model per material, in material order, as `extract_material`
+    does. Materials no mesh uses are skipped, so the result may be shorter than the material
+    list.
+    */
+    pub fn split_by_material(&self) -> Vec<Model> {
+        self.material_handles()
+            .filter(|&handle| self.meshes_with_material(handle).next().is_some())
+            .filter_map(|handle| self.extract_material(handle))
+            .collect()
+    }
+}
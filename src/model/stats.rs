@@ -0,0 +1,184 @@
+use std::fmt;
+use glam::*;
+use crate::model::loader::{Material, Mesh, Model, Vertex};
+use crate::model::{Aabb, Texture};
+
+/*
+The `MeshStats` struct summarizes a single mesh. The attribute flags tell whether any vertex
+carries a non-zero value for that attribute, since the loader zeroes the attributes a file
+leaves out. Memory figures are the sizes of the vertex and index arrays as stored, using the
+size of `Vertex` and the width the indices are stored with.
+*/
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MeshStats {
+    pub vertex_count: usize,
+    pub triangle_count: usize,
+    pub has_normals: bool,
+    pub has_tex_coords: bool,
+    pub has_tangents: bool,
+    pub vertex_bytes: usize,
+    pub index_bytes: usize,
+    pub bounds: Aabb
+}
+
+/*
+The `ModelStats` struct summarizes a whole model: the totals of its meshes, the number of
+textures referenced by its materials and the memory their decoded pixels take, the model's
+bounding box and the statistics of each mesh in order. A texture used by several material slots
+is counted once per slot, as each slot holds its own decoded copy.
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModelStats {
+    pub mesh_count: usize,
+    pub material_count: usize,
+    pub texture_count: usize,
+    pub vertex_count: usize,
+    pub triangle_count: usize,
+    pub vertex_bytes: usize,
+    pub index_bytes: usize,
+    pub texture_bytes: usize,
+    pub bounds: Aabb,
+    pub meshes: Vec<MeshStats>
+}
+
+/*
+The `MemoryFootprint` struct gives the bytes a model's data takes once uploaded as is: vertex
+arrays at the size of `Vertex`, indices at their stored width and decoded texture pixels. As in
+`ModelStats`, a texture is counted once per material slot holding it.
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryFootprint {
+    pub vertex_bytes: usize,
+    pub index_bytes: usize,
+    pub texture_bytes: usize
+}
+
+impl MemoryFootprint {
+    pub fn total(&self) -> usize {
+        self.vertex_bytes + self.index_bytes + self.texture_bytes
+    }
+}
+
+/*
+Lists every texture a material holds, across the base color and extension slots.
+*/
+pub(crate) fn material_textures(material: &Material) -> impl Iterator<Item = &Texture> {
+    [
+        material.base_color_texture.as_ref(),
+        material.metallic_roughness_texture.as_ref(),
+        material.normal_texture.as_ref(),
+        material.occlusion_texture.as_ref(),
+        material.emissive_texture.as_ref(),
+        material.transmission.as_ref().and_then(|transmission| transmission.texture.as_ref()),
+        material.volume.as_ref().and_then(|volume| volume.thickness_texture.as_ref()),
+        material.clearcoat_texture.as_ref(),
+        material.clearcoat_roughness_texture.as_ref(),
+        material.clearcoat_normal_texture.as_ref()
+    ]
+    .into_iter()
+    .flatten()
+}
+
+fn format_bytes(bytes: usize) -> String {
+    match bytes {
+        0..=1023 => format!("{} B", bytes),
+        1024..=1_048_575 => format!("{:.1} KiB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MiB", bytes as f64 / 1_048_576.0)
+    }
+}
+
+impl Mesh {
+    /*
+    Gathers the counts, attribute presence, memory use and bounds of the mesh.
+    */
+    pub fn stats(&self) -> MeshStats {
+        MeshStats {
+            vertex_count: self.vertices.len(),
+            triangle_count: self.indices.len() / 3,
+            has_normals: self.vertices.iter().any(|vertex| vertex.normal != Vec3::ZERO),
+            has_tex_coords: self.vertices.iter().any(|vertex| vertex.tex_coord != Vec2::ZERO),
+            has_tangents: self.vertices.iter().any(|vertex| vertex.tangent != Vec4::ZERO),
+            vertex_bytes: self.vertices.len() * std::mem::size_of::<Vertex>(),
+            index_bytes: self.indices.byte_size(),
+            bounds: self.aabb()
+        }
+    }
+}
+
+impl Model {
+    /*
+    Gathers the statistics of every mesh and totals them, along with the material and texture
+    figures. Printing the result gives a readable multi-line report.
+    */
+    pub fn stats(&self) -> ModelStats {
+        let meshes: Vec<MeshStats> = self.meshes.iter().map(Mesh::stats).collect();
+        let textures: Vec<&Texture> = self.materials.iter().flat_map(material_textures).collect();
+
+        ModelStats {
+            mesh_count: meshes.len(),
+            material_count: self.materials.len(),
+            texture_count: textures.len(),
+            vertex_count: meshes.iter().map(|mesh| mesh.vertex_count).sum(),
+            triangle_count: meshes.iter().map(|mesh| mesh.triangle_count).sum(),
+            vertex_bytes: meshes.iter().map(|mesh| mesh.vertex_bytes).sum(),
+            index_bytes: meshes.iter().map(|mesh| mesh.index_bytes).sum(),
+            texture_bytes: textures.iter().map(|texture| texture.byte_size()).sum(),
+            bounds: meshes.iter().fold(Aabb::EMPTY, |bounds, mesh| bounds.union(&mesh.bounds)),
+            meshes
+        }
+    }
+
+    /*
+    Adds up the memory the model's vertices, indices and textures take, to budget GPU memory
+    before uploading it. Cheaper than `stats`, which also scans every vertex.
+    */
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        MemoryFootprint {
+            vertex_bytes: self.meshes.iter().map(|mesh| mesh.vertices.len() * std::mem::size_of::<Vertex>()).sum(),
+            index_bytes: self.meshes.iter().map(|mesh| mesh.indices.byte_size()).sum(),
+            texture_bytes: self
+                .materials
+                .iter()
+                .flat_map(material_textures)
+                .map(|texture| texture.byte_size())
+                .sum()
+        }
+    }
+}
+
+impl fmt::Display for MeshStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let flag = |present: bool, name: &'static str| if present { name } else { "-" };
+        write!(
+            f,
+            "{} vertices, {} triangles, {} {} {}, {} vertex data, {} index data",
+            self.vertex_count,
+            self.triangle_count,
+            flag(self.has_normals, "normals"),
+            flag(self.has_tex_coords, "uvs"),
+            flag(self.has_tangents, "tangents"),
+            format_bytes(self.vertex_bytes),
+            format_bytes(self.index_bytes)
+        )
+    }
+}
+
+impl fmt::Display for ModelStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Meshes: {}", self.mesh_count)?;
+        writeln!(f, "Materials: {}", self.material_count)?;
+        writeln!(f, "Textures: {} ({})", self.texture_count, format_bytes(self.texture_bytes))?;
+        writeln!(f, "Vertices: {} ({})", self.vertex_count, format_bytes(self.vertex_bytes))?;
+        writeln!(f, "Triangles: {} ({} of indices)", self.triangle_count, format_bytes(self.index_bytes))?;
+        if self.bounds.is_empty() {
+            writeln!(f, "Extents: empty")?;
+        } else {
+            let (min, max) = (self.bounds.min, self.bounds.max);
+            writeln!(f, "Extents: ({}, {}, {}) to ({}, {}, {})", min.x, min.y, min.z, max.x, max.y, max.z)?;
+        }
+        for (index, mesh) in self.meshes.iter().enumerate() {
+            writeln!(f, "  Mesh {}: {}", index, mesh)?;
+        }
+        Ok(())
+    }
+}
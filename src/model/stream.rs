@@ -0,0 +1,310 @@
+use glam::*;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use gltf::accessor::DataType;
+use crate::model::loader::{buffer_read_error, is_relative_uri, parse_document, primitive_bounds, read_extras, resolve_uri, Mesh, Vertex};
+use crate::model::{Indices, LoadError, MaterialHandle};
+
+/*
+Describes where the bytes of a glTF buffer live. External files and the binary chunk of a GLB
+are read range by range, while buffers embedded as data URIs are already part of the JSON and
+are decoded into memory once.
+*/
+enum BufferSource {
+    File { file: File, offset: u64 },
+    Memory(Vec<u8>)
+}
+
+impl BufferSource {
+    fn read_range(&mut self, offset: usize, length: usize) -> Result<Vec<u8>, LoadError> {
+        match self {
+            BufferSource::File { file, offset: base } => {
+                let mut bytes = vec![0; length];
+                file.seek(SeekFrom::Start(*base + offset as u64))?;
+                file.read_exact(&mut bytes)?;
+                Ok(bytes)
+            },
+            BufferSource::Memory(data) => data
+                .get(offset..offset + length)
+                .map(|bytes| bytes.to_vec())
+                .ok_or_else(|| LoadError::InvalidData("Accessor reads past the end of its buffer".to_string()))
+        }
+    }
+}
+
+/*
+The `MeshStream` iterator yields the triangle primitives of a glTF document one `Mesh` at a
+time, in document order. Only the document JSON is held in memory; the vertex and index data
+of each primitive is read from disk when that primitive is reached.
+*/
+pub struct MeshStream {
+    document: Option<gltf::Document>,
+    buffers: StreamBuffers,
+    primitives: Vec<(usize, usize)>,
+    next: usize,
+    error: Option<LoadError>
+}
+
+/*
+Opens a glTF or GLB file for streaming. The document is parsed up front, but buffers are only
+opened, not read. A GLB's binary chunk is located by walking the chunk headers so the JSON can
+be parsed without pulling the binary payload into memory.
+*/
+fn open_stream(file_path: &Path) -> Result<(gltf::Document, StreamBuffers), LoadError> {
+    let mut file = File::open(file_path)?;
+    let mut magic = [0u8; 4];
+    let is_glb = file.read_exact(&mut magic).is_ok() && &magic == b"glTF";
+    file.seek(SeekFrom::Start(0))?;
+
+    let (json, bin_offset) = if is_glb {
+        let mut header = [0u8; 20];
+        file.read_exact(&mut header)?;
+        let json_length = u32::from_le_bytes([header[12], header[13], header[14], header[15]]) as usize;
+        let mut json = vec![0; json_length];
+        file.read_exact(&mut json)?;
+        (json, Some(20 + json_length as u64 + 8))
+    } else {
+        let mut json = Vec::new();
+        file.read_to_end(&mut json)?;
+        (json, None)
+    };
+
+    let (document, _) = parse_document(&json)?;
+
+    let mut sources = Vec::new();
+    for buffer in document.buffers() {
+        let source = match buffer.source() {
+            gltf::buffer::Source::Bin => {
+                let offset = bin_offset
+                    .ok_or_else(|| LoadError::InvalidData("Buffer refers to a missing binary chunk".to_string()))?;
+                BufferSource::File { file: File::open(file_path)?, offset }
+            },
+            gltf::buffer::Source::Uri(uri) if is_relative_uri(uri) => {
+                let buffer_path = resolve_uri(uri, file_path);
+                let file = File::open(&buffer_path).map_err(|error| buffer_read_error(buffer.index(), &buffer_path, error))?;
+                BufferSource::File { file, offset: 0 }
+            },
+            gltf::buffer::Source::Uri(_) => {
+                BufferSource::Memory(gltf::buffer::Data::from_source(buffer.source(), file_path.parent())?.0)
+            }
+        };
+        sources.push(source);
+    }
+
+    Ok((document, StreamBuffers { sources }))
+}
+
+/*
+Converts one component of an accessor element to `f32`, mapping normalized integers to the
+[0, 1] or [-1, 1] range as the glTF specification defines.
+*/
+fn read_component(bytes: &[u8], data_type: DataType, normalized: bool) -> f32 {
+    match data_type {
+        DataType::F32 => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        DataType::U32 => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f32,
+        DataType::U8 if normalized => bytes[0] as f32 / 255.0,
+        DataType::U8 => bytes[0] as f32,
+        DataType::I8 if normalized => (bytes[0] as i8 as f32 / 127.0).max(-1.0),
+        DataType::I8 => bytes[0] as i8 as f32,
+        DataType::U16 if normalized => u16::from_le_bytes([bytes[0], bytes[1]]) as f32 / 65535.0,
+        DataType::U16 => u16::from_le_bytes([bytes[0], bytes[1]]) as f32,
+        DataType::I16 if normalized => (i16::from_le_bytes([bytes[0], bytes[1]]) as f32 / 32767.0).max(-1.0),
+        DataType::I16 => i16::from_le_bytes([bytes[0], bytes[1]]) as f32
+    }
+}
+
+/*
+The buffers of a streamed document, indexed like `document.buffers()`.
+*/
+struct StreamBuffers {
+    sources: Vec<BufferSource>
+}
+
+impl StreamBuffers {
+    fn read_view_range(&mut self, view: &gltf::buffer::View, offset: usize, length: usize) -> Result<Vec<u8>, LoadError> {
+        let source = self
+            .sources
+            .get_mut(view.buffer().index())
+            .ok_or_else(|| LoadError::InvalidData("Buffer view refers to a missing buffer".to_string()))?;
+        source.read_range(view.offset() + offset, length)
+    }
+
+    /*
+    Reads the bytes covering an accessor and returns them together with the element stride.
+    Sparse accessors start from their base data, or from zeros when they have no buffer view,
+    and get every substituted element copied over it.
+    */
+    fn read_accessor_bytes(&mut self, accessor: &gltf::Accessor) -> Result<(Vec<u8>, usize), LoadError> {
+        let element_size = accessor.size();
+        let (mut bytes, stride) = match accessor.view() {
+            Some(view) => {
+                let stride = view.stride().unwrap_or(element_size);
+                let length = match accessor.count() {
+                    0 => 0,
+                    count => stride * (count - 1) + element_size
+                };
+                (self.read_view_range(&view, accessor.offset(), length)?, stride)
+            },
+            None if accessor.sparse().is_some() => (vec![0; accessor.count() * element_size], element_size),
+            None => return Err(LoadError::InvalidData("Accessor has no buffer view".to_string()))
+        };
+
+        if let Some(sparse) = accessor.sparse() {
+            let indices = sparse.indices();
+            let index_size = indices.index_type().size();
+            let index_bytes = self.read_view_range(&indices.view(), indices.offset(), sparse.count() * index_size)?;
+            let values = sparse.values();
+            let value_bytes = self.read_view_range(&values.view(), values.offset(), sparse.count() * element_size)?;
+
+            for (index, value) in index_bytes.chunks_exact(index_size).zip(value_bytes.chunks_exact(element_size)) {
+                let index = match index {
+                    [a] => *a as usize,
+                    [a, b] => u16::from_le_bytes([*a, *b]) as usize,
+                    _ => u32::from_le_bytes([index[0], index[1], index[2], index[3]]) as usize
+                };
+                if index >= accessor.count() {
+                    return Err(LoadError::InvalidData("Sparse accessor substitutes an element out of range".to_string()));
+                }
+                bytes[index * stride..index * stride + element_size].copy_from_slice(value);
+            }
+        }
+        Ok((bytes, stride))
+    }
+
+    fn read_floats<const N: usize>(&mut self, accessor: &gltf::Accessor) -> Result<Vec<[f32; N]>, LoadError> {
+        let (bytes, stride) = self.read_accessor_bytes(accessor)?;
+        let data_type = accessor.data_type();
+        let component_size = data_type.size();
+
+        Ok((0..accessor.count())
+            .map(|i| {
+                let mut element = [0.0; N];
+                for (c, component) in element.iter_mut().enumerate() {
+                    let start = i * stride + c * component_size;
+                    *component = read_component(&bytes[start..], data_type, accessor.normalized());
+                }
+                element
+            })
+            .collect())
+    }
+
+    fn read_indices(&mut self, accessor: &gltf::Accessor) -> Result<Vec<u32>, LoadError> {
+        let (bytes, stride) = self.read_accessor_bytes(accessor)?;
+        let data_type = accessor.data_type();
+
+        (0..accessor.count())
+            .map(|i| {
+                let bytes = &bytes[i * stride..];
+                match data_type {
+                    DataType::U8 => Ok(bytes[0] as u32),
+                    DataType::U16 => Ok(u16::from_le_bytes([bytes[0], bytes[1]]) as u32),
+                    DataType::U32 => Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])),
+                    _ => Err(LoadError::InvalidData("Indices must be unsigned integers".to_string()))
+                }
+            })
+            .collect()
+    }
+
+    fn read_mesh(&mut self, mesh: &gltf::Mesh, primitive: &gltf::Primitive, default_material: usize) -> Result<Mesh, LoadError> {
+        let positions = primitive
+            .get(&gltf::Semantic::Positions)
+            .ok_or_else(|| LoadError::InvalidData("Vertices must have positions".to_string()))?;
+        let mut vertices: Vec<Vertex> = self
+            .read_floats::<3>(&positions)?
+            .into_iter()
+            .map(|position| Vertex {
+                position: Vec3::from(position),
+                ..Default::default()
+            })
+            .collect();
+
+        if let Some(normals) = primitive.get(&gltf::Semantic::Normals) {
+            for (vertex, normal) in vertices.iter_mut().zip(self.read_floats::<3>(&normals)?) {
+                vertex.normal = Vec3::from(normal);
+            }
+        }
+
+        if let Some(tex_coords) = primitive.get(&gltf::Semantic::TexCoords(0)) {
+            for (vertex, tex_coord) in vertices.iter_mut().zip(self.read_floats::<2>(&tex_coords)?) {
+                vertex.tex_coord = Vec2::from(tex_coord);
+            }
+        }
+
+        if let Some(tangents) = primitive.get(&gltf::Semantic::Tangents) {
+            for (vertex, tangent) in vertices.iter_mut().zip(self.read_floats::<4>(&tangents)?) {
+                vertex.tangent = Vec4::from(tangent);
+            }
+        }
+
+        let indices = match primitive.indices() {
+            Some(indices) => self.read_indices(&indices)?,
+            None => (0..vertices.len() as u32).collect()
+        };
+
+        Ok(Mesh {
+            name: mesh.name().map(str::to_string),
+            bounds: Some(primitive_bounds(primitive, &vertices)),
+            indices: Indices::for_vertices(indices, vertices.len()),
+            vertices,
+            material: MaterialHandle::from(primitive.material().index().unwrap_or(default_material)),
+            instances: Vec::new(),
+            extras: read_extras(mesh.extras())
+        })
+    }
+}
+
+impl Iterator for MeshStream {
+    type Item = Result<Mesh, LoadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(error) = self.error.take() {
+            return Some(Err(error));
+        }
+
+        let (mesh_index, primitive_index) = *self.primitives.get(self.next)?;
+        self.next += 1;
+
+        let document = self.document.as_ref()?;
+        let mesh = document.meshes().nth(mesh_index)?;
+        let primitive = mesh.primitives().nth(primitive_index)?;
+        Some(self.buffers.read_mesh(&mesh, &primitive, document.materials().len()))
+    }
+}
+
+/*
+Streams the triangle primitives of a glTF or GLB file as individual meshes, reading only the
+buffer ranges each primitive uses. Meshes come out in document order with their vertices in
+mesh space, since no node transforms are applied, and no textures are loaded. Material handles
+match the loaders', a primitive without a material referring to the slot after the document's
+materials. A file that cannot be opened yields a single error.
+*/
+pub fn stream_meshes(file_path: impl AsRef<Path>) -> impl Iterator<Item = Result<Mesh, LoadError>> {
+    match open_stream(file_path.as_ref()) {
+        Ok((document, buffers)) => {
+            let primitives = document
+                .meshes()
+                .flat_map(|mesh| {
+                    mesh.primitives()
+                        .filter(|primitive| primitive.mode() == gltf::mesh::Mode::Triangles)
+                        .map(move |primitive| (mesh.index(), primitive.index()))
+                })
+                .collect();
+
+            MeshStream {
+                document: Some(document),
+                buffers,
+                primitives,
+                next: 0,
+                error: None
+            }
+        },
+        Err(error) => MeshStream {
+            document: None,
+            buffers: StreamBuffers { sources: Vec::new() },
+            primitives: Vec::new(),
+            next: 0,
+            error: Some(error)
+        }
+    }
+}
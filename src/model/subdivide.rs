@@ -0,0 +1,256 @@
+use glam::*;
+use std::collections::HashMap;
+use std::f32::consts::PI;
+use crate::model::loader::{Mesh, Vertex};
+use crate::model::{Indices, MeshError};
+
+/*
+Largest number of triangles `Mesh::subdivide` is allowed to produce. Each iteration multiplies
+the triangle count by four, so a few iterations too many quickly exhaust memory.
+*/
+pub const DEFAULT_SUBDIVISION_LIMIT: usize = 4_000_000;
+
+/*
+The `SubdivisionScheme` enum selects how `Mesh::subdivide` places vertices. Both split every
+triangle into four. `Midpoint` puts the new vertices halfway along each edge and leaves the
+surface unchanged, while `Loop` applies Loop's weights to new and existing positions so the
+surface is smoothed with every iteration.
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SubdivisionScheme {
+    Midpoint,
+    Loop
+}
+
+fn edge_key(a: u32, b: u32) -> (u32, u32) {
+    if a < b { (a, b) } else { (b, a) }
+}
+
+/*
+Returns the vertex halfway between two vertices. Normals and tangent directions are
+renormalized and the tangent handedness is taken from the first vertex.
+*/
+fn midpoint(a: &Vertex, b: &Vertex) -> Vertex {
+    let tangent = a.tangent.truncate().lerp(b.tangent.truncate(), 0.5).normalize_or_zero();
+    Vertex {
+        position: (a.position + b.position) * 0.5,
+        normal: a.normal.lerp(b.normal, 0.5).normalize_or_zero(),
+        tex_coord: a.tex_coord.lerp(b.tex_coord, 0.5),
+        tangent: tangent.extend(a.tangent.w),
+        barycentric: a.barycentric.lerp(b.barycentric, 0.5),
+        ..Default::default()
+    }
+}
+
+/*
+The connectivity of a mesh's positions for Loop subdivision. Vertices split along attribute
+seams share a position id, so the smoothing sees one connected surface and both sides of a seam
+move identically. `edges` lists every edge once, in the order the triangles first reach it,
+with the corners opposite it in `opposites`; the map only finds an edge's position in that
+list, so the neighbours are always summed in the same order and the result does not depend on
+the hash.
+*/
+struct PositionTopology {
+    ids: Vec<u32>,
+    positions: Vec<Vec3>,
+    edges: Vec<((u32, u32), Vec<u32>)>,
+    lookup: HashMap<(u32, u32), usize>
+}
+
+impl PositionTopology {
+    fn new(mesh: &Mesh, triangles: &[[u32; 3]]) -> PositionTopology {
+        let mut lookup: HashMap<[u32; 3], u32> = HashMap::new();
+        let mut positions = Vec::new();
+        let ids = mesh
+            .vertices
+            .iter()
+            .map(|vertex| {
+                *lookup.entry(vertex.position.to_array().map(f32::to_bits)).or_insert_with(|| {
+                    positions.push(vertex.position);
+                    positions.len() as u32 - 1
+                })
+            })
+            .collect::<Vec<u32>>();
+
+        let mut edges: Vec<((u32, u32), Vec<u32>)> = Vec::new();
+        let mut lookup: HashMap<(u32, u32), usize> = HashMap::new();
+        for triangle in triangles {
+            let corners = triangle.map(|vertex| ids[vertex as usize]);
+            for corner in 0..3 {
+                let (a, b, c) = (corners[corner], corners[(corner + 1) % 3], corners[(corner + 2) % 3]);
+                let key = edge_key(a, b);
+                let edge = *lookup.entry(key).or_insert_with(|| {
+                    edges.push((key, Vec::new()));
+                    edges.len() - 1
+                });
+                edges[edge].1.push(c);
+            }
+        }
+
+        PositionTopology { ids, positions, edges, lookup }
+    }
+
+    /*
+    Applies the Loop weights to an existing position: interior positions are averaged with all
+    their neighbours, boundary positions only with the two neighbours along the boundary, and
+    corners or non-manifold positions stay where they are.
+    */
+    fn smoothed_positions(&self) -> Vec<Vec3> {
+        let mut neighbours: Vec<Vec<u32>> = vec![Vec::new(); self.positions.len()];
+        let mut boundary_neighbours: Vec<Vec<u32>> = vec![Vec::new(); self.positions.len()];
+        let mut irregular = vec![false; self.positions.len()];
+
+        for ((a, b), opposite) in &self.edges {
+            let (a, b) = (*a, *b);
+            neighbours[a as usize].push(b);
+            neighbours[b as usize].push(a);
+            match opposite.len() {
+                1 => {
+                    boundary_neighbours[a as usize].push(b);
+                    boundary_neighbours[b as usize].push(a);
+                },
+                2 => {},
+                _ => {
+                    irregular[a as usize] = true;
+                    irregular[b as usize] = true;
+                }
+            }
+        }
+
+        self.positions
+            .iter()
+            .enumerate()
+            .map(|(id, &position)| {
+                let sum = |ids: &[u32]| ids.iter().map(|&other| self.positions[other as usize]).sum::<Vec3>();
+                match (irregular[id], boundary_neighbours[id].len()) {
+                    (false, 0) if !neighbours[id].is_empty() => {
+                        let n = neighbours[id].len() as f32;
+                        let t = 3.0 / 8.0 + (2.0 * PI / n).cos() / 4.0;
+                        let beta = (5.0 / 8.0 - t * t) / n;
+                        position * (1.0 - n * beta) + sum(&neighbours[id]) * beta
+                    },
+                    (false, 2) => position * 0.75 + sum(&boundary_neighbours[id]) * 0.125,
+                    _ => position
+                }
+            })
+            .collect()
+    }
+
+    /*
+    Returns the Loop position of the vertex inserted on the edge between two positions. Edges
+    shared by two triangles also weigh in the opposite corners; other edges use the midpoint.
+    */
+    fn edge_position(&self, a: u32, b: u32) -> Vec3 {
+        let (pa, pb) = (self.positions[a as usize], self.positions[b as usize]);
+        match self.lookup.get(&edge_key(a, b)).map(|&edge| self.edges[edge].1.as_slice()) {
+            Some(&[c, d]) => {
+                (pa + pb) * 0.375 + (self.positions[c as usize] + self.positions[d as usize]) * 0.125
+            },
+            _ => (pa + pb) * 0.5
+        }
+    }
+}
+
+/*
+Splits every triangle into four. An edge shared by several triangles gets a single new vertex,
+found through a map keyed on its sorted vertex indices, so neighbouring triangles stay connected.
+*/
+fn subdivide_once(mesh: &Mesh, scheme: SubdivisionScheme) -> Mesh {
+    let vertex_count = mesh.vertices.len();
+    let triangles: Vec<[u32; 3]> = mesh
+        .indices
+        .triangles()
+        .filter(|triangle| triangle.iter().all(|&index| (index as usize) < vertex_count))
+        .collect();
+
+    let topology = match scheme {
+        SubdivisionScheme::Loop => Some(PositionTopology::new(mesh, &triangles)),
+        SubdivisionScheme::Midpoint => None
+    };
+
+    let mut vertices = mesh.vertices.clone();
+    if let Some(topology) = &topology {
+        let smoothed = topology.smoothed_positions();
+        for (vertex, &id) in vertices.iter_mut().zip(&topology.ids) {
+            vertex.position = smoothed[id as usize];
+        }
+    }
+
+    let mut edge_vertices: HashMap<(u32, u32), u32> = HashMap::new();
+    let mut indices = Vec::with_capacity(triangles.len() * 12);
+
+    for triangle in &triangles {
+        let mut split = [0; 3];
+        for corner in 0..3 {
+            let (a, b) = (triangle[corner], triangle[(corner + 1) % 3]);
+            split[corner] = *edge_vertices.entry(edge_key(a, b)).or_insert_with(|| {
+                let mut vertex = midpoint(&mesh.vertices[a as usize], &mesh.vertices[b as usize]);
+                if let Some(topology) = &topology {
+                    vertex.position = topology.edge_position(topology.ids[a as usize], topology.ids[b as usize]);
+                }
+                vertices.push(vertex);
+                vertices.len() as u32 - 1
+            });
+        }
+
+        let [a, b, c] = *triangle;
+        let [ab, bc, ca] = split;
+        indices.extend_from_slice(&[a, ab, ca, ab, b, bc, ca, bc, c, ab, bc, ca]);
+    }
+
+    Mesh {
+        name: mesh.name.clone(),
+        indices: Indices::for_vertices(indices, vertices.len()),
+        vertices,
+        material: mesh.material,
+        bounds: match scheme {
+            SubdivisionScheme::Midpoint => mesh.bounds,
+            SubdivisionScheme::Loop => None
+        },
+        instances: mesh.instances.clone(),
+        extras: mesh.extras.clone()
+    }
+}
+
+impl Mesh {
+    /*
+    Returns a copy of the mesh with every triangle split into four, `iterations` times over.
+    New vertices interpolate the normals, texture coordinates and tangents of their edge
+    linearly; with `Loop` only the positions are smoothed, so normals may need recalculating
+    afterwards. Triangles with out-of-range indices are dropped. Fails without subdividing when
+    the index count is not a multiple of three or when the result would exceed
+    `DEFAULT_SUBDIVISION_LIMIT` triangles.
+    */
+    pub fn subdivide(&self, scheme: SubdivisionScheme, iterations: u32) -> Result<Mesh, MeshError> {
+        self.subdivide_with_limit(scheme, iterations, DEFAULT_SUBDIVISION_LIMIT)
+    }
+
+    /*
+    Same as `subdivide`, refusing results with more than `max_triangles` triangles instead of
+    the default limit.
+    */
+    pub fn subdivide_with_limit(
+        &self,
+        scheme: SubdivisionScheme,
+        iterations: u32,
+        max_triangles: usize
+    ) -> Result<Mesh, MeshError> {
+        if !self.indices.len().is_multiple_of(3) {
+            return Err(MeshError::IncompleteTriangles(self.indices.len()));
+        }
+
+        let projected = 4usize
+            .checked_pow(iterations)
+            .and_then(|factor| factor.checked_mul(self.indices.len() / 3))
+            .unwrap_or(usize::MAX);
+        if projected > max_triangles {
+            return Err(MeshError::TriangleLimitExceeded { projected, limit: max_triangles });
+        }
+
+        let mut mesh = self.clone();
+        for _ in 0..iterations {
+            mesh = subdivide_once(&mesh, scheme);
+        }
+        Ok(mesh)
+    }
+}
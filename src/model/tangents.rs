@@ -0,0 +1,112 @@
+use glam::*;
+use std::collections::HashMap;
+use crate::model::loader::Mesh;
+use crate::model::normals::face_normal;
+
+/*
+Vertices are grouped by the bits of their position, normal and texture coordinates, so copies
+of the same vertex under different indices share one tangent.
+*/
+type VertexKey = ([u32; 3], [u32; 3], [u32; 2]);
+
+fn vertex_key(mesh: &Mesh, index: u32) -> VertexKey {
+    let vertex = &mesh.vertices[index as usize];
+    (
+        vertex.position.to_array().map(f32::to_bits),
+        vertex.normal.to_array().map(f32::to_bits),
+        vertex.tex_coord.to_array().map(f32::to_bits)
+    )
+}
+
+/*
+Returns the direction of increasing u over a triangle, and whether its texture coordinates keep
+the winding of its positions. Triangles with no texture area have no direction.
+*/
+fn triangle_tangent(positions: [Vec3; 3], tex_coords: [Vec2; 3]) -> (Option<Vec3>, bool) {
+    let (edge1, edge2) = (positions[1] - positions[0], positions[2] - positions[0]);
+    let (uv1, uv2) = (tex_coords[1] - tex_coords[0], tex_coords[2] - tex_coords[0]);
+    let signed_area = uv1.perp_dot(uv2);
+    let preserving = signed_area >= 0.0;
+    if signed_area.abs() <= f32::EPSILON * uv1.length() * uv2.length() {
+        return (None, preserving);
+    }
+
+    let tangent = (edge1 * uv2.y - edge2 * uv1.y) / signed_area;
+    (Some(tangent).filter(|tangent| tangent.is_finite() && *tangent != Vec3::ZERO), preserving)
+}
+
+fn corner_angle(positions: [Vec3; 3], corner: usize) -> f32 {
+    let origin = positions[corner];
+    let to_next = positions[(corner + 1) % 3] - origin;
+    let to_previous = positions[(corner + 2) % 3] - origin;
+    to_next.angle_between(to_previous)
+}
+
+/*
+Computes per-vertex tangents for normal mapping, with the handedness in `tangent.w`. This is
+not MikkTSpace, the convention glTF and most bakers use: it averages per-triangle tangents the
+simpler way, so its tangents can differ from a baker's and maps baked against MikkTSpace may
+shade slightly differently. Each triangle's direction of increasing u is projected onto the
+plane of every corner's normal and averaged with the corner angle as weight, over all the
+triangles sharing a vertex with the same position, normal and texture coordinates. Triangles
+whose texture coordinates are mirrored do not average with the others: a vertex used by both
+kinds is split in two, and `tangent.w` is -1.0 for the mirrored side and 1.0 otherwise, so the
+bitangent is `w * normal.cross(tangent)`. Vertices without a usable direction get any tangent
+perpendicular to their normal. Triangles with out-of-range indices and trailing indices are
+left as they are.
+*/
+pub fn compute_tangents_mikktspace(mesh: &mut Mesh) {
+    let vertex_count = mesh.vertices.len();
+    let triangles: Vec<(usize, [u32; 3])> = mesh
+        .indices
+        .triangles()
+        .enumerate()
+        .filter(|(_, indices)| indices.iter().all(|&index| (index as usize) < vertex_count))
+        .collect();
+
+    let mut sums: HashMap<(VertexKey, bool), Vec3> = HashMap::new();
+    let mut corners = Vec::with_capacity(triangles.len() * 3);
+    for &(triangle, indices) in &triangles {
+        let positions = indices.map(|index| mesh.vertices[index as usize].position);
+        let tex_coords = indices.map(|index| mesh.vertices[index as usize].tex_coord);
+        let (tangent, preserving) = triangle_tangent(positions, tex_coords);
+        let face = face_normal(positions[0], positions[1], positions[2]).normalize_or_zero();
+
+        for (corner, &index) in indices.iter().enumerate() {
+            let key = (vertex_key(mesh, index), preserving);
+            let sum = sums.entry(key).or_insert(Vec3::ZERO);
+            if let Some(tangent) = tangent {
+                let normal = mesh.vertices[index as usize].normal.try_normalize().unwrap_or(face);
+                let projected = (tangent - normal * normal.dot(tangent)).normalize_or_zero();
+                let weight = corner_angle(positions, corner);
+                if weight.is_finite() {
+                    *sum += projected * weight;
+                }
+            }
+            corners.push((triangle * 3 + corner, index, key));
+        }
+    }
+
+    let mut handedness: Vec<Option<bool>> = vec![None; vertex_count];
+    let mut copies: HashMap<u32, u32> = HashMap::new();
+    for (position, original, key) in corners {
+        let preserving = key.1;
+        let index = match handedness[original as usize] {
+            None => {
+                handedness[original as usize] = Some(preserving);
+                original
+            },
+            Some(owner) if owner == preserving => original,
+            Some(_) => *copies.entry(original).or_insert_with(|| {
+                mesh.vertices.push(mesh.vertices[original as usize]);
+                mesh.vertices.len() as u32 - 1
+            })
+        };
+        mesh.indices.set(position, index);
+
+        let vertex = &mut mesh.vertices[index as usize];
+        let normal = vertex.normal.try_normalize().unwrap_or(Vec3::Z);
+        let tangent = sums[&key].try_normalize().unwrap_or_else(|| normal.any_orthonormal_vector());
+        vertex.tangent = tangent.extend(if preserving { 1.0 } else { -1.0 });
+    }
+}
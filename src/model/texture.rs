@@ -1,45 +1,813 @@
 use glam::*;
 use std::ffi::CString;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use crate::model::{LoadError, TextureError};
 
+/*
+The `TextureTransform` struct holds the `KHR_texture_transform` data of a texture reference:
+texture coordinates are scaled, then rotated by `rotation` radians, then offset. The default
+leaves them unchanged.
+*/
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TextureTransform {
+    pub offset: Vec2,
+    pub rotation: f32,
+    pub scale: Vec2
+}
+
+impl Default for TextureTransform {
+    fn default() -> Self {
+        TextureTransform {
+            offset: Vec2::ZERO,
+            rotation: 0.0,
+            scale: Vec2::ONE
+        }
+    }
+}
+
+impl TextureTransform {
+    pub fn is_identity(&self) -> bool {
+        *self == TextureTransform::default()
+    }
+
+    /*
+    Applies the transform to texture coordinates as the extension defines it, rotating
+    counter-clockwise in texture space where V points down.
+    */
+    pub fn apply(&self, tex_coord: Vec2) -> Vec2 {
+        let (sin, cos) = self.rotation.sin_cos();
+        let scaled = tex_coord * self.scale;
+        self.offset + Vec2::new(cos * scaled.x + sin * scaled.y, cos * scaled.y - sin * scaled.x)
+    }
+
+    /*
+    Returns the transform to apply to texture coordinates whose V axis was flipped, `v` becoming
+    `1.0 - v`, so the result is the flip of what this transform gives for the original
+    coordinates. Mirroring V reverses the direction of rotation and moves the offset.
+    */
+    pub fn flipped_v(&self) -> TextureTransform {
+        let origin = self.apply(Vec2::Y);
+        TextureTransform {
+            offset: Vec2::new(origin.x, 1.0 - origin.y),
+            rotation: -self.rotation,
+            scale: self.scale
+        }
+    }
+}
+
+/*
+The `WrapMode` enum tells how `Texture::sample` treats texture coordinates outside [0, 1], as
+the glTF sampler modes do: `Repeat` tiles the image, `ClampToEdge` extends its edge pixels and
+`MirroredRepeat` tiles it flipping every other copy. The default is `Repeat`, the glTF default.
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WrapMode {
+    #[default]
+    Repeat,
+    ClampToEdge,
+    MirroredRepeat
+}
+
+impl WrapMode {
+    /*
+    Reduces texture coordinates to one period of the mode, [0, 1) for `Repeat` and [0, 2) for
+    `MirroredRepeat`, so coordinates far from the origin keep the precision of nearby ones.
+    */
+    fn reduce(&self, coordinate: f32) -> f32 {
+        match self {
+            WrapMode::Repeat => coordinate.rem_euclid(1.0),
+            WrapMode::ClampToEdge => coordinate,
+            WrapMode::MirroredRepeat => coordinate.rem_euclid(2.0)
+        }
+    }
+
+    /*
+    Maps a pixel index, possibly out of range, to a pixel of a row or column of `size` pixels.
+    */
+    fn wrap(&self, index: i64, size: i64) -> usize {
+        let index = match self {
+            WrapMode::Repeat => index.rem_euclid(size),
+            WrapMode::ClampToEdge => index.clamp(0, size - 1),
+            WrapMode::MirroredRepeat => {
+                let index = index.rem_euclid(2 * size);
+                if index < size { index } else { 2 * size - 1 - index }
+            }
+        };
+        index as usize
+    }
+}
+
+/*
+The `FilterMode` enum tells how `Texture::sample` combines pixels: `Nearest` takes the pixel
+the coordinates fall in and `Bilinear` blends the four pixels around them by distance to their
+centers. The default is `Bilinear`.
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FilterMode {
+    Nearest,
+    #[default]
+    Bilinear
+}
+
+/*
+The `TextureSource` enum tells where the encoded image of a deferred texture lives: a file, with
+its path already resolved against the model file, or the bytes of a buffer view. Buffers do not
+outlive the load, so the bytes of an embedded image are kept, still encoded, and shared by every
+texture using the image.
+*/
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TextureSource {
+    File(PathBuf),
+    Embedded(Arc<[u8]>)
+}
+
+impl TextureSource {
+    /*
+    Reads and decodes the image, reporting a missing file or undecodable bytes instead of
+    panicking.
+    */
+    pub fn load(&self) -> Result<Texture, TextureError> {
+        match self {
+            TextureSource::File(path) => {
+                let bytes = std::fs::read(path).map_err(|error| {
+                    std::io::Error::new(error.kind(), format!("{}: {}", path.display(), error))
+                })?;
+                decode_texture(&bytes).ok_or_else(|| TextureError::Decode(path.display().to_string()))
+            },
+            TextureSource::Embedded(bytes) => {
+                decode_texture(bytes).ok_or_else(|| TextureError::Decode("embedded image".to_string()))
+            }
+        }
+    }
+}
+
+/*
+The `TextureFormat` enum tells how each channel of a texture is stored: `Rgba8` as an 8-bit
+integer, `Rgba16` as a 16-bit integer and `Rgba32F` as a 32-bit float, each channel taking 1, 2
+or 4 bytes, little-endian. Integers stand for values from 0.0 to 1.0, floats are kept as they
+are and may exceed 1.0, as the radiance of an HDR environment map does. How many channels a
+pixel has is told by `Texture::channel_count`, so a 16-bit RGB image is `Rgba16` with three
+channels.
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TextureFormat {
+    #[default]
+    Rgba8,
+    Rgba16,
+    Rgba32F
+}
+
+impl TextureFormat {
+    pub fn bytes_per_channel(&self) -> usize {
+        match self {
+            TextureFormat::Rgba8 => 1,
+            TextureFormat::Rgba16 => 2,
+            TextureFormat::Rgba32F => 4
+        }
+    }
+
+    /*
+    Reads the value of one channel from its bytes.
+    */
+    pub(crate) fn decode(&self, bytes: &[u8]) -> f32 {
+        match self {
+            TextureFormat::Rgba8 => bytes[0] as f32 / 255.0,
+            TextureFormat::Rgba16 => u16::from_le_bytes([bytes[0], bytes[1]]) as f32 / 65535.0,
+            TextureFormat::Rgba32F => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+        }
+    }
+
+    /*
+    Appends the bytes of one channel holding `value`, clamped to [0, 1] and rounded for the
+    integer formats.
+    */
+    pub(crate) fn encode(&self, value: f32, bytes: &mut Vec<u8>) {
+        match self {
+            TextureFormat::Rgba8 => bytes.push((value.clamp(0.0, 1.0) * 255.0).round() as u8),
+            TextureFormat::Rgba16 => bytes.extend(((value.clamp(0.0, 1.0) * 65535.0).round() as u16).to_le_bytes()),
+            TextureFormat::Rgba32F => bytes.extend(value.to_le_bytes())
+        }
+    }
+
+    /*
+    Tells the format an encoded image decodes to from its first bytes: OpenEXR and Radiance HDR
+    images decode to floats, PNG images with 16 bits per channel to 16-bit integers and every
+    other image to 8-bit integers.
+    */
+    fn detect(header: &[u8]) -> TextureFormat {
+        const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+        if header.starts_with(&[0x76, 0x2f, 0x31, 0x01]) || header.starts_with(b"#?RADIANCE") || header.starts_with(b"#?RGBE") {
+            TextureFormat::Rgba32F
+        } else if header.starts_with(PNG_SIGNATURE) && header.get(24) == Some(&16) {
+            TextureFormat::Rgba16
+        } else {
+            TextureFormat::Rgba8
+        }
+    }
+}
+
+/*
+The `Texture` struct holds decoded pixels along with the transform its material applies to the
+texture coordinates used to sample it and `tex_coord`, the index of the texture coordinate set
+those come from, as in `TEXCOORD_0`. Both belong to the reference the texture was loaded
+through, so an image shared by several slots gives each its own. A texture loaded with
+`TextureLoading::Deferred` has no pixels yet, only the `source` to decode them from and the size
+and channel count its image header declares, all zero when the header cannot be read; it must
+not be sampled before it is resolved.
+
+Pixels are stored row by row from the top of the image, each row from left to right with no
+padding after it, and the channels of a pixel next to each other, so with `size` the bytes of a
+channel in `format`, the row stride is `width * channel_count * size` bytes and pixel (x, y)
+starts at byte `(y * width + x) * channel_count * size`. Images keep the bit depth of their
+file, see `TextureFormat`; the constructors taking pixels build `Rgba8` textures. With the
+`serde` feature the pixels are serialized as raw bytes next to the dimensions, and deserializing
+rejects pixel data whose length does not match them.
+*/
 #[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "TextureData")
+)]
 pub struct Texture {
     data: Vec<u8>,
     width: u32,
     height: u32,
-    channel_count: usize
+    channel_count: usize,
+    format: TextureFormat,
+    transform: TextureTransform,
+    tex_coord: u32,
+    source: Option<TextureSource>
+}
+
+/*
+The serialized form of a `Texture`, checked before it becomes one.
+*/
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct TextureData {
+    data: Vec<u8>,
+    width: u32,
+    height: u32,
+    channel_count: usize,
+    #[serde(default)]
+    format: TextureFormat,
+    transform: TextureTransform,
+    #[serde(default)]
+    tex_coord: u32,
+    source: Option<TextureSource>
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<TextureData> for Texture {
+    type Error = String;
+
+    fn try_from(texture: TextureData) -> Result<Self, Self::Error> {
+        if let Some(source) = texture.source.filter(|_| texture.data.is_empty()) {
+            return Ok(Texture {
+                data: Vec::new(),
+                width: texture.width,
+                height: texture.height,
+                channel_count: texture.channel_count,
+                format: texture.format,
+                transform: texture.transform,
+                tex_coord: texture.tex_coord,
+                source: Some(source)
+            });
+        }
+        Texture::from_pixels(texture.data, texture.width, texture.height, texture.channel_count, texture.format)
+            .map(|decoded| decoded.with_transform(texture.transform).with_tex_coord(texture.tex_coord))
+            .map_err(|reason| format!("Failed to deserialize texture. ({})", reason))
+    }
+}
+
+/*
+Copies the pixels decoded by stb_image into a `Texture` and releases them, or returns `None`
+when decoding failed.
+*/
+unsafe fn decoded_texture(data: *mut u8, width: i32, height: i32, channel_count: i32) -> Option<Texture> {
+    if data.is_null() {
+        return None;
+    }
+
+    let pixels = std::slice::from_raw_parts(data, (width * height * channel_count) as usize).to_vec();
+    stb_image::stb_image::bindgen::stbi_image_free(data as *mut _);
+    Texture::from_pixels(pixels, width as u32, height as u32, channel_count as usize, TextureFormat::Rgba8).ok()
+}
+
+/*
+Decodes a Radiance HDR image with stb_image into a float texture.
+*/
+fn decode_hdr(bytes: &[u8]) -> Option<Texture> {
+    let (mut width, mut height, mut channel_count) = (0, 0, 0);
+    let data = unsafe {
+        stb_image::stb_image::bindgen::stbi_loadf_from_memory(
+            bytes.as_ptr(),
+            bytes.len() as i32,
+            &mut width,
+            &mut height,
+            &mut channel_count,
+            0
+        )
+    };
+    if data.is_null() {
+        return None;
+    }
+
+    let values = unsafe { std::slice::from_raw_parts(data, (width * height * channel_count) as usize) };
+    let pixels = values.iter().flat_map(|value| value.to_le_bytes()).collect();
+    unsafe { stb_image::stb_image::bindgen::stbi_image_free(data as *mut _) };
+    Texture::from_pixels(pixels, width as u32, height as u32, channel_count as usize, TextureFormat::Rgba32F).ok()
+}
+
+/*
+Decodes a PNG image with 16 bits per channel, which stb_image would reduce to 8 bits, into a
+16-bit texture. PNG stores the samples big-endian, so their bytes are swapped.
+*/
+fn decode_png16(bytes: &[u8]) -> Option<Texture> {
+    let mut decoder = png::Decoder::new(bytes);
+    decoder.set_transformations(png::Transformations::EXPAND);
+    let mut reader = decoder.read_info().ok()?;
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let frame = reader.next_frame(&mut pixels).ok()?;
+    if frame.bit_depth != png::BitDepth::Sixteen {
+        return None;
+    }
+
+    pixels.truncate(frame.buffer_size());
+    for sample in pixels.chunks_exact_mut(2) {
+        sample.swap(0, 1);
+    }
+    Texture::from_pixels(pixels, frame.width, frame.height, frame.color_type.samples(), TextureFormat::Rgba16).ok()
+}
+
+/*
+Decodes the first layer of an OpenEXR image holding red, green and blue channels into a float
+texture with four channels, alpha being 1.0 where the image has none.
+*/
+#[cfg(feature = "exr")]
+fn decode_exr(bytes: &[u8]) -> Option<Texture> {
+    use exr::prelude::*;
+
+    let image = read()
+        .no_deep_data()
+        .largest_resolution_level()
+        .rgba_channels(
+            |resolution, _| (resolution.width(), vec![0.0f32; resolution.width() * resolution.height() * 4]),
+            |(width, values), position, (r, g, b, a): (f32, f32, f32, f32)| {
+                let start = (position.y() * *width + position.x()) * 4;
+                values[start..start + 4].copy_from_slice(&[r, g, b, a]);
+            }
+        )
+        .first_valid_layer()
+        .all_attributes()
+        .from_buffered(std::io::Cursor::new(bytes))
+        .ok()?;
+
+    let size = image.layer_data.size;
+    let (_, values) = image.layer_data.channel_data.pixels;
+    let pixels = values.iter().flat_map(|value| value.to_le_bytes()).collect();
+    Texture::from_pixels(pixels, size.width() as u32, size.height() as u32, 4, TextureFormat::Rgba32F).ok()
 }
 
-pub fn load_texture(file_path: &str) -> Texture {
-    let file_path = CString::new(file_path.as_bytes()).unwrap();
+/*
+Converts a path to the C string stb_image opens. Unix paths are passed as their raw bytes, so
+paths that are not valid UTF-8 still open; elsewhere the path must be valid UTF-8.
+*/
+fn c_path(path: &Path) -> Option<CString> {
+    #[cfg(unix)]
+    let bytes = std::os::unix::ffi::OsStrExt::as_bytes(path.as_os_str());
+    #[cfg(not(unix))]
+    let bytes = path.to_str()?.as_bytes();
+    CString::new(bytes).ok()
+}
+
+/*
+Loads a texture from an image file, returning `LoadError::Texture` for a file that cannot be
+read or does not decode, such as a truncated PNG.
+*/
+pub fn load_texture(file_path: impl AsRef<Path>) -> Result<Texture, LoadError> {
+    Ok(TextureSource::File(file_path.as_ref().to_path_buf()).load()?)
+}
+
+/*
+Decodes a texture from an encoded image held in memory, such as a PNG or JPEG stored in a glTF
+buffer, returning `LoadError::Texture` when the bytes cannot be decoded. The format is chosen
+from the content rather than a file name, keeping the bit depth of the image: 16-bit PNG images
+give `Rgba16` textures, Radiance HDR images `Rgba32F` ones, and so do OpenEXR images with the
+`exr` feature, without which they do not decode. Everything else gives `Rgba8`.
+*/
+pub fn load_texture_from_memory(bytes: &[u8]) -> Result<Texture, LoadError> {
+    decode_texture(bytes).ok_or_else(|| LoadError::Texture(TextureError::Decode("image in memory".to_string())))
+}
+
+/*
+Decodes an encoded image as `load_texture_from_memory` does, returning `None` when it cannot,
+for callers naming the source in their own error.
+*/
+pub(crate) fn decode_texture(bytes: &[u8]) -> Option<Texture> {
+    match TextureFormat::detect(bytes) {
+        TextureFormat::Rgba32F if bytes.starts_with(b"#?") => return decode_hdr(bytes),
+        #[cfg(feature = "exr")]
+        TextureFormat::Rgba32F => return decode_exr(bytes),
+        #[cfg(not(feature = "exr"))]
+        TextureFormat::Rgba32F => return None,
+        TextureFormat::Rgba16 => return decode_png16(bytes),
+        TextureFormat::Rgba8 => {}
+    }
 
     unsafe {
         let mut width = 0;
         let mut height = 0;
         let mut channel_count = 0;
-        let data = stb_image::stb_image::bindgen::stbi_load(
-            file_path.as_ptr(),
+        let data = stb_image::stb_image::bindgen::stbi_load_from_memory(
+            bytes.as_ptr(),
+            bytes.len() as i32,
             &mut width,
             &mut height,
             &mut channel_count,
             0,
         );
 
-        assert!(!data.is_null(), "Failed to load texture.");
-        let data: Vec<u8> = std::slice::from_raw_parts(
+        decoded_texture(data, width, height, channel_count)
+    }
+}
+
+/*
+Reads the first bytes of an image, enough for `TextureFormat::detect`.
+*/
+fn image_header(source: &TextureSource) -> Vec<u8> {
+    match source {
+        TextureSource::File(path) => {
+            let mut header = Vec::new();
+            if let Ok(file) = std::fs::File::open(path) {
+                let _ = std::io::Read::read_to_end(&mut std::io::Read::take(file, 32), &mut header);
+            }
+            header
+        },
+        TextureSource::Embedded(bytes) => bytes[..bytes.len().min(32)].to_vec()
+    }
+}
+
+/*
+Reads the size and channel count a PNG image declares in its header, for the 16-bit images
+stb_image does not read.
+*/
+fn probe_png(header: &[u8]) -> Option<(u32, u32, usize)> {
+    let field = |start: usize| Some(u32::from_be_bytes(header.get(start..start + 4)?.try_into().ok()?));
+    let channel_count = match header.get(25)? {
+        0 => 1,
+        4 => 2,
+        2 | 3 => 3,
+        6 => 4,
+        _ => return None
+    };
+    Some((field(16)?, field(20)?, channel_count))
+}
+
+/*
+Reads the size and channel count an image declares in its header without decoding its pixels,
+or returns `None` when the file cannot be opened or the format is not recognized, as for
+OpenEXR images.
+*/
+fn probe_image(source: &TextureSource) -> Option<(u32, u32, usize)> {
+    let mut width = 0;
+    let mut height = 0;
+    let mut channel_count = 0;
+    let found = match source {
+        TextureSource::File(path) => {
+            let path = c_path(path)?;
+            unsafe { stb_image::stb_image::bindgen::stbi_info(path.as_ptr(), &mut width, &mut height, &mut channel_count) }
+        },
+        TextureSource::Embedded(bytes) => unsafe {
+            stb_image::stb_image::bindgen::stbi_info_from_memory(
+                bytes.as_ptr(),
+                bytes.len() as i32,
+                &mut width,
+                &mut height,
+                &mut channel_count
+            )
+        }
+    };
+    (found != 0).then_some((width as u32, height as u32, channel_count as usize))
+}
+
+impl Texture {
+    /*
+    Builds a texture from pixels stored row by row in `format`, with 1 to 4 channels per pixel
+    and no transform. Fails when the channel count is not supported or the data does not hold
+    exactly `width * height` pixels, with the reason.
+    */
+    pub(crate) fn from_pixels(data: Vec<u8>, width: u32, height: u32, channel_count: usize, format: TextureFormat) -> Result<Texture, &'static str> {
+        if !(1..=4).contains(&channel_count) {
+            return Err("Unsupported channel count");
+        }
+        let expected = (width as usize)
+            .checked_mul(height as usize)
+            .and_then(|pixels| pixels.checked_mul(channel_count * format.bytes_per_channel()));
+        if expected != Some(data.len()) {
+            return Err("Pixel data does not match the dimensions");
+        }
+        Ok(Texture {
             data,
-            (width * height * channel_count) as usize
-        ).to_vec();
+            width,
+            height,
+            channel_count,
+            format,
+            transform: TextureTransform::default(),
+            tex_coord: 0,
+            source: None
+        })
+    }
 
+    /*
+    Builds a texture with no pixels yet, to be decoded from `source` when resolved. Only the
+    header of the image is read, for its size, channel count and format.
+    */
+    pub fn deferred(source: TextureSource) -> Texture {
+        let header = image_header(&source);
+        let format = TextureFormat::detect(&header);
+        let (width, height, channel_count) = match format {
+            TextureFormat::Rgba16 => probe_png(&header),
+            _ => probe_image(&source)
+        }
+        .unwrap_or((0, 0, 0));
+        Texture::deferred_with_size(source, width, height, channel_count, format)
+    }
+
+    /*
+    Builds a deferred texture whose size and channel count are already known.
+    */
+    pub(crate) fn deferred_with_size(source: TextureSource, width: u32, height: u32, channel_count: usize, format: TextureFormat) -> Texture {
         Texture {
-            data,
-            width: width as u32,
-            height: height as u32,
-            channel_count: channel_count as usize
+            data: Vec::new(),
+            width,
+            height,
+            channel_count,
+            format,
+            transform: TextureTransform::default(),
+            tex_coord: 0,
+            source: Some(source)
+        }
+    }
+
+    /*
+    Tells whether the pixels are decoded, which is always the case unless the texture was
+    loaded deferred and not resolved since.
+    */
+    pub fn is_resolved(&self) -> bool {
+        self.source.is_none()
+    }
+
+    /*
+    Returns where the pixels of a deferred texture will be decoded from, or `None` once they are.
+    */
+    pub fn source(&self) -> Option<&TextureSource> {
+        self.source.as_ref()
+    }
+
+    /*
+    Decodes the pixels of a deferred texture, keeping its transform and texture coordinate set.
+    Resolved textures are left as they are.
+    */
+    pub fn resolve(&mut self) -> Result<(), TextureError> {
+        if let Some(source) = &self.source {
+            *self = source.load()?.with_transform(self.transform).with_tex_coord(self.tex_coord);
+        }
+        Ok(())
+    }
+
+    /*
+    Builds a texture from RGBA pixels laid out as described on `Texture`, four bytes per pixel.
+    Fails when `pixels` does not hold exactly `width * height * 4` bytes.
+    */
+    pub fn from_rgba8(width: u32, height: u32, pixels: Vec<u8>) -> Result<Texture, TextureError> {
+        let length = pixels.len();
+        Texture::from_pixels(pixels, width, height, 4, TextureFormat::Rgba8).map_err(|_| {
+            TextureError::InvalidData(format!("{} bytes of pixels for a {}x{} RGBA texture", length, width, height))
+        })
+    }
+
+    /*
+    Builds an RGBA texture by calling `f` with the column and row of every pixel, (0, 0) being
+    the top left one. Color components are clamped to [0, 1] and rounded to 8 bits.
+    */
+    pub fn from_fn(width: u32, height: u32, f: impl Fn(u32, u32) -> Vec4) -> Texture {
+        Texture::from_fn_with_format(width, height, TextureFormat::Rgba8, f)
+    }
+
+    /*
+    Builds an RGBA texture in `format` the way `from_fn` does. Integer formats clamp components
+    to [0, 1], `Rgba32F` keeps them as they are.
+    */
+    pub(crate) fn from_fn_with_format(width: u32, height: u32, format: TextureFormat, f: impl Fn(u32, u32) -> Vec4) -> Texture {
+        let mut pixels = Vec::with_capacity(width as usize * height as usize * 4 * format.bytes_per_channel());
+        for y in 0..height {
+            for x in 0..width {
+                for channel in f(x, y).to_array() {
+                    format.encode(channel, &mut pixels);
+                }
+            }
+        }
+        Texture::from_pixels(pixels, width, height, 4, format).expect("Failed to create texture. (Pixel count does not match the dimensions)")
+    }
+
+    /*
+    Builds a 1×1 RGBA texture of a single color, which samples as that color everywhere.
+    */
+    pub fn solid(color: Vec4) -> Texture {
+        Texture::from_fn(1, 1, |_, _| color)
+    }
+
+    /*
+    Builds a `size`×`size` RGBA checkerboard of `cells` by `cells` squares, alternating between
+    `color_a`, in the top left square, and `color_b`, for spotting stretched or flipped texture
+    coordinates. Squares split the pixels as evenly as they can when `cells` does not divide
+    `size`. Giving it to a default material makes a debug model of any mesh, as in
+    `model.materials[0].base_color_texture = Some(Texture::checkerboard(256, 8, Vec4::ONE, Vec4::W))`
+    after `Model::from_mesh(Mesh::plane(1.0, 1.0, 1))`. Panics when `cells` is zero.
+    */
+    pub fn checkerboard(size: u32, cells: u32, color_a: Vec4, color_b: Vec4) -> Texture {
+        assert!(cells > 0, "Failed to create checkerboard. (Cell count must be positive)");
+        let cell = |position: u32| position as u64 * cells as u64 / size as u64;
+        Texture::from_fn(size, size, |x, y| match (cell(x) + cell(y)) % 2 {
+            0 => color_a,
+            _ => color_b
+        })
+    }
+
+    /*
+    Builds a `size`×`size` RGBA texture holding the texture coordinates of each pixel center, U
+    in red and V in green, V growing down the image as in glTF. Blue is zero and alpha one.
+    */
+    pub fn uv_gradient(size: u32) -> Texture {
+        let coordinate = |position: u32| (position as f32 + 0.5) / size as f32;
+        Texture::from_fn(size, size, |x, y| Vec4::new(coordinate(x), coordinate(y), 0.0, 1.0))
+    }
+
+    /*
+    Returns the decoded pixels, laid out as described on `Texture`, empty for a deferred texture
+    not resolved yet.
+    */
+    pub fn pixels(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn channel_count(&self) -> usize {
+        self.channel_count
+    }
+
+    pub fn format(&self) -> TextureFormat {
+        self.format
+    }
+
+    /*
+    Reads the pixel at column `x` and row `y`, with components in [0, 1] expanded to RGBA the
+    way `sample` does. Panics when the position is outside the texture or the texture has no
+    pixels.
+    */
+    pub fn pixel(&self, x: u32, y: u32) -> Vec4 {
+        if x >= self.width || y >= self.height || self.data.is_empty() {
+            panic!("Failed to read pixel. (Position ({}, {}) is outside the texture)", x, y);
+        }
+        self.texel(x as usize, y as usize)
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn transform(&self) -> TextureTransform {
+        self.transform
+    }
+
+    pub fn with_transform(self, transform: TextureTransform) -> Texture {
+        Texture { transform, ..self }
+    }
+
+    pub(crate) fn set_transform(&mut self, transform: TextureTransform) {
+        self.transform = transform;
+    }
+
+    pub fn tex_coord(&self) -> u32 {
+        self.tex_coord
+    }
+
+    pub fn with_tex_coord(self, tex_coord: u32) -> Texture {
+        Texture { tex_coord, ..self }
+    }
+
+    /*
+    Encodes the pixels as a PNG image with as many channels as the texture has, with 16 bits per
+    channel for `Rgba16` textures and 8 otherwise. PNG cannot hold floats, so `Rgba32F` pixels
+    are clamped to [0, 1] first, losing what lies beyond.
+    */
+    pub fn encode_png(&self) -> Result<Vec<u8>, png::EncodingError> {
+        let color = match self.channel_count {
+            1 => png::ColorType::Grayscale,
+            2 => png::ColorType::GrayscaleAlpha,
+            3 => png::ColorType::Rgb,
+            _ => png::ColorType::Rgba
+        };
+
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, self.width, self.height);
+        encoder.set_color(color);
+        match self.format {
+            TextureFormat::Rgba8 => {
+                encoder.set_depth(png::BitDepth::Eight);
+                encoder.write_header()?.write_image_data(&self.data)?;
+            },
+            TextureFormat::Rgba16 => {
+                let mut samples = self.data.clone();
+                for sample in samples.chunks_exact_mut(2) {
+                    sample.swap(0, 1);
+                }
+                encoder.set_depth(png::BitDepth::Sixteen);
+                encoder.write_header()?.write_image_data(&samples)?;
+            },
+            TextureFormat::Rgba32F => {
+                let samples: Vec<u8> = self
+                    .data
+                    .chunks_exact(4)
+                    .map(|value| (f32::from_le_bytes([value[0], value[1], value[2], value[3]]).clamp(0.0, 1.0) * 255.0).round() as u8)
+                    .collect();
+                encoder.set_depth(png::BitDepth::Eight);
+                encoder.write_header()?.write_image_data(&samples)?;
+            }
+        }
+        Ok(bytes)
+    }
+
+    /*
+    Returns the number of bytes the decoded pixels take in memory.
+    */
+    pub fn byte_size(&self) -> usize {
+        self.data.len()
+    }
+
+    /*
+    Samples the texture at texture coordinates `uv`, where (0, 0) is the top left corner of the
+    image and (1, 1) the bottom right one, returning the color with components in [0, 1]. Pixel
+    centers sit half a pixel in from the edges, so bilinear filtering blends across the edges
+    with the pixels `wrap` picks beyond them: `Repeat` tiles without a seam and a 1×1 texture
+    gives its pixel everywhere. One-channel textures give gray, two-channel ones gray and alpha,
+    and textures without alpha an alpha of 1. The texture transform is not applied. Panics for a
+    texture without pixels, such as a deferred one not resolved yet.
+    */
+    pub fn sample(&self, uv: Vec2, wrap: WrapMode, filter: FilterMode) -> Vec4 {
+        if self.data.is_empty() {
+            panic!("Failed to sample texture. (Texture has no pixels)");
+        }
+        let (width, height) = (self.width as i64, self.height as i64);
+        let position = Vec2::new(wrap.reduce(uv.x) * self.width as f32, wrap.reduce(uv.y) * self.height as f32);
+
+        match filter {
+            FilterMode::Nearest => {
+                let x = wrap.wrap(position.x.floor() as i64, width);
+                let y = wrap.wrap(position.y.floor() as i64, height);
+                self.texel(x, y)
+            },
+            FilterMode::Bilinear => {
+                let position = position - 0.5;
+                let corner = position.floor();
+                let weight = position - corner;
+                let (x0, y0) = (corner.x as i64, corner.y as i64);
+                let (left, right) = (wrap.wrap(x0, width), wrap.wrap(x0 + 1, width));
+                let (top, bottom) = (wrap.wrap(y0, height), wrap.wrap(y0 + 1, height));
+
+                let upper = self.texel(left, top).lerp(self.texel(right, top), weight.x);
+                let lower = self.texel(left, bottom).lerp(self.texel(right, bottom), weight.x);
+                upper.lerp(lower, weight.y)
+            }
+        }
+    }
+
+    /*
+    Reads the pixel at column `x` and row `y` as a color with components in [0, 1].
+    */
+    fn texel(&self, x: usize, y: usize) -> Vec4 {
+        let size = self.format.bytes_per_channel();
+        let start = (y * self.width as usize + x) * self.channel_count * size;
+        let channel = |i: usize| self.format.decode(&self.data[start + i * size..]);
+        match self.channel_count {
+            1 => Vec4::new(channel(0), channel(0), channel(0), 1.0),
+            2 => Vec4::new(channel(0), channel(0), channel(0), channel(1)),
+            3 => Vec4::new(channel(0), channel(1), channel(2), 1.0),
+            _ => Vec4::new(channel(0), channel(1), channel(2), channel(3))
         }
     }
-}
 
-impl Texture {
     pub fn sample_pixel(&self, x: f32, y: f32) -> Vec4 {
         let inv_dims = Vec2::new(1.0 / self.width as f32, 1.0 / self.height as f32);
 
@@ -61,6 +829,9 @@ impl Texture {
     pub fn get_pixel(&self, x: f32, y: f32) -> Vec4 {
         let x = ((x * self.width as f32) as usize) % (self.width - 1) as usize;
         let y = ((y * self.height as f32) as usize) % (self.height - 1) as usize;
+        if self.format != TextureFormat::Rgba8 {
+            return self.texel(x, y);
+        }
 
         match self.channel_count {
             4 => {
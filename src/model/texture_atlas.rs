@@ -0,0 +1,342 @@
+use glam::*;
+use crate::model::loader::Model;
+use crate::model::stats::material_textures;
+use crate::model::{MaterialHandle, Texture, TextureFormat};
+
+/*
+How far outside [0, 1] a texture coordinate may stray, from rounding in the exporter, before
+the texture counts as tiled.
+*/
+const UV_EPSILON: f32 = 1e-4;
+
+/*
+The `AtlasOptions` struct holds the settings of `Model::pack_texture_atlas_with`. No atlas page
+grows beyond `max_size` pixels on a side, and every texture is surrounded by `padding` pixels
+repeating its edge, so bilinear filtering and mipmaps do not bleed between neighbours. The
+defaults are pages of up to 4096 pixels and a padding of 2.
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AtlasOptions {
+    pub max_size: u32,
+    pub padding: u32
+}
+
+impl Default for AtlasOptions {
+    fn default() -> Self {
+        AtlasOptions {
+            max_size: 4096,
+            padding: 2
+        }
+    }
+}
+
+/*
+The `AtlasExclusion` enum tells why a material kept its own base color texture. `Repeats`: its
+meshes have texture coordinates outside [0, 1], so the texture tiles, which an atlas would
+break. `SharedTexCoords`: another texture of the material samples the same coordinates and
+would be misplaced by remapping them. `TexCoordSet`: the texture samples a set other than the
+first, which meshes do not store. `Transformed`: the texture has a `KHR_texture_transform`.
+`NotResolved`: the texture is deferred and has no pixels. `Format`: the texture keeps more than
+8 bits per channel. `TooLarge`: the texture and its padding do not fit in a page. `Unused`: no
+mesh uses the material.
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AtlasExclusion {
+    Repeats,
+    SharedTexCoords,
+    TexCoordSet,
+    Transformed,
+    NotResolved,
+    Format,
+    TooLarge,
+    Unused
+}
+
+/*
+The `AtlasPlacement` struct tells where the base color texture of `material` went: the page
+and the rectangle, in pixels and without padding, it covers there.
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AtlasPlacement {
+    pub material: MaterialHandle,
+    pub page: usize,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32
+}
+
+/*
+The `AtlasReport` struct sums up a `Model::pack_texture_atlas` pass: the width and height of
+every page created, the placement of every material moved into one and the materials left out,
+with the reason.
+*/
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AtlasReport {
+    pub page_sizes: Vec<UVec2>,
+    pub placements: Vec<AtlasPlacement>,
+    pub excluded: Vec<(MaterialHandle, AtlasExclusion)>
+}
+
+/*
+One step of the skyline of a page: the pixels from `x` to `x + width` are taken up to row `y`.
+*/
+#[derive(Clone, Copy)]
+struct Segment {
+    x: u32,
+    y: u32,
+    width: u32
+}
+
+/*
+Returns the row a rectangle of `size` would rest on with its left edge at the segment `start`,
+or `None` when it would stick out of the page.
+*/
+fn skyline_fit(skyline: &[Segment], start: usize, size: UVec2, max_size: u32) -> Option<u32> {
+    let x = skyline[start].x;
+    if x + size.x > max_size {
+        return None;
+    }
+    let mut y = 0;
+    let mut covered = 0;
+    for segment in &skyline[start..] {
+        if covered >= size.x {
+            break;
+        }
+        y = y.max(segment.y);
+        covered += segment.width;
+    }
+    (y + size.y <= max_size).then_some(y)
+}
+
+/*
+Raises the skyline under a rectangle of `size` placed at (`skyline[start].x`, `y`), trimming
+the segments it covers and joining neighbours left at the same height.
+*/
+fn skyline_place(skyline: &mut Vec<Segment>, start: usize, y: u32, size: UVec2) {
+    let placed = Segment { x: skyline[start].x, y: y + size.y, width: size.x };
+    let end = placed.x + placed.width;
+    skyline.insert(start, placed);
+    while let Some(segment) = skyline.get_mut(start + 1) {
+        if segment.x >= end {
+            break;
+        }
+        let overlap = end - segment.x;
+        match segment.width <= overlap {
+            true => {
+                skyline.remove(start + 1);
+            },
+            false => {
+                segment.x += overlap;
+                segment.width -= overlap;
+                break;
+            }
+        }
+    }
+
+    let mut index = skyline.len() - 1;
+    while index > 0 {
+        if skyline[index - 1].y == skyline[index].y {
+            skyline[index - 1].width += skyline[index].width;
+            skyline.remove(index);
+        }
+        index -= 1;
+    }
+}
+
+/*
+Packs rectangles of `sizes` into as few pages of at most `max_size` pixels as a bottom-left
+skyline packer manages, tallest first. Returns the page and corner of every rectangle, in the
+order of `sizes`, and the extent each page uses. Every size must fit in a page.
+*/
+fn pack_rectangles(sizes: &[UVec2], max_size: u32) -> (Vec<(usize, UVec2)>, Vec<UVec2>) {
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by_key(|&index| std::cmp::Reverse((sizes[index].y, sizes[index].x)));
+
+    let mut skylines: Vec<Vec<Segment>> = Vec::new();
+    let mut extents: Vec<UVec2> = Vec::new();
+    let mut positions = vec![(0, UVec2::ZERO); sizes.len()];
+    for index in order {
+        let size = sizes[index];
+        let best = skylines.iter().enumerate().find_map(|(page, skyline)| {
+            (0..skyline.len())
+                .filter_map(|start| skyline_fit(skyline, start, size, max_size).map(|y| (y + size.y, skyline[start].x, start, y)))
+                .min()
+                .map(|(_, _, start, y)| (page, start, y))
+        });
+        let (page, start, y) = match best {
+            Some(best) => best,
+            None => {
+                skylines.push(vec![Segment { x: 0, y: 0, width: max_size }]);
+                extents.push(UVec2::ZERO);
+                (skylines.len() - 1, 0, 0)
+            }
+        };
+
+        let corner = UVec2::new(skylines[page][start].x, y);
+        skyline_place(&mut skylines[page], start, y, size);
+        extents[page] = extents[page].max(corner + size);
+        positions[index] = (page, corner);
+    }
+    (positions, extents)
+}
+
+impl Model {
+    /*
+    Tells why the base color texture of the material at `index` cannot join an atlas, if it
+    cannot.
+    */
+    fn atlas_exclusion(&self, index: usize, options: &AtlasOptions) -> Option<AtlasExclusion> {
+        let material = &self.materials[index];
+        let texture = material.base_color_texture.as_ref()?;
+        let handle = MaterialHandle::from(index);
+        let mut meshes = self.meshes.iter().filter(|mesh| mesh.material == handle).peekable();
+        let padded = UVec2::new(texture.width(), texture.height()) + options.padding * 2;
+
+        if texture.tex_coord() != 0 {
+            return Some(AtlasExclusion::TexCoordSet);
+        }
+        if !texture.transform().is_identity() {
+            return Some(AtlasExclusion::Transformed);
+        }
+        if !texture.is_resolved() {
+            return Some(AtlasExclusion::NotResolved);
+        }
+        if texture.format() != TextureFormat::Rgba8 {
+            return Some(AtlasExclusion::Format);
+        }
+        if padded.max_element() > options.max_size {
+            return Some(AtlasExclusion::TooLarge);
+        }
+        if material_textures(material).filter(|other| other.tex_coord() == 0).count() > 1 {
+            return Some(AtlasExclusion::SharedTexCoords);
+        }
+        if meshes.peek().is_none() {
+            return Some(AtlasExclusion::Unused);
+        }
+        let in_range = |value: f32| (-UV_EPSILON..=1.0 + UV_EPSILON).contains(&value);
+        if !meshes.all(|mesh| mesh.vertices.iter().all(|vertex| in_range(vertex.tex_coord.x) && in_range(vertex.tex_coord.y))) {
+            return Some(AtlasExclusion::Repeats);
+        }
+        None
+    }
+
+    /*
+    Same as `pack_texture_atlas_with`, with pages of up to `max_size` pixels and the default
+    padding.
+    */
+    pub fn pack_texture_atlas(&mut self, max_size: u32) -> AtlasReport {
+        self.pack_texture_atlas_with(&AtlasOptions { max_size, ..Default::default() })
+    }
+
+    /*
+    Merges the base color textures of the materials into shared atlas pages, so drawing many
+    small textures, such as decals, takes a handful of texture binds. Each distinct image is
+    placed once, a texture shared by several materials included, and every material using it
+    then refers to the page holding it. The texture coordinates of the meshes using those
+    materials are remapped into the rectangle of their texture, in the convention of
+    `Texture::sample`. Materials are left out, and listed in the report with the reason, when
+    atlasing would change how they look: see `AtlasExclusion`. Pages are 8-bit RGBA, sized to
+    the area they use. Nothing changes unless at least two distinct images can be packed.
+    Materials differing only by texture can afterwards be joined by `deduplicate_materials`.
+    */
+    pub fn pack_texture_atlas_with(&mut self, options: &AtlasOptions) -> AtlasReport {
+        let mut report = AtlasReport::default();
+        let mut images: Vec<usize> = Vec::new();
+        let mut members: Vec<(usize, usize)> = Vec::new();
+        for index in 0..self.materials.len() {
+            if self.materials[index].base_color_texture.is_none() {
+                continue;
+            }
+            if let Some(reason) = self.atlas_exclusion(index, options) {
+                report.excluded.push((MaterialHandle::from(index), reason));
+                continue;
+            }
+
+            let texture = self.materials[index].base_color_texture.as_ref().expect("Failed to pack texture atlas. (Material lost its texture)");
+            let same_image = |other: &Texture| {
+                other.width() == texture.width()
+                    && other.height() == texture.height()
+                    && other.channel_count() == texture.channel_count()
+                    && other.pixels() == texture.pixels()
+            };
+            let image = match images.iter().position(|&first| self.materials[first].base_color_texture.as_ref().is_some_and(same_image)) {
+                Some(image) => image,
+                None => {
+                    images.push(index);
+                    images.len() - 1
+                }
+            };
+            members.push((index, image));
+        }
+        if images.len() < 2 {
+            return report;
+        }
+
+        let textures: Vec<Texture> = images
+            .iter()
+            .map(|&index| self.materials[index].base_color_texture.clone().expect("Failed to pack texture atlas. (Material lost its texture)"))
+            .collect();
+        let padding = options.padding;
+        let sizes: Vec<UVec2> = textures.iter().map(|texture| UVec2::new(texture.width(), texture.height()) + padding * 2).collect();
+        let (positions, extents) = pack_rectangles(&sizes, options.max_size);
+
+        let mut pages: Vec<Vec<u8>> = extents.iter().map(|extent| vec![0; (extent.x * extent.y * 4) as usize]).collect();
+        for (texture, &(page, corner)) in textures.iter().zip(&positions) {
+            let (width, height) = (texture.width() as i64, texture.height() as i64);
+            let page_width = extents[page].x as usize;
+            let mut row = Vec::with_capacity(4);
+            for y in 0..(height + padding as i64 * 2) as usize {
+                for x in 0..(width + padding as i64 * 2) as usize {
+                    let source_x = (x as i64 - padding as i64).clamp(0, width - 1) as u32;
+                    let source_y = (y as i64 - padding as i64).clamp(0, height - 1) as u32;
+                    let color = texture.pixel(source_x, source_y);
+                    row.clear();
+                    for channel in color.to_array() {
+                        TextureFormat::Rgba8.encode(channel, &mut row);
+                    }
+                    let start = ((corner.y as usize + y) * page_width + corner.x as usize + x) * 4;
+                    pages[page][start..start + 4].copy_from_slice(&row);
+                }
+            }
+        }
+        let pages: Vec<Texture> = pages
+            .into_iter()
+            .zip(&extents)
+            .map(|(pixels, extent)| {
+                Texture::from_pixels(pixels, extent.x, extent.y, 4, TextureFormat::Rgba8)
+                    .expect("Failed to pack texture atlas. (Page size does not match its pixels)")
+            })
+            .collect();
+
+        for (index, image) in members {
+            let (page, corner) = positions[image];
+            let texture = &textures[image];
+            let origin = corner + padding;
+            let extent = extents[page].as_vec2();
+            let offset = origin.as_vec2() / extent;
+            let scale = Vec2::new(texture.width() as f32, texture.height() as f32) / extent;
+
+            let handle = MaterialHandle::from(index);
+            for mesh in self.meshes.iter_mut().filter(|mesh| mesh.material == handle) {
+                for vertex in &mut mesh.vertices {
+                    vertex.tex_coord = offset + vertex.tex_coord.clamp(Vec2::ZERO, Vec2::ONE) * scale;
+                }
+            }
+            self.materials[index].base_color_texture = Some(pages[page].clone());
+            report.placements.push(AtlasPlacement {
+                material: handle,
+                page,
+                x: origin.x,
+                y: origin.y,
+                width: texture.width(),
+                height: texture.height()
+            });
+        }
+        report.page_sizes = extents;
+        report
+    }
+}
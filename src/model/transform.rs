@@ -0,0 +1,130 @@
+use glam::*;
+use crate::model::loader::{Mesh, Model};
+use crate::model::MeshError;
+
+/*
+Checks that a matrix can be inverted, which is needed to transform normals. Singular matrices
+would turn every normal into NaN.
+*/
+fn check_invertible(matrix: &Mat4) -> Result<f32, MeshError> {
+    let determinant = matrix.determinant();
+    if determinant == 0.0 || !determinant.is_finite() {
+        return Err(MeshError::NonInvertibleMatrix);
+    }
+    Ok(determinant)
+}
+
+impl Mesh {
+    /*
+    Transforms the mesh by `matrix`. Positions are transformed as points, normals by the
+    inverse-transpose of the linear part and renormalized, and tangent directions by the linear
+    part. A matrix with a negative determinant mirrors the mesh, so the tangent handedness is
+    negated to keep the bitangent consistent; the winding is left unchanged. Instance transforms
+    are conjugated by the matrix so every copy moves with the mesh. Non-invertible matrices are
+    rejected without touching the mesh.
+    */
+    pub fn transform(&mut self, matrix: Mat4) -> Result<(), MeshError> {
+        let determinant = check_invertible(&matrix)?;
+
+        let linear = Mat3::from_mat4(matrix);
+        let normal_matrix = linear.inverse().transpose();
+        let handedness = if determinant < 0.0 { -1.0 } else { 1.0 };
+
+        for vertex in &mut self.vertices {
+            vertex.position = matrix.transform_point3(vertex.position);
+            vertex.normal = (normal_matrix * vertex.normal).normalize_or_zero();
+            vertex.tangent = (linear * vertex.tangent.xyz())
+                .normalize_or_zero()
+                .extend(vertex.tangent.w * handedness);
+        }
+
+        let inverse_matrix = matrix.inverse();
+        for instance in &mut self.instances {
+            *instance = matrix * *instance * inverse_matrix;
+        }
+        self.invalidate_bounds();
+        Ok(())
+    }
+}
+
+/*
+Returns a copy of the mesh moved into place by `matrix`, with its instances dropped and its
+bounds recomputed. A mirroring matrix also flips the winding, so the front faces keep facing
+the same side of the surface. Returns `None` for matrices that cannot be inverted.
+*/
+pub(crate) fn baked_copy(mesh: &Mesh, matrix: Mat4) -> Option<Mesh> {
+    let mut copy = Mesh { instances: Vec::new(), ..mesh.clone() };
+    copy.transform(matrix).ok()?;
+    if matrix.determinant() < 0.0 {
+        copy.flip_winding().ok()?;
+    }
+    copy.bounds = Some(copy.aabb());
+    Some(copy)
+}
+
+impl Model {
+    /*
+    Returns a copy of the model with every transform baked into the vertices, so all of them
+    are in world space and no mesh has instances left. An instanced mesh becomes one independent
+    copy per instance, in instance order, and mirrored copies get their winding flipped so their
+    front faces still face outwards. Meshes without a whole triangle and instances scaled to
+    nothing are skipped. Materials are cloned.
+    */
+    pub fn flattened(&self) -> Model {
+        let mut meshes = Vec::new();
+        for mesh in self.meshes.iter().filter(|mesh| !mesh.vertices.is_empty() && mesh.indices.len() >= 3) {
+            if mesh.instances.is_empty() {
+                meshes.push(mesh.clone());
+            } else {
+                meshes.extend(mesh.instances.iter().filter_map(|&instance| baked_copy(mesh, instance)));
+            }
+        }
+
+        Model {
+            meshes,
+            materials: self.materials.clone()
+        }
+    }
+
+    /*
+    Transforms every mesh of the model by `matrix`, see `Mesh::transform`.
+    */
+    pub fn transform(&mut self, matrix: Mat4) -> Result<(), MeshError> {
+        check_invertible(&matrix)?;
+        for mesh in &mut self.meshes {
+            mesh.transform(matrix)?;
+        }
+        Ok(())
+    }
+
+    /*
+    Centers the model's bounding box on the origin and uniformly scales it so its largest
+    extent becomes 1.0, then returns the matrix that was applied so callers can invert it
+    later. A degenerate model whose points all coincide is only centered, and an empty model is
+    left as it is with the identity returned.
+    */
+    pub fn normalize_to_unit(&mut self) -> Mat4 {
+        let aabb = self.aabb();
+        if aabb.is_empty() {
+            return Mat4::IDENTITY;
+        }
+
+        let largest = aabb.extent().max_element();
+        let scale = if largest > 0.0 && largest.is_finite() { 1.0 / largest } else { 1.0 };
+        let matrix = Mat4::from_scale(Vec3::splat(scale)) * Mat4::from_translation(-aabb.center());
+
+        match self.transform(matrix) {
+            Ok(()) => matrix,
+            Err(_) => Mat4::IDENTITY
+        }
+    }
+
+    /*
+    Uniformly scales the model so the largest side of its bounding box becomes 1.0 and moves
+    the center of the box to the origin. Returns the applied scale factor; a model without
+    extent is only recentered and reports a factor of 1.0.
+    */
+    pub fn scale_to_unit_box(&mut self) -> f32 {
+        self.normalize_to_unit().x_axis.x
+    }
+}
@@ -0,0 +1,69 @@
+use glam::*;
+use crate::model::loader::{Mesh, Vertex};
+use crate::model::normals::face_normal;
+
+/*
+The `Triangle` struct is one triangle of a mesh as yielded by `Mesh::triangles`: its position
+among the triangles of the index buffer, the indices of its corners and the vertices they refer
+to. The normal, area and centroid are computed when asked for.
+*/
+#[derive(Clone, Copy, Debug)]
+pub struct Triangle<'a> {
+    pub index: usize,
+    pub indices: [u32; 3],
+    pub vertices: [&'a Vertex; 3]
+}
+
+impl Triangle<'_> {
+    pub fn positions(&self) -> [Vec3; 3] {
+        self.vertices.map(|vertex| vertex.position)
+    }
+
+    /*
+    Returns the unit normal of the triangle's plane, facing the side from which its corners are
+    seen counter-clockwise, or zero for a triangle without area.
+    */
+    pub fn normal(&self) -> Vec3 {
+        let [a, b, c] = self.positions();
+        face_normal(a, b, c).normalize_or_zero()
+    }
+
+    pub fn area(&self) -> f32 {
+        let [a, b, c] = self.positions();
+        face_normal(a, b, c).length() * 0.5
+    }
+
+    pub fn centroid(&self) -> Vec3 {
+        let [a, b, c] = self.positions();
+        (a + b + c) / 3.0
+    }
+}
+
+impl Mesh {
+    /*
+    Iterates over the triangles of the mesh in index order, whatever the index storage. Trailing
+    indices that do not make a whole triangle and triangles with out-of-range indices are
+    skipped, and `Triangle::index` keeps counting them so it always matches the position in the
+    index buffer.
+    */
+    pub fn triangles(&self) -> impl Iterator<Item = Triangle<'_>> + '_ {
+        self.indices
+            .triangles()
+            .enumerate()
+            .filter(|(_, indices)| indices.iter().all(|&index| (index as usize) < self.vertices.len()))
+            .map(|(index, indices)| Triangle {
+                index,
+                indices,
+                vertices: indices.map(|vertex| &self.vertices[vertex as usize])
+            })
+    }
+
+    /*
+    Lists the same triangles as `triangles`, as their position in the index buffer and their
+    corner indices only. The list does not borrow the mesh, so a loop over it can edit the
+    vertices or rewrite triangles through `Indices::set`.
+    */
+    pub fn triangle_indices(&self) -> Vec<(usize, [u32; 3])> {
+        self.triangles().map(|triangle| (triangle.index, triangle.indices)).collect()
+    }
+}
@@ -0,0 +1,117 @@
+use glam::*;
+
+/*
+Returns the polygon's normal by Newell's method, which stays accurate for concave and slightly
+non-planar polygons. Its length is twice the polygon's area.
+*/
+fn newell_normal(points: &[Vec3]) -> Vec3 {
+    points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(a, b)| Vec3::new(
+            (a.y - b.y) * (a.z + b.z),
+            (a.z - b.z) * (a.x + b.x),
+            (a.x - b.x) * (a.y + b.y)
+        ))
+        .sum()
+}
+
+fn cross(a: Vec2, b: Vec2, c: Vec2) -> f32 {
+    (b - a).perp_dot(c - a)
+}
+
+/*
+Tells whether `point` lies inside or on the edges of the counter-clockwise triangle `a b c`.
+*/
+fn in_triangle(point: Vec2, a: Vec2, b: Vec2, c: Vec2, epsilon: f32) -> bool {
+    cross(a, b, point) >= -epsilon && cross(b, c, point) >= -epsilon && cross(c, a, point) >= -epsilon
+}
+
+/*
+Splits a simple polygon into triangles by ear clipping, returning indices into `points`. The
+polygon is projected onto the plane given by its Newell normal, so concave and slightly
+non-planar outlines are handled, and the triangles keep the polygon's winding. Repeated and
+collinear points are skipped rather than producing slivers. Degenerate polygons, with fewer
+than three distinct points or no area, produce no triangles. Self-intersecting outlines are
+still fully triangulated, though not necessarily without overlaps.
+*/
+pub fn triangulate_polygon(points: &[Vec3]) -> Vec<[u32; 3]> {
+    if points.len() < 3 {
+        return Vec::new();
+    }
+
+    let normal = newell_normal(points);
+    if !normal.is_finite() || normal.length_squared() == 0.0 {
+        return Vec::new();
+    }
+    let normal = normal.normalize();
+    let right = normal.any_orthonormal_vector();
+    let up = normal.cross(right);
+    let projected: Vec<Vec2> = points
+        .iter()
+        .map(|point| Vec2::new(point.dot(right), point.dot(up)))
+        .collect();
+
+    let extent = projected
+        .iter()
+        .fold(Vec2::splat(f32::NEG_INFINITY), |max, point| max.max(*point))
+        - projected.iter().fold(Vec2::splat(f32::INFINITY), |min, point| min.min(*point));
+    let epsilon = extent.length_squared() * 1e-7;
+
+    let mut remaining: Vec<usize> = Vec::with_capacity(points.len());
+    for index in 0..points.len() {
+        if remaining.last().is_none_or(|&last| projected[last] != projected[index]) {
+            remaining.push(index);
+        }
+    }
+    while remaining.len() > 1 && projected[remaining[0]] == projected[*remaining.last().unwrap()] {
+        remaining.pop();
+    }
+
+    let mut triangles = Vec::with_capacity(points.len().saturating_sub(2));
+    while remaining.len() >= 3 {
+        let count = remaining.len();
+        let corner = |position: usize| {
+            let previous = remaining[(position + count - 1) % count];
+            let next = remaining[(position + 1) % count];
+            (previous, remaining[position], next)
+        };
+
+        let collinear = (0..count).find(|&position| {
+            let (a, b, c) = corner(position);
+            cross(projected[a], projected[b], projected[c]).abs() <= epsilon
+        });
+        if let Some(position) = collinear {
+            remaining.remove(position);
+            continue;
+        }
+
+        let is_ear = |position: usize| {
+            let (a, b, c) = corner(position);
+            let (pa, pb, pc) = (projected[a], projected[b], projected[c]);
+            cross(pa, pb, pc) > 0.0
+                && remaining.iter().all(|&other| {
+                    let point = projected[other];
+                    point == pa || point == pb || point == pc || !in_triangle(point, pa, pb, pc, epsilon)
+                })
+        };
+
+        let position = (0..count)
+            .find(|&position| is_ear(position))
+            .or_else(|| {
+                (0..count).max_by(|&x, &y| {
+                    let area = |position: usize| {
+                        let (a, b, c) = corner(position);
+                        cross(projected[a], projected[b], projected[c])
+                    };
+                    area(x).total_cmp(&area(y))
+                })
+            })
+            .unwrap();
+
+        let (a, b, c) = corner(position);
+        triangles.push([a as u32, b as u32, c as u32]);
+        remaining.remove(position);
+    }
+    triangles
+}
@@ -0,0 +1,176 @@
+use glam::*;
+use std::collections::{HashMap, VecDeque};
+use crate::model::loader::Mesh;
+use crate::model::normals::face_normal;
+
+/*
+Neighbouring triangles join the same chart while their normal stays within about 45 degrees of
+the normal of the triangle the chart started from, so no triangle folds over when the chart is
+flattened onto that direction.
+*/
+const CHART_NORMAL_COS: f32 = 0.7;
+
+/*
+Gap left around every chart in the atlas, relative to the square root of the area the charts
+cover, so bilinear filtering and mipmaps do not bleed between neighbouring charts.
+*/
+const CHART_PADDING: f32 = 0.01;
+
+/*
+One group of connected triangles flattened together: the triangles in it, the directions its
+positions are projected onto and the rectangle those projections cover.
+*/
+struct Chart {
+    triangles: Vec<usize>,
+    right: Vec3,
+    up: Vec3,
+    min: Vec2,
+    size: Vec2
+}
+
+/*
+Groups the triangles into charts by growing each chart from its first triangle across shared
+edges, compared by position so vertices already split along seams do not stop the growth.
+Zero-area triangles join any chart they touch.
+*/
+fn build_charts(mesh: &Mesh, triangles: &[usize]) -> Vec<Chart> {
+    let corners = |triangle: usize| mesh.indices.triangle(triangle).map(|index| mesh.vertices[index as usize].position);
+    let mut normals = vec![Vec3::ZERO; mesh.indices.len() / 3];
+    for &triangle in triangles {
+        let [a, b, c] = corners(triangle);
+        normals[triangle] = face_normal(a, b, c).normalize_or_zero();
+    }
+
+    let key = |position: Vec3| position.to_array().map(f32::to_bits);
+    let mut edges: HashMap<([u32; 3], [u32; 3]), Vec<usize>> = HashMap::new();
+    for &triangle in triangles {
+        let points = corners(triangle).map(key);
+        for corner in 0..3 {
+            let (a, b) = (points[corner], points[(corner + 1) % 3]);
+            edges.entry((a.min(b), a.max(b))).or_default().push(triangle);
+        }
+    }
+
+    let mut charted = vec![false; mesh.indices.len() / 3];
+    let mut charts = Vec::new();
+    for &seed in triangles {
+        if charted[seed] {
+            continue;
+        }
+        charted[seed] = true;
+        let normal = normals[seed];
+        let mut members = vec![seed];
+        let mut queue = VecDeque::from([seed]);
+
+        while let Some(triangle) = queue.pop_front() {
+            let points = corners(triangle).map(key);
+            for corner in 0..3 {
+                let (a, b) = (points[corner], points[(corner + 1) % 3]);
+                for &neighbour in &edges[&(a.min(b), a.max(b))] {
+                    let neighbour_normal = normals[neighbour];
+                    if !charted[neighbour]
+                        && (neighbour_normal == Vec3::ZERO || neighbour_normal.dot(normal) >= CHART_NORMAL_COS)
+                    {
+                        charted[neighbour] = true;
+                        members.push(neighbour);
+                        queue.push_back(neighbour);
+                    }
+                }
+            }
+        }
+
+        let (right, up) = if normal == Vec3::ZERO { (Vec3::X, Vec3::Y) } else { normal.any_orthonormal_pair() };
+        let projected = members
+            .iter()
+            .flat_map(|&triangle| corners(triangle))
+            .map(|position| Vec2::new(position.dot(right), position.dot(up)));
+        let (min, max) = projected.fold((Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY)), |(min, max), point| {
+            (min.min(point), max.max(point))
+        });
+        charts.push(Chart { triangles: members, right, up, min, size: max - min });
+    }
+    charts
+}
+
+/*
+Places the charts on shelves, tallest first, in a square of roughly the charts' total area, and
+returns the offset of each chart along with the side of the square that holds them all.
+*/
+fn pack_charts(charts: &[Chart], padding: f32) -> (Vec<Vec2>, f32) {
+    let cell = |chart: &Chart| chart.size + Vec2::splat(padding);
+    let total_area: f32 = charts.iter().map(|chart| cell(chart).x * cell(chart).y).sum();
+    let widest = charts.iter().map(|chart| cell(chart).x).fold(0.0, f32::max);
+    let shelf_width = total_area.sqrt().max(widest);
+
+    let mut order: Vec<usize> = (0..charts.len()).collect();
+    order.sort_by(|&a, &b| charts[b].size.y.total_cmp(&charts[a].size.y));
+
+    let mut offsets = vec![Vec2::ZERO; charts.len()];
+    let mut cursor = Vec2::ZERO;
+    let mut shelf_height = 0.0f32;
+    let mut extent = Vec2::ZERO;
+    for chart in order {
+        let size = cell(&charts[chart]);
+        if cursor.x > 0.0 && cursor.x + size.x > shelf_width {
+            cursor = Vec2::new(0.0, cursor.y + shelf_height);
+            shelf_height = 0.0;
+        }
+        offsets[chart] = cursor + Vec2::splat(padding);
+        cursor.x += size.x;
+        shelf_height = shelf_height.max(size.y);
+        extent = extent.max(cursor + Vec2::new(0.0, size.y));
+    }
+    (offsets, extent.max_element() + padding)
+}
+
+/*
+Generates texture coordinates for a mesh that has none, such as a scan or an STL import, so it
+can be textured or lightmapped. Triangles are grouped into charts of connected, similarly
+facing triangles, each chart is flattened along its first triangle's normal and the charts are
+packed side by side into [0, 1] with a small gap between them, all at the same scale. Vertices
+shared by several charts get one copy per chart; indices of triangles with out-of-range
+indices and trailing indices are left as they are. Charts of strongly curved surfaces that
+wrap back onto themselves, like a helix, may still overlap.
+*/
+pub fn generate_uvs(mesh: &mut Mesh) {
+    let vertex_count = mesh.vertices.len();
+    let triangles: Vec<usize> = mesh
+        .indices
+        .triangles()
+        .enumerate()
+        .filter(|(_, indices)| indices.iter().all(|&index| (index as usize) < vertex_count))
+        .map(|(triangle, _)| triangle)
+        .collect();
+
+    let charts = build_charts(mesh, &triangles);
+    let chart_area: f32 = charts.iter().map(|chart| chart.size.x * chart.size.y).sum();
+    let (offsets, side) = pack_charts(&charts, chart_area.sqrt() * CHART_PADDING);
+    let scale = if side > 0.0 { 1.0 / side } else { 0.0 };
+
+    let mut assigned: Vec<Option<usize>> = vec![None; vertex_count];
+    let mut copies: HashMap<(u32, usize), u32> = HashMap::new();
+    for (chart_index, chart) in charts.iter().enumerate() {
+        for &triangle in &chart.triangles {
+            for corner in 0..3 {
+                let original = mesh.indices.triangle(triangle)[corner];
+                let index = match assigned[original as usize] {
+                    None => {
+                        assigned[original as usize] = Some(chart_index);
+                        original
+                    },
+                    Some(owner) if owner == chart_index => original,
+                    Some(_) => *copies.entry((original, chart_index)).or_insert_with(|| {
+                        mesh.vertices.push(mesh.vertices[original as usize]);
+                        mesh.vertices.len() as u32 - 1
+                    })
+                };
+                mesh.indices.set(triangle * 3 + corner, index);
+
+                let position = mesh.vertices[index as usize].position;
+                let projected = Vec2::new(position.dot(chart.right), position.dot(chart.up));
+                let tex_coord = (offsets[chart_index] + projected - chart.min) * scale;
+                mesh.vertices[index as usize].tex_coord = tex_coord.clamp(Vec2::ZERO, Vec2::ONE);
+            }
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use glam::*;
+use std::collections::HashMap;
+use std::f32::consts::TAU;
+use crate::model::loader::Mesh;
+use crate::model::normals::face_normal;
+use crate::model::Aabb;
+
+/*
+The `UvProjection` enum selects how `Mesh::project_uvs` generates texture coordinates.
+`Planar` projects every vertex along `axis`, `Box` projects each triangle along the axis its
+face normal is closest to, and `Spherical` wraps the texture around the mesh's centre by
+longitude and latitude.
+*/
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UvProjection {
+    Planar { axis: Vec3 },
+    Box,
+    Spherical
+}
+
+/*
+Returns the directions in which U grows and V shrinks when looking at the surface against
+`axis`, so the projected texture appears upright and unmirrored. Y is up unless the axis is
+close to it, in which case -Z is.
+*/
+fn projection_basis(axis: Vec3) -> (Vec3, Vec3) {
+    let axis = axis.normalize_or_zero();
+    let up = if axis.y.abs() > 0.99 { Vec3::NEG_Z * axis.y.signum() } else { Vec3::Y };
+    let right = up.cross(axis).normalize_or_zero();
+    (right, axis.cross(right))
+}
+
+/*
+Returns the index of the component with the largest magnitude.
+*/
+fn dominant_axis(vector: Vec3) -> usize {
+    let vector = vector.abs();
+    if vector.x >= vector.y && vector.x >= vector.z {
+        0
+    } else if vector.y >= vector.z {
+        1
+    } else {
+        2
+    }
+}
+
+/*
+Maps a coordinate into [0, 1] over a range, collapsing empty ranges to 0.
+*/
+fn normalize(value: f32, min: f32, max: f32) -> f32 {
+    if max > min { (value - min) / (max - min) } else { 0.0 }
+}
+
+impl Mesh {
+    /*
+    Replaces the texture coordinates with ones generated from the vertex positions, normalized
+    into [0, 1] over the mesh's extent. `Box` gives vertices shared by triangles projected along
+    different axes one copy per axis, and `Spherical` duplicates the vertices of triangles
+    crossing the seam where U wraps around, so neither smears the texture; the seam copies
+    carry U values above 1 so a repeating texture continues across the seam. Only those copies
+    are added; indices of other triangles and trailing indices are left as they are.
+    */
+    pub fn project_uvs(&mut self, projection: UvProjection) {
+        match projection {
+            UvProjection::Planar { axis } => self.project_planar(axis),
+            UvProjection::Box => self.project_box(),
+            UvProjection::Spherical => self.project_spherical()
+        }
+    }
+
+    fn project_planar(&mut self, axis: Vec3) {
+        let (right, up) = projection_basis(axis);
+        let projected: Vec<Vec2> = self
+            .vertices
+            .iter()
+            .map(|vertex| Vec2::new(vertex.position.dot(right), vertex.position.dot(up)))
+            .collect();
+        let min = projected.iter().copied().fold(Vec2::splat(f32::INFINITY), Vec2::min);
+        let max = projected.iter().copied().fold(Vec2::splat(f32::NEG_INFINITY), Vec2::max);
+
+        for (vertex, point) in self.vertices.iter_mut().zip(projected) {
+            vertex.tex_coord = Vec2::new(normalize(point.x, min.x, max.x), 1.0 - normalize(point.y, min.y, max.y));
+        }
+    }
+
+    fn project_box(&mut self) {
+        let bounds = Aabb::from_points(self.vertices.iter().map(|vertex| vertex.position));
+        let vertex_count = self.vertices.len();
+        let mut copies: HashMap<(u32, usize), u32> = HashMap::new();
+        let mut assigned: Vec<Option<usize>> = vec![None; vertex_count];
+
+        let mut indices = self.indices.to_u32();
+        for triangle in indices.chunks_exact_mut(3) {
+            if triangle.iter().any(|&index| index as usize >= vertex_count) {
+                continue;
+            }
+            let [a, b, c] = [0, 1, 2].map(|corner| self.vertices[triangle[corner] as usize].position);
+            let normal = face_normal(a, b, c);
+            let axis = dominant_axis(normal);
+            let sign = if normal[axis] < 0.0 { -1.0 } else { 1.0 };
+            let face = axis * 2 + (sign < 0.0) as usize;
+            let (right, up) = projection_basis(Vec3::AXES[axis] * sign);
+
+            for index in triangle.iter_mut() {
+                let original = *index;
+                *index = match assigned[original as usize] {
+                    None => {
+                        assigned[original as usize] = Some(face);
+                        original
+                    },
+                    Some(owner) if owner == face => original,
+                    Some(_) => *copies.entry((original, face)).or_insert_with(|| {
+                        self.vertices.push(self.vertices[original as usize]);
+                        self.vertices.len() as u32 - 1
+                    })
+                };
+
+                let position = self.vertices[*index as usize].position;
+                let along = |direction: Vec3| {
+                    let component = dominant_axis(direction);
+                    let t = normalize(position[component], bounds.min[component], bounds.max[component]);
+                    if direction[component] < 0.0 { 1.0 - t } else { t }
+                };
+                self.vertices[*index as usize].tex_coord = Vec2::new(along(right), 1.0 - along(up));
+            }
+        }
+        self.indices.replace(indices);
+    }
+
+    fn project_spherical(&mut self) {
+        let center = Aabb::from_points(self.vertices.iter().map(|vertex| vertex.position)).center();
+        for vertex in &mut self.vertices {
+            let direction = (vertex.position - center).normalize_or_zero();
+            let u = 0.5 - direction.z.atan2(direction.x) / TAU;
+            let v = direction.y.clamp(-1.0, 1.0).acos() / std::f32::consts::PI;
+            vertex.tex_coord = Vec2::new(u.clamp(0.0, 1.0), v);
+        }
+
+        let vertex_count = self.vertices.len();
+        let mut wrapped: HashMap<u32, u32> = HashMap::new();
+        let mut indices = self.indices.to_u32();
+        for triangle in indices.chunks_exact_mut(3) {
+            if triangle.iter().any(|&index| index as usize >= vertex_count) {
+                continue;
+            }
+            let us = [0, 1, 2].map(|corner| self.vertices[triangle[corner] as usize].tex_coord.x);
+            let (min, max) = (us.iter().copied().fold(1.0, f32::min), us.iter().copied().fold(0.0, f32::max));
+            if max - min <= 0.5 {
+                continue;
+            }
+
+            for index in triangle.iter_mut() {
+                if self.vertices[*index as usize].tex_coord.x < 0.5 {
+                    let original = *index;
+                    *index = *wrapped.entry(original).or_insert_with(|| {
+                        let mut copy = self.vertices[original as usize];
+                        copy.tex_coord.x += 1.0;
+                        self.vertices.push(copy);
+                        self.vertices.len() as u32 - 1
+                    });
+                }
+            }
+        }
+        self.indices.replace(indices);
+    }
+}
@@ -0,0 +1,244 @@
+use glam::*;
+use std::fmt;
+use crate::model::loader::{Mesh, Model};
+
+/*
+The `Severity` enum ranks a validation issue. Errors are data that breaks rendering or makes
+later processing read out of bounds, warnings are data that is legal but most likely wrong.
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error
+}
+
+/*
+The `IssueKind` enum lists the problems `Mesh::validate` looks for. The elements of an issue are
+vertex indices for the vertex checks and `BoundsMismatch`, triangle indices for the triangle
+checks, positions in `indices` for `IncompleteTriangle` and the material index for
+`MaterialOutOfRange`; `EmptyMesh` has none.
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IssueKind {
+    EmptyMesh,
+    IncompleteTriangle,
+    IndexOutOfRange,
+    NonFinitePosition,
+    NonFiniteAttribute,
+    ZeroNormal,
+    UnnormalizedNormal,
+    UvOutOfRange,
+    DuplicateIndex,
+    DegenerateTriangle,
+    MaterialOutOfRange,
+    BoundsMismatch
+}
+
+impl IssueKind {
+    pub fn severity(&self) -> Severity {
+        match self {
+            IssueKind::IncompleteTriangle
+            | IssueKind::IndexOutOfRange
+            | IssueKind::NonFinitePosition
+            | IssueKind::NonFiniteAttribute
+            | IssueKind::MaterialOutOfRange
+            | IssueKind::BoundsMismatch => Severity::Error,
+            _ => Severity::Warning
+        }
+    }
+}
+
+/*
+The `ValidationIssue` struct records one kind of problem found in a mesh, with every offending
+element. `mesh` is the index of the mesh within its model, or `None` when the issue comes from
+`Mesh::validate` on a standalone mesh.
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    pub kind: IssueKind,
+    pub mesh: Option<usize>,
+    pub elements: Vec<usize>
+}
+
+/*
+The `ValidationReport` struct collects the issues found by a validation pass, in mesh order.
+*/
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ValidationReport {
+    pub issues: Vec<ValidationIssue>
+}
+
+impl ValidationReport {
+    /*
+    Tells whether no error was found. Warnings do not make a report invalid.
+    */
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues.iter().filter(|issue| issue.severity == Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues.iter().filter(|issue| issue.severity == Severity::Warning)
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for issue in &self.issues {
+            match issue.mesh {
+                Some(mesh) => write!(f, "{:?}: mesh {}: {:?}", issue.severity, mesh, issue.kind)?,
+                None => write!(f, "{:?}: {:?}", issue.severity, issue.kind)?
+            }
+            writeln!(f, " ({} elements: {:?})", issue.elements.len(), issue.elements)?;
+        }
+        Ok(())
+    }
+}
+
+/*
+The `ValidationOptions` struct holds the thresholds of a validation pass. Normals whose length
+differs from 1.0 by more than `normal_tolerance` are reported as unnormalized, and texture
+coordinates with a component outside `uv_min..=uv_max` as out of range. Positions may stick out
+of the cached bounds by `bounds_tolerance` times the largest extent of the box, since exporters
+often round the min and max they declare. The defaults allow 0.01 of normal length error,
+texture coordinates within [-16, 16], which leaves room for tiling, and 0.0001 of the box size.
+*/
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ValidationOptions {
+    pub normal_tolerance: f32,
+    pub uv_min: f32,
+    pub uv_max: f32,
+    pub bounds_tolerance: f32
+}
+
+impl Default for ValidationOptions {
+    fn default() -> Self {
+        ValidationOptions {
+            normal_tolerance: 0.01,
+            uv_min: -16.0,
+            uv_max: 16.0,
+            bounds_tolerance: 1e-4
+        }
+    }
+}
+
+impl Mesh {
+    /*
+    Checks the mesh for out-of-range or incomplete indices, non-finite attributes, zero-length
+    or unnormalized normals, texture coordinates out of range, positions outside the cached
+    `bounds`, which the loaders take from the min and max declared on the position accessor,
+    triangles repeating an index, zero-area triangles and emptiness, using the default options.
+    Each kind of problem is reported once with all its offending elements.
+    */
+    pub fn validate(&self) -> ValidationReport {
+        self.validate_with(&ValidationOptions::default())
+    }
+
+    /*
+    Same as `validate`, with the thresholds taken from `options`.
+    */
+    pub fn validate_with(&self, options: &ValidationOptions) -> ValidationReport {
+        let mut found: Vec<(IssueKind, Vec<usize>)> = Vec::new();
+        if self.vertices.is_empty() || self.indices.is_empty() {
+            found.push((IssueKind::EmptyMesh, Vec::new()));
+        }
+        let mut report = |kind: IssueKind, elements: Vec<usize>| {
+            if !elements.is_empty() {
+                found.push((kind, elements));
+            }
+        };
+
+        let complete = self.indices.len() / 3 * 3;
+        report(IssueKind::IncompleteTriangle, (complete..self.indices.len()).collect());
+
+        let vertex_ids = |check: &dyn Fn(usize) -> bool| (0..self.vertices.len()).filter(|&i| check(i)).collect();
+        let vertices = &self.vertices;
+        report(IssueKind::NonFinitePosition, vertex_ids(&|i| !vertices[i].position.is_finite()));
+        report(IssueKind::NonFiniteAttribute, vertex_ids(&|i| {
+            let vertex = &vertices[i];
+            !vertex.normal.is_finite() || !vertex.tex_coord.is_finite() || !vertex.tangent.is_finite()
+        }));
+        report(IssueKind::ZeroNormal, vertex_ids(&|i| vertices[i].normal.length_squared() == 0.0));
+        report(IssueKind::UnnormalizedNormal, vertex_ids(&|i| {
+            let length = vertices[i].normal.length();
+            length != 0.0 && (length - 1.0).abs() > options.normal_tolerance
+        }));
+        report(IssueKind::UvOutOfRange, vertex_ids(&|i| {
+            let tex_coord = vertices[i].tex_coord;
+            tex_coord.is_finite()
+                && (tex_coord.min_element() < options.uv_min || tex_coord.max_element() > options.uv_max)
+        }));
+        if let Some(bounds) = self.bounds.filter(|bounds| !bounds.is_empty()) {
+            let tolerance = Vec3::splat((bounds.max - bounds.min).max_element() * options.bounds_tolerance);
+            let (min, max) = (bounds.min - tolerance, bounds.max + tolerance);
+            report(IssueKind::BoundsMismatch, vertex_ids(&|i| {
+                let position = vertices[i].position;
+                position.is_finite() && (position.cmplt(min).any() || position.cmpgt(max).any())
+            }));
+        }
+
+        let mut out_of_range = Vec::new();
+        let mut duplicates = Vec::new();
+        let mut degenerate = Vec::new();
+        for (triangle, indices) in self.indices.triangles().enumerate() {
+            let [a, b, c] = indices.map(|index| index as usize);
+            if [a, b, c].iter().any(|&index| index >= self.vertices.len()) {
+                out_of_range.push(triangle);
+            } else if a == b || b == c || a == c {
+                duplicates.push(triangle);
+            } else {
+                let [pa, pb, pc] = [a, b, c].map(|index| self.vertices[index].position);
+                let (ab, ac) = (pb - pa, pc - pa);
+                if ab.cross(ac).length() <= f32::EPSILON * ab.length() * ac.length() {
+                    degenerate.push(triangle);
+                }
+            }
+        }
+        report(IssueKind::IndexOutOfRange, out_of_range);
+        report(IssueKind::DuplicateIndex, duplicates);
+        report(IssueKind::DegenerateTriangle, degenerate);
+
+        ValidationReport {
+            issues: found
+                .into_iter()
+                .map(|(kind, elements)| ValidationIssue { severity: kind.severity(), kind, mesh: None, elements })
+                .collect()
+        }
+    }
+}
+
+impl Model {
+    /*
+    Validates every mesh as `Mesh::validate` does and also reports meshes whose material index
+    has no material, tagging each issue with its mesh index.
+    */
+    pub fn validate(&self) -> ValidationReport {
+        self.validate_with(&ValidationOptions::default())
+    }
+
+    /*
+    Same as `validate`, with the thresholds taken from `options`.
+    */
+    pub fn validate_with(&self, options: &ValidationOptions) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        for (mesh_index, mesh) in self.meshes.iter().enumerate() {
+            if self.material(mesh.material).is_none() {
+                report.issues.push(ValidationIssue {
+                    severity: Severity::Error,
+                    kind: IssueKind::MaterialOutOfRange,
+                    mesh: Some(mesh_index),
+                    elements: vec![mesh.material.index()]
+                });
+            }
+            for mut issue in mesh.validate_with(options).issues {
+                issue.mesh = Some(mesh_index);
+                report.issues.push(issue);
+            }
+        }
+        report
+    }
+}
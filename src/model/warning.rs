@@ -0,0 +1,69 @@
+use std::fmt;
+
+/*
+The `PrimitiveLocation` struct points at a primitive of the glTF document: the index of its
+mesh, the name of that mesh if it has one, and the index of the primitive within the mesh.
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrimitiveLocation {
+    pub mesh: usize,
+    pub mesh_name: Option<String>,
+    pub primitive: usize
+}
+
+impl PrimitiveLocation {
+    pub(crate) fn new(mesh: &gltf::Mesh, primitive: &gltf::Primitive) -> Self {
+        PrimitiveLocation {
+            mesh: mesh.index(),
+            mesh_name: mesh.name().map(str::to_string),
+            primitive: primitive.index()
+        }
+    }
+}
+
+impl fmt::Display for PrimitiveLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.mesh_name {
+            Some(name) => write!(f, "primitive {} of mesh {} (\"{}\")", self.primitive, self.mesh, name),
+            None => write!(f, "primitive {} of mesh {}", self.primitive, self.mesh)
+        }
+    }
+}
+
+/*
+The `LoadWarning` enum describes a decision the loader made on its own while building a model,
+none of which stops the load:
+
+- `UnsupportedPrimitiveMode` reports a primitive left out because it is not made of triangles.
+- `DefaultMaterial` reports a primitive without a material, given glTF's default material,
+  which comes after the materials of the document.
+- `MissingAttribute` reports a primitive without normals, texture coordinates or tangents,
+  whose vertices are given zeros instead. Normals are generated afterwards when the options
+  ask for it.
+- `ShortAttribute` reports an attribute holding fewer values than the primitive has vertices,
+  or none the loader can read, the rest being zeros.
+- `ImageDecodeFailed` reports an image that could not be read or decoded, such as a missing
+  or truncated file, with the reason, leaving the texture slots using it empty, or magenta with
+  `LoaderOptions::placeholder_textures`.
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub enum LoadWarning {
+    UnsupportedPrimitiveMode { location: PrimitiveLocation, mode: gltf::mesh::Mode },
+    DefaultMaterial { location: PrimitiveLocation },
+    MissingAttribute { location: PrimitiveLocation, semantic: &'static str },
+    ShortAttribute { location: PrimitiveLocation, semantic: &'static str, count: usize, expected: usize },
+    ImageDecodeFailed { image: usize, name: Option<String>, reason: String }
+}
+
+impl fmt::Display for LoadWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadWarning::UnsupportedPrimitiveMode { location, mode } => write!(f, "Skipped {}. (Mode {:?} is not supported)", location, mode),
+            LoadWarning::DefaultMaterial { location } => write!(f, "Used the default material for {}. (Primitive has no material)", location),
+            LoadWarning::MissingAttribute { location, semantic } => write!(f, "Used zeros for {} of {}. (Attribute is missing)", semantic, location),
+            LoadWarning::ShortAttribute { location, semantic, count, expected } => write!(f, "Padded {} of {} with zeros. (Attribute has {} of {} values)", semantic, location, count, expected),
+            LoadWarning::ImageDecodeFailed { image, name: Some(name), reason } => write!(f, "Left textures of image {} (\"{}\") empty. ({})", image, name, reason),
+            LoadWarning::ImageDecodeFailed { image, name: None, reason } => write!(f, "Left textures of image {} empty. ({})", image, reason)
+        }
+    }
+}
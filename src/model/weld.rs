@@ -0,0 +1,120 @@
+use glam::*;
+use std::collections::HashMap;
+use crate::model::loader::{Mesh, Vertex};
+
+/*
+Largest difference allowed between the normals and texture coordinates of two vertices welded
+with `compare_attributes` enabled and a non-zero position tolerance.
+*/
+const NORMAL_TOLERANCE: f32 = 1e-3;
+const TEX_COORD_TOLERANCE: f32 = 1e-5;
+
+fn attributes_match(a: &Vertex, b: &Vertex) -> bool {
+    a.normal.distance_squared(b.normal) <= NORMAL_TOLERANCE * NORMAL_TOLERANCE
+        && a.tex_coord.distance_squared(b.tex_coord) <= TEX_COORD_TOLERANCE * TEX_COORD_TOLERANCE
+}
+
+/*
+Returns the raw bits of a vertex, used as the key for exact welding. Positions are always
+part of the key; the other attributes only when they must match too.
+*/
+fn vertex_key(vertex: &Vertex, compare_attributes: bool) -> [u32; 8] {
+    let mut key = [0; 8];
+    for (bits, value) in key.iter_mut().zip(vertex.position.to_array()) {
+        *bits = value.to_bits();
+    }
+    if compare_attributes {
+        let attributes = vertex.normal.to_array().into_iter().chain(vertex.tex_coord.to_array());
+        for (bits, value) in key[3..].iter_mut().zip(attributes) {
+            *bits = value.to_bits();
+        }
+    }
+    key
+}
+
+impl Mesh {
+    /*
+    Merges vertices whose positions lie within `position_epsilon` of each other and rewrites the
+    indices to the surviving vertex, which is the first one encountered. With
+    `compare_attributes` the normals and texture coordinates must also match within their own
+    tolerances. An epsilon of 0.0 welds only bitwise-identical vertices. Candidates are found
+    through a spatial hash with cells as wide as the epsilon, so only the 27 surrounding cells
+    are searched for each vertex; the survivors keep their original order, so the result does
+    not depend on the hash. Returns the number of vertices removed.
+    */
+    pub fn weld_vertices(&mut self, position_epsilon: f32, compare_attributes: bool) -> usize {
+        let (remap, welded) = if position_epsilon > 0.0 {
+            self.weld_within(position_epsilon, compare_attributes)
+        } else {
+            self.weld_exact(compare_attributes)
+        };
+
+        let removed = self.vertices.len() - welded.len();
+        self.indices.remap(|index| remap.get(index as usize).copied().unwrap_or(index));
+        self.vertices = welded;
+        if removed > 0 {
+            self.invalidate_bounds();
+        }
+        removed
+    }
+
+    fn weld_exact(&self, compare_attributes: bool) -> (Vec<u32>, Vec<Vertex>) {
+        let mut welded: Vec<Vertex> = Vec::new();
+        let mut lookup: HashMap<[u32; 8], u32> = HashMap::with_capacity(self.vertices.len());
+
+        let remap = self
+            .vertices
+            .iter()
+            .map(|vertex| {
+                *lookup.entry(vertex_key(vertex, compare_attributes)).or_insert_with(|| {
+                    welded.push(*vertex);
+                    welded.len() as u32 - 1
+                })
+            })
+            .collect();
+
+        (remap, welded)
+    }
+
+    fn weld_within(&self, epsilon: f32, compare_attributes: bool) -> (Vec<u32>, Vec<Vertex>) {
+        let cell_of = |position: Vec3| -> IVec3 { (position / epsilon).floor().as_ivec3() };
+        let epsilon_squared = epsilon * epsilon;
+
+        let mut welded: Vec<Vertex> = Vec::new();
+        let mut grid: HashMap<IVec3, Vec<u32>> = HashMap::new();
+
+        let remap = self
+            .vertices
+            .iter()
+            .map(|vertex| {
+                let cell = cell_of(vertex.position);
+
+                for z in -1..=1 {
+                    for y in -1..=1 {
+                        for x in -1..=1 {
+                            let candidates = match grid.get(&(cell + IVec3::new(x, y, z))) {
+                                Some(candidates) => candidates,
+                                None => continue
+                            };
+                            for &candidate in candidates {
+                                let other = &welded[candidate as usize];
+                                if other.position.distance_squared(vertex.position) <= epsilon_squared
+                                    && (!compare_attributes || attributes_match(other, vertex))
+                                {
+                                    return candidate;
+                                }
+                            }
+                        }
+                    }
+                }
+
+                welded.push(*vertex);
+                let index = welded.len() as u32 - 1;
+                grid.entry(cell).or_default().push(index);
+                index
+            })
+            .collect();
+
+        (remap, welded)
+    }
+}
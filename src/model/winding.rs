@@ -0,0 +1,43 @@
+use crate::model::loader::Mesh;
+use crate::model::{Indices, MeshError};
+
+impl Mesh {
+    /*
+    Reverses the winding of every triangle by swapping its second and third indices, turning
+    clockwise front faces into counter-clockwise ones and back. The index count must be a
+    multiple of three; otherwise the mesh is left untouched and an error is returned.
+    */
+    pub fn flip_winding(&mut self) -> Result<(), MeshError> {
+        if !self.indices.len().is_multiple_of(3) {
+            return Err(MeshError::IncompleteTriangles(self.indices.len()));
+        }
+
+        match &mut self.indices {
+            Indices::U16(indices) => indices.chunks_exact_mut(3).for_each(|triangle| triangle.swap(1, 2)),
+            Indices::U32(indices) => indices.chunks_exact_mut(3).for_each(|triangle| triangle.swap(1, 2))
+        }
+        Ok(())
+    }
+
+    /*
+    Negates every vertex normal so the shading side of the surface is reversed. The tangent
+    handedness is negated as well, keeping the bitangent derived from the flipped normal
+    pointing the same way.
+    */
+    pub fn flip_normals(&mut self) {
+        for vertex in &mut self.vertices {
+            vertex.normal = -vertex.normal;
+            vertex.tangent.w = -vertex.tangent.w;
+        }
+    }
+
+    /*
+    Turns the mesh inside out by reversing both the winding and the normals, which fixes meshes
+    exported with clockwise winding and inward-facing normals.
+    */
+    pub fn flip(&mut self) -> Result<(), MeshError> {
+        self.flip_winding()?;
+        self.flip_normals();
+        Ok(())
+    }
+}
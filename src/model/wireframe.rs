@@ -0,0 +1,42 @@
+use glam::*;
+use std::collections::HashSet;
+use crate::model::loader::Mesh;
+
+impl Mesh {
+    /*
+    Lists the edges of the triangles as a line list, two indices per edge, for drawing the mesh
+    as a wireframe. An edge shared by several triangles is listed once, in the winding of the
+    first triangle using it, and edges come in the order their triangles do. Edges are compared
+    by index, so vertices split along seams give one edge per copy. Edges joining an index to
+    itself and trailing indices are skipped.
+    */
+    pub fn wireframe_indices(&self) -> Vec<u32> {
+        let mut seen = HashSet::new();
+        let mut lines = Vec::new();
+        for triangle in self.indices.triangles() {
+            for corner in 0..3 {
+                let (a, b) = (triangle[corner], triangle[(corner + 1) % 3]);
+                if a != b && seen.insert((a.min(b), a.max(b))) {
+                    lines.extend_from_slice(&[a, b]);
+                }
+            }
+        }
+        lines
+    }
+}
+
+/*
+Prepares a mesh for single-pass wireframe shading: every triangle gets its own three vertices,
+as `Mesh::to_non_indexed` gives them, and their `barycentric` attributes are set to (1, 0, 0),
+(0, 1, 0) and (0, 0, 1) in corner order, so a shader can tell its distance to the nearest edge
+from the smallest interpolated component. Triangles with out-of-range indices and trailing
+indices are dropped.
+*/
+pub fn add_barycentric_attribute(mesh: &mut Mesh) {
+    *mesh = mesh.to_non_indexed();
+    for triangle in mesh.vertices.chunks_exact_mut(3) {
+        for (vertex, barycentric) in triangle.iter_mut().zip([Vec3::X, Vec3::Y, Vec3::Z]) {
+            vertex.barycentric = barycentric;
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use glam::*;
+
+/*
+The `Camera` struct describes a perspective view: where the eye is, the point it looks at and
+which way is up, along with the vertical field of view in radians and the near and far clipping
+distances.
+*/
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Camera {
+    pub position: Vec3,
+    pub target: Vec3,
+    pub up: Vec3,
+    pub fov_y: f32,
+    pub near: f32,
+    pub far: f32
+}
+
+impl Camera {
+    /*
+    Creates a camera at `position` looking at `target` with +Y up, a 60 degree vertical field
+    of view and clipping distances of 0.01 and 1000.
+    */
+    pub fn look_at(position: Vec3, target: Vec3) -> Camera {
+        Camera {
+            position,
+            target,
+            up: Vec3::Y,
+            fov_y: 60.0f32.to_radians(),
+            near: 0.01,
+            far: 1000.0
+        }
+    }
+
+    pub fn view_matrix(&self) -> Mat4 {
+        Mat4::look_at_rh(self.position, self.target, self.up)
+    }
+
+    /*
+    Returns the right-handed perspective projection for an image of the given aspect ratio,
+    mapping depth into [0, 1].
+    */
+    pub fn projection_matrix(&self, aspect_ratio: f32) -> Mat4 {
+        Mat4::perspective_rh(self.fov_y, aspect_ratio, self.near, self.far)
+    }
+}
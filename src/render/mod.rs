@@ -0,0 +1,7 @@
+pub mod camera;
+pub mod raster;
+pub mod thumbnail;
+
+pub use camera::Camera;
+pub use raster::{render_to_image, render_to_image_lit, DirectionalLight, Lighting};
+pub use thumbnail::{render_thumbnail, thumbnail_image};
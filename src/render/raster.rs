@@ -0,0 +1,281 @@
+use glam::*;
+use crate::model::{FilterMode, Material, Model, Vertex, WrapMode};
+use crate::render::Camera;
+
+/*
+The `DirectionalLight` struct is a light infinitely far away, shining along `direction` with
+the given intensity.
+*/
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DirectionalLight {
+    pub direction: Vec3,
+    pub intensity: f32
+}
+
+/*
+The `Lighting` struct describes how `render_to_image_lit` shades surfaces: a constant ambient
+term plus the diffuse contribution of each directional light.
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lighting {
+    pub ambient: f32,
+    pub lights: Vec<DirectionalLight>
+}
+
+impl Lighting {
+    /*
+    Builds a three-point setup relative to the camera: a key light from the upper left, a
+    weaker fill light from the right and a rim light from behind the model.
+    */
+    pub fn three_point(camera: &Camera) -> Lighting {
+        let forward = (camera.target - camera.position).normalize_or_zero();
+        let right = forward.cross(camera.up).normalize_or_zero();
+        let up = right.cross(forward);
+        let light = |direction: Vec3, intensity: f32| DirectionalLight {
+            direction: direction.normalize_or_zero(),
+            intensity
+        };
+
+        Lighting {
+            ambient: 0.15,
+            lights: vec![
+                light(forward + right * 0.6 - up * 0.8, 0.8),
+                light(forward - right * 0.8 - up * 0.2, 0.35),
+                light(-forward - up * 0.5, 0.4)
+            ]
+        }
+    }
+
+    fn shade(&self, normal: Vec3) -> f32 {
+        self.ambient
+            + self
+                .lights
+                .iter()
+                .map(|light| light.intensity * normal.dot(-light.direction).max(0.0))
+                .sum::<f32>()
+    }
+}
+
+/*
+A vertex after projection: its position in pixels, its depth in [0, 1], the reciprocal of its
+clip-space `w` for perspective-correct interpolation, and its world-space normal and texture
+coordinates.
+*/
+#[derive(Clone, Copy)]
+struct ScreenVertex {
+    position: Vec2,
+    depth: f32,
+    inverse_w: f32,
+    normal: Vec3,
+    tex_coord: Vec2
+}
+
+/*
+Projects a vertex to pixel coordinates, with +Y up in clip space becoming row 0 at the top of
+the image. Vertices behind the near plane cannot be projected and give `None`.
+*/
+fn project(vertex: &Vertex, matrix: &Mat4, normal_matrix: &Mat3, size: Vec2) -> Option<ScreenVertex> {
+    let clip = *matrix * vertex.position.extend(1.0);
+    if clip.w <= f32::EPSILON || clip.z < 0.0 {
+        return None;
+    }
+
+    let inverse_w = 1.0 / clip.w;
+    let ndc = clip.xyz() * inverse_w;
+    Some(ScreenVertex {
+        position: Vec2::new((ndc.x + 1.0) * 0.5 * size.x, (1.0 - ndc.y) * 0.5 * size.y),
+        depth: ndc.z,
+        inverse_w,
+        normal: (*normal_matrix * vertex.normal).normalize_or_zero(),
+        tex_coord: vertex.tex_coord
+    })
+}
+
+fn edge_function(a: Vec2, b: Vec2, point: Vec2) -> f32 {
+    (b - a).perp_dot(point - a)
+}
+
+/*
+Returns the color of a material at the given texture coordinates: the base color, multiplied
+by the base color texture when there is one, sampled bilinearly and tiled as glTF's default
+sampler does. Textures without pixels, such as deferred ones not resolved yet, are ignored.
+*/
+fn material_color(material: &Material, tex_coord: Vec2) -> Vec3 {
+    let mut color = material.base_color.xyz();
+    if let Some(texture) = material.base_color_texture.as_ref().filter(|texture| !texture.pixels().is_empty()) {
+        let tex_coord = texture.transform().apply(tex_coord);
+        color *= texture.sample(tex_coord, WrapMode::Repeat, FilterMode::Bilinear).xyz();
+    }
+    color
+}
+
+/*
+A color and depth target the triangles are drawn into.
+*/
+struct Target {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+    depth: Vec<f32>
+}
+
+impl Target {
+    fn new(width: u32, height: u32) -> Target {
+        let (width, height) = (width as usize, height as usize);
+        Target {
+            width,
+            height,
+            pixels: vec![0; width * height * 4],
+            depth: vec![f32::INFINITY; width * height]
+        }
+    }
+
+    /*
+    Fills the pixels whose centres lie inside the triangle and in front of what was drawn
+    before. Both windings are drawn, so open and single-sided meshes show from every side; with
+    lighting, triangles seen from behind are shaded with their normals flipped, and unlit
+    materials keep their color.
+    */
+    fn draw_triangle(&mut self, corners: [ScreenVertex; 3], material: &Material, lighting: Option<&Lighting>) {
+        let [a, b, c] = corners;
+        let area = edge_function(a.position, b.position, c.position);
+        if area == 0.0 || !area.is_finite() {
+            return;
+        }
+        let facing = if area < 0.0 { 1.0 } else { -1.0 };
+
+        let min = a.position.min(b.position).min(c.position).max(Vec2::ZERO);
+        let max = a.position.max(b.position).max(c.position).min(Vec2::new(self.width as f32, self.height as f32));
+        if min.x >= max.x || min.y >= max.y {
+            return;
+        }
+
+        for y in (min.y as usize)..(max.y.ceil() as usize).min(self.height) {
+            for x in (min.x as usize)..(max.x.ceil() as usize).min(self.width) {
+                let point = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+                let weights = Vec3::new(
+                    edge_function(b.position, c.position, point),
+                    edge_function(c.position, a.position, point),
+                    edge_function(a.position, b.position, point)
+                ) / area;
+                if weights.min_element() < 0.0 {
+                    continue;
+                }
+
+                let depth = weights.dot(Vec3::new(a.depth, b.depth, c.depth));
+                let pixel = y * self.width + x;
+                if depth > 1.0 || depth >= self.depth[pixel] {
+                    continue;
+                }
+                self.depth[pixel] = depth;
+
+                let perspective = weights * Vec3::new(a.inverse_w, b.inverse_w, c.inverse_w);
+                let tex_coord = (a.tex_coord * perspective.x + b.tex_coord * perspective.y + c.tex_coord * perspective.z)
+                    / perspective.dot(Vec3::ONE);
+
+                let mut color = material_color(material, tex_coord);
+                if let Some(lighting) = lighting.filter(|_| !material.unlit) {
+                    let normal = (a.normal * perspective.x + b.normal * perspective.y + c.normal * perspective.z)
+                        .normalize_or_zero();
+                    color *= lighting.shade(normal * facing);
+                }
+                let color = color.clamp(Vec3::ZERO, Vec3::ONE);
+                let rgba = color.extend(1.0).to_array().map(|channel| (channel * 255.0).round() as u8);
+                self.pixels[pixel * 4..pixel * 4 + 4].copy_from_slice(&rgba);
+            }
+        }
+    }
+}
+
+/*
+Renders a model as seen by `camera` into an RGBA8 image of `width` by `height` pixels, stored
+row by row from the top. Triangles are depth-tested and filled with their material's flat color,
+multiplied by the base color texture when there is one; no lighting is applied. Pixels no
+triangle covers are transparent black. Instanced meshes are drawn once per instance, triangles
+reaching behind the near plane are skipped rather than clipped, and triangles with out-of-range
+indices are ignored.
+*/
+pub fn render_to_image(model: &Model, camera: &Camera, width: u32, height: u32) -> Vec<u8> {
+    render(model, camera, None, width, height)
+}
+
+/*
+Renders a model like `render_to_image`, with the flat colors shaded by `lighting` using the
+interpolated vertex normals.
+*/
+pub fn render_to_image_lit(model: &Model, camera: &Camera, lighting: &Lighting, width: u32, height: u32) -> Vec<u8> {
+    render(model, camera, Some(lighting), width, height)
+}
+
+fn render(model: &Model, camera: &Camera, lighting: Option<&Lighting>, width: u32, height: u32) -> Vec<u8> {
+    let mut target = Target::new(width, height);
+    if width == 0 || height == 0 {
+        return target.pixels;
+    }
+
+    let size = Vec2::new(width as f32, height as f32);
+    let view_projection = camera.projection_matrix(size.x / size.y) * camera.view_matrix();
+    let default_material = Material::default();
+
+    for mesh in &model.meshes {
+        let material = model.material(mesh.material).unwrap_or(&default_material);
+        let instances = if mesh.instances.is_empty() { vec![Mat4::IDENTITY] } else { mesh.instances.clone() };
+
+        for instance in instances {
+            let matrix = view_projection * instance;
+            let normal_matrix = Mat3::from_mat4(instance).inverse().transpose();
+            let projected: Vec<Option<ScreenVertex>> = mesh
+                .vertices
+                .iter()
+                .map(|vertex| project(vertex, &matrix, &normal_matrix, size))
+                .collect();
+
+            for triangle in mesh.indices.triangles() {
+                let corners = [0, 1, 2].map(|corner| projected.get(triangle[corner] as usize).copied().flatten());
+                if let [Some(a), Some(b), Some(c)] = corners {
+                    target.draw_triangle([a, b, c], material, lighting);
+                }
+            }
+        }
+    }
+    target.pixels
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::*;
+    use crate::model::{Mesh, Model, Texture, TextureSource};
+    use crate::render::{render_to_image, Camera};
+
+    fn textured_cube(texture: Texture) -> Model {
+        let mut model = Model::from_mesh(Mesh::cube(1.0));
+        model.materials[0].base_color_texture = Some(texture);
+        model
+    }
+
+    fn center_pixel(pixels: &[u8], size: u32) -> [u8; 4] {
+        let start = ((size / 2 * size + size / 2) * 4) as usize;
+        pixels[start..start + 4].try_into().unwrap()
+    }
+
+    #[test]
+    fn renders_one_pixel_texture() {
+        let model = textured_cube(Texture::solid(Vec4::new(1.0, 0.0, 0.0, 1.0)));
+        let pixels = render_to_image(&model, &Camera::look_at(Vec3::new(0.0, 0.0, 3.0), Vec3::ZERO), 32, 32);
+        let [red, green, blue, _] = center_pixel(&pixels, 32);
+        assert!(red > 0 && green == 0 && blue == 0);
+    }
+
+    #[test]
+    fn renders_gray_and_unresolved_textures() {
+        let camera = Camera::look_at(Vec3::new(0.0, 0.0, 3.0), Vec3::ZERO);
+        let gray = Texture::from_pixels(vec![128; 4], 2, 2, 1, Default::default()).unwrap();
+        let pixels = render_to_image(&textured_cube(gray), &camera, 32, 32);
+        let [red, green, blue, _] = center_pixel(&pixels, 32);
+        assert!(red > 0 && red == green && green == blue);
+
+        let deferred = Texture::deferred(TextureSource::File("missing.png".into()));
+        let pixels = render_to_image(&textured_cube(deferred), &camera, 32, 32);
+        assert!(center_pixel(&pixels, 32)[0] > 0);
+    }
+}
@@ -0,0 +1,62 @@
+use glam::*;
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::Path;
+use crate::model::{load_model_with, LoadError, LoaderOptions, Model, Sphere};
+use crate::render::raster::{render_to_image_lit, Lighting};
+use crate::render::Camera;
+
+/*
+Direction from the model towards the thumbnail camera: slightly from the right and above, so
+the front and one side of the model are visible.
+*/
+const VIEW_DIRECTION: Vec3 = Vec3::new(0.45, 0.35, 1.0);
+
+/*
+Places a camera looking at the bounding sphere from `VIEW_DIRECTION`, far enough for the whole
+sphere to fit in the field of view with a small margin.
+*/
+fn framing_camera(sphere: Sphere) -> Camera {
+    let radius = if sphere.radius > 0.0 { sphere.radius } else { 1.0 };
+    let mut camera = Camera::look_at(Vec3::ZERO, sphere.center);
+    let distance = radius * 1.1 / (camera.fov_y * 0.5).sin();
+
+    camera.position = sphere.center + VIEW_DIRECTION.normalize() * distance;
+    camera.near = (distance - radius * 1.5).max(distance * 0.01);
+    camera.far = distance + radius * 1.5;
+    camera
+}
+
+fn write_png(path: &Path, pixels: &[u8], size: u32) -> Result<(), LoadError> {
+    let mut encoder = png::Encoder::new(BufWriter::new(File::create(path)?), size, size);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(pixels))
+        .map_err(|error| LoadError::Io(io::Error::other(error)))
+}
+
+/*
+Renders a square image of a model framed by its bounding sphere and lit by a three-point setup.
+An empty model gives a fully transparent image.
+*/
+pub fn thumbnail_image(model: &Model, size: u32) -> Vec<u8> {
+    let sphere = model.bounding_sphere();
+    if sphere.is_empty() {
+        return vec![0; size as usize * size as usize * 4];
+    }
+
+    let camera = framing_camera(sphere);
+    render_to_image_lit(model, &camera, &Lighting::three_point(&camera), size, size)
+}
+
+/*
+Loads the model at `model_path` as `load_model` does and writes a `size` by `size` RGBA PNG
+thumbnail of it to `out_png`, see `thumbnail_image`. Loading and writing failures are both
+reported as errors.
+*/
+pub fn render_thumbnail(model_path: impl AsRef<Path>, out_png: impl AsRef<Path>, size: u32) -> Result<(), LoadError> {
+    let model = load_model_with(model_path, &LoaderOptions::default())?;
+    write_png(out_png.as_ref(), &thumbnail_image(&model, size), size)
+}
@@ -0,0 +1,137 @@
+mod common;
+
+use common::*;
+use glam::*;
+use motley::model::{load_scene_graph, AnimationWrap, Interpolation, Scene};
+use serde_json::json;
+
+/*
+A scene of three nodes animated over one second: node 0 moves linearly and turns a quarter
+turn about Y, node 1 steps along X and node 2 scales along a cubic spline. Two malformed
+channels, with keyframe times out of order and with a missing value, target node 0's scale.
+*/
+fn animated_scene(name: &str) -> Scene {
+    let mut fixture = Fixture::new();
+    let times = fixture.scalars(&[0.0, 1.0]);
+    let reversed_times = fixture.scalars(&[1.0, 0.0]);
+    let moves = fixture.floats(&[[0.0, 0.0, 0.0], [2.0, 4.0, 6.0]]);
+    let quarter_turn = Quat::from_rotation_y(std::f32::consts::FRAC_PI_2).to_array();
+    let turns = fixture.floats(&[[0.0, 0.0, 0.0, 1.0], quarter_turn]);
+    let steps = fixture.floats(&[[0.0, 0.0, 0.0], [5.0, 0.0, 0.0]]);
+    let spline = fixture.floats(&[
+        [0.0, 0.0, 0.0], [1.0, 1.0, 1.0], [8.0, 0.0, 0.0],
+        [0.0, 0.0, 0.0], [3.0, 3.0, 3.0], [0.0, 0.0, 0.0]
+    ]);
+    let short = fixture.floats(&[[1.0, 1.0, 1.0]]);
+    let document = json!({
+        "scene": 0,
+        "scenes": [{ "nodes": [0, 1, 2] }],
+        "nodes": [{ "name": "mover" }, { "name": "stepper", "translation": [0.0, 7.0, 0.0] }, { "name": "grower" }],
+        "animations": [{
+            "name": "everything",
+            "samplers": [
+                { "input": times, "output": moves },
+                { "input": times, "output": turns },
+                { "input": times, "output": steps, "interpolation": "STEP" },
+                { "input": times, "output": spline, "interpolation": "CUBICSPLINE" },
+                { "input": reversed_times, "output": moves },
+                { "input": times, "output": short }
+            ],
+            "channels": [
+                { "sampler": 0, "target": { "node": 0, "path": "translation" } },
+                { "sampler": 1, "target": { "node": 0, "path": "rotation" } },
+                { "sampler": 2, "target": { "node": 1, "path": "translation" } },
+                { "sampler": 3, "target": { "node": 2, "path": "scale" } },
+                { "sampler": 4, "target": { "node": 0, "path": "scale" } },
+                { "sampler": 5, "target": { "node": 0, "path": "scale" } }
+            ]
+        }]
+    });
+    load_scene_graph(fixture.write(name, document), 0).unwrap()
+}
+
+#[test]
+fn malformed_channels_are_skipped() {
+    let scene = animated_scene("animation_channels");
+    let animation = &scene.animations[0];
+    assert_eq!(animation.name.as_deref(), Some("everything"));
+    assert_eq!(animation.duration(), 1.0);
+    let interpolations: Vec<Interpolation> = animation.channels.iter().map(|channel| channel.interpolation).collect();
+    assert_eq!(interpolations, [Interpolation::Linear, Interpolation::Linear, Interpolation::Step, Interpolation::CubicSpline]);
+}
+
+#[test]
+fn keyframes_are_matched_exactly() {
+    let scene = animated_scene("animation_keyframes");
+    let start = scene.sample_animation(0, 0.0, AnimationWrap::Clamp);
+    assert_eq!(start[0].translation, Vec3::ZERO);
+    assert_eq!(start[1].translation, Vec3::ZERO);
+    assert_eq!(start[2].scale, Vec3::ONE);
+
+    let end = scene.sample_animation(0, 1.0, AnimationWrap::Clamp);
+    assert_eq!(end[0].translation, Vec3::new(2.0, 4.0, 6.0));
+    assert!(end[0].rotation.abs_diff_eq(Quat::from_rotation_y(std::f32::consts::FRAC_PI_2), 1e-6));
+    assert_eq!(end[1].translation, Vec3::new(5.0, 0.0, 0.0));
+    assert_eq!(end[2].scale, Vec3::splat(3.0));
+}
+
+#[test]
+fn midpoints_follow_the_interpolation() {
+    let scene = animated_scene("animation_midpoints");
+    let pose = scene.sample_animation(0, 0.5, AnimationWrap::Clamp);
+    assert_eq!(pose[0].translation, Vec3::new(1.0, 2.0, 3.0));
+    assert!(pose[0].rotation.abs_diff_eq(Quat::from_rotation_y(std::f32::consts::FRAC_PI_4), 1e-6));
+    assert_eq!(pose[0].scale, Vec3::ONE);
+    assert_eq!(pose[1].translation, Vec3::ZERO);
+    assert!(pose[2].scale.abs_diff_eq(Vec3::new(3.0, 2.0, 2.0), 1e-6));
+}
+
+#[test]
+fn times_outside_the_animation_clamp_or_repeat() {
+    let scene = animated_scene("animation_wrap");
+    assert_eq!(scene.sample_animation(0, -1.0, AnimationWrap::Clamp)[0].translation, Vec3::ZERO);
+    assert_eq!(scene.sample_animation(0, 1.5, AnimationWrap::Clamp)[0].translation, Vec3::new(2.0, 4.0, 6.0));
+    assert_eq!(scene.sample_animation(0, 1.5, AnimationWrap::Repeat)[0].translation, Vec3::new(1.0, 2.0, 3.0));
+    assert_eq!(scene.sample_animation(0, -0.25, AnimationWrap::Repeat)[0].translation, Vec3::new(1.5, 3.0, 4.5));
+}
+
+#[test]
+fn uneven_keyframes_interpolate_within_their_own_segment() {
+    let mut fixture = Fixture::new();
+    let times = fixture.scalars(&[0.0, 0.25, 1.0, 2.0]);
+    let path = fixture.floats(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 0.0, 3.0], [1.0, 2.0, 3.0]]);
+    let hop = fixture.floats(&[[0.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+    let document = json!({
+        "scene": 0,
+        "scenes": [{ "nodes": [0] }],
+        "nodes": [
+            { "name": "walker", "children": [1], "scale": [2.0, 2.0, 2.0] },
+            { "name": "rider", "translation": [0.0, 0.5, 0.0], "rotation": [0.0, 0.0, 1.0, 0.0] }
+        ],
+        "animations": [
+            { "name": "walk", "samplers": [{ "input": times, "output": path }], "channels": [{ "sampler": 0, "target": { "node": 0, "path": "translation" } }] },
+            { "name": "hop", "samplers": [{ "input": times, "output": hop, "interpolation": "STEP" }], "channels": [{ "sampler": 0, "target": { "node": 1, "path": "translation" } }] }
+        ]
+    });
+    let scene = load_scene_graph(fixture.write("animation_uneven", document), 0).unwrap();
+    assert_eq!(scene.animations[0].duration(), 2.0);
+
+    let walk = |time: f32| scene.sample_animation(0, time, AnimationWrap::Clamp);
+    assert_eq!(walk(0.25)[0].translation, Vec3::X);
+    assert_eq!(walk(0.125)[0].translation, Vec3::new(0.5, 0.0, 0.0));
+    assert_eq!(walk(0.625)[0].translation, Vec3::new(1.0, 0.0, 1.5));
+    assert_eq!(walk(1.5)[0].translation, Vec3::new(1.0, 1.0, 3.0));
+
+    // Properties and nodes the animation leaves alone keep their transform from the file.
+    let pose = walk(0.625);
+    assert_eq!(pose[0].scale, Vec3::splat(2.0));
+    assert_eq!(pose[1].translation, Vec3::new(0.0, 0.5, 0.0));
+    assert_eq!(pose[1].rotation, Quat::from_xyzw(0.0, 0.0, 1.0, 0.0));
+    let rider = pose[0].matrix() * pose[1].matrix();
+    assert!(rider.transform_point3(Vec3::ZERO).abs_diff_eq(Vec3::new(1.0, 1.0, 1.5), 1e-6));
+
+    // Steps change at their keyframe, not before.
+    let hop = |time: f32| scene.sample_animation(1, time, AnimationWrap::Clamp)[1].translation;
+    assert_eq!([hop(0.2), hop(0.25), hop(0.99), hop(1.0), hop(2.0)], [Vec3::ZERO, Vec3::Y, Vec3::Y, Vec3::ZERO, Vec3::Y]);
+    assert_eq!(scene.sample_animation(1, 0.5, AnimationWrap::Clamp)[0].translation, Vec3::ZERO);
+}
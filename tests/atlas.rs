@@ -0,0 +1,97 @@
+use glam::*;
+use motley::model::{AtlasExclusion, AtlasOptions, FilterMode, Material, MaterialHandle, Mesh, Model, Texture, WrapMode};
+
+/*
+A model of `count` planes, each with its own material showing a solid 8×8 texture of a
+different color.
+*/
+fn decals(count: usize) -> Model {
+    let mut model = Model { meshes: Vec::new(), materials: Vec::new() };
+    for index in 0..count {
+        let mut plane = Mesh::plane(1.0, 1.0, 1);
+        plane.material = MaterialHandle::from(index);
+        model.meshes.push(plane);
+        let color = Vec4::new(index as f32 / count as f32, 1.0 - index as f32 / count as f32, 0.5, 1.0);
+        let texture = Texture::from_fn(8, 8, |_, _| color);
+        model.materials.push(Material { base_color_texture: Some(texture), ..Default::default() });
+    }
+    model
+}
+
+fn color_at(model: &Model, mesh: usize, vertex: usize) -> Vec4 {
+    let texture = model.materials[model.meshes[mesh].material.index()].base_color_texture.as_ref().unwrap();
+    texture.sample(model.meshes[mesh].vertices[vertex].tex_coord, WrapMode::ClampToEdge, FilterMode::Bilinear)
+}
+
+#[test]
+fn decals_share_a_few_pages_and_keep_their_colors() {
+    let original = decals(30);
+    let mut model = original.clone();
+    let report = model.pack_texture_atlas_with(&AtlasOptions { max_size: 64, padding: 2 });
+
+    assert!(report.excluded.is_empty());
+    assert_eq!(report.placements.len(), 30);
+    assert!(report.page_sizes.len() >= 2 && report.page_sizes.len() < 30);
+    assert!(report.page_sizes.iter().all(|size| size.max_element() <= 64));
+
+    // Padded rectangles stay inside their page and never overlap.
+    for (index, a) in report.placements.iter().enumerate() {
+        assert!(a.x >= 2 && a.y >= 2 && a.x + a.width + 2 <= report.page_sizes[a.page].x && a.y + a.height + 2 <= report.page_sizes[a.page].y);
+        for b in &report.placements[index + 1..] {
+            let apart = a.x + a.width + 2 <= b.x - 2 || b.x + b.width + 2 <= a.x - 2 || a.y + a.height + 2 <= b.y - 2 || b.y + b.height + 2 <= a.y - 2;
+            assert!(a.page != b.page || apart);
+        }
+    }
+
+    for mesh in 0..30 {
+        for vertex in 0..model.meshes[mesh].vertices.len() {
+            assert!(color_at(&model, mesh, vertex).abs_diff_eq(color_at(&original, mesh, vertex), 1.0 / 255.0));
+        }
+    }
+    let pages: Vec<u32> = model.materials.iter().map(|material| material.base_color_texture.as_ref().unwrap().width()).collect();
+    assert!(pages.iter().all(|&width| report.page_sizes.iter().any(|size| size.x == width)));
+}
+
+#[test]
+fn shared_images_are_placed_once() {
+    let mut model = decals(3);
+    model.materials[2].base_color_texture = model.materials[0].base_color_texture.clone();
+    let report = model.pack_texture_atlas(256);
+
+    let rectangle = |material: usize| {
+        let placement = report.placements.iter().find(|placement| placement.material == MaterialHandle::from(material)).unwrap();
+        (placement.page, placement.x, placement.y)
+    };
+    assert_eq!(rectangle(0), rectangle(2));
+    assert_ne!(rectangle(0), rectangle(1));
+    assert_eq!(model.meshes[0].vertices[0].tex_coord, model.meshes[2].vertices[0].tex_coord);
+}
+
+#[test]
+fn tiled_and_oversized_textures_are_left_out() {
+    let mut model = decals(4);
+    for vertex in &mut model.meshes[1].vertices {
+        vertex.tex_coord *= 3.0;
+    }
+    model.materials[2].base_color_texture = Some(Texture::from_fn(100, 10, |_, _| Vec4::ONE));
+    let original = model.clone();
+
+    let report = model.pack_texture_atlas(64);
+    assert_eq!(report.excluded, [(MaterialHandle::from(1), AtlasExclusion::Repeats), (MaterialHandle::from(2), AtlasExclusion::TooLarge)]);
+    assert_eq!(report.placements.len(), 2);
+    for kept in [1, 2] {
+        assert_eq!(model.meshes[kept].interleaved_bytes(), original.meshes[kept].interleaved_bytes());
+        let texture = model.materials[kept].base_color_texture.as_ref().unwrap();
+        assert_eq!(texture.pixels(), original.materials[kept].base_color_texture.as_ref().unwrap().pixels());
+    }
+}
+
+#[test]
+fn a_single_image_is_left_alone() {
+    let mut model = decals(2);
+    model.materials[1].base_color_texture = model.materials[0].base_color_texture.clone();
+    let original = model.clone();
+    let report = model.pack_texture_atlas(256);
+    assert!(report.placements.is_empty() && report.page_sizes.is_empty());
+    assert_eq!(model.meshes[1].interleaved_bytes(), original.meshes[1].interleaved_bytes());
+}
@@ -0,0 +1,96 @@
+use glam::*;
+use motley::model::{compute_tangents_mikktspace, CoordinateSystem, Handedness, Mesh, MeshError, Model};
+
+/*
+A cube with tangents and an instance, shifted so no coordinate is symmetric around the origin.
+*/
+fn tangent_cube() -> Model {
+    let mut cube = Mesh::cube(1.0);
+    cube.transform(Mat4::from_translation(Vec3::new(1.0, 2.0, 3.0))).unwrap();
+    compute_tangents_mikktspace(&mut cube);
+    cube.instances = vec![Mat4::from_rotation_translation(Quat::from_rotation_x(0.5), Vec3::new(0.0, 0.0, 4.0))];
+    Model::from_mesh(cube)
+}
+
+#[test]
+fn z_up_turns_into_y_up_exactly() {
+    let mut model = tangent_cube();
+    let original = model.clone();
+    model.convert_axes(CoordinateSystem::ZUp, CoordinateSystem::YUp);
+
+    // Up becomes +Y and -Y, towards the viewer in Z-up, becomes +Z, with no rounding.
+    for (vertex, before) in model.meshes[0].vertices.iter().zip(&original.meshes[0].vertices) {
+        let [x, y, z] = before.position.to_array();
+        assert_eq!(vertex.position, Vec3::new(x, z, -y));
+        let [x, y, z] = before.normal.to_array();
+        assert_eq!(vertex.normal, Vec3::new(x, z, -y));
+        assert_eq!(vertex.tangent.w, before.tangent.w);
+    }
+    assert!((model.meshes[0].signed_volume() - original.meshes[0].signed_volume()).abs() < 1e-5);
+
+    let moved = model.meshes[0].instances[0].transform_point3(model.meshes[0].vertices[0].position);
+    let [x, y, z] = original.meshes[0].instances[0].transform_point3(original.meshes[0].vertices[0].position).to_array();
+    assert!(moved.abs_diff_eq(Vec3::new(x, z, -y), 1e-5));
+}
+
+#[test]
+fn converting_back_restores_every_value() {
+    let original = tangent_cube();
+    let mut model = original.clone();
+    model.convert_axes(CoordinateSystem::YUp, CoordinateSystem::ZUp);
+    assert_ne!(model.meshes[0].interleaved_bytes(), original.meshes[0].interleaved_bytes());
+    model.convert_axes(CoordinateSystem::ZUp, CoordinateSystem::YUp);
+
+    assert_eq!(model.meshes[0].interleaved_bytes(), original.meshes[0].interleaved_bytes());
+    assert_eq!(model.meshes[0].instances, original.meshes[0].instances);
+    assert_eq!(model.meshes[0].cached_bounds(), original.meshes[0].cached_bounds());
+
+    model.convert_axes(CoordinateSystem::YUp, CoordinateSystem::YUp);
+    assert_eq!(model.meshes[0].interleaved_bytes(), original.meshes[0].interleaved_bytes());
+}
+
+#[test]
+fn left_handed_cube_keeps_its_faces_pointing_out() {
+    let original = tangent_cube();
+    let mut model = original.clone();
+    model.convert_handedness(Handedness::RightHanded, Handedness::LeftHanded).unwrap();
+    let mesh = &model.meshes[0];
+
+    for (vertex, before) in mesh.vertices.iter().zip(&original.meshes[0].vertices) {
+        assert_eq!(vertex.position, before.position * Vec3::new(1.0, 1.0, -1.0));
+        assert_eq!(vertex.normal, before.normal * Vec3::new(1.0, 1.0, -1.0));
+        assert_eq!(vertex.tangent, before.tangent * Vec4::new(1.0, 1.0, -1.0, -1.0));
+    }
+
+    // The winding is reversed, so every face still agrees with its vertex normals.
+    for triangle in mesh.indices.triangles() {
+        let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(|index| mesh.vertices[index as usize]);
+        let winding = (b.position - a.position).cross(c.position - a.position).normalize();
+        assert!(winding.abs_diff_eq(a.normal, 1e-6));
+    }
+    assert!((mesh.signed_volume() - 1.0).abs() < 1e-5);
+
+    let moved = mesh.instances[0].transform_point3(mesh.vertices[0].position);
+    let before = original.meshes[0].instances[0].transform_point3(original.meshes[0].vertices[0].position);
+    assert!(moved.abs_diff_eq(before * Vec3::new(1.0, 1.0, -1.0), 1e-5));
+
+    model.convert_handedness(Handedness::LeftHanded, Handedness::RightHanded).unwrap();
+    assert_eq!(model.meshes[0].interleaved_bytes(), original.meshes[0].interleaved_bytes());
+    assert_eq!(model.meshes[0].indices, original.meshes[0].indices);
+    assert_eq!(model.meshes[0].instances, original.meshes[0].instances);
+}
+
+#[test]
+fn incomplete_triangles_leave_the_model_untouched() {
+    let mut model = tangent_cube();
+    model.meshes.push(Mesh::cube(1.0));
+    model.meshes[1].indices.push(0);
+    let original = model.clone();
+
+    let result = model.convert_handedness(Handedness::RightHanded, Handedness::LeftHanded);
+    assert!(matches!(result, Err(MeshError::IncompleteTriangles(37))));
+    for (mesh, before) in model.meshes.iter().zip(&original.meshes) {
+        assert_eq!(mesh.interleaved_bytes(), before.interleaved_bytes());
+        assert_eq!(mesh.indices, before.indices);
+    }
+}
@@ -0,0 +1,118 @@
+use glam::*;
+use motley::model::{Aabb, Material, Mesh, MeshBuilder, Model, NormalMode, Sphere};
+
+#[test]
+fn empty_aabb_unions_as_a_no_op() {
+    let aabb = Aabb::new(Vec3::new(-1.0, 0.0, 2.0), Vec3::new(1.0, 3.0, 4.0));
+    assert!(Aabb::EMPTY.is_empty());
+    assert_eq!(Aabb::EMPTY.union(&aabb), aabb);
+    assert_eq!(aabb.union(&Aabb::EMPTY), aabb);
+    assert_eq!(Aabb::EMPTY.center(), Vec3::ZERO);
+    assert_eq!(Aabb::EMPTY.extent(), Vec3::ZERO);
+    assert_eq!(Aabb::EMPTY.transformed(Mat4::from_scale(Vec3::splat(2.0))), Aabb::EMPTY);
+    assert_eq!(Aabb::from_points(std::iter::empty()), Aabb::EMPTY);
+}
+
+#[test]
+fn aabb_center_extent_and_rotation() {
+    let aabb = Aabb::new(Vec3::new(-1.0, 0.0, 2.0), Vec3::new(1.0, 3.0, 4.0));
+    assert_eq!(aabb.center(), Vec3::new(0.0, 1.5, 3.0));
+    assert_eq!(aabb.extent(), Vec3::new(2.0, 3.0, 2.0));
+
+    let unit = Aabb::new(Vec3::splat(-1.0), Vec3::splat(1.0));
+    let rotated = unit.transformed(Mat4::from_rotation_y(std::f32::consts::FRAC_PI_4));
+    let half_diagonal = 2.0f32.sqrt();
+    assert!(rotated.max.abs_diff_eq(Vec3::new(half_diagonal, 1.0, half_diagonal), 1e-5));
+    assert!(rotated.min.abs_diff_eq(-rotated.max, 1e-5));
+}
+
+#[test]
+fn mesh_and_model_aabb() {
+    let cube = Mesh::cube(2.0);
+    assert_eq!(cube.aabb(), Aabb::new(Vec3::splat(-1.0), Vec3::splat(1.0)));
+
+    let mut plane = Mesh::plane(2.0, 2.0, 1);
+    plane.transform(Mat4::from_translation(Vec3::new(4.0, 5.0, 4.0))).unwrap();
+    assert_eq!(plane.aabb(), Aabb::new(Vec3::new(3.0, 5.0, 3.0), Vec3::splat(5.0)));
+
+    let empty = MeshBuilder::new().build();
+    assert_eq!(empty.aabb(), Aabb::EMPTY);
+    let model = Model {
+        meshes: vec![cube, plane, empty],
+        materials: vec![Material::default()]
+    };
+    assert_eq!(model.aabb(), Aabb::new(Vec3::splat(-1.0), Vec3::splat(5.0)));
+}
+
+/*
+Two triangles sharing an edge, one with a corner 0.0005 past the other's, plus `unused` vertices
+drawn by no triangle; the builder caches bounds covering them all.
+*/
+fn cached_strip(unused: &[Vec3]) -> Mesh {
+    let mut builder = MeshBuilder::new();
+    for &position in [Vec3::ZERO, Vec3::X, Vec3::Y, Vec3::new(1.0005, 0.0, 0.0)].iter().chain(unused) {
+        builder.add_vertex(position, Vec3::Z, Vec2::ZERO);
+    }
+    builder.add_triangle(0, 1, 2).unwrap();
+    builder.add_triangle(0, 3, 2).unwrap();
+    builder.build()
+}
+
+#[test]
+fn mutators_keep_the_cached_bounds_current() {
+    let strip_bounds = Aabb::new(Vec3::ZERO, Vec3::new(1.0005, 1.0, 0.0));
+    let mut welded = cached_strip(&[]);
+    assert_eq!(welded.cached_bounds(), Some(strip_bounds));
+    assert_eq!(welded.weld_vertices(0.001, false), 1);
+    assert_eq!(welded.aabb(), Aabb::new(Vec3::ZERO, Vec3::new(1.0, 1.0, 0.0)));
+
+    // Flat normals give every corner its own vertex and leave unused vertices out.
+    let mut flat = cached_strip(&[Vec3::splat(9.0)]);
+    assert_eq!(flat.cached_bounds(), Some(Aabb::new(Vec3::ZERO, Vec3::splat(9.0))));
+    flat.recalculate_normals(NormalMode::Flat);
+    assert_eq!(flat.aabb(), strip_bounds);
+
+    let mut scaled = cached_strip(&[]);
+    scaled.transform(Mat4::from_scale(Vec3::splat(2.0))).unwrap();
+    assert_eq!(scaled.aabb(), Aabb::new(Vec3::ZERO, Vec3::new(2.001, 2.0, 0.0)));
+
+    let mut edited = cached_strip(&[]);
+    edited.vertices[2].position = Vec3::new(-1.0, 1.0, 0.0);
+    edited.invalidate_bounds();
+    assert_eq!(edited.cached_bounds(), None);
+    assert_eq!(edited.aabb(), Aabb::new(Vec3::NEG_X, Vec3::new(1.0005, 1.0, 0.0)));
+}
+
+#[test]
+fn sphere_mesh_gets_its_own_radius() {
+    let sphere = Mesh::uv_sphere(2.0, 32).bounding_sphere();
+    assert!(sphere.center.length() < 0.05, "{:?}", sphere.center);
+    assert!((2.0..2.05).contains(&sphere.radius), "{}", sphere.radius);
+    assert!(MeshBuilder::new().build().bounding_sphere().is_empty());
+}
+
+#[test]
+fn model_sphere_encloses_every_mesh() {
+    let mut far = Mesh::cube(2.0);
+    far.transform(Mat4::from_translation(Vec3::new(10.0, 0.0, 0.0))).unwrap();
+    let model = Model {
+        meshes: vec![Mesh::cube(2.0), far],
+        materials: vec![Material::default()]
+    };
+
+    let sphere = model.bounding_sphere();
+    for vertex in model.meshes.iter().flat_map(|mesh| &mesh.vertices) {
+        assert!(vertex.position.distance(sphere.center) <= sphere.radius + 1e-5);
+    }
+    assert!(sphere.radius < 6.5, "{}", sphere.radius);
+}
+
+#[test]
+fn transformed_sphere_takes_the_largest_scale() {
+    let sphere = Sphere::new(Vec3::X, 1.0);
+    let matrix = Mat4::from_scale_rotation_translation(Vec3::new(1.0, 3.0, 2.0), Quat::from_rotation_z(1.0), Vec3::Y);
+    let transformed = sphere.transformed(matrix);
+    assert!((transformed.radius - 3.0).abs() < 1e-5);
+    assert!(transformed.center.abs_diff_eq(matrix.transform_point3(Vec3::X), 1e-6));
+    assert!(Sphere::EMPTY.transformed(matrix).is_empty());
+}
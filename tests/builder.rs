@@ -0,0 +1,68 @@
+use glam::*;
+use motley::model::{Aabb, MaterialHandle, Mesh, MeshBuilder, MeshError};
+
+/*
+Builds a unit cube face by face, each face with its own four vertices facing `normal`.
+*/
+fn cube() -> MeshBuilder {
+    let mut builder = MeshBuilder::new().with_name("cube").with_material(MaterialHandle::from(2));
+    for normal in [Vec3::X, Vec3::NEG_X, Vec3::Y, Vec3::NEG_Y, Vec3::Z, Vec3::NEG_Z] {
+        let up = if normal.y == 0.0 { Vec3::Y } else { Vec3::Z };
+        let right = up.cross(normal);
+        let corner = |u: f32, v: f32| (normal + right * u + up * v) * 0.5;
+        let a = builder.add_vertex(corner(-1.0, -1.0), normal, Vec2::new(0.0, 1.0));
+        let b = builder.add_vertex(corner(1.0, -1.0), normal, Vec2::new(1.0, 1.0));
+        let c = builder.add_vertex(corner(1.0, 1.0), normal, Vec2::new(1.0, 0.0));
+        let d = builder.add_vertex(corner(-1.0, 1.0), normal, Vec2::new(0.0, 0.0));
+        builder.add_quad(a, b, c, d).unwrap();
+    }
+    builder
+}
+
+#[test]
+fn builds_a_cube_with_outward_faces() {
+    let builder = cube();
+    assert_eq!((builder.vertex_count(), builder.triangle_count()), (24, 12));
+
+    let mesh = builder.build();
+    assert_eq!(mesh.name.as_deref(), Some("cube"));
+    assert_eq!(mesh.material, MaterialHandle::from(2));
+    assert_eq!(mesh.vertices.len(), 24);
+    assert_eq!(mesh.indices.len(), 36);
+    assert!(mesh.indices.is_u16());
+    assert_eq!(mesh.aabb(), Aabb::new(Vec3::splat(-0.5), Vec3::splat(0.5)));
+
+    for [a, b, c] in mesh.indices.triangles() {
+        let [a, b, c] = [a, b, c].map(|index| mesh.vertices[index as usize]);
+        let face = (b.position - a.position).cross(c.position - a.position).normalize();
+        assert!(face.abs_diff_eq(a.normal, 1e-6));
+        assert!(face.dot(a.position) > 0.0);
+    }
+}
+
+#[test]
+fn out_of_range_indices_are_refused() {
+    let mut builder = MeshBuilder::new();
+    let a = builder.add_vertex(Vec3::ZERO, Vec3::Z, Vec2::ZERO);
+    let b = builder.add_vertex(Vec3::X, Vec3::Z, Vec2::ZERO);
+
+    assert_eq!(builder.add_triangle(a, b, 2), Err(MeshError::IndexOutOfRange { index: 2, vertex_count: 2 }));
+    assert_eq!(builder.add_quad(a, b, 5, 2), Err(MeshError::IndexOutOfRange { index: 5, vertex_count: 2 }));
+    assert_eq!(builder.triangle_count(), 0);
+
+    let c = builder.add_vertex(Vec3::Y, Vec3::Z, Vec2::ZERO);
+    assert_eq!(builder.add_triangle(a, b, c), Ok(()));
+    assert_eq!(builder.build().indices.to_u32(), [0, 1, 2]);
+}
+
+#[test]
+fn uv_sphere_uses_half_as_many_rings_as_segments() {
+    let sphere = Mesh::uv_sphere(2.0, 16);
+    assert_eq!(sphere.vertices.len(), 17 * 9);
+    // The rings touching a pole have one triangle per segment instead of two.
+    assert_eq!(sphere.indices.len() / 3, 16 * (8 * 2 - 2));
+    assert_eq!(sphere.vertices.len(), Mesh::uv_sphere_with_rings(2.0, 16, 8).vertices.len());
+    for vertex in &sphere.vertices {
+        assert!((vertex.position.length() - 2.0).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use common::*;
+use motley::model::cache::CACHE_VERSION;
+use glam::*;
+use motley::model::{LoadError, Mesh, Model};
+
+#[test]
+fn cache_round_trips_the_model() {
+    let model = cube_and_plane();
+    let path = temp_dir("cache_round_trip").join("model.cache");
+    model.save_cache(&path).unwrap();
+
+    let loaded = Model::load_cache(&path).unwrap();
+    assert_same_geometry(&loaded, &model);
+    assert_same_materials(&loaded, &model);
+    for (loaded, mesh) in loaded.meshes.iter().zip(&model.meshes) {
+        assert_eq!(loaded.indices, mesh.indices);
+        assert_eq!(loaded.interleaved_bytes(), mesh.interleaved_bytes());
+    }
+}
+
+#[test]
+fn cache_of_another_version_is_rejected() {
+    let path = temp_dir("cache_version").join("model.cache");
+    cube_and_plane().save_cache(&path).unwrap();
+    let mut bytes = std::fs::read(&path).unwrap();
+    bytes[8..12].copy_from_slice(&(CACHE_VERSION + 1).to_le_bytes());
+    std::fs::write(&path, bytes).unwrap();
+
+    match Model::load_cache(&path) {
+        Err(LoadError::CacheVersionMismatch { found, expected }) => assert_eq!((found, expected), (CACHE_VERSION + 1, CACHE_VERSION)),
+        other => panic!("Expected a version mismatch, got {:?}", other.map(|_| ()))
+    }
+}
+
+#[test]
+fn truncated_or_foreign_cache_is_rejected() {
+    let directory = temp_dir("cache_invalid");
+    let path = directory.join("model.cache");
+    cube_and_plane().save_cache(&path).unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+
+    let truncated = directory.join("truncated.cache");
+    std::fs::write(&truncated, &bytes[..bytes.len() / 2]).unwrap();
+    assert!(matches!(Model::load_cache(&truncated), Err(LoadError::InvalidData(_))));
+
+    let trailing = directory.join("trailing.cache");
+    std::fs::write(&trailing, [bytes.as_slice(), &[0]].concat()).unwrap();
+    assert!(matches!(Model::load_cache(&trailing), Err(LoadError::InvalidData(_))));
+
+    let foreign = directory.join("foreign.cache");
+    std::fs::write(&foreign, b"not a cache").unwrap();
+    assert!(matches!(Model::load_cache(&foreign), Err(LoadError::InvalidData(_))));
+
+    assert!(matches!(Model::load_cache(directory.join("missing.cache")), Err(LoadError::Io(_))));
+}
+
+#[test]
+fn cache_keeps_wide_indices_instances_and_names() {
+    let mut mesh = Mesh::plane(1.0, 1.0, 300);
+    mesh.name = Some("ground".to_string());
+    mesh.instances = vec![Mat4::IDENTITY, Mat4::from_translation(Vec3::X)];
+    let mut model = Model::from_mesh(mesh);
+    model.materials[0].name = Some("grass".to_string());
+    assert!(!model.meshes[0].indices.is_u16());
+
+    let path = temp_dir("cache_wide").join("model.cache");
+    model.save_cache(&path).unwrap();
+    let loaded = Model::load_cache(&path).unwrap();
+
+    assert_eq!(loaded.meshes[0].indices, model.meshes[0].indices);
+    assert_eq!(loaded.meshes[0].instances, model.meshes[0].instances);
+    assert_eq!(loaded.meshes[0].name.as_deref(), Some("ground"));
+    assert_eq!(loaded.materials[0].name.as_deref(), Some("grass"));
+    assert_eq!(loaded.meshes[0].interleaved_bytes(), model.meshes[0].interleaved_bytes());
+}
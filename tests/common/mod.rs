@@ -0,0 +1,169 @@
+#![allow(dead_code)]
+
+use glam::*;
+use motley::model::{Material, MaterialHandle, Mesh, Model, Texture};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+/*
+Builds small glTF files for the tests: accessor data is appended to one binary buffer, and
+`write` stores the document next to it in a directory of its own under the system temporary
+directory.
+*/
+#[derive(Default)]
+pub struct Fixture {
+    pub buffer: Vec<u8>,
+    pub views: Vec<Value>,
+    pub accessors: Vec<Value>
+}
+
+impl Fixture {
+    pub fn new() -> Fixture {
+        Fixture::default()
+    }
+
+    /*
+    Appends raw bytes as a buffer view, padded to four bytes, and returns its index.
+    */
+    pub fn view(&mut self, bytes: &[u8]) -> usize {
+        while !self.buffer.len().is_multiple_of(4) {
+            self.buffer.push(0);
+        }
+        self.views.push(json!({ "buffer": 0, "byteOffset": self.buffer.len(), "byteLength": bytes.len() }));
+        self.buffer.extend_from_slice(bytes);
+        self.views.len() - 1
+    }
+
+    /*
+    Appends float vectors of `N` components as an accessor and returns its index. Positions
+    need the min and max glTF requires, so every float accessor gets them.
+    */
+    pub fn floats<const N: usize>(&mut self, values: &[[f32; N]]) -> usize {
+        let bytes: Vec<u8> = values.iter().flatten().flat_map(|value| value.to_le_bytes()).collect();
+        let view = self.view(&bytes);
+        let min: Vec<f32> = (0..N).map(|i| values.iter().map(|value| value[i]).fold(f32::INFINITY, f32::min)).collect();
+        let max: Vec<f32> = (0..N).map(|i| values.iter().map(|value| value[i]).fold(f32::NEG_INFINITY, f32::max)).collect();
+        let kind = match N {
+            1 => "SCALAR",
+            2 => "VEC2",
+            3 => "VEC3",
+            _ => "VEC4"
+        };
+        self.accessors.push(json!({
+            "bufferView": view,
+            "componentType": 5126,
+            "count": values.len(),
+            "type": kind,
+            "min": min,
+            "max": max
+        }));
+        self.accessors.len() - 1
+    }
+
+    pub fn scalars(&mut self, values: &[f32]) -> usize {
+        let vectors: Vec<[f32; 1]> = values.iter().map(|&value| [value]).collect();
+        self.floats(&vectors)
+    }
+
+    pub fn indices(&mut self, values: &[u16]) -> usize {
+        let bytes: Vec<u8> = values.iter().flat_map(|value| value.to_le_bytes()).collect();
+        let view = self.view(&bytes);
+        self.accessors.push(json!({ "bufferView": view, "componentType": 5123, "count": values.len(), "type": "SCALAR" }));
+        self.accessors.len() - 1
+    }
+
+    /*
+    Writes `document` with the buffer, views and accessors filled in, and returns the path of
+    the glTF file.
+    */
+    pub fn write(&self, name: &str, mut document: Value) -> PathBuf {
+        let directory = temp_dir(name);
+        std::fs::write(directory.join("buffer.bin"), &self.buffer).unwrap();
+        document["asset"] = json!({ "version": "2.0" });
+        if !self.buffer.is_empty() {
+            document["buffers"] = json!([{ "byteLength": self.buffer.len(), "uri": "buffer.bin" }]);
+            document["bufferViews"] = json!(self.views);
+            document["accessors"] = json!(self.accessors);
+        }
+        let path = directory.join(format!("{}.gltf", name));
+        std::fs::write(&path, serde_json::to_vec_pretty(&document).unwrap()).unwrap();
+        path
+    }
+}
+
+/*
+Creates an empty directory for one test, removing what an earlier run left there.
+*/
+pub fn temp_dir(name: &str) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("motley-test-{}-{}", std::process::id(), name));
+    let _ = std::fs::remove_dir_all(&directory);
+    std::fs::create_dir_all(&directory).unwrap();
+    directory
+}
+
+/*
+Encodes a 2×2 RGBA image as PNG.
+*/
+pub fn png() -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let mut encoder = png::Encoder::new(&mut bytes, 2, 2);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header().unwrap().write_image_data(&[255; 16]).unwrap();
+    bytes
+}
+
+pub const TRIANGLE: [[f32; 3]; 3] = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+
+/*
+A document with one node drawing `mesh`, and the given materials.
+*/
+pub fn single_mesh(mesh: Value, materials: Value) -> Value {
+    json!({
+        "scene": 0,
+        "scenes": [{ "nodes": [0] }],
+        "nodes": [{ "mesh": 0 }],
+        "meshes": [mesh],
+        "materials": materials
+    })
+}
+
+/*
+A model of a red cube and a textured plane, each with its own material.
+*/
+pub fn cube_and_plane() -> Model {
+    let texture = Texture::from_rgba8(2, 2, vec![255, 0, 0, 255, 0, 255, 0, 255, 0, 0, 255, 255, 255, 255, 255, 255]).unwrap();
+    let mut plane = Mesh::plane(2.0, 2.0, 2);
+    plane.material = MaterialHandle::from(1);
+    Model {
+        meshes: vec![Mesh::cube(1.0), plane],
+        materials: vec![
+            Material { base_color: Vec4::new(1.0, 0.0, 0.0, 1.0), ..Default::default() },
+            Material { base_color_texture: Some(texture), roughness_factor: 0.25, ..Default::default() }
+        ]
+    }
+}
+
+pub fn assert_same_geometry(loaded: &Model, model: &Model) {
+    assert_eq!(loaded.meshes.len(), model.meshes.len());
+    for (loaded, mesh) in loaded.meshes.iter().zip(&model.meshes) {
+        assert_eq!(loaded.indices.to_u32(), mesh.indices.to_u32());
+        assert_eq!(loaded.vertices.len(), mesh.vertices.len());
+        for (a, b) in loaded.vertices.iter().zip(&mesh.vertices) {
+            assert!(a.position.abs_diff_eq(b.position, 1e-6));
+            assert!(a.normal.abs_diff_eq(b.normal, 1e-6));
+            assert!(a.tex_coord.abs_diff_eq(b.tex_coord, 1e-6));
+        }
+        assert_eq!(loaded.material, mesh.material);
+    }
+}
+
+pub fn assert_same_materials(loaded: &Model, model: &Model) {
+    assert_eq!(loaded.materials.len(), model.materials.len());
+    for (loaded, material) in loaded.materials.iter().zip(&model.materials) {
+        assert_eq!(loaded.base_color, material.base_color);
+        assert_eq!(loaded.roughness_factor, material.roughness_factor);
+        let pixels = |material: &Material| material.base_color_texture.as_ref().map(|texture| texture.pixels().to_vec());
+        assert_eq!(pixels(loaded), pixels(material));
+    }
+}
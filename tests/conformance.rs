@@ -0,0 +1,77 @@
+mod common;
+
+use common::*;
+use motley::model::{validate_gltf, GltfValidationMessage, Severity};
+use serde_json::json;
+
+fn errors(messages: &[GltfValidationMessage]) -> Vec<&str> {
+    messages.iter().filter(|message| message.severity == Severity::Error).map(|message| message.path.as_str()).collect()
+}
+
+#[test]
+fn well_formed_files_have_no_messages() {
+    let mut fixture = Fixture::new();
+    let positions = fixture.floats(&TRIANGLE);
+    let normals = fixture.floats(&[[0.0, 0.0, 1.0]; 3]);
+    let indices = fixture.indices(&[0, 1, 2]);
+    let mesh = json!({ "primitives": [{ "attributes": { "POSITION": positions, "NORMAL": normals }, "indices": indices, "material": 0 }] });
+    let path = fixture.write("conforming", single_mesh(mesh, json!([{ "pbrMetallicRoughness": { "roughnessFactor": 0.5 } }])));
+    assert_eq!(validate_gltf(&path), []);
+}
+
+#[test]
+fn spec_violations_are_reported_with_their_path() {
+    let mut fixture = Fixture::new();
+    let positions = fixture.floats(&TRIANGLE);
+    let normals = fixture.floats(&[[0.0, 1.0]; 3]);
+    let tex_coords = fixture.floats(&[[0.0, 0.0], [1.0, 0.0]]);
+    fixture.accessors[tex_coords]["normalized"] = json!(true);
+    let indices = fixture.indices(&[0, 1, 2]);
+    // Reads one index past the end of its buffer view.
+    fixture.accessors[indices]["count"] = json!(4);
+    let mesh = json!({
+        "primitives": [{ "attributes": { "POSITION": positions, "NORMAL": normals, "TEXCOORD_0": tex_coords }, "indices": indices, "material": 0 }]
+    });
+    let material = json!({ "pbrMetallicRoughness": { "metallicFactor": 1.5 } });
+    let path = fixture.write("violations", single_mesh(mesh, json!([material])));
+
+    let messages = validate_gltf(&path);
+    let mut paths = errors(&messages);
+    paths.sort();
+    assert_eq!(paths, [
+        "accessors[2].normalized",
+        "accessors[3]",
+        "materials[0].pbrMetallicRoughness.metallicFactor",
+        "meshes[0].primitives[0].attributes[\"NORMAL\"]",
+        "meshes[0].primitives[0].attributes[\"TEXCOORD_0\"]",
+        "meshes[0].primitives[0].attributes[\"TEXCOORD_0\"]"
+    ]);
+    assert!(messages.iter().all(|message| message.to_string().starts_with("Error: ")));
+}
+
+#[test]
+fn short_buffers_are_reported() {
+    let mut fixture = Fixture::new();
+    let positions = fixture.floats(&TRIANGLE);
+    let mesh = json!({ "primitives": [{ "attributes": { "POSITION": positions } }] });
+    let path = fixture.write("short_buffer", single_mesh(mesh, json!([])));
+    std::fs::write(path.with_file_name("buffer.bin"), [0; 12]).unwrap();
+
+    let messages = validate_gltf(&path);
+    assert_eq!(errors(&messages), ["buffers[0].byteLength"]);
+    assert_eq!(messages[0].message, "Buffer declares 36 bytes but holds 12");
+}
+
+#[test]
+fn broken_structure_stops_the_checks() {
+    let mut fixture = Fixture::new();
+    let positions = fixture.floats(&TRIANGLE);
+    // Both the dangling material and the metallic factor are wrong, but only the first is reported.
+    let mesh = json!({ "primitives": [{ "attributes": { "POSITION": positions }, "material": 3 }] });
+    let path = fixture.write("broken_structure", single_mesh(mesh, json!([{ "pbrMetallicRoughness": { "metallicFactor": 2.0 } }])));
+    assert_eq!(errors(&validate_gltf(&path)), ["meshes[0].primitives[0].material"]);
+
+    let missing = validate_gltf(path.with_file_name("missing.gltf"));
+    assert_eq!(missing.len(), 1);
+    assert_eq!((missing[0].severity, missing[0].path.as_str()), (Severity::Error, ""));
+}
@@ -0,0 +1,65 @@
+use glam::*;
+use motley::model::{equirect_to_cubemap, Texture, TextureFormat};
+use std::f32::consts::PI;
+
+/*
+A panorama whose colors encode the direction each pixel looks along, mapped from [-1, 1] to
+[0, 1]. Its center looks along -Z and its top row up along +Y.
+*/
+fn direction_panorama(width: u32, height: u32) -> Texture {
+    Texture::from_fn(width, height, |x, y| {
+        let longitude = ((x as f32 + 0.5) / width as f32 - 0.5) * 2.0 * PI;
+        let polar = (y as f32 + 0.5) / height as f32 * PI;
+        let direction = Vec3::new(polar.sin() * longitude.sin(), polar.cos(), -polar.sin() * longitude.cos());
+        (direction * 0.5 + 0.5).extend(1.0)
+    })
+}
+
+fn decode(color: Vec4) -> Vec3 {
+    color.xyz() * 2.0 - 1.0
+}
+
+#[test]
+fn faces_look_along_their_axis() {
+    let faces = equirect_to_cubemap(&direction_panorama(256, 128), 8);
+    let axes = [Vec3::X, Vec3::NEG_X, Vec3::Y, Vec3::NEG_Y, Vec3::Z, Vec3::NEG_Z];
+    for (face, axis) in faces.iter().zip(axes) {
+        assert_eq!((face.width(), face.height(), face.format()), (8, 8, TextureFormat::Rgba8));
+        for (x, y) in [(3, 3), (4, 4), (3, 4)] {
+            assert!(decode(face.pixel(x, y)).dot(axis) > 0.95);
+        }
+    }
+}
+
+#[test]
+fn face_corners_follow_the_cubemap_layout() {
+    let faces = equirect_to_cubemap(&direction_panorama(512, 256), 4);
+    // Face texture coordinates grow right and down, seen from inside the cube.
+    let corner = |s: f32, t: f32| [
+        Vec3::new(1.0, -t, -s),
+        Vec3::new(-1.0, -t, s),
+        Vec3::new(s, 1.0, t),
+        Vec3::new(s, -1.0, -t),
+        Vec3::new(s, -t, 1.0),
+        Vec3::new(-s, -t, -1.0)
+    ];
+    for (x, y) in [(0, 0), (3, 0), (0, 3), (3, 3)] {
+        let (s, t) = ((x as f32 + 0.5) / 2.0 - 1.0, (y as f32 + 0.5) / 2.0 - 1.0);
+        for (face, expected) in faces.iter().zip(corner(s, t)) {
+            let direction = decode(face.pixel(x, y));
+            assert!(direction.abs_diff_eq(expected.normalize(), 0.05));
+        }
+    }
+}
+
+#[test]
+fn sky_and_ground_land_on_the_top_and_bottom_faces() {
+    let panorama = Texture::from_fn(64, 32, |_, y| if y < 16 { Vec4::ONE } else { Vec4::W });
+    let faces = equirect_to_cubemap(&panorama, 16);
+    assert!((0..16).all(|x| (0..16).all(|y| faces[2].pixel(x, y) == Vec4::ONE && faces[3].pixel(x, y) == Vec4::W)));
+    // The sides show the horizon across their middle.
+    for side in [0, 1, 4, 5] {
+        assert_eq!(faces[side].pixel(8, 0), Vec4::ONE);
+        assert_eq!(faces[side].pixel(8, 15), Vec4::W);
+    }
+}
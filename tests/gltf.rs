@@ -0,0 +1,48 @@
+mod common;
+
+use common::*;
+use motley::model::{export_gltf, load_model_with, load_scene, LoaderOptions};
+use serde_json::json;
+
+#[test]
+fn gltf_export_round_trips_through_the_loader() {
+    let model = cube_and_plane();
+    let directory = temp_dir("export_gltf");
+    for (name, binary) in [("model.gltf", false), ("model.glb", true)] {
+        let path = directory.join(name);
+        export_gltf(&model, &path, binary).unwrap();
+        let loaded = load_scene(&path, 0).unwrap();
+        assert_same_geometry(&loaded, &model);
+        assert_same_materials(&loaded, &model);
+    }
+}
+
+#[test]
+fn loaded_model_exports_and_reloads() {
+    let mut fixture = Fixture::new();
+    let positions = fixture.floats(&TRIANGLE);
+    let normals = fixture.floats(&[[0.0, 0.0, 1.0]; 3]);
+    let tex_coords = fixture.floats(&[[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]]);
+    let indices = fixture.indices(&[0, 1, 2]);
+    let attributes = json!({ "POSITION": positions, "NORMAL": normals, "TEXCOORD_0": tex_coords });
+    let mesh = json!({
+        "primitives": [
+            { "attributes": attributes, "indices": indices, "material": 0 },
+            { "attributes": attributes, "indices": indices, "material": 1 }
+        ]
+    });
+    let materials = json!([
+        { "pbrMetallicRoughness": { "baseColorFactor": [1.0, 0.5, 0.0, 1.0], "metallicFactor": 0.0 } },
+        { "pbrMetallicRoughness": { "roughnessFactor": 0.5 } }
+    ]);
+    let path = fixture.write("export_loaded", single_mesh(mesh, materials));
+    let model = load_model_with(&path, &LoaderOptions::default()).unwrap();
+    assert_eq!((model.meshes.len(), model.materials.len()), (2, 2));
+
+    let exported = temp_dir("export_loaded_out").join("model.glb");
+    export_gltf(&model, &exported, true).unwrap();
+    let reloaded = load_model_with(&exported, &LoaderOptions::default()).unwrap();
+    assert_same_geometry(&reloaded, &model);
+    assert_same_materials(&reloaded, &model);
+    assert_eq!(reloaded.materials[0].metallic_factor, 0.0);
+}
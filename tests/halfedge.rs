@@ -0,0 +1,90 @@
+use glam::*;
+use motley::model::{HalfEdgeMesh, Mesh};
+
+#[test]
+fn cube_corners_join_across_the_face_seams() {
+    let cube = Mesh::cube(2.0);
+    let topology = HalfEdgeMesh::from_mesh(&cube);
+    assert_eq!(topology.vertex_count(), 8);
+    assert_eq!(topology.face_count(), 12);
+    assert_eq!(topology.edges().count(), 18);
+    assert_eq!(topology.boundary_edges().count(), 0);
+
+    for vertex in 0..topology.vertex_count() {
+        // Every corner touches three sides, each split into one or two triangles at the corner.
+        let faces: Vec<usize> = topology.vertex_faces(vertex).collect();
+        let expected = (0..topology.face_count()).filter(|&face| topology.face_vertices(face).contains(&vertex)).count();
+        assert_eq!(faces.len(), expected);
+        assert!((3..=6).contains(&faces.len()));
+        assert!(faces.iter().all(|&face| topology.face_vertices(face).contains(&vertex)));
+
+        let neighbors: Vec<usize> = topology.vertex_neighbors(vertex).collect();
+        assert_eq!(neighbors.len(), faces.len());
+        for neighbor in neighbors {
+            // The three edges of the cube and the face diagonals are at most sqrt(8) long.
+            assert!(topology.position(vertex).distance(topology.position(neighbor)) <= 8f32.sqrt() + 1e-6);
+        }
+        assert!(!topology.is_boundary_vertex(vertex));
+    }
+
+    for (index, half_edge) in topology.half_edges().iter().enumerate() {
+        let twin = half_edge.twin.unwrap();
+        assert_eq!(topology.half_edges()[twin].twin, Some(index));
+        assert_eq!(topology.destination(twin), half_edge.origin);
+    }
+    assert!((0..12).all(|face| topology.face_neighbors(face).count() == 3));
+    assert_eq!(topology.to_mesh().indices, cube.indices);
+}
+
+#[test]
+fn plane_border_is_its_boundary() {
+    let plane = Mesh::plane(1.0, 1.0, 2);
+    let topology = HalfEdgeMesh::from_mesh(&plane);
+    assert_eq!(topology.boundary_edges().count(), 8);
+    assert_eq!(plane.boundary_edges().len(), 8);
+    assert!(!plane.is_watertight());
+
+    let center = (0..topology.vertex_count()).find(|&vertex| topology.position(vertex) == Vec3::ZERO).unwrap();
+    assert!(!topology.is_boundary_vertex(center));
+    let corner = (0..topology.vertex_count()).find(|&vertex| topology.position(vertex) == Vec3::new(0.5, 0.0, 0.5)).unwrap();
+    assert!(topology.is_boundary_vertex(corner));
+}
+
+#[test]
+fn open_topped_box_reports_its_rim() {
+    let cube = Mesh::cube(2.0);
+    assert!(cube.boundary_edges().is_empty());
+    assert!(cube.is_watertight());
+
+    let mut open = cube.clone();
+    let walls: Vec<u32> = cube.indices.triangles()
+        .filter(|triangle| cube.vertices[triangle[0] as usize].normal != Vec3::Y)
+        .flatten()
+        .collect();
+    open.indices.replace(walls);
+    assert_eq!(open.triangle_count(), 10);
+    assert!(!open.is_watertight());
+
+    let rim: Vec<[Vec3; 2]> = open.boundary_edges()
+        .into_iter()
+        .map(|(a, b)| [open.vertices[a as usize].position, open.vertices[b as usize].position])
+        .collect();
+    assert_eq!(rim.len(), 4);
+    for [a, b] in &rim {
+        assert_eq!((a.y, b.y), (1.0, 1.0));
+        assert_eq!(a.distance(*b), 2.0);
+    }
+    // Each rim edge follows the winding of its wall, so they chain around the opening.
+    for [_, end] in &rim {
+        assert_eq!(rim.iter().filter(|[start, _]| start == end).count(), 1);
+    }
+}
+
+#[test]
+fn edges_shared_by_three_faces_are_not_watertight() {
+    let mut cube = Mesh::cube(2.0);
+    let fin = cube.indices.triangle(0);
+    cube.indices.extend(fin);
+    assert!(cube.boundary_edges().is_empty());
+    assert!(!cube.is_watertight());
+}
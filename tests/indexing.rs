@@ -0,0 +1,47 @@
+use glam::*;
+use motley::model::{Mesh, MeshBuilder};
+
+#[test]
+fn soup_round_trips_through_an_index_buffer() {
+    let sphere = Mesh::uv_sphere(1.0, 12);
+    let soup = sphere.to_non_indexed();
+    assert_eq!(soup.vertices.len(), sphere.indices.len());
+    assert_eq!(soup.indices.to_u32(), (0..soup.vertices.len() as u32).collect::<Vec<_>>());
+
+    // The sphere repeats no vertex, but its seam has a corner at each pole no triangle uses.
+    let indexed = soup.to_indexed();
+    let used: std::collections::HashSet<u32> = sphere.indices.to_u32().into_iter().collect();
+    assert_eq!(indexed.vertices.len(), used.len());
+    assert_eq!(indexed.triangle_count(), sphere.triangle_count());
+
+    let again = indexed.to_non_indexed();
+    assert_eq!(again.interleaved_bytes(), soup.interleaved_bytes());
+    assert_eq!(again.indices, soup.indices);
+}
+
+#[test]
+fn indexing_matches_vertices_bitwise() {
+    let mut builder = MeshBuilder::new();
+    for position in [Vec3::ZERO, Vec3::X, Vec3::Y, Vec3::new(-0.0, 0.0, 0.0), Vec3::Y, Vec3::X] {
+        builder.add_vertex(position, Vec3::Z, Vec2::ZERO);
+    }
+    builder.add_triangle(0, 1, 2).unwrap();
+    builder.add_triangle(3, 5, 4).unwrap();
+    let indexed = builder.build().to_indexed();
+
+    // Negative zero is a different bit pattern, so that corner stays apart.
+    assert_eq!(indexed.vertices.len(), 4);
+    assert_eq!(indexed.indices.to_u32(), [0, 1, 2, 3, 1, 2]);
+}
+
+#[test]
+fn broken_triangles_are_dropped() {
+    let mut cube = Mesh::cube(1.0);
+    for index in [0, 1, 99, 2] {
+        cube.indices.push(index);
+    }
+    assert_eq!(cube.to_non_indexed().vertices.len(), 36);
+    let indexed = cube.to_indexed();
+    assert_eq!(indexed.vertices.len(), 24);
+    assert_eq!(indexed.to_non_indexed().interleaved_bytes(), Mesh::cube(1.0).to_non_indexed().interleaved_bytes());
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use common::*;
+use glam::*;
+use motley::model::{load_model_with, Indices, LoaderOptions, Mesh, Model};
+use serde_json::json;
+
+#[test]
+fn indices_are_stored_at_the_smallest_width() {
+    let small = Indices::for_vertices(vec![0, 1, 2], 3);
+    assert!(small.is_u16());
+    assert_eq!(small.to_le_bytes(), [0, 0, 1, 0, 2, 0]);
+
+    let reserved = Indices::for_vertices(vec![0, 1, 65535], 70000);
+    assert!(!reserved.is_u16());
+    assert_eq!(reserved.element_size(), 4);
+}
+
+#[test]
+fn large_indices_widen_the_buffer() {
+    let mut indices = Indices::from(vec![0u16, 1, 2]);
+    indices.push(65534);
+    assert!(indices.is_u16());
+    indices.push(65535);
+    assert!(!indices.is_u16());
+    assert_eq!(indices.to_u32(), [0, 1, 2, 65534, 65535]);
+
+    let mut indices = Indices::from(vec![0u16, 1, 2]);
+    indices.set(1, 100_000);
+    assert_eq!(indices.to_u32(), [0, 100_000, 2]);
+    assert!(!indices.shrink());
+
+    indices.set(1, 1);
+    assert!(indices.shrink());
+    assert!(indices.is_u16());
+    assert_eq!(indices.to_u32(), [0, 1, 2]);
+}
+
+#[test]
+fn loader_picks_16_bit_indices_for_small_meshes() {
+    let mut fixture = Fixture::new();
+    let positions = fixture.floats(&TRIANGLE);
+    let indices = fixture.indices(&[0, 1, 2]);
+    let mesh = json!({ "primitives": [{ "attributes": { "POSITION": positions }, "indices": indices }] });
+    let path = fixture.write("small_indices", single_mesh(mesh, json!([])));
+
+    let model = load_model_with(&path, &LoaderOptions::default()).unwrap();
+    assert!(model.meshes[0].indices.is_u16());
+    assert_eq!(model.meshes[0].indices.to_u32(), [0, 1, 2]);
+}
+
+#[test]
+fn meshes_shrink_and_widen_their_indices() {
+    let mut cube = Mesh::cube(1.0);
+    let indices = cube.indices.to_u32();
+    cube.widen_indices();
+    assert!(!cube.indices.is_u16());
+    assert_eq!(cube.indices.to_u32(), indices);
+
+    assert!(cube.shrink_indices());
+    assert_eq!(cube.indices.to_u32(), indices);
+
+    let mut large = Mesh::plane(1.0, 1.0, 300);
+    assert!(large.vertices.len() > u16::MAX as usize);
+    assert!(!large.shrink_indices());
+}
+
+#[test]
+fn both_widths_give_the_same_results() {
+    let narrow = Mesh::cube(1.0);
+    let mut wide = narrow.clone();
+    wide.widen_indices();
+
+    let hit = |mesh: &Mesh| mesh.raycast(Vec3::new(0.1, 0.2, 5.0), Vec3::NEG_Z, true);
+    assert_eq!(hit(&narrow), hit(&wide));
+    assert_eq!(narrow.interleaved_bytes(), wide.interleaved_bytes());
+
+    let mut model = Model::from_mesh(narrow);
+    model.merge(Model::from_mesh(wide));
+    model.deduplicate_materials();
+    assert_eq!(model.merge_by_material(), 1);
+    assert_eq!(model.meshes[0].indices.len(), 72);
+    assert!(model.meshes[0].indices.is_u16());
+}
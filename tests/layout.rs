@@ -0,0 +1,105 @@
+use glam::*;
+use motley::model::{vertex_layout, Mesh, MeshBuilder, VertexFormat, VertexSemantic, VERTEX_STRIDE};
+
+fn floats(bytes: &[u8]) -> Vec<f32> {
+    bytes.chunks_exact(4).map(|chunk| f32::from_le_bytes(chunk.try_into().unwrap())).collect()
+}
+
+#[test]
+fn interleaved_bytes_follow_the_vertex_layout() {
+    let mut builder = MeshBuilder::new();
+    builder.add_vertex(Vec3::new(1.0, 2.0, 3.0), Vec3::Z, Vec2::new(0.25, 0.75));
+    builder.add_vertex(Vec3::X, Vec3::Z, Vec2::X);
+    builder.add_vertex(Vec3::Y, Vec3::Z, Vec2::Y);
+    builder.add_triangle(0, 1, 2).unwrap();
+    let mut mesh = builder.build();
+    mesh.vertices[0].tangent = Vec4::new(1.0, 0.0, 0.0, -1.0);
+    mesh.vertices[0].barycentric = Vec3::X;
+
+    let bytes = mesh.interleaved_bytes();
+    assert_eq!(bytes.len(), mesh.vertices.len() * VERTEX_STRIDE);
+
+    let first = &bytes[..VERTEX_STRIDE];
+    let read = |semantic: VertexSemantic| {
+        let attribute = vertex_layout().iter().find(|attribute| attribute.semantic == semantic).unwrap();
+        floats(&first[attribute.offset..attribute.offset + attribute.format.size()])
+    };
+    assert_eq!(read(VertexSemantic::Position), [1.0, 2.0, 3.0]);
+    assert_eq!(read(VertexSemantic::Normal), [0.0, 0.0, 1.0]);
+    assert_eq!(read(VertexSemantic::TexCoord), [0.25, 0.75]);
+    assert_eq!(read(VertexSemantic::Tangent), [1.0, 0.0, 0.0, -1.0]);
+    assert_eq!(read(VertexSemantic::Barycentric), [1.0, 0.0, 0.0]);
+    assert_eq!(first[60..], [0; 4]);
+}
+
+#[test]
+fn vertex_layout_attributes_fit_the_stride_without_overlapping() {
+    let layout = vertex_layout();
+    assert_eq!(layout[0].offset, 0);
+    for pair in layout.windows(2) {
+        assert_eq!(pair[0].offset + pair[0].format.size(), pair[1].offset);
+    }
+    let last = layout.last().unwrap();
+    assert!(last.offset + last.format.size() <= VERTEX_STRIDE);
+    assert_eq!(Mesh::cube(1.0).interleaved_bytes().len(), 24 * VERTEX_STRIDE);
+}
+
+#[cfg(feature = "bytemuck")]
+#[test]
+fn vertices_cast_to_the_interleaved_bytes() {
+    let cube = Mesh::cube(1.0);
+    let bytes: &[u8] = bytemuck::cast_slice(&cube.vertices);
+    assert_eq!(bytes.len(), cube.vertices.len() * VERTEX_STRIDE);
+    assert_eq!(bytes, cube.interleaved_bytes());
+
+    let vertices: &[motley::model::Vertex] = bytemuck::cast_slice(bytes);
+    assert_eq!(vertices[3].position, cube.vertices[3].position);
+}
+
+#[test]
+fn attribute_iterators_follow_the_vertices() {
+    let plane = Mesh::plane(2.0, 2.0, 1);
+    assert_eq!(plane.positions().len(), 4);
+    assert_eq!(plane.positions().next(), Some(plane.vertices[0].position.to_array()));
+    assert!(plane.normals().all(|normal| normal == [0.0, 1.0, 0.0]));
+    let tex_coords: Vec<[f32; 2]> = plane.tex_coords().collect();
+    assert_eq!(tex_coords, [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0], [1.0, 1.0]]);
+    assert_eq!(plane.tangents().len(), 4);
+    assert_eq!(plane.barycentrics().len(), 4);
+}
+
+#[test]
+fn packed_vertices_hold_the_requested_attributes() {
+    let mut plane = Mesh::plane(2.0, 2.0, 1);
+    plane.vertices[0].tangent = Vec4::new(0.5, 0.0, 1.5, -1.0);
+
+    let shadow = plane.pack_vertices(&[(VertexSemantic::Position, VertexFormat::Float32x3)]);
+    assert_eq!(shadow.stride, 12);
+    assert_eq!(shadow.bytes.len(), 4 * 12);
+    assert_eq!(floats(&shadow.bytes[..12]), plane.vertices[0].position.to_array());
+
+    let main = plane.pack_vertices(&[
+        (VertexSemantic::TexCoord, VertexFormat::Float32x2),
+        (VertexSemantic::Tangent, VertexFormat::Unorm8x4),
+        (VertexSemantic::Position, VertexFormat::Float32x4)
+    ]);
+    let offsets: Vec<usize> = main.attributes.iter().map(|attribute| attribute.offset).collect();
+    assert_eq!(offsets, [0, 8, 12]);
+    assert_eq!(main.stride, 28);
+    assert_eq!(main.bytes.len(), 4 * 28);
+
+    let first = &main.bytes[..28];
+    assert_eq!(floats(&first[..8]), [0.0, 0.0]);
+    // Unorm values are clamped to [0, 1] and rounded to the nearest step.
+    assert_eq!(first[8..12], [128, 0, 255, 0]);
+    assert_eq!(floats(&first[12..28]), [-1.0, 0.0, -1.0, 1.0]);
+}
+
+#[test]
+fn packed_attributes_follow_each_other_without_gaps() {
+    let cube = Mesh::cube(1.0);
+    let packed = cube.pack_vertices(&[(VertexSemantic::Normal, VertexFormat::Unorm8x4), (VertexSemantic::TexCoord, VertexFormat::Float32x2)]);
+    assert_eq!(packed.stride, 12);
+    assert_eq!(packed.attributes[1].offset, 4);
+    assert!(cube.pack_vertices(&[]).bytes.is_empty());
+}
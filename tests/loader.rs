@@ -0,0 +1,470 @@
+mod common;
+
+use common::*;
+use glam::*;
+use motley::model::{load_model, load_model_reporting, load_model_with, load_texture, stream_meshes, Aabb, LoadError, LoadPhase, LoaderOptions, TextureLoading};
+use serde_json::{json, Value};
+
+#[test]
+fn non_indexed_primitive_gets_sequential_indices() {
+    let mut fixture = Fixture::new();
+    let positions = fixture.floats(&TRIANGLE);
+    let mesh = json!({ "primitives": [{ "attributes": { "POSITION": positions } }] });
+    let path = fixture.write("non_indexed", single_mesh(mesh, json!([])));
+
+    let model = load_model_with(&path, &LoaderOptions::default()).unwrap();
+    assert_eq!(model.meshes[0].indices.iter().collect::<Vec<u32>>(), [0, 1, 2]);
+
+    let streamed = stream_meshes(&path).next().unwrap().unwrap();
+    assert_eq!(streamed.indices.iter().collect::<Vec<u32>>(), [0, 1, 2]);
+}
+
+#[test]
+fn primitive_without_positions_fails_the_load() {
+    let mut fixture = Fixture::new();
+    let normals = fixture.floats(&TRIANGLE);
+    let indices = fixture.indices(&[0, 1, 2]);
+    let mesh = json!({ "primitives": [{ "attributes": { "NORMAL": normals }, "indices": indices }] });
+    let path = fixture.write("no_positions", single_mesh(mesh, json!([])));
+
+    assert!(load_model_with(&path, &LoaderOptions::default()).is_err());
+}
+
+#[test]
+fn unreadable_positions_fail_the_load() {
+    let mut fixture = Fixture::new();
+    let bytes: Vec<u8> = [0u32, 0, 0, 1, 0, 0, 0, 1, 0].iter().flat_map(|value| value.to_le_bytes()).collect();
+    let view = fixture.view(&bytes);
+    fixture.accessors.push(json!({
+        "bufferView": view,
+        "componentType": 5125,
+        "count": 3,
+        "type": "VEC3",
+        "min": [0, 0, 0],
+        "max": [1, 1, 0]
+    }));
+    let mesh = json!({ "primitives": [{ "attributes": { "POSITION": 0 } }] });
+    let path = fixture.write("unreadable_positions", single_mesh(mesh, json!([])));
+
+    match load_model_with(&path, &LoaderOptions::default()) {
+        Err(LoadError::InvalidData(message)) => assert!(message.contains("primitive 0 of mesh 0"), "{}", message),
+        other => panic!("expected InvalidData, got {:?}", other.map(|model| model.meshes.len()))
+    }
+}
+
+#[cfg(feature = "draco")]
+#[test]
+fn corrupt_draco_primitive_fails_the_load() {
+    let mut fixture = Fixture::new();
+    let view = fixture.view(&[0xde, 0xad, 0xbe, 0xef, 1, 2, 3, 4]);
+    fixture.accessors.push(json!({
+        "componentType": 5126,
+        "count": 3,
+        "type": "VEC3",
+        "min": [0.0, 0.0, 0.0],
+        "max": [1.0, 1.0, 0.0]
+    }));
+    let mesh = json!({ "primitives": [{
+        "attributes": { "POSITION": 0 },
+        "extensions": { "KHR_draco_mesh_compression": { "bufferView": view, "attributes": { "POSITION": 0 } } }
+    }] });
+    let mut document = single_mesh(mesh, json!([]));
+    document["extensionsUsed"] = json!(["KHR_draco_mesh_compression"]);
+    document["extensionsRequired"] = json!(["KHR_draco_mesh_compression"]);
+    let path = fixture.write("corrupt_draco", document);
+
+    match load_model_with(&path, &LoaderOptions::default()) {
+        Err(LoadError::InvalidData(message)) => assert!(message.contains("primitive 0 of mesh 0"), "{}", message),
+        other => panic!("expected InvalidData, got {:?}", other.map(|model| model.meshes.len()))
+    }
+}
+
+#[test]
+fn primitives_without_material_get_their_own_default_slot() {
+    let mut fixture = Fixture::new();
+    let positions = fixture.floats(&TRIANGLE);
+    let mesh = json!({ "primitives": [
+        { "attributes": { "POSITION": positions } },
+        { "attributes": { "POSITION": positions }, "material": 0 }
+    ] });
+    let materials = json!([{ "name": "red", "pbrMetallicRoughness": { "baseColorFactor": [1.0, 0.0, 0.0, 1.0] } }]);
+    let path = fixture.write("default_material", single_mesh(mesh, materials));
+
+    let model = load_model_with(&path, &LoaderOptions::default()).unwrap();
+    assert_eq!(model.materials.len(), 2);
+    assert_eq!(model.materials[0].name.as_deref(), Some("red"));
+    assert_eq!(model.materials[0].base_color, glam::Vec4::new(1.0, 0.0, 0.0, 1.0));
+    assert_eq!(model.materials[1].name, None);
+    assert_eq!(model.materials[1].base_color, glam::Vec4::ONE);
+    assert_eq!(model.meshes[0].material.index(), 1);
+    assert_eq!(model.meshes[1].material.index(), 0);
+
+    let streamed: Vec<usize> = stream_meshes(&path).map(|mesh| mesh.unwrap().material.index()).collect();
+    assert_eq!(streamed, [1, 0]);
+}
+
+#[test]
+fn documents_with_only_materialless_primitives_keep_one_default() {
+    let mut fixture = Fixture::new();
+    let positions = fixture.floats(&TRIANGLE);
+    let mesh = json!({ "primitives": [{ "attributes": { "POSITION": positions } }] });
+    let path = fixture.write("only_default_material", single_mesh(mesh, json!([])));
+
+    let model = load_model_with(&path, &LoaderOptions::default()).unwrap();
+    assert_eq!(model.materials.len(), 1);
+    assert_eq!(model.meshes[0].material.index(), 0);
+}
+
+#[test]
+fn repeated_loads_give_identical_bytes() {
+    let directory = temp_dir("deterministic");
+    let options = LoaderOptions::new().generate_normals(true).texture_loading(TextureLoading::Skip);
+    let load = |name: &str| {
+        let mut model = load_model_with("assets/DamagedHelmet/DamagedHelmet.gltf", &options).unwrap();
+        model.deduplicate_materials();
+        for mesh in &mut model.meshes {
+            mesh.weld_vertices(1e-5, true);
+        }
+        let path = directory.join(name);
+        model.save_cache(&path).unwrap();
+        let vertices: Vec<Vec<u8>> = model.meshes.iter().map(|mesh| mesh.interleaved_bytes()).collect();
+        let indices: Vec<Vec<u32>> = model.meshes.iter().map(|mesh| mesh.indices.iter().collect()).collect();
+        (vertices, indices, std::fs::read(path).unwrap())
+    };
+
+    let (first_vertices, first_indices, first_cache) = load("first.cache");
+    let (second_vertices, second_indices, second_cache) = load("second.cache");
+    assert_eq!(first_vertices, second_vertices);
+    assert_eq!(first_indices, second_indices);
+    assert_eq!(first_cache, second_cache);
+}
+
+#[test]
+fn progress_counts_every_mesh_before_finishing() {
+    let mut fixture = Fixture::new();
+    let primitives: Vec<_> = (0..3)
+        .map(|_| {
+            let positions = fixture.floats(&TRIANGLE);
+            let indices = fixture.indices(&[0, 1, 2]);
+            json!({ "attributes": { "POSITION": positions }, "indices": indices })
+        })
+        .collect();
+    let document = single_mesh(json!({ "primitives": primitives }), json!([]));
+    let path = fixture.write("progress", document);
+
+    let mut phases = Vec::new();
+    let model = load_model_reporting(&path, |progress| phases.push(progress.phase)).unwrap();
+    assert_eq!(model.meshes.len(), 3);
+
+    let counts: Vec<(usize, usize)> = phases
+        .iter()
+        .filter_map(|phase| match *phase {
+            LoadPhase::ProcessingMeshes { processed, total } => Some((processed, total)),
+            _ => None
+        })
+        .collect();
+    assert_eq!(counts.first(), Some(&(0, 3)));
+    assert_eq!(counts.last(), Some(&(3, 3)));
+    assert!(counts.windows(2).all(|pair| pair[0].0 <= pair[1].0 && pair[1].1 == 3));
+    assert_eq!(phases.last(), Some(&LoadPhase::Finished));
+    let last_mesh = phases.iter().rposition(|phase| matches!(phase, LoadPhase::ProcessingMeshes { .. })).unwrap();
+    assert_eq!(last_mesh, phases.len() - 2);
+}
+
+#[test]
+fn invalid_scale_is_refused_by_the_builder() {
+    let mut fixture = Fixture::new();
+    let positions = fixture.floats(&TRIANGLE);
+    let mesh = json!({ "primitives": [{ "attributes": { "POSITION": positions } }] });
+    let path = fixture.write("invalid_scale", single_mesh(mesh, json!([])));
+
+    for scale in [0.0, -0.0, f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+        assert!(matches!(LoaderOptions::new().scale(scale), Err(LoadError::InvalidOptions(_))));
+    }
+    let model = load_model_with(&path, &LoaderOptions::new().scale(2.0).unwrap()).unwrap();
+    assert_eq!(model.meshes[0].vertices[1].position.x, 2.0);
+}
+
+#[test]
+fn missing_or_malformed_files_fail_the_load() {
+    let directory = temp_dir("malformed");
+    let missing = load_model_with(directory.join("missing.gltf"), &LoaderOptions::default());
+    assert!(matches!(missing, Err(LoadError::Gltf(gltf::Error::Io(_)))));
+
+    let path = directory.join("broken.gltf");
+    std::fs::write(&path, "{ \"asset\": ").unwrap();
+    assert!(matches!(load_model_with(&path, &LoaderOptions::default()), Err(LoadError::Gltf(_))));
+}
+
+#[test]
+fn paths_with_spaces_and_percent_encoded_uris_load() {
+    let mut fixture = Fixture::new();
+    let positions = fixture.floats(&TRIANGLE);
+    let tex_coords = fixture.floats(&[[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]]);
+    let mesh = json!({ "primitives": [{ "attributes": { "POSITION": positions, "TEXCOORD_0": tex_coords }, "material": 0 }] });
+    let material = json!({ "pbrMetallicRoughness": { "baseColorTexture": { "index": 0 } } });
+    let mut document = single_mesh(mesh, json!([material]));
+    document["images"] = json!([{ "uri": "my%20texture%231.png" }]);
+    document["textures"] = json!([{ "source": 0 }]);
+    let path = fixture.write("spaced path", document);
+    let image = path.with_file_name("my texture#1.png");
+    std::fs::write(&image, png()).unwrap();
+
+    let model = load_model(path.clone());
+    let texture = model.materials[0].base_color_texture.as_ref().unwrap();
+    assert_eq!((texture.width(), texture.height()), (2, 2));
+    assert_eq!(load_texture(&image).unwrap().width(), 2);
+
+    // Paths that are not UTF-8 are valid on Linux and load the same way.
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        let mut name = b"motley-test-\xff-".to_vec();
+        name.extend(std::process::id().to_string().bytes());
+        let raw = path.parent().unwrap().with_file_name(std::ffi::OsStr::from_bytes(&name));
+        let _ = std::fs::remove_dir_all(&raw);
+        std::fs::rename(path.parent().unwrap(), &raw).unwrap();
+        let loaded = load_model_with(raw.join("spaced path.gltf"), &LoaderOptions::default()).unwrap();
+        assert!(loaded.materials[0].base_color_texture.is_some());
+        std::fs::remove_dir_all(&raw).unwrap();
+    }
+}
+
+#[test]
+fn missing_buffer_file_names_the_buffer() {
+    let mut fixture = Fixture::new();
+    let positions = fixture.floats(&TRIANGLE);
+    let mesh = json!({ "primitives": [{ "attributes": { "POSITION": positions } }] });
+    let path = fixture.write("missing_buffer", single_mesh(mesh, json!([])));
+    std::fs::remove_file(path.with_file_name("buffer.bin")).unwrap();
+
+    match load_model_with(&path, &LoaderOptions::default()) {
+        Err(LoadError::Io(error)) => assert!(error.to_string().starts_with("Buffer 0 could not be read")),
+        other => panic!("Expected an I/O error, got {:?}", other.map(|_| ()))
+    }
+}
+
+#[test]
+fn buffers_resolve_percent_encoded_uris_beside_the_model() {
+    let mut fixture = Fixture::new();
+    let positions = fixture.floats(&TRIANGLE);
+    let mesh = json!({ "primitives": [{ "attributes": { "POSITION": positions } }] });
+    let path = fixture.write("spaced_buffer", single_mesh(mesh, json!([])));
+    let directory = path.parent().unwrap();
+
+    // The buffer lives in a sibling directory, under a name with a space.
+    let mut document: Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+    document["buffers"][0]["uri"] = json!("data/my%20buffer.bin");
+    std::fs::write(&path, serde_json::to_vec(&document).unwrap()).unwrap();
+    std::fs::create_dir(directory.join("data")).unwrap();
+    std::fs::rename(directory.join("buffer.bin"), directory.join("data/my buffer.bin")).unwrap();
+
+    let model = load_model_with(&path, &LoaderOptions::default()).unwrap();
+    assert_eq!(model.meshes[0].vertices.len(), 3);
+
+    // A link to the model elsewhere still finds the buffer next to the file it points to.
+    #[cfg(unix)]
+    {
+        let elsewhere = temp_dir("spaced_buffer_link");
+        let link = elsewhere.join("linked.gltf");
+        std::os::unix::fs::symlink(&path, &link).unwrap();
+        let linked = load_model_with(&link, &LoaderOptions::default()).unwrap();
+        assert_eq!(linked.meshes[0].vertices.len(), 3);
+    }
+}
+
+#[test]
+fn loaded_meshes_take_their_bounds_from_the_accessor() {
+    let mut fixture = Fixture::new();
+    let positions = fixture.floats(&TRIANGLE);
+    // Looser bounds than the positions are still valid glTF, and tell the two sources apart.
+    fixture.accessors[positions]["min"] = json!([-1.0, -2.0, -3.0]);
+    fixture.accessors[positions]["max"] = json!([4.0, 5.0, 6.0]);
+    let mesh = json!({ "primitives": [{ "attributes": { "POSITION": positions } }] });
+    let path = fixture.write("accessor_bounds", single_mesh(mesh, json!([])));
+
+    let model = load_model_with(&path, &LoaderOptions::default()).unwrap();
+    let declared = Aabb::new(Vec3::new(-1.0, -2.0, -3.0), Vec3::new(4.0, 5.0, 6.0));
+    assert_eq!(model.meshes[0].cached_bounds(), Some(declared));
+    assert_eq!(model.aabb(), declared);
+}
+
+#[test]
+fn loaded_meshes_with_unusable_accessor_bounds_are_scanned() {
+    let mut fixture = Fixture::new();
+    let positions = fixture.floats(&TRIANGLE);
+    fixture.accessors[positions]["min"] = json!([1.0, 1.0, 1.0]);
+    fixture.accessors[positions]["max"] = json!([0.0, 0.0, 0.0]);
+    let mesh = json!({ "primitives": [{ "attributes": { "POSITION": positions } }] });
+    let path = fixture.write("scanned_bounds", single_mesh(mesh, json!([])));
+
+    let model = load_model_with(&path, &LoaderOptions::default()).unwrap();
+    let scanned = Aabb::new(Vec3::ZERO, Vec3::new(1.0, 1.0, 0.0));
+    assert_eq!(model.meshes[0].cached_bounds(), Some(scanned));
+}
+
+#[test]
+fn gpu_instancing_gives_the_mesh_its_instances() {
+    let mut fixture = Fixture::new();
+    let positions = fixture.floats(&TRIANGLE);
+    let translations = fixture.floats(&[[0.0, 0.0, 0.0], [5.0, 0.0, 0.0], [0.0, 0.0, -5.0]]);
+    let scales = fixture.floats(&[[1.0, 1.0, 1.0], [2.0, 2.0, 2.0], [1.0, 1.0, 1.0]]);
+    let document = json!({
+        "extensionsUsed": ["EXT_mesh_gpu_instancing"],
+        "scene": 0,
+        "scenes": [{ "nodes": [0] }],
+        "nodes": [{
+            "mesh": 0,
+            "extensions": { "EXT_mesh_gpu_instancing": { "attributes": { "TRANSLATION": translations, "SCALE": scales } } }
+        }],
+        "meshes": [{ "primitives": [{ "attributes": { "POSITION": positions } }] }]
+    });
+    let path = fixture.write("instancing", document);
+
+    let model = load_model_with(&path, &LoaderOptions::default()).unwrap();
+    let instances = &model.meshes[0].instances;
+    assert_eq!(instances.len(), 3);
+    assert_eq!(instances[1], Mat4::from_scale_rotation_translation(Vec3::splat(2.0), Quat::IDENTITY, Vec3::new(5.0, 0.0, 0.0)));
+    assert_eq!(instances[2].transform_point3(Vec3::X), Vec3::new(1.0, 0.0, -5.0));
+
+    let plain = fixture.write("not_instanced", single_mesh(json!({ "primitives": [{ "attributes": { "POSITION": positions } }] }), json!([])));
+    assert!(load_model_with(&plain, &LoaderOptions::default()).unwrap().meshes[0].instances.is_empty());
+}
+
+#[test]
+fn mesh_and_material_names_are_kept() {
+    let mut fixture = Fixture::new();
+    let positions = fixture.floats(&TRIANGLE);
+    let mesh = json!({ "name": "Body", "primitives": [{ "attributes": { "POSITION": positions }, "material": 1 }] });
+    let path = fixture.write("names", single_mesh(mesh, json!([{ "name": "Paint" }, { "name": "Chrome" }, {}])));
+
+    let model = load_model_with(&path, &LoaderOptions::default()).unwrap();
+    assert_eq!(model.meshes[0].name.as_deref(), Some("Body"));
+    let names: Vec<Option<&str>> = model.materials.iter().map(|material| material.name.as_deref()).collect();
+    assert_eq!(names, [Some("Paint"), Some("Chrome"), None]);
+}
+
+#[test]
+fn quantized_attributes_are_dequantized() {
+    let mut fixture = Fixture::new();
+    let accessor = |fixture: &mut Fixture, bytes: Vec<u8>, component_type: u32, kind: &str, normalized: bool, bounds: Option<(Value, Value)>| {
+        let view = fixture.view(&bytes);
+        if kind == "VEC3" {
+            fixture.views[view]["byteStride"] = json!(bytes.len() / 3);
+        }
+        let mut accessor = json!({ "bufferView": view, "componentType": component_type, "count": 3, "type": kind, "normalized": normalized });
+        if let Some((min, max)) = bounds {
+            accessor["min"] = min;
+            accessor["max"] = max;
+        }
+        fixture.accessors.push(accessor);
+        fixture.accessors.len() - 1
+    };
+    let shorts = |values: &[i16]| values.iter().flat_map(|value| value.to_le_bytes()).collect::<Vec<u8>>();
+    // Each vertex of the byte and short attributes is padded to four bytes, as glTF requires.
+    let positions = accessor(&mut fixture, shorts(&[0, 0, 0, 0, 32767, 0, 0, 0, 0, 32767, 0, 0]), 5122, "VEC3", true, Some((json!([0, 0, 0]), json!([32767, 32767, 0]))));
+    let offsets = accessor(&mut fixture, shorts(&[0, 0, 0, 0, 4, 0, 0, 0, 0, 2, 0, 0]), 5122, "VEC3", false, Some((json!([0, 0, 0]), json!([4, 2, 0]))));
+    let normals = accessor(&mut fixture, vec![0, 0, 127, 0, 0, 127, 0, 0, 129, 0, 0, 0], 5120, "VEC3", true, None);
+    let tex_coords = accessor(&mut fixture, [0u16, 0, 65535, 0, 0, 65535].iter().flat_map(|value| value.to_le_bytes()).collect(), 5123, "VEC2", true, None);
+
+    let primitive = |positions: usize| json!({ "attributes": { "POSITION": positions, "NORMAL": normals, "TEXCOORD_0": tex_coords } });
+    let document = json!({
+        "extensionsUsed": ["KHR_mesh_quantization"],
+        "extensionsRequired": ["KHR_mesh_quantization"],
+        "scene": 0,
+        "scenes": [{ "nodes": [0] }],
+        "nodes": [{ "mesh": 0 }],
+        "meshes": [{ "primitives": [primitive(positions), primitive(offsets)] }]
+    });
+    let path = fixture.write("quantized", document);
+
+    let model = load_model_with(&path, &LoaderOptions::default()).unwrap();
+    let normalized = &model.meshes[0];
+    for (vertex, expected) in normalized.vertices.iter().zip(TRIANGLE) {
+        assert!(vertex.position.abs_diff_eq(Vec3::from(expected), 1e-6));
+    }
+    assert_eq!(normalized.vertices.iter().map(|vertex| vertex.normal).collect::<Vec<_>>(), [Vec3::Z, Vec3::Y, Vec3::NEG_X]);
+    assert_eq!(normalized.vertices[1].tex_coord, Vec2::X);
+    assert_eq!(normalized.vertices[2].tex_coord, Vec2::Y);
+
+    let offsets = &model.meshes[1];
+    assert_eq!(offsets.vertices[1].position, Vec3::new(4.0, 0.0, 0.0));
+    assert_eq!(offsets.vertices[2].position, Vec3::new(0.0, 2.0, 0.0));
+    assert_eq!(offsets.aabb(), Aabb::new(Vec3::ZERO, Vec3::new(4.0, 2.0, 0.0)));
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn async_load_matches_the_blocking_load() {
+    let mut fixture = Fixture::new();
+    let positions = fixture.floats(&TRIANGLE);
+    let indices = fixture.indices(&[0, 2, 1]);
+    let mesh = json!({ "name": "triangle", "primitives": [{ "attributes": { "POSITION": positions }, "indices": indices, "material": 0 }] });
+    let path = fixture.write("async", single_mesh(mesh, json!([{ "name": "red", "pbrMetallicRoughness": { "baseColorFactor": [1.0, 0.0, 0.0, 1.0] } }])));
+
+    let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    let loaded = runtime.block_on(motley::model::load_model_async(&path)).unwrap();
+    let blocking = load_model_with(&path, &LoaderOptions::default()).unwrap();
+
+    assert_eq!(loaded.meshes.len(), blocking.meshes.len());
+    assert_eq!(loaded.meshes[0].name, blocking.meshes[0].name);
+    assert_eq!(loaded.meshes[0].indices, blocking.meshes[0].indices);
+    assert_eq!(loaded.meshes[0].interleaved_bytes(), blocking.meshes[0].interleaved_bytes());
+    assert_eq!(loaded.materials.len(), blocking.materials.len());
+    assert_eq!(loaded.materials[0].base_color, blocking.materials[0].base_color);
+
+    let missing = runtime.block_on(motley::model::load_model_async(path.with_file_name("missing.gltf")));
+    assert!(missing.is_err());
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn extras_are_kept_on_meshes_materials_and_nodes() {
+    let mut fixture = Fixture::new();
+    let positions = fixture.floats(&TRIANGLE);
+    let mesh = json!({ "extras": { "collision": true }, "primitives": [{ "attributes": { "POSITION": positions }, "material": 0 }] });
+    let mut document = single_mesh(mesh, json!([{ "extras": { "surface": "metal", "friction": 0.5 } }, {}]));
+    document["nodes"][0]["extras"] = json!({ "spawn": [1, 2] });
+    let path = fixture.write("extras", document);
+
+    let model = load_model_with(&path, &LoaderOptions::default()).unwrap();
+    assert_eq!(model.meshes[0].extras, Some(json!({ "collision": true })));
+    assert_eq!(model.meshes[0].extras.as_ref().unwrap()["collision"], true);
+    assert_eq!(model.materials[0].extras, Some(json!({ "surface": "metal", "friction": 0.5 })));
+    assert_eq!(model.materials[1].extras, None);
+
+    let scene = motley::model::load_scene_graph(&path, 0).unwrap();
+    assert_eq!(scene.nodes[0].extras, Some(json!({ "spawn": [1, 2] })));
+}
+
+#[test]
+fn sparse_accessors_override_their_base_values() {
+    let mut fixture = Fixture::new();
+    let floats = |values: &[[f32; 3]]| -> Vec<u8> { values.iter().flatten().flat_map(|value| value.to_le_bytes()).collect() };
+    let base = fixture.view(&floats(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]]));
+    let moved = fixture.view(&2u16.to_le_bytes());
+    let moved_values = fixture.view(&floats(&[[1.0, 1.0, 3.0]]));
+    let lit = fixture.view(&[1u16, 3].map(u16::to_le_bytes).concat());
+    let lit_values = fixture.view(&floats(&[[0.0, 0.0, 1.0], [0.0, 1.0, 0.0]]));
+    let sparse = |count: usize, indices: usize, values: usize| {
+        json!({ "count": count, "indices": { "bufferView": indices, "componentType": 5123 }, "values": { "bufferView": values } })
+    };
+    fixture.accessors.push(json!({
+        "bufferView": base, "componentType": 5126, "count": 4, "type": "VEC3",
+        "min": [0.0, 0.0, 0.0], "max": [1.0, 1.0, 3.0], "sparse": sparse(1, moved, moved_values)
+    }));
+    // Without a buffer view the base values are all zero.
+    fixture.accessors.push(json!({ "componentType": 5126, "count": 4, "type": "VEC3", "sparse": sparse(2, lit, lit_values) }));
+    let indices = fixture.indices(&[0, 1, 2, 0, 2, 3]);
+    let mesh = json!({ "primitives": [{ "attributes": { "POSITION": 0, "NORMAL": 1 }, "indices": indices }] });
+    let path = fixture.write("sparse", single_mesh(mesh, json!([])));
+
+    let loaded = load_model_with(&path, &LoaderOptions::default()).unwrap();
+    let streamed = stream_meshes(&path).next().unwrap().unwrap();
+    for mesh in [&loaded.meshes[0], &streamed] {
+        let positions: Vec<Vec3> = mesh.vertices.iter().map(|vertex| vertex.position).collect();
+        assert_eq!(positions, [Vec3::ZERO, Vec3::X, Vec3::new(1.0, 1.0, 3.0), Vec3::Y]);
+        let normals: Vec<Vec3> = mesh.vertices.iter().map(|vertex| vertex.normal).collect();
+        assert_eq!(normals, [Vec3::ZERO, Vec3::Z, Vec3::ZERO, Vec3::Y]);
+    }
+}
@@ -0,0 +1,57 @@
+use glam::*;
+use motley::model::{Material, MaterialHandle, Mesh, Model};
+
+/*
+A model of a red and a blue material, with a cube and a plane using red and a sphere using
+blue.
+*/
+fn model() -> Model {
+    let material = |name: &str, base_color: Vec4| Material { name: Some(name.to_string()), base_color, ..Default::default() };
+    let mut sphere = Mesh::uv_sphere(1.0, 8);
+    sphere.material = MaterialHandle::from(1);
+    Model {
+        meshes: vec![Mesh::cube(1.0), sphere, Mesh::plane(1.0, 1.0, 1)],
+        materials: vec![material("red", Vec4::new(1.0, 0.0, 0.0, 1.0)), material("blue", Vec4::new(0.0, 0.0, 1.0, 1.0))]
+    }
+}
+
+#[test]
+fn materials_are_found_by_their_authored_name() {
+    let model = model();
+    assert_eq!(model.material_by_name("blue").unwrap().base_color, Vec4::new(0.0, 0.0, 1.0, 1.0));
+    assert_eq!(model.material_by_name("red").unwrap().base_color, Vec4::new(1.0, 0.0, 0.0, 1.0));
+    assert!(model.material_by_name("green").is_none());
+    assert!(model.material_by_name("Red").is_none());
+}
+
+#[test]
+fn meshes_are_listed_by_material() {
+    let model = model();
+    let red: Vec<usize> = model.meshes_with_material(MaterialHandle::from(0)).map(|mesh| mesh.vertices.len()).collect();
+    assert_eq!(red, [24, 4]);
+    assert_eq!(model.meshes_with_material(MaterialHandle::from(1)).count(), 1);
+    assert_eq!(model.meshes_with_material(MaterialHandle::from(2)).count(), 0);
+}
+
+#[test]
+fn meshes_are_grouped_under_every_material() {
+    let mut model = model();
+    model.materials.push(Material { name: Some("unused".to_string()), ..Default::default() });
+    let mut stray = Mesh::cube(2.0);
+    stray.material = MaterialHandle::from(7);
+    model.meshes.push(stray);
+
+    let groups: Vec<(MaterialHandle, Option<&str>, Vec<usize>)> = model
+        .meshes_by_material()
+        .map(|(handle, material, meshes)| (handle, material.name.as_deref(), meshes.map(|mesh| mesh.vertices.len()).collect()))
+        .collect();
+    // Materials come in order, each once, and the mesh past the material list is left out.
+    assert_eq!(groups, [
+        (MaterialHandle::from(0), Some("red"), vec![24, 4]),
+        (MaterialHandle::from(1), Some("blue"), vec![model.meshes[1].vertices.len()]),
+        (MaterialHandle::from(2), Some("unused"), vec![])
+    ]);
+
+    let unused: Vec<MaterialHandle> = model.meshes_by_material().filter_map(|(handle, _, mut meshes)| meshes.next().is_none().then_some(handle)).collect();
+    assert_eq!(unused, [MaterialHandle::from(2)]);
+}
@@ -0,0 +1,268 @@
+mod common;
+
+use common::*;
+use glam::*;
+use motley::model::{load_model_with, LoaderOptions, Material, Model, TextureChannel, TextureLoading, TextureSource, TextureTransform};
+use serde_json::{json, Value};
+
+/*
+Loads a triangle drawn with `material`, declaring the extensions it uses, and returns the
+loaded material.
+*/
+fn load_material(name: &str, material: Value, extensions: &[&str]) -> Material {
+    let mut fixture = Fixture::new();
+    let positions = fixture.floats(&TRIANGLE);
+    let mesh = json!({ "primitives": [{ "attributes": { "POSITION": positions }, "material": 0 }] });
+    let mut document = single_mesh(mesh, json!([material]));
+    document["extensionsUsed"] = json!(extensions);
+    let path = fixture.write(name, document);
+    load_model_with(&path, &LoaderOptions::default()).unwrap().materials.remove(0)
+}
+
+#[test]
+fn glass_reads_transmission_ior_and_volume() {
+    let glass = json!({
+        "extensions": {
+            "KHR_materials_transmission": { "transmissionFactor": 1.0 },
+            "KHR_materials_ior": { "ior": 1.5 },
+            "KHR_materials_volume": { "thicknessFactor": 0.2, "attenuationColor": [0.9, 1.0, 0.8], "attenuationDistance": 3.0 }
+        }
+    });
+    let material = load_material("glass", glass, &["KHR_materials_transmission", "KHR_materials_ior", "KHR_materials_volume"]);
+
+    let transmission = material.transmission.unwrap();
+    assert_eq!(transmission.factor, 1.0);
+    assert!(transmission.texture.is_none());
+    assert_eq!(material.ior, 1.5);
+    let volume = material.volume.unwrap();
+    assert_eq!(volume.thickness_factor, 0.2);
+    assert_eq!(volume.attenuation_color, Vec3::new(0.9, 1.0, 0.8));
+    assert_eq!(volume.attenuation_distance, 3.0);
+}
+
+#[test]
+fn plain_materials_have_no_transmission() {
+    let material = load_material("opaque", json!({ "extensions": { "KHR_materials_ior": { "ior": 1.33 } } }), &["KHR_materials_ior"]);
+    assert!(material.transmission.is_none());
+    assert!(material.volume.is_none());
+    assert_eq!(material.ior, 1.33);
+
+    let default = load_material("default_ior", json!({}), &[]);
+    assert_eq!(default.ior, 1.5);
+}
+
+#[test]
+fn car_paint_reads_its_clearcoat() {
+    let paint = json!({ "extensions": { "KHR_materials_clearcoat": { "clearcoatFactor": 1.0, "clearcoatRoughnessFactor": 0.25 } } });
+    let material = load_material("clearcoat", paint, &["KHR_materials_clearcoat"]);
+    assert_eq!(material.clearcoat_factor, 1.0);
+    assert_eq!(material.clearcoat_roughness_factor, 0.25);
+    assert!(material.clearcoat_texture.is_none());
+    assert!(material.clearcoat_normal_texture.is_none());
+
+    let plain = load_material("no_clearcoat", json!({}), &[]);
+    assert_eq!((plain.clearcoat_factor, plain.clearcoat_roughness_factor), (0.0, 0.0));
+}
+
+/*
+Loads a triangle whose material samples a PNG through a `KHR_texture_transform` scaling the
+texture coordinates by two and offsetting them by a quarter.
+*/
+fn load_tiled(name: &str, options: &LoaderOptions) -> Model {
+    let mut fixture = Fixture::new();
+    let positions = fixture.floats(&TRIANGLE);
+    let tex_coords = fixture.floats(&[[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]]);
+    let mesh = json!({ "primitives": [{ "attributes": { "POSITION": positions, "TEXCOORD_0": tex_coords }, "material": 0 }] });
+    let transform = json!({ "KHR_texture_transform": { "scale": [2.0, 2.0], "offset": [0.25, 0.0] } });
+    let material = json!({ "pbrMetallicRoughness": { "baseColorTexture": { "index": 0, "extensions": transform } } });
+    let mut document = single_mesh(mesh, json!([material]));
+    document["extensionsUsed"] = json!(["KHR_texture_transform"]);
+    document["images"] = json!([{ "uri": "tile.png" }]);
+    document["textures"] = json!([{ "source": 0 }]);
+    let path = fixture.write(name, document);
+    std::fs::write(path.with_file_name("tile.png"), png()).unwrap();
+    load_model_with(&path, options).unwrap()
+}
+
+#[test]
+fn texture_transform_is_stored_with_the_texture() {
+    let model = load_tiled("texture_transform", &LoaderOptions::default());
+    let texture = model.materials[0].base_color_texture.as_ref().unwrap();
+    let transform = texture.transform();
+    assert_eq!(transform.scale, Vec2::splat(2.0));
+    assert_eq!(transform.offset, Vec2::new(0.25, 0.0));
+    assert_eq!(transform.rotation, 0.0);
+    assert_eq!(model.meshes[0].vertices[1].tex_coord, Vec2::X);
+}
+
+#[test]
+fn texture_transform_can_be_baked_into_the_vertices() {
+    let model = load_tiled("texture_transform_baked", &LoaderOptions::new().bake_texture_transform(true));
+    let tex_coords: Vec<Vec2> = model.meshes[0].vertices.iter().map(|vertex| vertex.tex_coord).collect();
+    assert_eq!(tex_coords, [Vec2::new(0.25, 0.0), Vec2::new(2.25, 0.0), Vec2::new(0.25, 2.0)]);
+    let texture = model.materials[0].base_color_texture.as_ref().unwrap();
+    assert_eq!(texture.transform(), TextureTransform::default());
+}
+
+#[test]
+fn orm_packed_materials_share_their_occlusion_image() {
+    let mut fixture = Fixture::new();
+    let positions = fixture.floats(&TRIANGLE);
+    let primitives: Vec<Value> = (0..3).map(|material| json!({ "attributes": { "POSITION": positions }, "material": material })).collect();
+    let metallic_roughness = json!({ "metallicRoughnessTexture": { "index": 0 } });
+    let materials = json!([
+        // Two textures sampling one image, as ORM exporters write them.
+        { "pbrMetallicRoughness": metallic_roughness, "occlusionTexture": { "index": 1 } },
+        { "pbrMetallicRoughness": metallic_roughness, "occlusionTexture": { "index": 2 } },
+        { "pbrMetallicRoughness": metallic_roughness }
+    ]);
+    let mut document = single_mesh(json!({ "primitives": primitives }), materials);
+    document["images"] = json!([{ "uri": "orm.png" }, { "uri": "occlusion.png" }]);
+    document["textures"] = json!([{ "source": 0 }, { "source": 0 }, { "source": 1 }]);
+    let path = fixture.write("orm_packing", document);
+    for image in ["orm.png", "occlusion.png"] {
+        std::fs::write(path.with_file_name(image), png()).unwrap();
+    }
+
+    let model = load_model_with(&path, &LoaderOptions::default()).unwrap();
+    let shared: Vec<bool> = model.materials.iter().map(|material| material.channel_packing.occlusion_shared).collect();
+    assert_eq!(shared, [true, false, false]);
+
+    let packing = model.materials[0].channel_packing;
+    assert_eq!((packing.occlusion, packing.roughness, packing.metallic), (TextureChannel::R, TextureChannel::G, TextureChannel::B));
+    assert_eq!([packing.occlusion, packing.roughness, packing.metallic].map(|channel| channel.index()), [0, 1, 2]);
+    assert!(model.materials[0].occlusion_texture.is_some() && model.materials[0].metallic_roughness_texture.is_some());
+}
+
+#[test]
+fn every_texture_slot_records_its_uv_set() {
+    let mut fixture = Fixture::new();
+    let positions = fixture.floats(&TRIANGLE);
+    let tex_coords = fixture.floats(&[[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]]);
+    let mesh = json!({ "primitives": [{ "attributes": { "POSITION": positions, "TEXCOORD_0": tex_coords }, "material": 0 }] });
+    // A lightmapped material: detail maps on the first set, occlusion and normals on the second.
+    let material = json!({
+        "pbrMetallicRoughness": {
+            "baseColorTexture": { "index": 0 },
+            "metallicRoughnessTexture": { "index": 0, "texCoord": 0 }
+        },
+        "normalTexture": { "index": 0, "texCoord": 1 },
+        "occlusionTexture": { "index": 0, "texCoord": 1 },
+        "emissiveTexture": {
+            "index": 0,
+            "texCoord": 1,
+            "extensions": { "KHR_texture_transform": { "offset": [0.5, 0.0], "texCoord": 2 } }
+        }
+    });
+    let mut document = single_mesh(mesh, json!([material]));
+    document["extensionsUsed"] = json!(["KHR_texture_transform"]);
+    document["images"] = json!([{ "uri": "lightmap.png" }]);
+    document["textures"] = json!([{ "source": 0 }]);
+    let path = fixture.write("uv_sets", document);
+    std::fs::write(path.with_file_name("lightmap.png"), png()).unwrap();
+
+    let model = load_model_with(&path, &LoaderOptions::new().bake_texture_transform(true)).unwrap();
+    let material = &model.materials[0];
+    let sets = [
+        &material.base_color_texture,
+        &material.metallic_roughness_texture,
+        &material.normal_texture,
+        &material.occlusion_texture,
+        &material.emissive_texture
+    ].map(|texture| texture.as_ref().unwrap().tex_coord());
+    // The transform's own set overrides the one of the texture info, as the extension says.
+    assert_eq!(sets, [0, 0, 1, 1, 2]);
+
+    // Only transforms on the first set can be baked into the vertices.
+    let emissive = material.emissive_texture.as_ref().unwrap();
+    assert_eq!(emissive.transform().offset, Vec2::new(0.5, 0.0));
+    assert_eq!(model.meshes[0].vertices[1].tex_coord, Vec2::X);
+}
+
+/*
+Writes a triangle whose base color texture is the image at `uri`, without writing the image.
+*/
+fn textured(name: &str, uri: &str) -> std::path::PathBuf {
+    let mut fixture = Fixture::new();
+    let positions = fixture.floats(&TRIANGLE);
+    let mesh = json!({ "primitives": [{ "attributes": { "POSITION": positions }, "material": 0 }] });
+    let material = json!({ "pbrMetallicRoughness": { "baseColorTexture": { "index": 0 } } });
+    let mut document = single_mesh(mesh, json!([material]));
+    document["images"] = json!([{ "uri": uri }]);
+    document["textures"] = json!([{ "source": 0 }]);
+    fixture.write(name, document)
+}
+
+#[test]
+fn truncated_images_leave_the_material_without_a_texture() {
+    let path = textured("truncated_texture", "albedo.png");
+    let bytes = png();
+    std::fs::write(path.with_file_name("albedo.png"), &bytes[..bytes.len() / 2]).unwrap();
+
+    let heard = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let sink = heard.clone();
+    let options = LoaderOptions::new().warning_handler(move |warning| sink.lock().unwrap().push(warning.to_string()));
+    let model = load_model_with(&path, &options).unwrap();
+    assert!(model.materials[0].base_color_texture.is_none());
+    let heard = heard.lock().unwrap();
+    assert_eq!(heard.iter().filter(|warning| warning.starts_with("Left textures of image 0 empty.")).count(), 1);
+
+    let placeholder = load_model_with(&path, &LoaderOptions::new().placeholder_textures(true)).unwrap();
+    let texture = placeholder.materials[0].base_color_texture.as_ref().unwrap();
+    assert_eq!((texture.width(), texture.height()), (1, 1));
+    assert_eq!(texture.pixel(0, 0), Vec4::new(1.0, 0.0, 1.0, 1.0));
+}
+
+#[test]
+fn texture_resolver_supplies_images_missing_from_disk() {
+    let path = textured("texture_resolver", "bundle/tile.png");
+    let options = LoaderOptions::new().texture_resolver(|uri| (uri == "bundle/tile.png").then(png));
+    let model = load_model_with(&path, &options).unwrap();
+    let texture = model.materials[0].base_color_texture.as_ref().unwrap();
+    assert_eq!((texture.width(), texture.height()), (2, 2));
+    assert!(texture.pixels().iter().all(|&byte| byte == 255));
+
+    let without = load_model_with(&path, &LoaderOptions::default()).unwrap();
+    assert!(without.materials[0].base_color_texture.is_none());
+}
+
+#[test]
+fn texture_resolver_falls_back_to_the_filesystem() {
+    let path = textured("texture_resolver_fallback", "tile.png");
+    std::fs::write(path.with_file_name("tile.png"), png()).unwrap();
+    let options = LoaderOptions::new().texture_resolver(|_| None);
+    let model = load_model_with(&path, &options).unwrap();
+    assert!(model.materials[0].base_color_texture.is_some());
+}
+
+#[test]
+fn deferred_textures_record_their_source_until_resolved() {
+    let path = textured("texture_deferred", "tile.png");
+    let image = path.with_file_name("tile.png");
+    std::fs::write(&image, png()).unwrap();
+
+    let mut model = load_model_with(&path, &LoaderOptions::new().texture_loading(TextureLoading::Deferred)).unwrap();
+    let texture = model.materials[0].base_color_texture.as_ref().unwrap();
+    assert!(!texture.is_resolved());
+    assert!(texture.pixels().is_empty());
+    assert_eq!((texture.width(), texture.height()), (2, 2));
+    assert!(matches!(texture.source(), Some(TextureSource::File(file)) if *file == image));
+    assert_eq!(model.memory_footprint().texture_bytes, 0);
+
+    model.resolve_textures().unwrap();
+    let texture = model.materials[0].base_color_texture.as_ref().unwrap();
+    assert!(texture.is_resolved() && texture.source().is_none());
+    assert_eq!(texture.pixels().len(), 2 * 2 * 4);
+
+    let skipped = load_model_with(&path, &LoaderOptions::new().texture_loading(TextureLoading::Skip)).unwrap();
+    assert!(skipped.materials[0].base_color_texture.is_none());
+}
+
+#[test]
+fn unlit_extension_sets_the_flag() {
+    let unlit = load_material("unlit", json!({ "extensions": { "KHR_materials_unlit": {} } }), &["KHR_materials_unlit"]);
+    assert!(unlit.unlit);
+    let lit = load_material("lit", json!({ "pbrMetallicRoughness": { "metallicFactor": 0.5 } }), &[]);
+    assert!(!lit.unlit);
+    assert!(!Material::default().unlit);
+}
@@ -0,0 +1,36 @@
+use glam::*;
+use motley::model::{Mesh, Model};
+
+fn close(value: f32, expected: f32) -> bool {
+    (value - expected).abs() < 1e-5
+}
+
+#[test]
+fn unit_cube_has_unit_volume_and_six_sides() {
+    let mut cube = Mesh::cube(1.0);
+    assert!(close(cube.signed_volume(), 1.0));
+    assert!(close(cube.surface_area(), 6.0));
+
+    // The tetrahedra cancel out away from the origin too.
+    cube.transform(Mat4::from_translation(Vec3::new(10.0, -5.0, 3.0))).unwrap();
+    assert!(close(cube.signed_volume(), 1.0));
+    assert!(close(cube.surface_area(), 6.0));
+
+    cube.flip_winding().unwrap();
+    assert!(close(cube.signed_volume(), -1.0));
+    assert!(close(cube.surface_area(), 6.0));
+}
+
+#[test]
+fn model_sums_meshes_and_their_instances() {
+    let mut doubled = Mesh::cube(1.0);
+    doubled.instances = vec![Mat4::IDENTITY, Mat4::from_scale_rotation_translation(Vec3::splat(2.0), Quat::from_rotation_y(0.7), Vec3::X * 5.0)];
+    // Instances place the mesh in the model, so the mesh alone still measures one cube.
+    assert!(close(doubled.signed_volume(), 1.0));
+    assert!(close(doubled.surface_area(), 6.0));
+
+    let mut model = Model::from_mesh(doubled);
+    model.meshes.push(Mesh::plane(2.0, 3.0, 4));
+    assert!(close(model.signed_volume(), 1.0 + 8.0));
+    assert!(close(model.surface_area(), 6.0 + 24.0 + 6.0));
+}
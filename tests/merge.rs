@@ -0,0 +1,104 @@
+mod common;
+
+use common::*;
+use glam::*;
+use motley::model::{deduplicate_materials, load_scene, Material, MaterialHandle, Mesh, Model};
+use serde_json::json;
+
+#[test]
+fn merge_offsets_material_handles() {
+    let red = Material { base_color: Vec4::new(1.0, 0.0, 0.0, 1.0), ..Default::default() };
+    let mut model = Model::from_mesh(Mesh::cube(1.0));
+    let mut other = Model::from_mesh(Mesh::plane(1.0, 1.0, 1));
+    other.materials[0] = red;
+
+    model.merge(other);
+    assert_eq!(model.meshes.len(), 2);
+    assert_eq!(model.materials.len(), 2);
+    assert_eq!(model.meshes[0].material, MaterialHandle::from(0));
+    assert_eq!(model.meshes[1].material, MaterialHandle::from(1));
+    assert_eq!(model.materials[0].base_color, Vec4::ONE);
+    assert_eq!(model.materials[1].base_color, Vec4::new(1.0, 0.0, 0.0, 1.0));
+    assert_eq!(model.meshes[1].vertices.len(), Mesh::plane(1.0, 1.0, 1).vertices.len());
+}
+
+#[test]
+fn duplicate_materials_fold_before_merging_meshes() {
+    let mut model = Model::from_mesh(Mesh::cube(1.0));
+    model.merge(Model::from_mesh(Mesh::cube(1.0)));
+    let vertex_count = model.meshes[0].vertices.len();
+    let index_count = model.meshes[0].indices.len();
+
+    assert_eq!(model.merge_by_material(), 0);
+    assert_eq!(model.deduplicate_materials(), 1);
+    assert_eq!(model.meshes[1].material, MaterialHandle::from(0));
+    assert_eq!(model.merge_by_material(), 1);
+
+    let merged = &model.meshes[0];
+    assert_eq!(model.meshes.len(), 1);
+    assert_eq!(merged.vertices.len(), vertex_count * 2);
+    assert_eq!(merged.indices.len(), index_count * 2);
+    assert_eq!(merged.indices.get(index_count), Some(merged.indices.get(0).unwrap() + vertex_count as u32));
+}
+
+#[test]
+fn identical_loaded_materials_collapse_to_one() {
+    let mut fixture = Fixture::new();
+    let positions = fixture.floats(&TRIANGLE);
+    let primitive = |material: usize| json!({ "attributes": { "POSITION": positions }, "material": material });
+    let white = json!({ "pbrMetallicRoughness": { "baseColorFactor": [1.0, 1.0, 1.0, 1.0] } });
+    let red = json!({ "pbrMetallicRoughness": { "baseColorFactor": [1.0, 0.0, 0.0, 1.0] } });
+    let document = json!({
+        "scene": 0,
+        "scenes": [{ "nodes": [0, 1, 2] }],
+        "nodes": [{ "mesh": 0 }, { "mesh": 1 }, { "mesh": 2 }],
+        "meshes": [{ "primitives": [primitive(0)] }, { "primitives": [primitive(1)] }, { "primitives": [primitive(2)] }],
+        "materials": [white, red, white]
+    });
+    let path = fixture.write("duplicate_materials", document);
+
+    let mut model = load_scene(&path, 0).unwrap();
+    assert_eq!(model.materials.len(), 3);
+    assert_eq!(deduplicate_materials(&mut model), 1);
+    assert_eq!(model.materials.len(), 2);
+    let handles: Vec<MaterialHandle> = model.meshes.iter().map(|mesh| mesh.material).collect();
+    assert_eq!(handles, [0, 1, 0].map(MaterialHandle::from));
+    assert_eq!(model.materials[1].base_color, Vec4::new(1.0, 0.0, 0.0, 1.0));
+}
+
+#[test]
+fn meshes_sharing_a_material_become_one() {
+    let named = |mut mesh: Mesh, name: &str, material: usize| {
+        mesh.name = Some(name.to_string());
+        mesh.material = MaterialHandle::from(material);
+        mesh
+    };
+    let mut instanced = named(Mesh::cube(1.0), "bolt", 0);
+    instanced.instances = vec![Mat4::IDENTITY, Mat4::from_translation(Vec3::X)];
+    let mut model = Model {
+        meshes: vec![
+            named(Mesh::cube(1.0), "part", 0),
+            named(Mesh::plane(1.0, 1.0, 1), "glass", 1),
+            named(Mesh::plane(2.0, 2.0, 2), "part", 0),
+            instanced,
+            named(Mesh::plane(1.0, 1.0, 1), "window", 1)
+        ],
+        materials: vec![Material::default(), Material { base_color: Vec4::ZERO, ..Default::default() }]
+    };
+    let cube = Mesh::cube(1.0);
+    let plane = Mesh::plane(2.0, 2.0, 2);
+
+    assert_eq!(model.merge_by_material(), 2);
+    let names: Vec<Option<&str>> = model.meshes.iter().map(|mesh| mesh.name.as_deref()).collect();
+    assert_eq!(names, [Some("part"), None, Some("bolt")]);
+
+    let merged = &model.meshes[0];
+    assert_eq!(merged.vertices.len(), cube.vertices.len() + plane.vertices.len());
+    let offset = cube.vertices.len() as u32;
+    let expected: Vec<u32> = cube.indices.iter().chain(plane.indices.iter().map(|index| index + offset)).collect();
+    assert_eq!(merged.indices.to_u32(), expected);
+    for (vertex, original) in merged.vertices[cube.vertices.len()..].iter().zip(&plane.vertices) {
+        assert_eq!((vertex.position, vertex.normal, vertex.tex_coord), (original.position, original.normal, original.tex_coord));
+    }
+    assert_eq!(model.meshes[2].instances.len(), 2);
+}
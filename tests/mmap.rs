@@ -0,0 +1,45 @@
+mod common;
+
+use common::*;
+use motley::model::{export_gltf, load_scene};
+
+/*
+The GLB loads through the memory mapping with the `mmap` feature and through a plain read
+without it; both must give the model the text glTF gives.
+*/
+#[test]
+fn glb_loads_like_the_same_gltf() {
+    let model = cube_and_plane();
+    let directory = temp_dir("mmap_same");
+    export_gltf(&model, directory.join("model.glb"), true).unwrap();
+    export_gltf(&model, directory.join("model.gltf"), false).unwrap();
+
+    let binary = load_scene(directory.join("model.glb"), 0).unwrap();
+    let text = load_scene(directory.join("model.gltf"), 0).unwrap();
+    assert_same_geometry(&binary, &model);
+    assert_same_materials(&binary, &text);
+    for (a, b) in binary.meshes.iter().zip(&text.meshes) {
+        assert_eq!(a.interleaved_bytes(), b.interleaved_bytes());
+        assert_eq!(a.indices, b.indices);
+    }
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn glb_with_a_short_binary_chunk_is_rejected() {
+    let path = temp_dir("mmap_short").join("model.glb");
+    export_gltf(&cube_and_plane(), &path, true).unwrap();
+    let mut bytes = std::fs::read(&path).unwrap();
+
+    // Shrink the binary chunk, and the container length with it, by four bytes.
+    let json_length = u32::from_le_bytes(bytes[12..16].try_into().unwrap()) as usize;
+    let bin_header = 20 + json_length;
+    let bin_length = u32::from_le_bytes(bytes[bin_header..bin_header + 4].try_into().unwrap());
+    bytes[bin_header..bin_header + 4].copy_from_slice(&(bin_length - 4).to_le_bytes());
+    let total = bytes.len() as u32 - 4;
+    bytes[8..12].copy_from_slice(&total.to_le_bytes());
+    bytes.truncate(total as usize);
+    std::fs::write(&path, bytes).unwrap();
+
+    assert!(matches!(load_scene(&path, 0), Err(motley::model::LoadError::InvalidData(_))));
+}
@@ -0,0 +1,161 @@
+use glam::*;
+use motley::model::{Mesh, MeshBuilder, MeshError, NormalMode};
+
+/*
+Two triangles folded along the Y axis: one of area 2 facing +Z and one of area 1 facing +X,
+plus a zero-area sliver along the fold. Every normal starts out zero.
+*/
+fn fold() -> Mesh {
+    let mut builder = MeshBuilder::new();
+    for position in [Vec3::ZERO, Vec3::Y, Vec3::new(-4.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 2.0), Vec3::new(0.0, 2.0, 0.0)] {
+        builder.add_vertex(position, Vec3::ZERO, position.truncate());
+    }
+    builder.add_triangle(0, 1, 2).unwrap();
+    builder.add_triangle(0, 1, 3).unwrap();
+    builder.add_triangle(0, 1, 4).unwrap();
+    builder.build()
+}
+
+#[test]
+fn smooth_normals_are_weighted_by_area() {
+    let mut mesh = fold();
+    mesh.recalculate_normals(NormalMode::Smooth);
+
+    let shared = Vec3::new(1.0, 0.0, 2.0).normalize();
+    assert!(mesh.vertices[0].normal.abs_diff_eq(shared, 1e-6));
+    assert!(mesh.vertices[1].normal.abs_diff_eq(shared, 1e-6));
+    assert_eq!(mesh.vertices[2].normal, Vec3::Z);
+    assert_eq!(mesh.vertices[3].normal, Vec3::X);
+    // The sliver has no normal to give, so its lone vertex keeps none.
+    assert_eq!(mesh.vertices[4].normal, Vec3::ZERO);
+    assert_eq!(mesh.indices.len(), 9);
+}
+
+#[test]
+fn flat_normals_split_every_triangle_and_drop_slivers() {
+    let mut mesh = fold();
+    mesh.recalculate_normals(NormalMode::Flat);
+
+    assert_eq!(mesh.vertices.len(), 6);
+    assert_eq!(mesh.indices.to_u32(), [0, 1, 2, 3, 4, 5]);
+    for (vertex, normal) in mesh.vertices.iter().zip([Vec3::Z, Vec3::Z, Vec3::Z, Vec3::X, Vec3::X, Vec3::X]) {
+        assert_eq!(vertex.normal, normal);
+        assert_eq!(vertex.tex_coord, vertex.position.truncate());
+    }
+}
+
+#[test]
+fn flat_normals_carry_tangents_along() {
+    let mut cube = Mesh::cube(2.0);
+    for vertex in &mut cube.vertices {
+        vertex.tangent = vertex.normal.any_orthonormal_vector().extend(-1.0);
+    }
+    let corners: Vec<_> = cube.indices.iter().map(|index| cube.vertices[index as usize]).collect();
+    cube.recalculate_normals(NormalMode::Flat);
+
+    assert_eq!(cube.vertices.len(), 36);
+    for (vertex, corner) in cube.vertices.iter().zip(&corners) {
+        assert_eq!(vertex.position, corner.position);
+        assert_eq!(vertex.tex_coord, corner.tex_coord);
+        assert_eq!(vertex.tangent, corner.tangent);
+        assert!(vertex.normal.abs_diff_eq(corner.normal, 1e-6));
+    }
+}
+
+#[test]
+fn flip_turns_a_cube_inside_out_and_back() {
+    let original = Mesh::cube(2.0);
+    let mut cube = original.clone();
+    for vertex in &mut cube.vertices {
+        vertex.tangent = Vec4::new(1.0, 0.0, 0.0, 1.0);
+    }
+    cube.flip().unwrap();
+
+    for [a, b, c] in cube.indices.triangles() {
+        let [a, b, c] = [a, b, c].map(|index| cube.vertices[index as usize]);
+        let face = (b.position - a.position).cross(c.position - a.position).normalize();
+        assert!(face.abs_diff_eq(a.normal, 1e-6));
+        assert!(face.dot(a.position) < 0.0);
+        assert_eq!(a.tangent.w, -1.0);
+    }
+
+    cube.flip().unwrap();
+    assert_eq!(cube.indices, original.indices);
+    for (vertex, original) in cube.vertices.iter().zip(&original.vertices) {
+        assert_eq!(vertex.normal, original.normal);
+        assert_eq!(vertex.tangent.w, 1.0);
+    }
+}
+
+#[test]
+fn incomplete_triangles_are_not_flipped() {
+    let mut mesh = fold();
+    mesh.indices.push(0);
+    let indices = mesh.indices.clone();
+
+    assert_eq!(mesh.flip_winding(), Err(MeshError::IncompleteTriangles(10)));
+    assert_eq!(mesh.flip(), Err(MeshError::IncompleteTriangles(10)));
+    assert_eq!(mesh.indices, indices);
+    assert!(mesh.vertices.iter().all(|vertex| vertex.normal == Vec3::ZERO));
+}
+
+#[test]
+fn angle_threshold_splits_only_hard_edges() {
+    // The fold's two faces meet at 90 degrees.
+    let mut soft = fold();
+    soft.recalculate_normals_with_angle(100.0);
+    let mut smooth = fold();
+    smooth.recalculate_normals(NormalMode::Smooth);
+    assert_eq!(soft.vertices.len(), 5);
+    for (a, b) in soft.vertices.iter().zip(&smooth.vertices) {
+        assert!(a.normal.abs_diff_eq(b.normal, 1e-6));
+    }
+
+    let mut hard = fold();
+    hard.recalculate_normals_with_angle(80.0);
+    assert_eq!(hard.vertices.len(), 7);
+    let triangles: Vec<[u32; 3]> = hard.indices.triangles().collect();
+    for &index in &triangles[0] {
+        assert_eq!(hard.vertices[index as usize].normal, Vec3::Z);
+    }
+    for &index in &triangles[1] {
+        assert_eq!(hard.vertices[index as usize].normal, Vec3::X);
+        // Copies along the hard edge keep the other attributes of their original.
+        let vertex = &hard.vertices[index as usize];
+        assert_eq!(vertex.tex_coord, vertex.position.truncate());
+    }
+}
+
+#[test]
+fn angle_extremes_match_the_smooth_and_flat_modes() {
+    let mut cube = Mesh::cube(2.0);
+    cube.weld_vertices(0.0, false);
+    for vertex in &mut cube.vertices {
+        vertex.tangent = Vec4::new(0.0, 1.0, 0.0, -1.0);
+    }
+
+    let mut widest = cube.clone();
+    widest.recalculate_normals_with_angle(180.0);
+    let mut smooth = cube.clone();
+    smooth.recalculate_normals(NormalMode::Smooth);
+    assert_eq!(widest.vertices.len(), 8);
+    assert_eq!(widest.indices, smooth.indices);
+    for (a, b) in widest.vertices.iter().zip(&smooth.vertices) {
+        assert!(a.normal.abs_diff_eq(b.normal, 1e-6));
+    }
+
+    // Coplanar triangles still share their corners, so each face keeps four vertices.
+    let mut sharpest = cube.clone();
+    sharpest.recalculate_normals_with_angle(0.0);
+    assert_eq!(sharpest.vertices.len(), 24);
+    let mut flat = cube.clone();
+    flat.recalculate_normals(NormalMode::Flat);
+    for (triangle, flat_triangle) in sharpest.indices.triangles().zip(flat.indices.triangles()) {
+        for (index, flat_index) in triangle.into_iter().zip(flat_triangle) {
+            let (vertex, flat_vertex) = (&sharpest.vertices[index as usize], &flat.vertices[flat_index as usize]);
+            assert_eq!(vertex.position, flat_vertex.position);
+            assert!(vertex.normal.abs_diff_eq(flat_vertex.normal, 1e-6));
+            assert_eq!(vertex.tangent, Vec4::new(0.0, 1.0, 0.0, -1.0));
+        }
+    }
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use common::*;
+use glam::*;
+use motley::model::{export_obj, Mesh, Model};
+
+/*
+The corners of every face of an OBJ file, resolving its one-based position, texture coordinate
+and normal references.
+*/
+fn obj_corners(obj: &str) -> Vec<(Vec3, Vec2, Vec3)> {
+    let floats = |line: &str| -> Vec<f32> { line.split_whitespace().skip(1).map(|value| value.parse().unwrap()).collect() };
+    let (mut positions, mut tex_coords, mut normals) = (Vec::new(), Vec::new(), Vec::new());
+    let mut corners = Vec::new();
+    for line in obj.lines() {
+        if line.starts_with("v ") {
+            positions.push(Vec3::from_slice(&floats(line)));
+        } else if line.starts_with("vt ") {
+            tex_coords.push(Vec2::from_slice(&floats(line)));
+        } else if line.starts_with("vn ") {
+            normals.push(Vec3::from_slice(&floats(line)));
+        } else if let Some(face) = line.strip_prefix("f ") {
+            for corner in face.split_whitespace() {
+                let index: Vec<usize> = corner.split('/').map(|index| index.parse::<usize>().unwrap() - 1).collect();
+                corners.push((positions[index[0]], tex_coords[index[1]], normals[index[2]]));
+            }
+        }
+    }
+    corners
+}
+
+#[test]
+fn obj_export_round_trips_the_geometry() {
+    let mut cube = Mesh::cube(2.0);
+    cube.transform(Mat4::from_translation(Vec3::new(1.0, 2.0, 3.0))).unwrap();
+    let model = Model::from_mesh(cube);
+    let path = temp_dir("obj_round_trip").join("cube.obj");
+    export_obj(&model, &path).unwrap();
+
+    let corners = obj_corners(&std::fs::read_to_string(&path).unwrap());
+    let mesh = &model.meshes[0];
+    assert_eq!(corners.len(), mesh.indices.len());
+    for ((position, tex_coord, normal), index) in corners.into_iter().zip(mesh.indices.to_u32()) {
+        let vertex = &mesh.vertices[index as usize];
+        assert!(position.abs_diff_eq(vertex.position, 1e-6));
+        assert!(normal.abs_diff_eq(vertex.normal, 1e-6));
+        // OBJ puts the texture origin at the bottom left.
+        assert!(tex_coord.abs_diff_eq(Vec2::new(vertex.tex_coord.x, 1.0 - vertex.tex_coord.y), 1e-6));
+    }
+}
+
+#[test]
+fn obj_export_writes_every_vertex_and_the_materials() {
+    let model = cube_and_plane();
+    let path = temp_dir("export_obj").join("model.obj");
+    export_obj(&model, &path).unwrap();
+
+    let obj = std::fs::read_to_string(&path).unwrap();
+    let vertex_count: usize = model.meshes.iter().map(|mesh| mesh.vertices.len()).sum();
+    let triangle_count: usize = model.meshes.iter().map(|mesh| mesh.indices.len() / 3).sum();
+    assert_eq!(obj.lines().filter(|line| line.starts_with("v ")).count(), vertex_count);
+    assert_eq!(obj.lines().filter(|line| line.starts_with("f ")).count(), triangle_count);
+    assert!(obj.starts_with("mtllib model.mtl"));
+
+    let mtl = std::fs::read_to_string(path.with_extension("mtl")).unwrap();
+    assert_eq!(mtl.lines().filter(|line| line.starts_with("newmtl")).count(), model.materials.len());
+}
+
@@ -0,0 +1,67 @@
+use glam::*;
+use motley::model::optimize::{ACMR_CACHE_SIZE, MIN_OPTIMIZE_TRIANGLES};
+use motley::model::Mesh;
+
+/*
+A finely subdivided plane whose triangles are shuffled, so its index order has poor locality.
+*/
+fn shuffled_plane() -> Mesh {
+    let mut plane = Mesh::plane(1.0, 1.0, 24);
+    let triangles: Vec<[u32; 3]> = plane.indices.triangles().collect();
+    let count = triangles.len();
+    let indices: Vec<u32> = (0..count).flat_map(|i| triangles[i * 97 % count]).collect();
+    plane.indices.replace(indices);
+    plane
+}
+
+/*
+The triangles as sets of corner positions, rotated to start at their smallest corner so the
+winding is kept but the starting corner does not matter, and sorted.
+*/
+fn surface(mesh: &Mesh) -> Vec<[[u32; 3]; 3]> {
+    let mut triangles: Vec<[[u32; 3]; 3]> = mesh
+        .indices
+        .triangles()
+        .map(|triangle| {
+            let corners = triangle.map(|index| mesh.vertices[index as usize].position.to_array().map(f32::to_bits));
+            let first = (0..3).min_by_key(|&i| corners[i]).unwrap();
+            [0, 1, 2].map(|i| corners[(first + i) % 3])
+        })
+        .collect();
+    triangles.sort();
+    triangles
+}
+
+#[test]
+fn optimize_improves_locality_and_keeps_the_surface() {
+    let mut mesh = shuffled_plane();
+    let before = surface(&mesh);
+    let estimate = mesh.acmr(ACMR_CACHE_SIZE);
+
+    let result = mesh.optimize();
+    assert_eq!(result.acmr_before, estimate);
+    assert_eq!(result.acmr_after, mesh.acmr(ACMR_CACHE_SIZE));
+    assert!(result.acmr_after < result.acmr_before * 0.6, "{result:?}");
+    assert_eq!(surface(&mesh), before);
+
+    // Vertices are now numbered in the order the triangles first use them.
+    let mut next = 0;
+    for index in mesh.indices.to_u32() {
+        assert!(index <= next);
+        if index == next {
+            next += 1;
+        }
+    }
+}
+
+#[test]
+fn small_meshes_are_left_alone() {
+    let mut cube = Mesh::cube(1.0);
+    assert!(cube.triangle_count() < MIN_OPTIMIZE_TRIANGLES);
+    let original = cube.clone();
+
+    let result = cube.optimize();
+    assert_eq!(result.acmr_before, result.acmr_after);
+    assert_eq!(cube.indices, original.indices);
+    assert_eq!(cube.interleaved_bytes(), original.interleaved_bytes());
+}
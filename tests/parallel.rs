@@ -0,0 +1,40 @@
+mod common;
+
+use common::*;
+use glam::*;
+use motley::model::{export_gltf, load_scene, Material, MaterialHandle, Mesh, Model, Texture};
+
+/*
+A model of many cubes, each in its own place with its own material and texture, so a loader
+working out of order would mix them up.
+*/
+fn crowd() -> Model {
+    let mut model = Model { meshes: Vec::new(), materials: Vec::new() };
+    for index in 0..12u8 {
+        let mut cube = Mesh::cube(1.0);
+        cube.name = Some(format!("cube_{}", index));
+        cube.material = MaterialHandle::from(index as usize);
+        cube.transform(Mat4::from_translation(Vec3::X * index as f32)).unwrap();
+        model.meshes.push(cube);
+
+        let texture = Texture::from_rgba8(1, 1, vec![index, 255 - index, index * 2, 255]).unwrap();
+        model.materials.push(Material { base_color_texture: Some(texture), roughness_factor: index as f32 / 12.0, ..Default::default() });
+    }
+    model
+}
+
+#[test]
+fn loading_keeps_document_order_on_every_run() {
+    let model = crowd();
+    let path = temp_dir("parallel_order").join("crowd.glb");
+    export_gltf(&model, &path, true).unwrap();
+
+    for _ in 0..4 {
+        let loaded = load_scene(&path, 0).unwrap();
+        assert_same_geometry(&loaded, &model);
+        assert_same_materials(&loaded, &model);
+        let names: Vec<Option<&str>> = loaded.meshes.iter().map(|mesh| mesh.name.as_deref()).collect();
+        let expected: Vec<String> = (0..12).map(|index| format!("cube_{}", index)).collect();
+        assert_eq!(names, expected.iter().map(|name| Some(name.as_str())).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,81 @@
+use glam::*;
+use motley::model::{Material, MaterialHandle, MaterialMatcher, MaterialPatch, Mesh, Model, Texture, Transmission};
+
+/*
+A model of the materials of a car: its glass, two paints and an unnamed default.
+*/
+fn car() -> Model {
+    let named = |name: &str| Material { name: Some(name.to_string()), ..Default::default() };
+    let mut model = Model::from_mesh(Mesh::cube(1.0));
+    model.materials = vec![named("Glass"), named("Body_paint"), named("Trim_paint"), Material::default()];
+    model
+}
+
+#[test]
+fn named_materials_can_be_edited_in_place() {
+    let mut model = car();
+    model.material_mut_by_name("Glass").unwrap().ior = 1.52;
+    assert_eq!(model.materials[0].ior, 1.52);
+    assert!(model.material_mut_by_name("glass").is_none());
+}
+
+#[test]
+fn patches_change_only_what_they_set() {
+    let mut model = car();
+    let albedo = Texture::from_fn(2, 2, |_, _| Vec4::new(1.0, 0.0, 0.0, 1.0));
+    let patches = [
+        (MaterialMatcher::Name("Glass".to_string()), MaterialPatch {
+            base_color: Some(Vec4::new(1.0, 1.0, 1.0, 0.2)),
+            transmission: Some(Some(Transmission { factor: 1.0, texture: None })),
+            ..Default::default()
+        }),
+        (MaterialMatcher::Glob("*_paint".to_string()), MaterialPatch { roughness_factor: Some(0.3), ..Default::default() }),
+        (MaterialMatcher::Name("Body_paint".to_string()), MaterialPatch {
+            base_color_texture: Some(Some(albedo)),
+            roughness_factor: Some(0.1),
+            ..Default::default()
+        }),
+        (MaterialMatcher::Handle(MaterialHandle::from(3)), MaterialPatch { unlit: Some(true), ..Default::default() })
+    ];
+    assert!(model.apply_material_patches(&patches).is_empty());
+
+    let [glass, body, trim, default] = &model.materials[..] else { panic!() };
+    assert_eq!(glass.base_color.w, 0.2);
+    assert_eq!(glass.transmission.as_ref().unwrap().factor, 1.0);
+    assert_eq!(glass.roughness_factor, Material::default().roughness_factor);
+
+    // The later, exact patch wins over the glob where both set the roughness.
+    assert_eq!((body.roughness_factor, trim.roughness_factor), (0.1, 0.3));
+    assert_eq!(body.base_color_texture.as_ref().unwrap().width(), 2);
+    assert!(trim.base_color_texture.is_none());
+    assert_eq!(body.base_color, Vec4::ONE);
+
+    assert!(default.unlit);
+    assert!(!glass.unlit && !body.unlit);
+}
+
+#[test]
+fn texture_patches_can_remove_a_texture() {
+    let mut model = car();
+    model.materials[1].base_color_texture = Some(Texture::from_fn(1, 1, |_, _| Vec4::W));
+    let remove = MaterialPatch { base_color_texture: Some(None), ..Default::default() };
+    model.apply_material_patches(&[(MaterialMatcher::Handle(MaterialHandle::from(1)), remove)]);
+    assert!(model.materials[1].base_color_texture.is_none());
+}
+
+#[test]
+fn unmatched_patches_are_reported() {
+    let mut model = car();
+    let original = model.materials.clone();
+    let ior = MaterialPatch { ior: Some(2.0), ..Default::default() };
+    let patches = [
+        (MaterialMatcher::Name("Glas".to_string()), ior.clone()),
+        (MaterialMatcher::Glob("Trim_?aint".to_string()), ior.clone()),
+        (MaterialMatcher::Glob("Chrome*".to_string()), ior.clone()),
+        (MaterialMatcher::Handle(MaterialHandle::from(9)), ior)
+    ];
+    assert_eq!(model.apply_material_patches(&patches), [0, 2, 3]);
+
+    let iors: Vec<f32> = model.materials.iter().map(|material| material.ior).collect();
+    assert_eq!(iors, [original[0].ior, original[1].ior, 2.0, original[3].ior]);
+}
@@ -0,0 +1,97 @@
+use glam::*;
+use motley::model::{Material, Mesh, Model};
+use std::f32::consts::PI;
+
+/*
+Checks that every triangle winds counter-clockwise around the normals of its vertices, that the
+normals are unit length and that texture coordinates stay in the unit square.
+*/
+fn assert_outward_and_ccw(mesh: &Mesh) {
+    for vertex in &mesh.vertices {
+        assert!((vertex.normal.length() - 1.0).abs() < 1e-5, "{:?}", vertex.normal);
+        assert!(vertex.tex_coord.cmpge(Vec2::ZERO).all() && vertex.tex_coord.cmple(Vec2::ONE).all());
+    }
+    for triangle in mesh.indices.to_u32().chunks(3) {
+        let [a, b, c] = [0, 1, 2].map(|corner| &mesh.vertices[triangle[corner] as usize]);
+        let face = (b.position - a.position).cross(c.position - a.position);
+        assert!(face.dot(a.normal + b.normal + c.normal) > 0.0);
+    }
+}
+
+#[test]
+fn cube_has_flat_outward_faces() {
+    let cube = Mesh::cube(2.0);
+    assert_eq!(cube.vertices.len(), 24);
+    assert_eq!(cube.triangle_count(), 12);
+    assert_outward_and_ccw(&cube);
+    for vertex in &cube.vertices {
+        assert_eq!(vertex.position.abs(), Vec3::ONE);
+        assert_eq!(vertex.position.dot(vertex.normal), 1.0);
+    }
+    assert!((cube.signed_volume() - 8.0).abs() < 1e-5);
+    assert!((cube.surface_area() - 24.0).abs() < 1e-5);
+}
+
+#[test]
+fn sphere_is_closed_and_normals_point_away_from_the_centre() {
+    let sphere = Mesh::uv_sphere(2.0, 32);
+    assert_outward_and_ccw(&sphere);
+    assert!(sphere.is_watertight());
+    for vertex in &sphere.vertices {
+        assert!((vertex.position.length() - 2.0).abs() < 1e-5);
+        assert!(vertex.normal.dot(vertex.position.normalize()) > 0.9999);
+    }
+
+    let exact = 4.0 / 3.0 * PI * 8.0;
+    assert!((sphere.signed_volume() - exact).abs() / exact < 0.02);
+    assert_eq!(Mesh::uv_sphere_with_rings(1.0, 8, 3).triangle_count(), 8 * 4);
+}
+
+#[test]
+fn plane_faces_up_and_spans_its_size() {
+    let plane = Mesh::plane(4.0, 2.0, 3);
+    assert_eq!(plane.vertices.len(), 16);
+    assert_eq!(plane.triangle_count(), 18);
+    assert_outward_and_ccw(&plane);
+    assert!(plane.vertices.iter().all(|vertex| vertex.normal == Vec3::Y));
+    assert!((plane.surface_area() - 8.0).abs() < 1e-5);
+
+    let bounds = plane.aabb();
+    assert_eq!((bounds.min, bounds.max), (Vec3::new(-2.0, 0.0, -1.0), Vec3::new(2.0, 0.0, 1.0)));
+}
+
+#[test]
+fn cylinder_caps_close_the_side() {
+    let cylinder = Mesh::cylinder(1.0, 2.0, 24);
+    assert_outward_and_ccw(&cylinder);
+    assert!(cylinder.is_watertight());
+
+    let exact = PI * 2.0;
+    assert!((cylinder.signed_volume() - exact).abs() / exact < 0.02);
+    let bounds = cylinder.aabb();
+    assert_eq!((bounds.min.y, bounds.max.y), (-1.0, 1.0));
+}
+
+#[test]
+fn torus_is_closed_with_its_hole_around_y() {
+    let torus = Mesh::torus(2.0, 0.5, 32, 16);
+    assert_outward_and_ccw(&torus);
+    assert!(torus.is_watertight());
+    for vertex in &torus.vertices {
+        let ring = Vec3::new(vertex.position.x, 0.0, vertex.position.z).normalize() * 2.0;
+        assert!(((vertex.position - ring).length() - 0.5).abs() < 1e-5);
+    }
+
+    // The polygonal tube cuts inside the round one, so the volume is slightly smaller.
+    let exact = 2.0 * PI * PI * 2.0 * 0.25;
+    assert!(torus.signed_volume() < exact && torus.signed_volume() > exact * 0.95);
+}
+
+#[test]
+fn model_from_mesh_assigns_the_default_material() {
+    let model = Model::from_mesh(Mesh::cube(1.0));
+    assert_eq!(model.meshes.len(), 1);
+    assert_eq!(model.materials.len(), 1);
+    assert_eq!(model.materials[0].base_color, Material::default().base_color);
+    assert!(model.material(model.meshes[0].material).is_some());
+}
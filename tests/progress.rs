@@ -0,0 +1,63 @@
+mod common;
+
+use common::*;
+use motley::model::{load_model_with_progress, LoadError, LoadPhase, LoadProgress, LoaderOptions};
+use serde_json::json;
+use std::ops::ControlFlow;
+
+/*
+A mesh of three primitives drawn with two textured materials, whose images are on disk.
+*/
+fn textured_mesh(name: &str) -> std::path::PathBuf {
+    let mut fixture = Fixture::new();
+    let positions = fixture.floats(&TRIANGLE);
+    let primitive = |material: usize| json!({ "attributes": { "POSITION": positions }, "material": material });
+    let mesh = json!({ "primitives": [primitive(0), primitive(1), primitive(0)] });
+    let textured = |index: usize| json!({ "pbrMetallicRoughness": { "baseColorTexture": { "index": index } } });
+    let mut document = single_mesh(mesh, json!([textured(0), textured(1)]));
+    document["images"] = json!([{ "uri": "a.png" }, { "uri": "b.png" }]);
+    document["textures"] = json!([{ "source": 0 }, { "source": 1 }]);
+    let path = fixture.write(name, document);
+    for image in ["a.png", "b.png"] {
+        std::fs::write(path.with_file_name(image), png()).unwrap();
+    }
+    path
+}
+
+#[test]
+fn progress_walks_through_every_phase_once_per_item() {
+    let path = textured_mesh("progress_phases");
+    let mut reports: Vec<LoadProgress> = Vec::new();
+    let model = load_model_with_progress(&path, &LoaderOptions::default(), |progress| {
+        reports.push(progress);
+        ControlFlow::Continue(())
+    })
+    .unwrap();
+    assert_eq!(model.meshes.len(), 3);
+
+    assert_eq!(reports.first().unwrap().phase, LoadPhase::ParsingDocument);
+    assert_eq!(*reports.last().unwrap(), LoadProgress { phase: LoadPhase::Finished, fraction: 1.0 });
+    assert!(reports.windows(2).all(|pair| pair[0].fraction <= pair[1].fraction));
+    assert!(reports.iter().any(|report| report.phase == LoadPhase::DecodingTextures { decoded: 2, total: 2 }));
+    assert!(reports.iter().any(|report| report.phase == LoadPhase::ProcessingMeshes { processed: 3, total: 3 }));
+    // Parsing, buffers, finishing and at most one report per item plus one before each phase.
+    assert!(reports.len() <= 3 + 3 + 4);
+}
+
+#[test]
+fn cancelled_progress_stops_the_load() {
+    let path = textured_mesh("progress_cancelled");
+    let result = load_model_with_progress(&path, &LoaderOptions::default(), |_| ControlFlow::Break(()));
+    assert!(matches!(result, Err(LoadError::Cancelled)));
+
+    let mut calls = 0;
+    let result = load_model_with_progress(&path, &LoaderOptions::default(), |progress| {
+        calls += 1;
+        match progress.phase {
+            LoadPhase::ProcessingMeshes { .. } => ControlFlow::Break(()),
+            _ => ControlFlow::Continue(())
+        }
+    });
+    assert!(matches!(result, Err(LoadError::Cancelled)));
+    assert!(calls <= 6, "{calls}");
+}
@@ -0,0 +1,50 @@
+use glam::*;
+use motley::model::{Mesh, Model};
+
+#[test]
+fn ray_hits_the_plane_with_interpolated_attributes() {
+    let plane = Mesh::plane(2.0, 2.0, 1);
+    let hit = plane.raycast(Vec3::new(0.5, 3.0, 0.25), Vec3::new(0.0, -2.0, 0.0), false).unwrap();
+
+    assert!((hit.distance - 3.0).abs() < 1e-6);
+    assert!(hit.position.abs_diff_eq(Vec3::new(0.5, 0.0, 0.25), 1e-6));
+    assert!(hit.normal.abs_diff_eq(Vec3::Y, 1e-6));
+    assert!(hit.tex_coord.abs_diff_eq(Vec2::new(0.75, 0.625), 1e-6));
+    assert!((hit.barycentric.x + hit.barycentric.y + hit.barycentric.z - 1.0).abs() < 1e-6);
+    assert!(hit.triangle < plane.triangle_count());
+}
+
+#[test]
+fn back_faces_are_hit_only_when_not_culled() {
+    let plane = Mesh::plane(2.0, 2.0, 1);
+    let below = Vec3::new(0.1, -1.0, 0.1);
+    assert!(plane.raycast(below, Vec3::Y, true).is_none());
+    assert!((plane.raycast(below, Vec3::Y, false).unwrap().distance - 1.0).abs() < 1e-6);
+}
+
+#[test]
+fn hits_behind_the_origin_and_parallel_rays_are_ignored() {
+    let plane = Mesh::plane(2.0, 2.0, 1);
+    assert!(plane.raycast(Vec3::new(0.1, 1.0, 0.1), Vec3::Y, false).is_none());
+    assert!(plane.raycast(Vec3::new(-5.0, 0.0, 0.1), Vec3::X, false).is_none());
+    assert!(plane.raycast(Vec3::new(-5.0, 1e-4, 0.1), Vec3::X, false).is_none());
+    assert!(plane.raycast(Vec3::new(0.1, 1.0, 0.1), Vec3::ZERO, false).is_none());
+}
+
+#[test]
+fn model_returns_the_closest_mesh_and_instance() {
+    let mut model = Model::from_mesh(Mesh::cube(1.0));
+    let mut instanced = Mesh::cube(1.0);
+    instanced.instances = vec![Mat4::from_translation(Vec3::new(0.0, 0.0, -5.0)), Mat4::from_translation(Vec3::new(0.0, 0.0, 5.0))];
+    model.meshes.push(instanced);
+
+    let (mesh, hit) = model.raycast(Vec3::new(0.1, 0.2, 10.0), Vec3::NEG_Z, true).unwrap();
+    assert_eq!(mesh, 1);
+    assert!((hit.distance - 4.5).abs() < 1e-5);
+    assert!(hit.position.abs_diff_eq(Vec3::new(0.1, 0.2, 5.5), 1e-5));
+    assert!(hit.normal.abs_diff_eq(Vec3::Z, 1e-6));
+
+    let (mesh, hit) = model.raycast(Vec3::new(0.1, 0.2, 2.0), Vec3::NEG_Z, true).unwrap();
+    assert_eq!(mesh, 0);
+    assert!((hit.distance - 1.5).abs() < 1e-5);
+}
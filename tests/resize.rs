@@ -0,0 +1,100 @@
+mod common;
+
+use common::*;
+use glam::*;
+use motley::model::{load_model_with, LoaderOptions, ResizeFilter, Texture};
+use serde_json::json;
+
+fn to_linear(value: f32) -> f32 {
+    match value <= 0.04045 {
+        true => value / 12.92,
+        false => ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/*
+Averages the colors of a texture in linear space, alpha included as it is.
+*/
+fn linear_average(texture: &Texture) -> Vec4 {
+    let mut sum = Vec4::ZERO;
+    for y in 0..texture.height() {
+        for x in 0..texture.width() {
+            let pixel = texture.pixel(x, y);
+            sum += Vec4::new(to_linear(pixel.x), to_linear(pixel.y), to_linear(pixel.z), pixel.w);
+        }
+    }
+    sum / (texture.width() * texture.height()) as f32
+}
+
+/*
+A texture of uneven size holding colorful gradients and a fine checker pattern.
+*/
+fn busy(width: u32, height: u32) -> Texture {
+    Texture::from_fn(width, height, |x, y| {
+        let checker = ((x + y) % 2) as f32;
+        Vec4::new(x as f32 / width as f32, checker, y as f32 / height as f32, 0.5 + 0.5 * checker)
+    })
+}
+
+#[test]
+fn big_downscales_keep_the_average_color() {
+    let source = busy(333, 217);
+    let expected = linear_average(&source);
+    for filter in [ResizeFilter::Box, ResizeFilter::Bilinear] {
+        for (width, height) in [(40, 26), (7, 5), (1, 1)] {
+            let small = source.resized(width, height, filter);
+            assert_eq!((small.width(), small.height()), (width, height));
+            assert!(linear_average(&small).abs_diff_eq(expected, 0.02));
+        }
+    }
+}
+
+#[test]
+fn colors_are_averaged_in_linear_space() {
+    let checker = Texture::checkerboard(64, 64, Vec4::ONE, Vec4::W);
+    // Half white and half black is half the light, which sRGB encodes as 188, not 128.
+    let pixel = checker.resized(1, 1, ResizeFilter::Box).pixel(0, 0);
+    assert!(pixel.abs_diff_eq(Vec4::new(188.0, 188.0, 188.0, 255.0) / 255.0, 1.5 / 255.0));
+    let data = checker.resized_linear(1, 1, ResizeFilter::Box).pixel(0, 0);
+    assert!(data.abs_diff_eq(Vec4::new(0.5, 0.5, 0.5, 1.0), 1.0 / 255.0));
+}
+
+#[test]
+fn upscaling_blends_neighbouring_pixels() {
+    let stripe = Texture::from_fn(2, 1, |x, _| Vec4::new(x as f32, x as f32, x as f32, 1.0));
+    for filter in [ResizeFilter::Box, ResizeFilter::Bilinear] {
+        let wide = stripe.resized_linear(8, 3, filter);
+        let row: Vec<f32> = (0..8).map(|x| wide.pixel(x, 1).x).collect();
+        assert_eq!((row[0], row[7]), (0.0, 1.0));
+        assert!(row.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert!((0..8).all(|x| wide.pixel(x, 0) == wide.pixel(x, 2)));
+    }
+}
+
+#[test]
+fn loader_clamps_large_textures_keeping_their_aspect() {
+    let mut fixture = Fixture::new();
+    let positions = fixture.floats(&TRIANGLE);
+    let mesh = json!({ "primitives": [{ "attributes": { "POSITION": positions }, "material": 0 }] });
+    let material = json!({ "pbrMetallicRoughness": { "baseColorTexture": { "index": 0 } }, "normalTexture": { "index": 1 } });
+    let mut document = single_mesh(mesh, json!([material]));
+    document["images"] = json!([{ "uri": "albedo.png" }, { "uri": "normals.png" }]);
+    document["textures"] = json!([{ "source": 0 }, { "source": 1 }]);
+    let path = fixture.write("max_texture_size", document);
+    let wide = Texture::from_fn(60, 30, |x, y| if (x + y) % 2 == 0 { Vec4::ONE } else { Vec4::W });
+    for image in ["albedo.png", "normals.png"] {
+        std::fs::write(path.with_file_name(image), wide.encode_png().unwrap()).unwrap();
+    }
+
+    let model = load_model_with(&path, &LoaderOptions::new().max_texture_size(6)).unwrap();
+    let albedo = model.materials[0].base_color_texture.as_ref().unwrap();
+    let normals = model.materials[0].normal_texture.as_ref().unwrap();
+    assert_eq!((albedo.width(), albedo.height()), (6, 3));
+    assert_eq!((normals.width(), normals.height()), (6, 3));
+    // Base color is filtered as sRGB color and normals as data.
+    assert!((albedo.pixel(2, 1).x - 188.0 / 255.0).abs() < 2.0 / 255.0);
+    assert!((normals.pixel(2, 1).x - 0.5).abs() < 2.0 / 255.0);
+
+    let full = load_model_with(&path, &LoaderOptions::new().max_texture_size(60)).unwrap();
+    assert_eq!(full.materials[0].base_color_texture.as_ref().unwrap().width(), 60);
+}
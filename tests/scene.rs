@@ -0,0 +1,172 @@
+mod common;
+
+use common::*;
+use glam::*;
+use motley::model::{load_model_node, load_model_node_with, load_scene, load_scene_graph, load_scene_graph_with, load_scene_with, AnimationWrap, CoordinateSystem, LoadError, LoaderOptions};
+use serde_json::json;
+
+/*
+A Z-up scene of a root node and a translated child drawing a triangle, with an animation
+moving the child along Z.
+*/
+fn animated_scene(name: &str) -> std::path::PathBuf {
+    let mut fixture = Fixture::new();
+    let positions = fixture.floats(&TRIANGLE);
+    let times = fixture.scalars(&[0.0, 1.0]);
+    let translations = fixture.floats(&[[1.0, 2.0, 3.0], [1.0, 2.0, 5.0]]);
+    let document = json!({
+        "scene": 0,
+        "scenes": [{ "nodes": [0] }],
+        "nodes": [
+            { "name": "root", "children": [1], "translation": [0.0, 0.0, 10.0] },
+            { "name": "child", "mesh": 0, "translation": [1.0, 2.0, 3.0] }
+        ],
+        "meshes": [{ "primitives": [{ "attributes": { "POSITION": positions } }] }],
+        "animations": [{
+            "samplers": [{ "input": times, "output": translations }],
+            "channels": [{ "sampler": 0, "target": { "node": 1, "path": "translation" } }]
+        }]
+    });
+    fixture.write(name, document)
+}
+
+/*
+A street of a car, whose wheel is a translated child node, and a tree, in scene 0. Scene 1
+holds the tree alone.
+*/
+fn street(name: &str) -> std::path::PathBuf {
+    let mut fixture = Fixture::new();
+    let positions = fixture.floats(&TRIANGLE);
+    let primitives = json!([{ "attributes": { "POSITION": positions } }]);
+    let document = json!({
+        "scene": 0,
+        "scenes": [{ "nodes": [0, 2] }, { "nodes": [2] }],
+        "nodes": [
+            { "name": "car", "children": [1], "translation": [10.0, 0.0, 0.0] },
+            { "name": "wheel", "mesh": 0, "translation": [0.0, 1.0, 0.0] },
+            { "name": "tree", "mesh": 1, "translation": [0.0, 0.0, -5.0] }
+        ],
+        "meshes": [{ "name": "wheel_mesh", "primitives": primitives }, { "name": "tree_mesh", "primitives": primitives }]
+    });
+    fixture.write(name, document)
+}
+
+#[test]
+fn named_node_loads_only_its_subtree() {
+    let path = street("named_node");
+    let car = load_model_node(&path, "car").unwrap();
+    assert_eq!(car.meshes.len(), 1);
+    assert_eq!(car.meshes[0].name.as_deref(), Some("wheel_mesh"));
+    assert_eq!(car.meshes[0].vertices[0].position, Vec3::new(10.0, 1.0, 0.0));
+
+    // No node is called `tree_mesh`, so the node drawing the mesh of that name is used.
+    let tree = load_model_node(&path, "tree_mesh").unwrap();
+    assert_eq!(tree.meshes.len(), 1);
+    assert_eq!(tree.meshes[0].vertices[0].position, Vec3::new(0.0, 0.0, -5.0));
+
+    assert!(matches!(load_model_node(&path, "absent"), Err(LoadError::NodeNotFound(name)) if name == "absent"));
+}
+
+#[test]
+fn each_scene_loads_its_own_nodes() {
+    let path = street("two_scenes");
+    let street = load_scene(&path, 0).unwrap();
+    let names: Vec<_> = street.meshes.iter().map(|mesh| mesh.name.as_deref()).collect();
+    assert_eq!(names, [Some("wheel_mesh"), Some("tree_mesh")]);
+    assert_eq!(street.meshes[0].vertices[0].position, Vec3::new(10.0, 1.0, 0.0));
+
+    let tree = load_scene(&path, 1).unwrap();
+    assert_eq!(tree.meshes.len(), 1);
+    assert_eq!(tree.meshes[0].name.as_deref(), Some("tree_mesh"));
+    assert_eq!(tree.meshes[0].vertices[0].position, Vec3::new(0.0, 0.0, -5.0));
+}
+
+#[test]
+fn missing_scenes_fail_the_load() {
+    let path = street("missing_scenes");
+    assert!(matches!(load_scene(&path, 2), Err(LoadError::SceneNotFound(2))));
+    assert!(matches!(load_scene_graph(&path, 2), Err(LoadError::SceneNotFound(2))));
+}
+
+#[test]
+fn flattening_bakes_each_placement_of_a_shared_mesh() {
+    let mut fixture = Fixture::new();
+    let positions = fixture.floats(&TRIANGLE);
+    let normals = fixture.floats(&[[0.0, 0.0, 1.0]; 3]);
+    let document = json!({
+        "scene": 0,
+        "scenes": [{ "nodes": [0] }],
+        "nodes": [
+            { "name": "parent", "children": [1, 2, 3], "translation": [0.0, 0.0, 10.0] },
+            { "mesh": 0, "translation": [1.0, 0.0, 0.0] },
+            { "mesh": 0, "scale": [-1.0, 1.0, 1.0] },
+            { "name": "empty" }
+        ],
+        "meshes": [{ "primitives": [{ "attributes": { "POSITION": positions, "NORMAL": normals } }] }]
+    });
+    let path = fixture.write("flatten", document);
+    let scene = load_scene_graph(&path, 0).unwrap();
+    assert_eq!(scene.model.meshes.len(), 1);
+
+    let flat = scene.to_model().flattened();
+    assert_eq!(flat.meshes.len(), 2);
+    assert!(flat.meshes.iter().all(|mesh| mesh.instances.is_empty()));
+    assert_eq!(flat.meshes[0].vertices[1].position, Vec3::new(2.0, 0.0, 10.0));
+    assert_eq!(flat.meshes[1].vertices[1].position, Vec3::new(-1.0, 0.0, 10.0));
+
+    // The mirrored copy is rewound so its triangle still faces along its normal.
+    for mesh in &flat.meshes {
+        let [a, b, c] = mesh.indices.triangle(0).map(|index| mesh.vertices[index as usize].position);
+        assert!((b - a).cross(c - a).dot(mesh.vertices[0].normal) > 0.0);
+    }
+}
+
+#[test]
+fn scene_graph_options_apply_to_nodes_and_animations() {
+    let path = animated_scene("scene_graph_options");
+    let options = LoaderOptions::new().scale(2.0).unwrap().coordinate_system(CoordinateSystem::ZUp);
+    let scene = load_scene_graph_with(&path, 0, &options).unwrap();
+
+    assert_eq!(scene.nodes[0].translation, Vec3::new(0.0, 20.0, 0.0));
+    assert_eq!(scene.nodes[1].translation, Vec3::new(2.0, 6.0, -4.0));
+    assert_eq!(scene.model.meshes[0].vertices[2].position, Vec3::new(0.0, 0.0, -2.0));
+
+    let pose = scene.sample_animation(0, 1.0, AnimationWrap::Clamp);
+    assert_eq!(pose[1].translation, Vec3::new(2.0, 10.0, -4.0));
+    let world = scene.world_transform(1).transform_point3(scene.model.meshes[0].vertices[2].position);
+    assert!(world.abs_diff_eq(Vec3::new(2.0, 26.0, -6.0), 1e-5));
+}
+
+#[test]
+fn scene_options_apply_to_baked_meshes() {
+    let path = animated_scene("scene_options");
+    let model = load_scene_with(&path, 0, &LoaderOptions::new().scale(2.0).unwrap()).unwrap();
+    assert_eq!(model.meshes[0].vertices[0].position, Vec3::new(2.0, 4.0, 26.0));
+}
+
+#[test]
+fn model_node_options_apply_to_baked_meshes() {
+    let path = animated_scene("model_node_options");
+    let options = LoaderOptions::new().scale(2.0).unwrap().coordinate_system(CoordinateSystem::ZUp);
+    let model = load_model_node_with(&path, "child", &options).unwrap();
+    assert_eq!(model.meshes[0].vertices[0].position, Vec3::new(2.0, 26.0, -4.0));
+}
+
+#[test]
+fn scene_axes_conversion_round_trips_nodes_and_animations() {
+    let path = animated_scene("scene_axes_round_trip");
+    let original = load_scene_graph(&path, 0).unwrap();
+    let mut scene = original.clone();
+
+    scene.convert_axes(CoordinateSystem::YUp, CoordinateSystem::ZUp);
+    assert_eq!(scene.nodes[0].translation, Vec3::new(0.0, -10.0, 0.0));
+    let pose = scene.sample_animation(0, 1.0, AnimationWrap::Clamp);
+    assert_eq!(pose[1].translation, Vec3::new(1.0, -5.0, 2.0));
+
+    scene.convert_axes(CoordinateSystem::ZUp, CoordinateSystem::YUp);
+    for (node, before) in scene.nodes.iter().zip(&original.nodes) {
+        assert_eq!((node.translation, node.rotation, node.scale), (before.translation, before.rotation, before.scale));
+    }
+    assert_eq!(scene.sample_animation(0, 0.5, AnimationWrap::Clamp), original.sample_animation(0, 0.5, AnimationWrap::Clamp));
+    assert_eq!(scene.model.meshes[0].interleaved_bytes(), original.model.meshes[0].interleaved_bytes());
+}
@@ -0,0 +1,47 @@
+#![cfg(feature = "serde")]
+
+mod common;
+
+use common::*;
+use motley::model::{Mesh, Model, Texture, TextureTransform, Vertex};
+use serde_json::json;
+
+#[test]
+fn model_round_trips_through_json() {
+    let model = cube_and_plane();
+    let text = serde_json::to_string(&model).unwrap();
+    let loaded: Model = serde_json::from_str(&text).unwrap();
+
+    assert_same_geometry(&loaded, &model);
+    assert_same_materials(&loaded, &model);
+    for (loaded, mesh) in loaded.meshes.iter().zip(&model.meshes) {
+        assert_eq!(loaded.indices, mesh.indices);
+        assert_eq!(loaded.interleaved_bytes(), mesh.interleaved_bytes());
+    }
+}
+
+#[test]
+fn vertices_and_meshes_serialize_their_glam_fields_as_arrays() {
+    let vertex = Mesh::cube(2.0).vertices[0];
+    let value = serde_json::to_value(vertex).unwrap();
+    assert_eq!(value["position"], json!(vertex.position.to_array()));
+    assert_eq!(value["tex_coord"], json!(vertex.tex_coord.to_array()));
+    assert!(value.get("padding").is_none());
+
+    let back: Vertex = serde_json::from_value(value).unwrap();
+    assert_eq!(back.position, vertex.position);
+    assert_eq!(back.padding, 0.0);
+}
+
+#[test]
+fn texture_keeps_its_pixels_and_rejects_mismatched_sizes() {
+    let texture = Texture::from_rgba8(2, 1, vec![1, 2, 3, 4, 5, 6, 7, 8]).unwrap().with_tex_coord(1);
+    let mut value = serde_json::to_value(&texture).unwrap();
+    let back: Texture = serde_json::from_value(value.clone()).unwrap();
+    assert_eq!(back.pixels(), texture.pixels());
+    assert_eq!((back.width(), back.height(), back.tex_coord()), (2, 1, 1));
+    assert_eq!(back.transform(), TextureTransform::default());
+
+    value["width"] = json!(3);
+    assert!(serde_json::from_value::<Texture>(value).is_err());
+}
@@ -0,0 +1,49 @@
+use glam::*;
+use motley::model::Mesh;
+
+fn boundary_length(mesh: &Mesh) -> f32 {
+    mesh.boundary_edges()
+        .iter()
+        .map(|&(a, b)| mesh.vertices[a as usize].position.distance(mesh.vertices[b as usize].position))
+        .sum()
+}
+
+#[test]
+fn plane_halves_its_triangles_and_keeps_its_outline() {
+    let plane = Mesh::plane(2.0, 2.0, 16);
+    assert_eq!(plane.triangle_count(), 512);
+
+    let simplified = plane.simplify(0.5, f32::INFINITY);
+    assert!((250..=256).contains(&simplified.triangle_count()), "{} triangles", simplified.triangle_count());
+    assert_eq!(simplified.aabb(), plane.aabb());
+    assert!((boundary_length(&simplified) - 8.0).abs() < 1e-5);
+    for vertex in &simplified.vertices {
+        assert_eq!(vertex.position.y, 0.0);
+    }
+}
+
+#[test]
+fn uv_seams_stay_closed() {
+    // The texture wraps around both directions of a torus, so it has two seams.
+    let torus = Mesh::torus(1.0, 0.25, 32, 16);
+    assert!(torus.is_watertight());
+
+    let simplified = torus.simplify(0.25, f32::INFINITY);
+    assert_eq!(simplified.triangle_count(), 256);
+    assert!(simplified.is_watertight());
+}
+
+#[test]
+fn ratio_is_clamped_and_error_bounds_stop_early() {
+    let plane = Mesh::plane(1.0, 1.0, 4);
+    assert_eq!(plane.simplify(2.0, f32::INFINITY).triangle_count(), plane.triangle_count());
+
+    // A flat plane collapses without error, so only a bumpy surface can hit the bound.
+    let mut bumpy = Mesh::plane(1.0, 1.0, 8);
+    for (index, vertex) in bumpy.vertices.iter_mut().enumerate() {
+        vertex.position.y = if index % 2 == 0 { 0.1 } else { -0.1 };
+    }
+    let bounded = bumpy.simplify(0.1, 1e-6);
+    let unbounded = bumpy.simplify(0.1, f32::INFINITY);
+    assert!(bounded.triangle_count() > unbounded.triangle_count());
+}
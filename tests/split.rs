@@ -0,0 +1,49 @@
+use glam::*;
+use motley::model::{Material, MaterialHandle, Mesh, Model};
+
+/*
+A cube drawn with the first material, which also carries a vertex no triangle uses, and a plane
+drawn with the third. The second material is unused.
+*/
+fn model() -> Model {
+    let mut cube = Mesh::cube(1.0);
+    let mut unused = cube.vertices[0];
+    unused.position = Vec3::splat(10.0);
+    cube.vertices.push(unused);
+    let mut plane = Mesh::plane(1.0, 1.0, 1);
+    plane.material = MaterialHandle::from(2);
+
+    let material = |red: f32| Material { base_color: Vec4::new(red, 0.0, 0.0, 1.0), ..Default::default() };
+    Model {
+        meshes: vec![cube, plane],
+        materials: vec![material(0.25), material(0.5), material(0.75)]
+    }
+}
+
+#[test]
+fn extracted_material_keeps_only_its_meshes_and_vertices() {
+    let model = model();
+    let cube = model.extract_material(MaterialHandle::from(0)).unwrap();
+    assert_eq!(cube.materials.len(), 1);
+    assert_eq!(cube.materials[0].base_color.x, 0.25);
+    assert_eq!(cube.meshes.len(), 1);
+    assert_eq!(cube.meshes[0].material, MaterialHandle::from(0));
+    assert_eq!(cube.meshes[0].vertices.len(), 24);
+    assert_eq!(cube.meshes[0].indices, Mesh::cube(1.0).indices);
+
+    let plane = model.extract_material(MaterialHandle::from(2)).unwrap();
+    assert_eq!(plane.materials[0].base_color.x, 0.75);
+    assert_eq!(plane.meshes[0].material, MaterialHandle::from(0));
+
+    assert!(model.extract_material(MaterialHandle::from(1)).unwrap().meshes.is_empty());
+    assert!(model.extract_material(MaterialHandle::from(3)).is_none());
+}
+
+#[test]
+fn split_skips_unused_materials() {
+    let parts = model().split_by_material();
+    assert_eq!(parts.len(), 2);
+    let reds: Vec<f32> = parts.iter().map(|part| part.materials[0].base_color.x).collect();
+    assert_eq!(reds, [0.25, 0.75]);
+    assert!(parts.iter().all(|part| part.meshes.len() == 1 && part.materials.len() == 1));
+}
@@ -0,0 +1,30 @@
+mod common;
+
+use common::*;
+use motley::model::{export_gltf, load_scene, Vertex};
+
+#[test]
+fn footprint_of_a_loaded_model_counts_vertices_indices_and_pixels() {
+    let path = temp_dir("footprint").join("model.glb");
+    export_gltf(&cube_and_plane(), &path, true).unwrap();
+    let model = load_scene(&path, 0).unwrap();
+
+    let vertex_count: usize = model.meshes.iter().map(|mesh| mesh.vertices.len()).sum();
+    let index_count: usize = model.meshes.iter().map(|mesh| mesh.indices.len()).sum();
+    let footprint = model.memory_footprint();
+    assert_eq!(footprint.vertex_bytes, vertex_count * std::mem::size_of::<Vertex>());
+    assert_eq!(footprint.index_bytes, index_count * 2);
+    assert_eq!(footprint.texture_bytes, 2 * 2 * 4);
+    assert_eq!(footprint.total(), footprint.vertex_bytes + footprint.index_bytes + footprint.texture_bytes);
+
+    let stats = model.stats();
+    assert_eq!((stats.vertex_bytes, stats.index_bytes, stats.texture_bytes), (footprint.vertex_bytes, footprint.index_bytes, footprint.texture_bytes));
+}
+
+#[test]
+fn wide_indices_take_four_bytes_each() {
+    let mut model = cube_and_plane();
+    model.meshes[0].widen_indices();
+    let footprint = model.memory_footprint();
+    assert_eq!(footprint.index_bytes, 36 * 4 + model.meshes[1].indices.len() * 2);
+}
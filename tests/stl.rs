@@ -0,0 +1,63 @@
+mod common;
+
+use common::*;
+use glam::*;
+use motley::model::{Mesh, MeshBuilder, Model, StlFormat};
+
+/*
+Reads the facets of a binary STL file as a normal and three corners each.
+*/
+fn read_binary_stl(bytes: &[u8]) -> Vec<[Vec3; 4]> {
+    let count = u32::from_le_bytes(bytes[80..84].try_into().unwrap()) as usize;
+    let floats: Vec<f32> = (0..count)
+        .flat_map(|facet| bytes[84 + facet * 50..84 + facet * 50 + 48].chunks_exact(4))
+        .map(|chunk| f32::from_le_bytes(chunk.try_into().unwrap()))
+        .collect();
+    floats.chunks_exact(12).map(|facet| std::array::from_fn(|i| Vec3::from_slice(&facet[i * 3..]))).collect()
+}
+
+#[test]
+fn stl_export_writes_one_facet_per_triangle() {
+    let model = cube_and_plane();
+    let triangle_count: usize = model.meshes.iter().map(|mesh| mesh.indices.len() / 3).sum();
+    let directory = temp_dir("export_stl");
+
+    let binary = directory.join("model.stl");
+    assert_eq!(model.export_stl(&binary).unwrap(), 0);
+    let bytes = std::fs::read(&binary).unwrap();
+    assert_eq!(u32::from_le_bytes(bytes[80..84].try_into().unwrap()) as usize, triangle_count);
+    assert_eq!(bytes.len(), 84 + triangle_count * 50);
+
+    let ascii = directory.join("ascii.stl");
+    assert_eq!(model.export_stl_with(&ascii, StlFormat::Ascii).unwrap(), 0);
+    let text = std::fs::read_to_string(&ascii).unwrap();
+    assert_eq!(text.matches("facet normal").count(), triangle_count);
+}
+
+#[test]
+fn stl_facets_are_baked_and_take_their_normal_from_the_winding() {
+    let mut builder = MeshBuilder::new();
+    // The vertex normals point the wrong way and must not be trusted.
+    for position in [Vec3::ZERO, Vec3::X, Vec3::Y, Vec3::X * 2.0] {
+        builder.add_vertex(position, Vec3::NEG_Z, Vec2::ZERO);
+    }
+    builder.add_triangle(0, 1, 2).unwrap();
+    builder.add_triangle(0, 1, 3).unwrap();
+    let mut mesh = builder.build();
+    mesh.instances = vec![Mat4::from_translation(Vec3::Z), Mat4::from_scale(Vec3::new(-1.0, 1.0, 1.0))];
+    let mut model = Model::from_mesh(mesh);
+    model.meshes.push(Mesh::cube(1.0));
+
+    let path = temp_dir("export_stl_facets").join("model.stl");
+    // Each instance drops the collinear triangle.
+    assert_eq!(model.export_stl(&path).unwrap(), 2);
+    let facets = read_binary_stl(&std::fs::read(&path).unwrap());
+    assert_eq!(facets.len(), 2 + 12);
+
+    assert_eq!(facets[0], [Vec3::Z, Vec3::Z, Vec3::new(1.0, 0.0, 1.0), Vec3::new(0.0, 1.0, 1.0)]);
+    // The mirrored instance is rewound, so it still faces +Z.
+    assert_eq!(facets[1][0], Vec3::Z);
+    for [normal, a, b, c] in facets {
+        assert!((b - a).cross(c - a).normalize().abs_diff_eq(normal, 1e-6));
+    }
+}
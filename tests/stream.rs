@@ -0,0 +1,61 @@
+mod common;
+
+use common::*;
+use glam::*;
+use motley::model::{export_gltf, load_scene, stream_meshes, MaterialHandle, Mesh, Model};
+use serde_json::json;
+
+#[test]
+fn streams_every_primitive_in_document_order() {
+    let mut fixture = Fixture::new();
+    let triangle = fixture.floats(&TRIANGLE);
+    let quad = fixture.floats(&[[0.0, 0.0, 1.0], [1.0, 0.0, 1.0], [1.0, 1.0, 1.0], [0.0, 1.0, 1.0]]);
+    let quad_indices = fixture.indices(&[0, 1, 2, 0, 2, 3]);
+    let document = json!({
+        "scene": 0,
+        "scenes": [{ "nodes": [0, 1] }],
+        "nodes": [{ "mesh": 0, "translation": [5.0, 0.0, 0.0] }, { "mesh": 1 }],
+        "meshes": [
+            { "name": "first", "primitives": [{ "attributes": { "POSITION": triangle }, "material": 0 }] },
+            { "name": "second", "primitives": [
+                { "attributes": { "POSITION": quad }, "indices": quad_indices, "material": 1 },
+                { "attributes": { "POSITION": triangle } }
+            ] }
+        ],
+        "materials": [{ "name": "a" }, { "name": "b" }]
+    });
+    let path = fixture.write("stream", document);
+
+    let meshes: Vec<Mesh> = stream_meshes(&path).collect::<Result<_, _>>().unwrap();
+    assert_eq!(meshes.len(), 3);
+    let names: Vec<Option<&str>> = meshes.iter().map(|mesh| mesh.name.as_deref()).collect();
+    assert_eq!(names, [Some("first"), Some("second"), Some("second")]);
+    let materials: Vec<MaterialHandle> = meshes.iter().map(|mesh| mesh.material).collect();
+    assert_eq!(materials, [0, 1, 2].map(MaterialHandle::from));
+
+    // Node transforms are not applied, so the first mesh stays where its accessor put it.
+    assert_eq!(meshes[0].vertices[1].position, Vec3::X);
+    assert_eq!(meshes[1].indices.to_u32(), [0, 1, 2, 0, 2, 3]);
+    assert_eq!(meshes[1].vertices[2].position, Vec3::new(1.0, 1.0, 1.0));
+    assert_eq!(meshes[2].indices.to_u32(), [0, 1, 2]);
+}
+
+#[test]
+fn streamed_glb_matches_the_loaded_model() {
+    let model = Model::from_mesh(Mesh::uv_sphere(1.0, 16));
+    let path = temp_dir("stream_glb").join("sphere.glb");
+    export_gltf(&model, &path, true).unwrap();
+
+    let loaded = load_scene(&path, 0).unwrap();
+    let streamed: Vec<Mesh> = stream_meshes(&path).collect::<Result<_, _>>().unwrap();
+    assert_eq!(streamed.len(), 1);
+    assert_eq!(streamed[0].indices, loaded.meshes[0].indices);
+    assert_eq!(streamed[0].interleaved_bytes(), loaded.meshes[0].interleaved_bytes());
+}
+
+#[test]
+fn missing_file_yields_one_error() {
+    let results: Vec<_> = stream_meshes(temp_dir("stream_missing").join("missing.glb")).collect();
+    assert_eq!(results.len(), 1);
+    assert!(results[0].is_err());
+}
@@ -0,0 +1,93 @@
+use glam::*;
+use motley::model::{Mesh, MeshBuilder, MeshError, SubdivisionScheme};
+
+fn position_bits(mesh: &Mesh) -> Vec<[u32; 3]> {
+    mesh.vertices.iter().map(|vertex| vertex.position.to_array().map(f32::to_bits)).collect()
+}
+
+/*
+A closed tetrahedron of four shared vertices, every edge shared by two triangles.
+*/
+fn tetrahedron() -> Mesh {
+    let mut builder = MeshBuilder::new();
+    let corners = [Vec3::new(1.0, 1.0, 1.0), Vec3::new(1.0, -1.0, -1.0), Vec3::new(-1.0, 1.0, -1.0), Vec3::new(-1.0, -1.0, 1.0)];
+    for corner in corners {
+        builder.add_vertex(corner, corner.normalize(), Vec2::ZERO);
+    }
+    for [a, b, c] in [[0, 1, 2], [0, 3, 1], [0, 2, 3], [1, 3, 2]] {
+        builder.add_triangle(a, b, c).unwrap();
+    }
+    builder.build()
+}
+
+#[test]
+fn loop_turns_a_tetrahedron_into_sixteen_triangles() {
+    let subdivided = tetrahedron().subdivide(SubdivisionScheme::Loop, 1).unwrap();
+    assert_eq!(subdivided.indices.len() / 3, 16);
+    assert_eq!(subdivided.vertices.len(), 4 + 6);
+
+    // Every corner has three neighbours, so Loop pulls all four towards the centre equally.
+    let radii: Vec<f32> = subdivided.vertices[..4].iter().map(|vertex| vertex.position.length()).collect();
+    assert!(radii.iter().all(|&radius| radius < 3f32.sqrt() && (radius - radii[0]).abs() < 1e-6));
+
+    let twice = tetrahedron().subdivide(SubdivisionScheme::Loop, 2).unwrap();
+    assert_eq!(twice.indices.len() / 3, 64);
+}
+
+#[test]
+fn one_level_splits_every_triangle_into_four() {
+    let cube = Mesh::cube(1.0);
+    for scheme in [SubdivisionScheme::Midpoint, SubdivisionScheme::Loop] {
+        let subdivided = cube.subdivide(scheme, 1).unwrap();
+        assert_eq!(subdivided.indices.len(), cube.indices.len() * 4);
+        // Every face of four vertices gets one new vertex per side and one on its diagonal.
+        assert_eq!(subdivided.vertices.len(), 24 + 6 * 5);
+    }
+}
+
+#[test]
+fn midpoint_keeps_the_surface() {
+    let subdivided = Mesh::cube(1.0).subdivide(SubdivisionScheme::Midpoint, 2).unwrap();
+    for vertex in &subdivided.vertices {
+        assert_eq!(vertex.position.abs().max_element(), 0.5);
+    }
+}
+
+#[test]
+fn loop_smooths_seams_identically() {
+    let subdivided = Mesh::cube(1.0).subdivide(SubdivisionScheme::Loop, 1).unwrap();
+    let corner = subdivided
+        .vertices
+        .iter()
+        .filter(|vertex| vertex.position.x > 0.0 && vertex.position.y > 0.0 && vertex.position.z > 0.0)
+        .map(|vertex| vertex.position)
+        .max_by(|a, b| a.length_squared().total_cmp(&b.length_squared()))
+        .unwrap();
+    assert!(corner.max_element() < 0.5);
+    let copies = subdivided.vertices.iter().filter(|vertex| vertex.position == corner).count();
+    assert_eq!(copies, 3);
+}
+
+#[test]
+fn loop_output_is_bitwise_stable() {
+    let sphere = Mesh::uv_sphere(1.0, 16);
+    let first = sphere.subdivide(SubdivisionScheme::Loop, 2).unwrap();
+    for _ in 0..8 {
+        let again = sphere.subdivide(SubdivisionScheme::Loop, 2).unwrap();
+        assert_eq!(position_bits(&again), position_bits(&first));
+        assert_eq!(again.indices, first.indices);
+    }
+}
+
+#[test]
+fn oversized_or_incomplete_input_is_refused() {
+    let cube = Mesh::cube(1.0);
+    assert_eq!(
+        cube.subdivide_with_limit(SubdivisionScheme::Loop, 2, 100).unwrap_err(),
+        MeshError::TriangleLimitExceeded { projected: 192, limit: 100 }
+    );
+
+    let mut incomplete = cube.clone();
+    incomplete.indices.truncate(35);
+    assert_eq!(incomplete.subdivide(SubdivisionScheme::Midpoint, 1).unwrap_err(), MeshError::IncompleteTriangles(35));
+}
@@ -0,0 +1,55 @@
+use glam::*;
+use motley::model::{compute_tangents_mikktspace, Mesh, MeshBuilder};
+
+/*
+A strip of two quads in the XY plane facing +Z. The texture runs left to right over the first
+quad and is mirrored back over the second, so the two meet on a UV seam at x = 1.
+*/
+fn mirrored_strip() -> Mesh {
+    let mut builder = MeshBuilder::new();
+    for (x, u) in [(0.0, 0.0), (1.0, 1.0), (2.0, 0.0)] {
+        builder.add_vertex(Vec3::new(x, 0.0, 0.0), Vec3::Z, Vec2::new(u, 1.0));
+        builder.add_vertex(Vec3::new(x, 1.0, 0.0), Vec3::Z, Vec2::new(u, 0.0));
+    }
+    builder.add_quad(0, 2, 3, 1).unwrap();
+    builder.add_quad(2, 4, 5, 3).unwrap();
+    builder.build()
+}
+
+#[test]
+fn handedness_flips_across_a_mirrored_seam() {
+    let mut strip = mirrored_strip();
+    compute_tangents_mikktspace(&mut strip);
+
+    // The two seam vertices are shared by both sides, so each gets a mirrored copy.
+    assert_eq!(strip.vertices.len(), 8);
+    let left_w = strip.vertices[0].tangent.w;
+    assert_eq!(left_w.abs(), 1.0);
+    for [a, b, c] in strip.indices.triangles() {
+        let corners = [a, b, c].map(|index| strip.vertices[index as usize]);
+        let left = corners.iter().map(|vertex| vertex.position.x).sum::<f32>() < 3.0;
+        for vertex in corners {
+            let (tangent, w) = (vertex.tangent.truncate(), vertex.tangent.w);
+            assert_eq!(w, if left { left_w } else { -left_w });
+            assert!(tangent.abs_diff_eq(if left { Vec3::X } else { Vec3::NEG_X }, 1e-6));
+
+            // The bitangent points along increasing v, which runs down the strip on both sides.
+            let bitangent = w * vertex.normal.cross(tangent);
+            assert!(bitangent.abs_diff_eq(Vec3::NEG_Y, 1e-6));
+        }
+    }
+}
+
+#[test]
+fn seamless_vertices_share_one_tangent() {
+    let mut plane = Mesh::plane(2.0, 2.0, 4);
+    let vertex_count = plane.vertices.len();
+    compute_tangents_mikktspace(&mut plane);
+
+    assert_eq!(plane.vertices.len(), vertex_count);
+    for vertex in &plane.vertices {
+        assert!(vertex.tangent.truncate().abs_diff_eq(vertex.tangent.truncate().normalize(), 1e-6));
+        assert!(vertex.tangent.truncate().dot(vertex.normal).abs() < 1e-6);
+        assert_eq!(vertex.tangent, plane.vertices[0].tangent);
+    }
+}
@@ -0,0 +1,241 @@
+mod common;
+
+use common::*;
+use glam::*;
+use motley::model::{load_texture, load_texture_from_memory, FilterMode, LoadError, Mesh, Model, Texture, TextureError, TextureFormat, WrapMode};
+
+#[test]
+fn png_loads_from_file_and_memory() {
+    let path = temp_dir("texture_png").join("image.png");
+    std::fs::write(&path, png()).unwrap();
+
+    let texture = load_texture(&path).unwrap();
+    assert_eq!((texture.width(), texture.height()), (2, 2));
+    let texture = load_texture_from_memory(&png()).unwrap();
+    assert_eq!((texture.width(), texture.height()), (2, 2));
+}
+
+#[test]
+fn truncated_png_fails_to_load() {
+    let bytes = png();
+    let truncated = &bytes[..bytes.len() / 2];
+    let path = temp_dir("texture_truncated").join("image.png");
+    std::fs::write(&path, truncated).unwrap();
+
+    assert!(matches!(load_texture(&path), Err(LoadError::Texture(_))));
+    assert!(matches!(load_texture_from_memory(truncated), Err(LoadError::Texture(_))));
+}
+
+#[test]
+fn missing_file_fails_to_load() {
+    let path = temp_dir("texture_missing").join("missing.png");
+    assert!(matches!(load_texture(&path), Err(LoadError::Texture(_))));
+}
+
+#[test]
+fn raw_pixels_are_rows_from_the_top() {
+    // Red, green on the top row and blue, transparent white on the bottom one.
+    let pixels = vec![255, 0, 0, 255, 0, 255, 0, 255, 0, 0, 255, 255, 255, 255, 255, 0];
+    let texture = Texture::from_rgba8(2, 2, pixels.clone()).unwrap();
+    assert_eq!((texture.width(), texture.height(), texture.channel_count()), (2, 2, 4));
+    assert_eq!(texture.pixels(), pixels);
+    assert_eq!(texture.pixel(1, 0), Vec4::new(0.0, 1.0, 0.0, 1.0));
+    assert_eq!(texture.pixel(0, 1), Vec4::new(0.0, 0.0, 1.0, 1.0));
+    assert_eq!(texture.pixel(1, 1), Vec4::new(1.0, 1.0, 1.0, 0.0));
+
+    let built = Texture::from_fn(2, 2, |x, y| texture.pixel(x, y));
+    assert_eq!(built.pixels(), pixels);
+}
+
+#[test]
+fn raw_pixels_must_fill_the_texture() {
+    for length in [0, 15, 17] {
+        let error = Texture::from_rgba8(2, 2, vec![0; length]).unwrap_err();
+        assert!(matches!(error, TextureError::InvalidData(_)));
+        assert!(error.to_string().contains(&format!("{} bytes", length)));
+    }
+}
+
+#[test]
+fn generated_pixels_are_clamped_and_rounded_to_bytes() {
+    let texture = Texture::from_fn(3, 1, |x, _| match x {
+        0 => Vec4::new(-1.0, 2.0, 0.5, 1.0),
+        1 => Vec4::splat(0.1),
+        _ => Vec4::ONE
+    });
+    assert_eq!(texture.pixels(), [0, 255, 128, 255, 26, 26, 26, 26, 255, 255, 255, 255]);
+}
+
+#[test]
+#[should_panic(expected = "outside the texture")]
+fn pixels_outside_the_texture_panic() {
+    Texture::from_fn(2, 2, |_, _| Vec4::ONE).pixel(2, 0);
+}
+
+/*
+A 2×1 texture, black on the left and white on the right, sampled along its row.
+*/
+fn stripe(u: f32, wrap: WrapMode, filter: FilterMode) -> f32 {
+    let texture = Texture::from_fn(2, 1, |x, _| Vec4::new(x as f32, x as f32, x as f32, 1.0));
+    texture.sample(Vec2::new(u, 0.5), wrap, filter).x
+}
+
+#[test]
+fn single_pixel_textures_sample_their_pixel_everywhere() {
+    let color = Vec4::new(0.2, 0.4, 0.6, 1.0);
+    let texture = Texture::from_fn(1, 1, |_, _| color);
+    let expected = texture.sample(Vec2::ZERO, WrapMode::ClampToEdge, FilterMode::Nearest);
+    assert!(expected.abs_diff_eq(color, 1.0 / 255.0));
+    for wrap in [WrapMode::Repeat, WrapMode::ClampToEdge, WrapMode::MirroredRepeat] {
+        for filter in [FilterMode::Nearest, FilterMode::Bilinear] {
+            for uv in [Vec2::ZERO, Vec2::ONE, Vec2::new(0.5, 0.25), Vec2::new(-3.7, 12.1), Vec2::splat(1e4)] {
+                assert_eq!(texture.sample(uv, wrap, filter), expected);
+            }
+        }
+    }
+}
+
+#[test]
+fn bilinear_filtering_blends_between_pixel_centers() {
+    for wrap in [WrapMode::Repeat, WrapMode::ClampToEdge, WrapMode::MirroredRepeat] {
+        assert_eq!(stripe(0.25, wrap, FilterMode::Bilinear), 0.0);
+        assert_eq!(stripe(0.5, wrap, FilterMode::Bilinear), 0.5);
+        assert_eq!(stripe(0.75, wrap, FilterMode::Bilinear), 1.0);
+    }
+
+    // Repeat blends across the 0/1 boundary, so the edges of neighbouring tiles meet.
+    assert_eq!(stripe(0.0, WrapMode::Repeat, FilterMode::Bilinear), 0.5);
+    assert_eq!(stripe(1.0, WrapMode::Repeat, FilterMode::Bilinear), 0.5);
+    assert_eq!(stripe(0.125, WrapMode::Repeat, FilterMode::Bilinear), 0.25);
+    // Clamping and mirroring hold the edge pixel up to the border.
+    assert_eq!(stripe(0.0, WrapMode::ClampToEdge, FilterMode::Bilinear), 0.0);
+    assert_eq!(stripe(1.0, WrapMode::ClampToEdge, FilterMode::Bilinear), 1.0);
+    assert_eq!(stripe(0.0, WrapMode::MirroredRepeat, FilterMode::Bilinear), 0.0);
+    assert_eq!(stripe(1.0, WrapMode::MirroredRepeat, FilterMode::Bilinear), 1.0);
+}
+
+#[test]
+fn coordinates_outside_the_texture_wrap_in_every_period() {
+    let nearest = |u: f32, wrap: WrapMode| stripe(u, wrap, FilterMode::Nearest);
+
+    for u in [0.25, 1.25, -0.75, 1000.25, -999.75] {
+        assert_eq!(nearest(u, WrapMode::Repeat), 0.0);
+    }
+    for u in [0.75, -0.25, 7.75] {
+        assert_eq!(nearest(u, WrapMode::Repeat), 1.0);
+    }
+
+    // Every other period is flipped, so the pixel next to an edge is the same on both sides.
+    for u in [0.25, -0.25, 1.75, 2.25, -1.75, 1000.25, -1000.25] {
+        assert_eq!(nearest(u, WrapMode::MirroredRepeat), 0.0);
+    }
+    for u in [0.75, 1.25, -1.25, -0.75] {
+        assert_eq!(nearest(u, WrapMode::MirroredRepeat), 1.0);
+    }
+
+    for u in [-0.25, -5.0, -1e6] {
+        assert_eq!(nearest(u, WrapMode::ClampToEdge), 0.0);
+    }
+    for u in [1.25, 7.0, 1e6] {
+        assert_eq!(nearest(u, WrapMode::ClampToEdge), 1.0);
+    }
+}
+
+#[test]
+fn solid_textures_are_one_pixel() {
+    let magenta = Vec4::new(1.0, 0.0, 1.0, 1.0);
+    let texture = Texture::solid(magenta);
+    assert_eq!((texture.width(), texture.height()), (1, 1));
+    assert_eq!(texture.pixel(0, 0), magenta);
+}
+
+#[test]
+fn checkerboards_alternate_from_the_top_left() {
+    let (white, black) = (Vec4::ONE, Vec4::W);
+    let board = Texture::checkerboard(4, 2, white, black);
+    let rows: Vec<Vec<bool>> = (0..4).map(|y| (0..4).map(|x| board.pixel(x, y) == white).collect()).collect();
+    assert_eq!(rows, [
+        [true, true, false, false],
+        [true, true, false, false],
+        [false, false, true, true],
+        [false, false, true, true]
+    ]);
+
+    // Five pixels in two squares split three and two.
+    let uneven = Texture::checkerboard(5, 2, white, black);
+    let row: Vec<bool> = (0..5).map(|x| uneven.pixel(x, 0) == white).collect();
+    assert_eq!(row, [true, true, true, false, false]);
+}
+
+#[test]
+fn checkerboard_debug_material_is_two_lines() {
+    let mut model = Model::from_mesh(Mesh::plane(1.0, 1.0, 1));
+    model.materials[0].base_color_texture = Some(Texture::checkerboard(256, 8, Vec4::ONE, Vec4::W));
+
+    let texture = model.materials[0].base_color_texture.as_ref().unwrap();
+    assert_eq!(texture.sample(Vec2::splat(0.01), WrapMode::Repeat, FilterMode::Nearest), Vec4::ONE);
+    assert_eq!(texture.sample(Vec2::new(0.14, 0.01), WrapMode::Repeat, FilterMode::Nearest), Vec4::W);
+}
+
+#[test]
+fn uv_gradient_holds_the_coordinates_of_pixel_centers() {
+    let gradient = Texture::uv_gradient(4);
+    for (x, y) in [(0, 0), (1, 2), (3, 3)] {
+        let expected = Vec2::new(x as f32 + 0.5, y as f32 + 0.5) / 4.0;
+        let pixel = gradient.pixel(x, y);
+        assert!(pixel.xy().abs_diff_eq(expected, 0.5 / 255.0));
+        assert_eq!(pixel.zw(), Vec2::new(0.0, 1.0));
+    }
+    // Sampling the gradient gives back about the coordinates asked for.
+    let uv = Vec2::new(0.4, 0.7);
+    assert!(gradient.sample(uv, WrapMode::ClampToEdge, FilterMode::Bilinear).xy().abs_diff_eq(uv, 0.01));
+}
+
+#[test]
+fn sixteen_bit_pngs_keep_their_precision() {
+    let mut bytes = Vec::new();
+    let mut encoder = png::Encoder::new(&mut bytes, 2, 1);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Sixteen);
+    let samples: [u16; 6] = [0x1234, 0, 65535, 1, 32768, 65534];
+    let data: Vec<u8> = samples.iter().flat_map(|sample| sample.to_be_bytes()).collect();
+    encoder.write_header().unwrap().write_image_data(&data).unwrap();
+    let path = temp_dir("texture_png16").join("normals.png");
+    std::fs::write(&path, &bytes).unwrap();
+
+    let texture = load_texture(&path).unwrap();
+    assert_eq!((texture.format(), texture.channel_count()), (TextureFormat::Rgba16, 3));
+    let stored: Vec<u8> = samples.iter().flat_map(|sample| sample.to_le_bytes()).collect();
+    assert_eq!(texture.pixels(), stored);
+    // Values 8 bits could not tell apart from their neighbours stay distinct.
+    assert_eq!(texture.pixel(0, 0), Vec4::new(0x1234 as f32 / 65535.0, 0.0, 1.0, 1.0));
+    assert_eq!(texture.pixel(1, 0), Vec4::new(1.0 / 65535.0, 32768.0 / 65535.0, 65534.0 / 65535.0, 1.0));
+
+    assert_eq!(load_texture_from_memory(&png()).unwrap().format(), TextureFormat::Rgba8);
+}
+
+#[test]
+fn radiance_hdr_images_keep_values_above_one() {
+    // Two pixels stored flat as RGBE: a mantissa per channel sharing the last byte as exponent.
+    let mut bytes = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 1 +X 2\n".to_vec();
+    bytes.extend([128, 64, 32, 129, 128, 128, 128, 131]);
+    let path = temp_dir("texture_hdr").join("sky.hdr");
+    std::fs::write(&path, &bytes).unwrap();
+
+    let texture = load_texture(&path).unwrap();
+    assert_eq!((texture.format(), texture.width(), texture.height()), (TextureFormat::Rgba32F, 2, 1));
+    assert_eq!(texture.pixel(0, 0), Vec4::new(1.0, 0.5, 0.25, 1.0));
+    assert_eq!(texture.pixel(1, 0), Vec4::new(4.0, 4.0, 4.0, 1.0));
+}
+
+#[cfg(feature = "exr")]
+#[test]
+fn openexr_images_decode_to_floats() {
+    let path = temp_dir("texture_exr").join("light.exr");
+    exr::prelude::write_rgba_file(&path, 2, 2, |x, y| (x as f32 * 8.0, y as f32, 0.5, 1.0)).unwrap();
+
+    let texture = load_texture(&path).unwrap();
+    assert_eq!((texture.format(), texture.channel_count()), (TextureFormat::Rgba32F, 4));
+    assert_eq!(texture.pixel(1, 0), Vec4::new(8.0, 0.0, 0.5, 1.0));
+    assert_eq!(texture.pixel(0, 1), Vec4::new(0.0, 1.0, 0.5, 1.0));
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use common::*;
+use motley::model::{export_gltf, Mesh, Model};
+use motley::render::{render_thumbnail, thumbnail_image};
+
+/*
+Decodes an RGBA PNG into its size and pixels.
+*/
+fn decode(path: &std::path::Path) -> (u32, u32, Vec<u8>) {
+    let mut reader = png::Decoder::new(std::fs::File::open(path).unwrap()).read_info().unwrap();
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut pixels).unwrap();
+    assert_eq!(info.color_type, png::ColorType::Rgba);
+    (info.width, info.height, pixels)
+}
+
+#[test]
+fn cube_thumbnail_fills_the_middle_of_the_image() {
+    let directory = temp_dir("thumbnail_cube");
+    let model_path = directory.join("cube.glb");
+    export_gltf(&Model::from_mesh(Mesh::cube(1.0)), &model_path, true).unwrap();
+    let png_path = directory.join("cube.png");
+    render_thumbnail(&model_path, &png_path, 32).unwrap();
+
+    let (width, height, pixels) = decode(&png_path);
+    assert_eq!((width, height), (32, 32));
+    let alpha = |x: usize, y: usize| pixels[(y * 32 + x) * 4 + 3];
+    assert_eq!(alpha(16, 16), 255);
+    assert_eq!(alpha(0, 0), 0);
+    assert!(pixels.chunks(4).any(|pixel| pixel[..3] != [0, 0, 0]));
+}
+
+#[test]
+fn empty_model_gives_a_blank_image() {
+    let empty = Model { meshes: Vec::new(), materials: Vec::new() };
+    let pixels = thumbnail_image(&empty, 8);
+    assert_eq!(pixels.len(), 8 * 8 * 4);
+    assert!(pixels.iter().all(|&byte| byte == 0));
+}
+
+#[test]
+fn missing_model_is_an_error() {
+    let directory = temp_dir("thumbnail_missing");
+    assert!(render_thumbnail(directory.join("missing.glb"), directory.join("missing.png"), 16).is_err());
+    assert!(!directory.join("missing.png").exists());
+}
@@ -0,0 +1,122 @@
+use glam::*;
+use motley::model::{Aabb, Mesh, MeshBuilder, MeshError, Model};
+
+/*
+A model of one cube with sides of `size`, centered on `center`.
+*/
+fn cube_at(size: f32, center: Vec3) -> Model {
+    let mut cube = Mesh::cube(size);
+    cube.transform(Mat4::from_translation(center)).unwrap();
+    Model::from_mesh(cube)
+}
+
+#[test]
+fn ten_unit_cube_scales_to_a_unit_box() {
+    let mut model = cube_at(10.0, Vec3::new(20.0, -3.0, 7.0));
+    assert!((model.scale_to_unit_box() - 0.1).abs() < 1e-6);
+
+    let aabb = model.aabb();
+    assert!(aabb.min.abs_diff_eq(Vec3::splat(-0.5), 1e-5));
+    assert!(aabb.max.abs_diff_eq(Vec3::splat(0.5), 1e-5));
+}
+
+#[test]
+fn flat_model_scales_by_its_largest_side() {
+    let mut model = Model::from_mesh(Mesh::plane(4.0, 2.0, 1));
+    assert_eq!(model.scale_to_unit_box(), 0.25);
+    assert_eq!(model.aabb(), Aabb::new(Vec3::new(-0.5, 0.0, -0.25), Vec3::new(0.5, 0.0, 0.25)));
+}
+
+#[test]
+fn normals_follow_the_inverse_transpose() {
+    // A slope rising along X; stretching X by 2 halves the slope, tilting the normal up.
+    let mut builder = MeshBuilder::new();
+    let normal = Vec3::new(-1.0, 1.0, 0.0).normalize();
+    for position in [Vec3::ZERO, Vec3::new(1.0, 1.0, 0.0), Vec3::new(0.0, 0.0, -1.0)] {
+        builder.add_vertex(position, normal, Vec2::ZERO);
+    }
+    builder.add_triangle(0, 1, 2).unwrap();
+    let mut slope = builder.build();
+    slope.vertices[0].tangent = Vec3::new(1.0, 1.0, 0.0).normalize().extend(1.0);
+
+    slope.transform(Mat4::from_scale(Vec3::new(2.0, 1.0, 1.0))).unwrap();
+    assert_eq!(slope.vertices[1].position, Vec3::new(2.0, 1.0, 0.0));
+    let expected = Vec3::new(-1.0, 2.0, 0.0).normalize();
+    assert!(slope.vertices[0].normal.abs_diff_eq(expected, 1e-6));
+    let tangent = slope.vertices[0].tangent;
+    assert!(tangent.xyz().abs_diff_eq(Vec3::new(2.0, 1.0, 0.0).normalize(), 1e-6));
+    assert_eq!(tangent.w, 1.0);
+    assert!(tangent.xyz().dot(slope.vertices[0].normal).abs() < 1e-6);
+}
+
+#[test]
+fn mirroring_flips_the_tangent_handedness() {
+    let mut cube = Mesh::cube(1.0);
+    for vertex in &mut cube.vertices {
+        vertex.tangent = vertex.normal.any_orthonormal_vector().extend(1.0);
+    }
+    cube.transform(Mat4::from_scale(Vec3::new(-1.0, 1.0, 1.0))).unwrap();
+    assert!(cube.vertices.iter().all(|vertex| vertex.tangent.w == -1.0));
+}
+
+#[test]
+fn singular_matrices_are_refused_untouched() {
+    let mut model = Model::from_mesh(Mesh::cube(1.0));
+    let before = model.meshes[0].interleaved_bytes();
+    for matrix in [Mat4::ZERO, Mat4::from_scale(Vec3::new(1.0, 0.0, 1.0)), Mat4::from_scale(Vec3::splat(f32::NAN))] {
+        assert_eq!(model.meshes[0].transform(matrix), Err(MeshError::NonInvertibleMatrix));
+        assert_eq!(model.transform(matrix), Err(MeshError::NonInvertibleMatrix));
+    }
+    assert_eq!(model.meshes[0].interleaved_bytes(), before);
+}
+
+#[test]
+fn model_transform_moves_every_mesh_and_instance() {
+    let mut instanced = Mesh::plane(1.0, 1.0, 1);
+    instanced.instances = vec![Mat4::from_translation(Vec3::X)];
+    let mut model = Model::from_mesh(Mesh::cube(1.0));
+    model.merge(Model::from_mesh(instanced));
+
+    let matrix = Mat4::from_translation(Vec3::new(0.0, 3.0, 0.0));
+    model.transform(matrix).unwrap();
+    assert_eq!(model.meshes[0].aabb().center(), Vec3::new(0.0, 3.0, 0.0));
+    assert_eq!(model.meshes[1].aabb().center(), Vec3::new(0.0, 3.0, 0.0));
+    // The copy still sits one unit along X from the moved mesh.
+    let placed = model.meshes[1].instances[0].transform_point3(model.meshes[1].aabb().center());
+    assert!(placed.abs_diff_eq(Vec3::new(1.0, 3.0, 0.0), 1e-6));
+}
+
+#[test]
+fn normalize_to_unit_returns_an_invertible_matrix() {
+    let mut model = cube_at(4.0, Vec3::new(100.0, 0.0, -50.0));
+    model.merge(cube_at(2.0, Vec3::new(104.0, 0.0, -50.0)));
+    let original = model.clone();
+
+    let matrix = model.normalize_to_unit();
+    let aabb = model.aabb();
+    assert!(aabb.center().abs_diff_eq(Vec3::ZERO, 1e-6));
+    assert!((aabb.extent().max_element() - 1.0).abs() < 1e-6);
+
+    let restored = matrix.inverse();
+    for (mesh, original) in model.meshes.iter().zip(&original.meshes) {
+        for (vertex, original) in mesh.vertices.iter().zip(&original.vertices) {
+            assert!(restored.transform_point3(vertex.position).abs_diff_eq(original.position, 1e-4));
+        }
+    }
+}
+
+#[test]
+fn degenerate_models_are_only_centered() {
+    let mut builder = MeshBuilder::new();
+    for _ in 0..3 {
+        builder.add_vertex(Vec3::new(3.0, 4.0, 5.0), Vec3::Z, Vec2::ZERO);
+    }
+    builder.add_triangle(0, 1, 2).unwrap();
+    let mut point = Model::from_mesh(builder.build());
+
+    assert_eq!(point.normalize_to_unit(), Mat4::from_translation(Vec3::new(-3.0, -4.0, -5.0)));
+    assert!(point.meshes[0].vertices.iter().all(|vertex| vertex.position == Vec3::ZERO));
+
+    let mut empty = Model::from_mesh(MeshBuilder::new().build());
+    assert_eq!(empty.normalize_to_unit(), Mat4::IDENTITY);
+}
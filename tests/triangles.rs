@@ -0,0 +1,64 @@
+use glam::*;
+use motley::model::{Mesh, MeshBuilder};
+
+#[test]
+fn cube_triangles_have_their_face_normal_area_and_centroid() {
+    let cube = Mesh::cube(2.0);
+    assert_eq!(cube.triangles().count(), 12);
+
+    for (position, triangle) in cube.triangles().enumerate() {
+        assert_eq!(triangle.index, position);
+        assert_eq!(triangle.indices, cube.indices.triangle(position));
+        assert_eq!(triangle.normal(), triangle.vertices[0].normal);
+        assert_eq!(triangle.area(), 2.0);
+        // The centroid sits a third of the way across the side, on the side's plane.
+        assert_eq!(triangle.centroid().dot(triangle.normal()), 1.0);
+    }
+    assert_eq!(cube.triangles().map(|triangle| triangle.area()).sum::<f32>(), 24.0);
+}
+
+#[test]
+fn both_index_widths_yield_the_same_triangles() {
+    let narrow = Mesh::uv_sphere(1.0, 8);
+    let mut wide = narrow.clone();
+    wide.widen_indices();
+    assert!(narrow.indices.is_u16() && !wide.indices.is_u16());
+
+    let corners = |mesh: &Mesh| mesh.triangles().map(|triangle| (triangle.index, triangle.positions())).collect::<Vec<_>>();
+    assert_eq!(corners(&narrow), corners(&wide));
+    assert_eq!(narrow.triangle_indices(), wide.triangle_indices());
+}
+
+#[test]
+fn partial_and_out_of_range_triangles_are_skipped() {
+    let mut builder = MeshBuilder::new();
+    for position in [Vec3::ZERO, Vec3::X, Vec3::Y] {
+        builder.add_vertex(position, Vec3::Z, Vec2::ZERO);
+    }
+    let mut mesh = builder.build();
+    mesh.indices.extend([0, 1, 2, 0, 1, 7, 2, 1, 0, 1]);
+
+    let triangles: Vec<(usize, [u32; 3])> = mesh.triangles().map(|triangle| (triangle.index, triangle.indices)).collect();
+    assert_eq!(triangles, [(0, [0, 1, 2]), (2, [2, 1, 0])]);
+    assert_eq!(mesh.triangle_indices(), triangles);
+
+    let first = mesh.triangles().next().unwrap();
+    assert_eq!((first.normal(), first.area()), (Vec3::Z, 0.5));
+    mesh.vertices[2].position = Vec3::X * 2.0;
+    let flat = mesh.triangles().next().unwrap();
+    assert_eq!((flat.normal(), flat.area()), (Vec3::ZERO, 0.0));
+}
+
+#[test]
+fn triangle_indices_allow_editing_the_mesh() {
+    let mut plane = Mesh::plane(1.0, 1.0, 2);
+    for (index, [a, b, c]) in plane.triangle_indices() {
+        plane.indices.set(index * 3 + 1, c);
+        plane.indices.set(index * 3 + 2, b);
+        for corner in [a, b, c] {
+            plane.vertices[corner as usize].normal = Vec3::NEG_Y;
+        }
+    }
+    assert!(plane.triangles().all(|triangle| triangle.normal() == Vec3::NEG_Y));
+    assert!(plane.vertices.iter().all(|vertex| vertex.normal == Vec3::NEG_Y));
+}
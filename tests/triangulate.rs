@@ -0,0 +1,53 @@
+use glam::*;
+use motley::model::triangulate_polygon;
+
+/*
+The summed area vector of the triangles, which points along the polygon's normal when they keep
+its winding.
+*/
+fn area(points: &[Vec3], triangles: &[[u32; 3]]) -> Vec3 {
+    triangles
+        .iter()
+        .map(|&[a, b, c]| {
+            let [a, b, c] = [a, b, c].map(|index| points[index as usize]);
+            (b - a).cross(c - a) * 0.5
+        })
+        .sum()
+}
+
+#[test]
+fn concave_polygon_is_covered_without_overlaps() {
+    // An L shape whose reflex corner breaks a fan from the first point.
+    let points = [[0.0, 0.0], [2.0, 0.0], [2.0, 1.0], [1.0, 1.0], [1.0, 2.0], [0.0, 2.0]].map(|[x, y]| Vec3::new(x, y, 0.0));
+    let triangles = triangulate_polygon(&points);
+    assert_eq!(triangles.len(), 4);
+    for &[a, b, c] in &triangles {
+        let [a, b, c] = [a, b, c].map(|index| points[index as usize]);
+        assert!((b - a).cross(c - a).z > 0.0);
+    }
+    assert!(area(&points, &triangles).abs_diff_eq(Vec3::new(0.0, 0.0, 3.0), 1e-6));
+}
+
+#[test]
+fn polygons_off_the_xy_plane_keep_their_winding() {
+    let points = [Vec3::ZERO, Vec3::Z, Vec3::new(1.0, 0.0, 1.0), Vec3::X];
+    let triangles = triangulate_polygon(&points);
+    assert_eq!(triangles.len(), 2);
+    assert!(area(&points, &triangles).abs_diff_eq(Vec3::Y, 1e-6));
+}
+
+#[test]
+fn collinear_and_repeated_points_add_no_slivers() {
+    let points = [Vec3::ZERO, Vec3::new(0.5, 0.0, 0.0), Vec3::X, Vec3::X, Vec3::new(1.0, 1.0, 0.0), Vec3::Y];
+    let triangles = triangulate_polygon(&points);
+    assert_eq!(triangles.len(), 2);
+    assert!(area(&points, &triangles).abs_diff_eq(Vec3::Z, 1e-6));
+}
+
+#[test]
+fn degenerate_polygons_give_no_triangles() {
+    assert!(triangulate_polygon(&[]).is_empty());
+    assert!(triangulate_polygon(&[Vec3::ZERO, Vec3::X]).is_empty());
+    assert!(triangulate_polygon(&[Vec3::ZERO, Vec3::X, Vec3::X, Vec3::ZERO]).is_empty());
+    assert!(triangulate_polygon(&[Vec3::ZERO, Vec3::X, Vec3::new(2.0, 0.0, 0.0)]).is_empty());
+}
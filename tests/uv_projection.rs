@@ -0,0 +1,63 @@
+use glam::*;
+use motley::model::{Mesh, UvProjection};
+
+fn triangle_uvs(mesh: &Mesh) -> Vec<[Vec2; 3]> {
+    mesh.indices
+        .to_u32()
+        .chunks(3)
+        .map(|triangle| [0, 1, 2].map(|corner| mesh.vertices[triangle[corner] as usize].tex_coord))
+        .collect()
+}
+
+#[test]
+fn planar_projection_spans_the_unit_square() {
+    let mut plane = Mesh::plane(4.0, 2.0, 2);
+    for vertex in &mut plane.vertices {
+        vertex.tex_coord = Vec2::ZERO;
+    }
+    plane.project_uvs(UvProjection::Planar { axis: Vec3::Y });
+
+    assert_eq!(plane.vertices.len(), 9);
+    for vertex in &plane.vertices {
+        // Seen from above with -Z up, X runs along U and Z down along V.
+        let expected = Vec2::new(vertex.position.x / 4.0 + 0.5, vertex.position.z / 2.0 + 0.5);
+        assert!(vertex.tex_coord.abs_diff_eq(expected, 1e-6), "{:?}", vertex.tex_coord);
+    }
+}
+
+#[test]
+fn box_projection_splits_corners_between_faces() {
+    let mut cube = Mesh::cube(2.0);
+    cube.weld_vertices(0.0, false);
+    assert_eq!(cube.vertices.len(), 8);
+
+    cube.project_uvs(UvProjection::Box);
+    assert_eq!(cube.vertices.len(), 24);
+    assert_eq!(cube.triangle_count(), 12);
+    for uvs in triangle_uvs(&cube) {
+        for uv in uvs {
+            assert!(uv == Vec2::ZERO || uv == Vec2::ONE || uv == Vec2::X || uv == Vec2::Y, "{uv:?}");
+        }
+        assert_ne!((uvs[1] - uvs[0]).perp_dot(uvs[2] - uvs[0]), 0.0);
+    }
+}
+
+#[test]
+fn spherical_projection_duplicates_the_seam() {
+    let mut sphere = Mesh::uv_sphere(1.0, 16);
+    sphere.weld_vertices(0.0, false);
+    let welded = sphere.vertices.len();
+
+    sphere.project_uvs(UvProjection::Spherical);
+    assert!(sphere.vertices.len() > welded);
+    assert!(sphere.vertices[welded..].iter().all(|vertex| vertex.tex_coord.x >= 1.0));
+    assert!(sphere.vertices[..welded].iter().all(|vertex| (0.0..=1.0).contains(&vertex.tex_coord.x)));
+
+    for (uvs, triangle) in triangle_uvs(&sphere).iter().zip(sphere.indices.to_u32().chunks(3)) {
+        let at_pole = triangle.iter().any(|&index| sphere.vertices[index as usize].position.y.abs() > 0.999);
+        let us = uvs.map(|uv| uv.x);
+        let span = us.iter().copied().fold(f32::MIN, f32::max) - us.iter().copied().fold(f32::MAX, f32::min);
+        assert!(at_pole || span <= 0.5, "{us:?}");
+        assert!(uvs.iter().all(|uv| (0.0..=1.0).contains(&uv.y)));
+    }
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use common::*;
+use glam::*;
+use motley::model::{load_model_with, IssueKind, LoaderOptions, MaterialHandle, Mesh, MeshBuilder, Model, Severity, ValidationOptions, ValidationReport};
+use serde_json::json;
+
+fn elements(report: &ValidationReport, kind: IssueKind) -> Option<Vec<usize>> {
+    report.issues.iter().find(|issue| issue.kind == kind).map(|issue| issue.elements.clone())
+}
+
+#[test]
+fn clean_primitives_have_no_issues() {
+    for mesh in [Mesh::cube(1.0), Mesh::uv_sphere(1.0, 16), Mesh::torus(1.0, 0.25, 16, 8)] {
+        assert_eq!(mesh.validate(), ValidationReport::default());
+    }
+}
+
+#[test]
+fn broken_mesh_reports_every_problem_without_panicking() {
+    let mut mesh = Mesh::plane(1.0, 1.0, 1);
+    mesh.vertices[0].position.x = f32::NAN;
+    mesh.vertices[1].normal = Vec3::ZERO;
+    mesh.vertices[2].normal = Vec3::Y * 2.0;
+    mesh.vertices[3].tex_coord = Vec2::new(100.0, 0.0);
+    for index in [0, 0, 1, 1, 2, 9, 1] {
+        mesh.indices.push(index);
+    }
+
+    let report = mesh.validate();
+    assert!(!report.is_valid());
+    assert_eq!(elements(&report, IssueKind::NonFinitePosition), Some(vec![0]));
+    assert_eq!(elements(&report, IssueKind::ZeroNormal), Some(vec![1]));
+    assert_eq!(elements(&report, IssueKind::UnnormalizedNormal), Some(vec![2]));
+    assert_eq!(elements(&report, IssueKind::UvOutOfRange), Some(vec![3]));
+    assert_eq!(elements(&report, IssueKind::DuplicateIndex), Some(vec![2]));
+    assert_eq!(elements(&report, IssueKind::IndexOutOfRange), Some(vec![3]));
+    assert_eq!(elements(&report, IssueKind::IncompleteTriangle), Some(vec![12]));
+    assert!(report.issues.iter().all(|issue| issue.mesh.is_none() && issue.severity == issue.kind.severity()));
+    assert!(!report.to_string().is_empty());
+
+    let wide = ValidationOptions { uv_max: 128.0, ..Default::default() };
+    assert_eq!(elements(&mesh.validate_with(&wide), IssueKind::UvOutOfRange), None);
+}
+
+#[test]
+fn degenerate_triangles_are_only_a_warning() {
+    let mut builder = MeshBuilder::new();
+    for position in [Vec3::ZERO, Vec3::X, Vec3::X * 2.0] {
+        builder.add_vertex(position, Vec3::Z, Vec2::ZERO);
+    }
+    builder.add_triangle(0, 1, 2).unwrap();
+    let report = builder.build().validate();
+
+    assert_eq!(elements(&report, IssueKind::DegenerateTriangle), Some(vec![0]));
+    assert!(report.is_valid());
+    assert_eq!(report.warnings().count(), 1);
+}
+
+#[test]
+fn model_tags_issues_with_their_mesh() {
+    let mut model = Model::from_mesh(Mesh::cube(1.0));
+    let mut orphan = Mesh::cube(1.0);
+    orphan.material = MaterialHandle::from(5);
+    model.meshes.push(orphan);
+    model.meshes.push(MeshBuilder::new().build());
+
+    let report = model.validate();
+    let kinds: Vec<(Option<usize>, IssueKind)> = report.issues.iter().map(|issue| (issue.mesh, issue.kind)).collect();
+    assert_eq!(kinds, [(Some(1), IssueKind::MaterialOutOfRange), (Some(2), IssueKind::EmptyMesh)]);
+    assert_eq!(report.issues[0].elements, [5]);
+    assert_eq!(report.errors().next().unwrap().severity, Severity::Error);
+}
+
+#[test]
+fn positions_outside_the_declared_accessor_bounds_are_reported() {
+    let mut fixture = Fixture::new();
+    let positions = fixture.floats(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [1.0, 1.0, 0.0]]);
+    // The exporter claims the quad ends at x = 0.5, and rounds its top edge down a little.
+    fixture.accessors[positions]["max"] = json!([0.5, 0.99999, 0.0]);
+    let indices = fixture.indices(&[0, 1, 2, 2, 1, 3]);
+    let mesh = json!({ "primitives": [{ "attributes": { "POSITION": positions }, "indices": indices }] });
+    let path = fixture.write("validate_bounds", single_mesh(mesh, json!([])));
+
+    let model = load_model_with(&path, &LoaderOptions::default()).unwrap();
+    let report = model.validate();
+    assert_eq!(elements(&report, IssueKind::BoundsMismatch), Some(vec![1, 3]));
+    assert_eq!(report.errors().next().unwrap().mesh, Some(0));
+    assert!(!report.is_valid());
+
+    // Recomputing the bounds from the data clears the issue.
+    let mut fixed = model.clone();
+    fixed.meshes[0].invalidate_bounds();
+    assert!(fixed.validate().is_valid());
+}
@@ -0,0 +1,125 @@
+mod common;
+
+use common::*;
+use motley::model::{load_model_with, LoadWarning, LoaderOptions, PrimitiveLocation};
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+
+/*
+Writes a mesh whose primitives make the loader decide on its own: a textured triangle with a
+missing image, too few texture coordinates and no tangents, a point cloud, and a bare triangle
+without material.
+*/
+fn questionable(name: &str) -> std::path::PathBuf {
+    let mut fixture = Fixture::new();
+    let positions = fixture.floats(&TRIANGLE);
+    let normals = fixture.floats(&[[0.0, 0.0, 1.0]; 3]);
+    let tex_coords = fixture.floats(&[[0.0, 0.0], [1.0, 0.0]]);
+    let mesh = json!({
+        "name": "parts",
+        "primitives": [
+            { "attributes": { "POSITION": positions, "NORMAL": normals, "TEXCOORD_0": tex_coords }, "material": 0 },
+            { "attributes": { "POSITION": positions }, "mode": 0, "material": 0 },
+            { "attributes": { "POSITION": positions } }
+        ]
+    });
+    let material = json!({ "pbrMetallicRoughness": { "baseColorTexture": { "index": 0 } } });
+    let mut document = single_mesh(mesh, json!([material]));
+    document["images"] = json!([{ "uri": "gone.png", "name": "albedo" }]);
+    document["textures"] = json!([{ "source": 0 }]);
+    fixture.write(name, document)
+}
+
+fn location(primitive: usize) -> PrimitiveLocation {
+    PrimitiveLocation { mesh: 0, mesh_name: Some("parts".to_string()), primitive }
+}
+
+#[test]
+fn every_decision_reaches_the_warning_handler() {
+    let path = questionable("load_warnings");
+    let heard = Arc::new(Mutex::new(Vec::new()));
+    let sink = heard.clone();
+    let options = LoaderOptions::new().warning_handler(move |warning| sink.lock().unwrap().push(warning.clone()));
+    let model = load_model_with(&path, &options).unwrap();
+    assert_eq!(model.meshes.len(), 2);
+
+    let heard = heard.lock().unwrap();
+    assert_eq!(heard.len(), 8);
+    assert_eq!(heard[0], LoadWarning::UnsupportedPrimitiveMode { location: location(1), mode: gltf::mesh::Mode::Points });
+    assert!(matches!(&heard[1], LoadWarning::ImageDecodeFailed { image: 0, name: Some(name), reason } if name == "albedo" && reason.contains("gone.png")));
+    assert_eq!(heard[2..4], [
+        LoadWarning::MissingAttribute { location: location(0), semantic: "TANGENT" },
+        LoadWarning::ShortAttribute { location: location(0), semantic: "TEXCOORD_0", count: 2, expected: 3 }
+    ]);
+    assert_eq!(heard[4..], [
+        LoadWarning::MissingAttribute { location: location(2), semantic: "NORMAL" },
+        LoadWarning::MissingAttribute { location: location(2), semantic: "TEXCOORD_0" },
+        LoadWarning::MissingAttribute { location: location(2), semantic: "TANGENT" },
+        LoadWarning::DefaultMaterial { location: location(2) }
+    ]);
+    assert_eq!(heard[7].to_string(), "Used the default material for primitive 2 of mesh 0 (\"parts\"). (Primitive has no material)");
+
+    // Without a handler the load is quiet and gives the same model.
+    let quiet = load_model_with(&path, &LoaderOptions::default()).unwrap();
+    assert_eq!(quiet.meshes.len(), model.meshes.len());
+}
+
+#[cfg(feature = "tracing")]
+mod tracing_events {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata};
+
+    /*
+    Records the names of the spans opened and counts the warning events, from any thread.
+    */
+    struct Recorder {
+        spans: Mutex<Vec<&'static str>>,
+        warnings: AtomicU64,
+        next_id: AtomicU64
+    }
+
+    static RECORDER: Recorder = Recorder { spans: Mutex::new(Vec::new()), warnings: AtomicU64::new(0), next_id: AtomicU64::new(0) };
+
+    impl tracing::Subscriber for &'static Recorder {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            self.spans.lock().unwrap().push(span.metadata().name());
+            Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+        }
+
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            if *event.metadata().level() == tracing::Level::WARN {
+                self.warnings.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn loading_emits_spans_for_each_phase_and_its_warnings() {
+        let path = questionable("load_tracing");
+        let recorder = &RECORDER;
+        // Primitives and images may be processed on worker threads, which only see the global
+        // subscriber.
+        tracing::subscriber::set_global_default(recorder).unwrap();
+        load_model_with(&path, &LoaderOptions::default()).unwrap();
+
+        let spans = recorder.spans.lock().unwrap();
+        assert!(spans.contains(&"parse_document"));
+        assert!(spans.contains(&"decode_image"));
+        assert!(spans.iter().filter(|&&name| name == "process_primitive").count() >= 2);
+        assert!(recorder.warnings.load(Ordering::Relaxed) >= 8);
+    }
+}
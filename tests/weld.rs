@@ -0,0 +1,73 @@
+use glam::*;
+use motley::model::{Mesh, MeshBuilder, NormalMode};
+
+/*
+A mesh of the given positions and normals, without texture coordinates, drawing `indices`.
+*/
+fn mesh(vertices: &[([f32; 3], [f32; 3])], indices: &[u32]) -> Mesh {
+    let mut builder = MeshBuilder::new();
+    for &(position, normal) in vertices {
+        builder.add_vertex(Vec3::from(position), Vec3::from(normal), Vec2::ZERO);
+    }
+    for triangle in indices.chunks(3) {
+        builder.add_triangle(triangle[0], triangle[1], triangle[2]).unwrap();
+    }
+    builder.build()
+}
+
+#[test]
+fn weld_merges_identical_vertices_in_order() {
+    let up = [0.0, 0.0, 1.0];
+    let vertices = [
+        ([0.0, 0.0, 0.0], up),
+        ([1.0, 0.0, 0.0], up),
+        ([0.0, 1.0, 0.0], up),
+        ([1.0, 0.0, 0.0], up),
+        ([1.0, 1.0, 0.0], up),
+        ([0.0, 1.0, 0.0], up)
+    ];
+    let mut quad = mesh(&vertices, &[0, 1, 2, 3, 4, 5]);
+
+    assert_eq!(quad.weld_vertices(0.0, true), 2);
+    let positions: Vec<Vec3> = quad.vertices.iter().map(|vertex| vertex.position).collect();
+    assert_eq!(positions, [Vec3::ZERO, Vec3::X, Vec3::Y, Vec3::new(1.0, 1.0, 0.0)]);
+    assert_eq!(quad.indices.to_u32(), [0, 1, 2, 1, 3, 2]);
+}
+
+#[test]
+fn weld_respects_epsilon_and_attributes() {
+    let vertices = [
+        ([0.0, 0.0, 0.0], [0.0, 0.0, 1.0]),
+        ([0.0005, 0.0, 0.0], [0.0, 0.0, 1.0]),
+        ([0.0, 0.0, 0.0], [1.0, 0.0, 0.0])
+    ];
+    let mut exact = mesh(&vertices, &[0, 1, 2]);
+    assert_eq!(exact.weld_vertices(0.0, false), 1);
+    assert_eq!(exact.indices.to_u32(), [0, 1, 0]);
+
+    let mut near = mesh(&vertices, &[0, 1, 2]);
+    assert_eq!(near.weld_vertices(0.001, true), 1);
+    assert_eq!(near.indices.to_u32(), [0, 0, 1]);
+
+    let mut positions_only = mesh(&vertices, &[0, 1, 2]);
+    assert_eq!(positions_only.weld_vertices(0.001, false), 2);
+    assert_eq!(positions_only.indices.to_u32(), [0, 0, 0]);
+}
+
+#[test]
+fn unshared_cube_welds_to_its_corners_for_smooth_normals() {
+    let mut cube = Mesh::cube(2.0).to_non_indexed();
+    assert_eq!(cube.vertices.len(), 36);
+
+    // Flat normals differ on every face, so only comparing positions shares the corners.
+    assert_eq!(cube.clone().weld_vertices(0.0, true), 12);
+    assert_eq!(cube.weld_vertices(0.0, false), 28);
+    assert_eq!(cube.triangle_count(), 12);
+
+    cube.recalculate_normals(NormalMode::Smooth);
+    assert_eq!(cube.vertices.len(), 8);
+    for vertex in &cube.vertices {
+        assert!((vertex.normal.length() - 1.0).abs() < 1e-6);
+        assert!(vertex.normal.dot(vertex.position.normalize()) > 0.9);
+    }
+}
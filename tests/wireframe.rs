@@ -0,0 +1,40 @@
+use glam::*;
+use motley::model::{add_barycentric_attribute, Mesh, MeshBuilder};
+
+#[test]
+fn triangle_gives_its_three_edges() {
+    let mut builder = MeshBuilder::new();
+    for position in [Vec3::ZERO, Vec3::X, Vec3::Y] {
+        builder.add_vertex(position, Vec3::Z, Vec2::ZERO);
+    }
+    builder.add_triangle(0, 1, 2).unwrap();
+    assert_eq!(builder.build().wireframe_indices(), [0, 1, 1, 2, 2, 0]);
+}
+
+#[test]
+fn shared_edges_are_listed_once() {
+    let plane = Mesh::plane(1.0, 1.0, 1);
+    let lines = plane.wireframe_indices();
+    assert_eq!(lines.len(), 5 * 2);
+
+    // The cube's faces have their own vertices, so each gives its four sides and its diagonal.
+    // Welded, the 12 sides are shared and only the 6 diagonals are added.
+    let cube = Mesh::cube(1.0);
+    assert_eq!(cube.wireframe_indices().len(), 6 * 5 * 2);
+    let mut welded = cube.clone();
+    welded.weld_vertices(0.0, false);
+    assert_eq!(welded.wireframe_indices().len(), 18 * 2);
+}
+
+#[test]
+fn barycentric_attribute_marks_each_corner() {
+    let mut cube = Mesh::cube(1.0);
+    add_barycentric_attribute(&mut cube);
+    assert_eq!(cube.vertices.len(), 36);
+    assert_eq!(cube.indices.to_u32(), (0..36).collect::<Vec<u32>>());
+    for triangle in cube.vertices.chunks(3) {
+        let corners: Vec<Vec3> = triangle.iter().map(|vertex| vertex.barycentric).collect();
+        assert_eq!(corners, [Vec3::X, Vec3::Y, Vec3::Z]);
+    }
+    assert_eq!(cube.to_non_indexed().interleaved_bytes(), cube.interleaved_bytes());
+}